@@ -8,7 +8,7 @@ use once_cell::sync::Lazy;
 use replicore_context::Context;
 use replicore_injector::Injector;
 
-mod constants;
+pub mod constants;
 mod v0;
 
 /// Arguments to pass around apply handlers.
@@ -4,6 +4,7 @@ use actix_web::HttpResponse;
 use replisdk::core::models::cluster::ClusterSpec;
 
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 use replicore_store::query::LookupClusterSpec;
 
 use super::decode;
@@ -65,7 +66,8 @@ pub async fn cluster_spec(args: ApplyArgs<'_>) -> Result<HttpResponse, crate::ap
 
     // Apply the cluster spec.
     let event = Event::new_with_payload(APPLY_CLUSTER_SPEC, &cluster)?;
-    args.injector.events.change(&args.context, event).await?;
-    args.injector.store.persist(&args.context, cluster).await?;
+    let op = PersistWithEvents::new(cluster).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(args.injector.as_ref());
+    sdk.persist_with_events(&args.context, op).await?;
     Ok(crate::api::done())
 }
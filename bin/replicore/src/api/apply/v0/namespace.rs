@@ -5,6 +5,7 @@ use replisdk::core::models::namespace::Namespace;
 use replisdk::core::models::namespace::NamespaceStatus;
 
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 use replicore_store::query::LookupNamespace;
 
 use super::decode;
@@ -43,12 +44,10 @@ pub async fn namespace(args: ApplyArgs<'_>) -> Result<HttpResponse, crate::api::
     }
 
     // Apply the namespace.
-    let event = Event::new_with_payload(APPLY_NAMESPACE, &namespace)?;
-    args.injector.events.change(&args.context, event).await?;
-    args.injector
-        .store
-        .persist(&args.context, namespace)
-        .await?;
+    let event = Event::new_with_payload(APPLY_NAMESPACE, &namespace)?.for_namespace(&namespace.id);
+    let op = PersistWithEvents::new(namespace).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(args.injector.as_ref());
+    sdk.persist_with_events(&args.context, op).await?;
     Ok(crate::api::done())
 }
 
@@ -4,6 +4,7 @@ use actix_web::HttpResponse;
 use replisdk::core::models::platform::Platform;
 
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 
 use super::decode;
 use super::PLATFORM_SCHEMA;
@@ -24,7 +25,8 @@ pub async fn platform(args: ApplyArgs<'_>) -> Result<HttpResponse, crate::api::E
 
     // Apply the platform.
     let event = Event::new_with_payload(APPLY_PLATFORM, &platform)?;
-    args.injector.events.change(&args.context, event).await?;
-    args.injector.store.persist(&args.context, platform).await?;
+    let op = PersistWithEvents::new(platform).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(args.injector.as_ref());
+    sdk.persist_with_events(&args.context, op).await?;
     Ok(crate::api::done())
 }
@@ -0,0 +1,83 @@
+//! Event codes emitted by API handlers and the contract of their payloads.
+use replicore_events::EventCode;
+use replicore_sdk::catalog::CLUSTER_SPEC_SCHEMA;
+
+use crate::api::apply::constants::APPLY_CLUSTER_SPEC;
+use crate::api::apply::constants::APPLY_NAMESPACE;
+use crate::api::apply::constants::APPLY_PLATFORM;
+use crate::api::constants::CLUSTER_SPEC_DELETED;
+use crate::api::constants::NAMESPACE_DELETE_REQUESTED;
+use crate::api::constants::PLATFORM_DELETED;
+
+/// JSON Schema for events with a `Namespace` payload.
+const NAMESPACE_SCHEMA: &str = include_str!("catalog/namespace.schema.json");
+
+/// JSON Schema for events with a `Platform` payload.
+const PLATFORM_SCHEMA: &str = include_str!("catalog/platform.schema.json");
+
+/// JSON Schema for events with a `NamespacedResourceID` payload.
+const RESOURCE_ID_SCHEMA: &str = include_str!("catalog/resource-id.schema.json");
+
+/// Event codes emitted by API handlers.
+pub fn event_codes() -> Vec<EventCode> {
+    vec![
+        EventCode::change(
+            APPLY_CLUSTER_SPEC,
+            "ClusterSpec",
+            "ClusterSpec applied (created or updated)",
+        )
+        .schema(CLUSTER_SPEC_SCHEMA),
+        EventCode::change(
+            APPLY_NAMESPACE,
+            "Namespace",
+            "Namespace applied (created or updated)",
+        )
+        .schema(NAMESPACE_SCHEMA),
+        EventCode::change(
+            APPLY_PLATFORM,
+            "Platform",
+            "Platform applied (created or updated)",
+        )
+        .schema(PLATFORM_SCHEMA),
+        EventCode::change(
+            CLUSTER_SPEC_DELETED,
+            "NamespacedResourceID",
+            "ClusterSpec deleted from the control plane",
+        )
+        .schema(RESOURCE_ID_SCHEMA),
+        EventCode::change(
+            NAMESPACE_DELETE_REQUESTED,
+            "Namespace",
+            "Namespace moved to Deleting",
+        )
+        .schema(NAMESPACE_SCHEMA),
+        EventCode::change(
+            PLATFORM_DELETED,
+            "NamespacedResourceID",
+            "Platform deleted from the control plane",
+        )
+        .schema(RESOURCE_ID_SCHEMA),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events::catalog::EventsCatalog;
+
+    #[test]
+    fn core_event_codes_register() {
+        let mut catalog = EventsCatalog::build();
+        let codes = super::event_codes()
+            .into_iter()
+            .chain(replicore_auth::access::event_codes())
+            .chain(replicore_sdk::catalog::event_codes())
+            .chain(replicore_task_discovery::events::event_codes())
+            .chain(replicore_task_orchestrate::event_codes());
+        for code in codes {
+            catalog.register(code);
+        }
+        let catalog = catalog.finish();
+        assert!(catalog.lookup("CLUSTER_SPEC_APPLY").is_some());
+        assert!(catalog.lookup("AUDIT_AUTHORISATION").is_some());
+    }
+}
@@ -20,12 +20,18 @@ use replisdk::core::models::auth::Resource;
 
 use replicore_auth::access::Authoriser;
 use replicore_auth::identity::Authenticator;
+use replicore_auth::identity::Unauthenticated;
 use replicore_context::Context;
 use replicore_context::ContextBuilder;
 
 /// Resource kind for HTTP Endpoints.
 const HTTP_ENDPOINT_KIND: &str = "HttpEndpoint";
 
+/// Telemetry and introspection endpoints served without authentication or authorisation.
+///
+/// Monitoring systems, such as Prometheus, scrape these endpoints without credentials.
+const UNAUTHENTICATED_PATHS: [&str; 1] = ["/metrics"];
+
 /// Derive a per-request [`Context`] and attach it to requests before they are handled.
 pub struct ContextService<S> {
     authenticator: Authenticator,
@@ -68,10 +74,18 @@ where
         let authenticator = self.authenticator.clone();
         let authoriser = self.authoriser.clone();
         let service = Arc::clone(&self.service);
+        let unauthenticated = UNAUTHENTICATED_PATHS.contains(&request.path());
         Box::pin(async move {
             let context = context_derive_logging(context, &config);
+
+            // Skip authentication and authorisation for telemetry endpoints.
+            if unauthenticated {
+                request.extensions_mut().insert(context.build());
+                return service.call(request).await;
+            }
+
             let context = context_derive_auth(authenticator, &pcontext, context, &request).await;
-            let context = context.map_err(replisdk::utils::actix::error::Error::from)?;
+            let context = context.map_err(auth_error)?;
             let context = context.build();
 
             // Authorise the request with the newly derived context before processing it.
@@ -149,6 +163,15 @@ impl Default for ContextConfig {
     }
 }
 
+/// Respond to requests that failed authentication with the appropriate status code.
+fn auth_error(error: anyhow::Error) -> replisdk::utils::actix::error::Error {
+    if error.is::<Unauthenticated>() {
+        let status = actix_web::http::StatusCode::UNAUTHORIZED;
+        return replisdk::utils::actix::error::Error::with_status(status, error);
+    }
+    replisdk::utils::actix::error::Error::from(error)
+}
+
 /// Configure authentication parameters for the derived context.
 async fn context_derive_auth(
     authenticator: Authenticator,
@@ -202,18 +225,39 @@ fn context_derive_logging(context: ContextBuilder, config: &ContextConfig) -> Co
 mod tests {
     use actix_web::test::call_and_read_body_json;
     use actix_web::test::init_service;
+    use actix_web::test::try_call_service;
     use actix_web::test::TestRequest;
     use actix_web::FromRequest;
     use actix_web::HttpMessage;
     use actix_web::HttpResponse;
+    use anyhow::Result;
 
+    use replicore_auth::identity::Authentication;
+    use replicore_auth::identity::Authenticator;
+    use replicore_auth::identity::IdentityReader;
+    use replicore_auth::identity::Unauthenticated;
+    use replicore_auth::Entity;
     use replicore_context::Context;
 
+    /// Authentication backend rejecting all requests.
+    struct Reject;
+
+    #[async_trait::async_trait]
+    impl Authentication for Reject {
+        async fn authenticate(&self, _: &Context, _: &dyn IdentityReader) -> Result<Entity> {
+            anyhow::bail!(Unauthenticated::reason("test rejects all requests"))
+        }
+    }
+
     fn factory(root: Context) -> super::ContextMiddleware {
+        factory_with(root, replicore_auth_insecure::Anonymous.into())
+    }
+
+    fn factory_with(root: Context, authenticator: Authenticator) -> super::ContextMiddleware {
         let injector = replicore_injector::Injector::fixture();
         super::ContextMiddleware::new(
             root,
-            replicore_auth_insecure::Anonymous.into(),
+            authenticator,
             replicore_auth::access::Authoriser::wrap(
                 replicore_auth_insecure::Unrestricted,
                 injector.events.backend().into(),
@@ -226,6 +270,11 @@ mod tests {
         HttpResponse::Ok().json(43u64)
     }
 
+    #[actix_web::get("/metrics")]
+    async fn metrics() -> HttpResponse {
+        HttpResponse::Ok().json(42u64)
+    }
+
     #[actix_web::test]
     async fn extract_context() {
         let context = Context::fixture();
@@ -244,4 +293,31 @@ mod tests {
         let response: u64 = call_and_read_body_json(&app, request).await;
         assert_eq!(response, 43u64);
     }
+
+    #[actix_web::test]
+    async fn reject_unauthenticated() {
+        let root = Context::fixture();
+        let app = actix_web::App::new()
+            .service(inspect)
+            .wrap(factory_with(root, Reject.into()));
+        let app = init_service(app).await;
+
+        let request = TestRequest::get().uri("/").to_request();
+        let error = try_call_service(&app, request).await.unwrap_err();
+        let status = error.as_response_error().status_code();
+        assert_eq!(status, actix_web::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn telemetry_skips_authentication() {
+        let root = Context::fixture();
+        let app = actix_web::App::new()
+            .service(metrics)
+            .wrap(factory_with(root, Reject.into()));
+        let app = init_service(app).await;
+
+        let request = TestRequest::get().uri("/metrics").to_request();
+        let response: u64 = call_and_read_body_json(&app, request).await;
+        assert_eq!(response, 42u64);
+    }
 }
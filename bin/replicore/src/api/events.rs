@@ -0,0 +1,292 @@
+//! API endpoints to read events back from the events streaming platform.
+use std::time::Duration;
+
+use actix_web::web::Bytes;
+use actix_web::web::Data;
+use actix_web::web::Query;
+use actix_web::HttpRequest;
+use actix_web::HttpResponse;
+
+use replisdk::utils::error::slog::ErrorAttributes;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events_models::CloudEventsPage;
+use replicore_events_models::EventCodeList;
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsCursor;
+use replicore_events_models::EventsFormat;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
+use replicore_injector::Injector;
+
+use crate::api::Error;
+
+/// Header set by Server-Sent Events clients to resume a stream after reconnecting.
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+/// Maximum number of events returned by a single request.
+const MAX_LIMIT: usize = 1000;
+
+/// Send a comment to watch clients if no events were sent for this long.
+const WATCH_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Time to wait between checks for new events to send to watch clients.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Encoding of events returned by the API, selected with the `format` query parameter.
+#[derive(Debug, serde::Deserialize)]
+struct FormatQuery {
+    /// Encoding of returned events, the native events format if not set.
+    #[serde(default)]
+    format: EventsFormat,
+}
+
+/// List event codes known to the control plane, with their stream and payload schema.
+#[actix_web::get("/events/catalog")]
+pub async fn catalog(injector: Data<Injector>) -> Result<HttpResponse, Error> {
+    let items = injector.events.catalog().codes().cloned().collect();
+    let response = EventCodeList { items };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// List events matching the query filters, oldest first.
+///
+/// The response includes a cursor to fetch events emitted after the last returned event.
+/// With the `latest` parameter set the most recent matching events are returned instead.
+/// Events are returned as CloudEvents when the `format=cloudevents` parameter is set.
+#[actix_web::get("/events")]
+pub async fn list(
+    context: Context,
+    injector: Data<Injector>,
+    query: Query<EventsQuery>,
+    format: Query<FormatQuery>,
+) -> Result<HttpResponse, Error> {
+    let mut query = query.into_inner();
+    query.limit = query.limit.min(MAX_LIMIT);
+
+    // Reject invalid cursors as client errors.
+    check_cursor(&query)?;
+
+    let page = injector.events.query(&context, query).await?;
+    match format.format {
+        EventsFormat::CloudEvents => {
+            let page = CloudEventsPage::try_from(page).map_err(anyhow::Error::from)?;
+            Ok(HttpResponse::Ok().json(page))
+        }
+        EventsFormat::Native => Ok(HttpResponse::Ok().json(page)),
+    }
+}
+
+/// Stream change events matching the query filters as Server-Sent Events.
+///
+/// Each SSE event is an [`EventEntry`](replicore_events_models::EventEntry) encoded as JSON,
+/// or a CloudEvent in the structured JSON format when the `format=cloudevents` parameter is set.
+/// The last event sent for each page of events carries the cursor to resume watching from,
+/// which clients can provide with the `cursor` parameter or the `Last-Event-ID` header.
+/// Events may be sent again when resuming a stream interrupted part way through a page.
+///
+/// Without a cursor or `since` filter only events emitted after the request are sent.
+/// In this case the stream starts with a message carrying only the cursor of the starting point
+/// so clients can resume without gaps even if interrupted before any event is sent.
+#[actix_web::get("/events/watch")]
+pub async fn watch(
+    context: Context,
+    injector: Data<Injector>,
+    query: Query<EventsQuery>,
+    format: Query<FormatQuery>,
+    request: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let mut query = query.into_inner();
+    query.latest = false;
+    query.limit = query.limit.min(MAX_LIMIT);
+    query.stream = Some(EventStream::Change);
+    if let Some(cursor) = request.headers().get(LAST_EVENT_ID) {
+        let cursor = cursor
+            .to_str()
+            .map_err(|error| Error::bad_request(anyhow::Error::from(error)))?;
+        query.cursor = Some(cursor.to_string());
+    }
+    check_cursor(&query)?;
+
+    // Start after the most recent matching event, according to the server's event store.
+    let mut start = None;
+    if query.cursor.is_none() && query.since.is_none() {
+        let mut latest = query.clone();
+        latest.latest = true;
+        latest.limit = 1;
+        let page = injector.events.query(&context, latest).await?;
+        query.cursor = Some(page.cursor.clone());
+        start = Some(page.cursor);
+    }
+
+    let state = WatchState {
+        context,
+        events: injector.events.clone(),
+        format: format.format,
+        idle: Duration::ZERO,
+        query,
+        start,
+        wait: false,
+    };
+    let stream = futures_util::stream::unfold(Some(state), |state| async move {
+        match state {
+            None => None,
+            Some(state) => state.next().await,
+        }
+    });
+    let response = HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream);
+    Ok(response)
+}
+
+/// Reject invalid cursors as client errors.
+fn check_cursor(query: &EventsQuery) -> Result<(), Error> {
+    if let Some(cursor) = &query.cursor {
+        if let Err(error) = EventsCursor::decode(cursor) {
+            return Err(Error::bad_request(anyhow::Error::from(error)));
+        }
+    }
+    Ok(())
+}
+
+/// Progress of an events watch stream.
+struct WatchState {
+    context: Context,
+    events: Events,
+    format: EventsFormat,
+    idle: Duration,
+    query: EventsQuery,
+    start: Option<String>,
+    wait: bool,
+}
+
+impl WatchState {
+    /// Wait for the next chunk of data to send to the client.
+    ///
+    /// Query errors are sent to the client as an `error` event before the stream is closed.
+    async fn next(mut self) -> Option<(Result<Bytes, std::io::Error>, Option<Self>)> {
+        if let Some(cursor) = self.start.take() {
+            let chunk = encode_cursor(&cursor);
+            return Some((Ok(chunk), Some(self)));
+        }
+        loop {
+            if self.wait {
+                tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+            }
+            let page = match self.events.query(&self.context, self.query.clone()).await {
+                Ok(page) => page,
+                Err(error) => {
+                    slog::warn!(
+                        self.context.logger, "Unable to query events for watch stream";
+                        ErrorAttributes::from(&error),
+                    );
+                    let message = format!("{:#}", error);
+                    let chunk = Bytes::from(format!("event: error\ndata: {}\n\n", message));
+                    return Some((Ok(chunk), None));
+                }
+            };
+            self.query.cursor = Some(page.cursor.clone());
+
+            // Keep the connection alive while there are no events to send.
+            if page.items.is_empty() {
+                self.wait = true;
+                self.idle += WATCH_POLL_INTERVAL;
+                if self.idle >= WATCH_KEEPALIVE {
+                    self.idle = Duration::ZERO;
+                    let chunk = Bytes::from_static(b": keep-alive\n\n");
+                    return Some((Ok(chunk), Some(self)));
+                }
+                continue;
+            }
+
+            // Fetch the next page right away if more events may be available.
+            self.idle = Duration::ZERO;
+            self.wait = page.items.len() < self.query.limit;
+            let chunk = encode_page(&page, self.format);
+            return Some((chunk, Some(self)));
+        }
+    }
+}
+
+/// Encode a Server-Sent Event carrying only the cursor to resume watching from.
+fn encode_cursor(cursor: &str) -> Bytes {
+    Bytes::from(format!("id: {}\n\n", cursor))
+}
+
+/// Encode a page of events into Server-Sent Events.
+fn encode_page(page: &EventsPage, format: EventsFormat) -> Result<Bytes, std::io::Error> {
+    let mut chunk = String::new();
+    let last = page.items.len() - 1;
+    for (index, entry) in page.items.iter().enumerate() {
+        let data = format.encode(entry)?;
+        let data = String::from_utf8_lossy(&data);
+        if index == last {
+            chunk.push_str(&format!("id: {}\n", page.cursor));
+        }
+        chunk.push_str(&format!("event: {}\ndata: {}\n\n", entry.stream, data));
+    }
+    Ok(Bytes::from(chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events_models::Event;
+    use replicore_events_models::EventEntry;
+    use replicore_events_models::EventStream;
+    use replicore_events_models::EventsFormat;
+    use replicore_events_models::EventsPage;
+
+    use super::encode_cursor;
+    use super::encode_page;
+
+    fn page(count: usize) -> EventsPage {
+        let items = (0..count)
+            .map(|index| EventEntry {
+                event: Event::new_with_payload("TEST_EVENT", index).unwrap(),
+                stream: EventStream::Change,
+            })
+            .collect();
+        EventsPage {
+            cursor: String::from("test-cursor"),
+            items,
+        }
+    }
+
+    #[test]
+    fn encode_cursor_only() {
+        let chunk = encode_cursor("test-cursor");
+        assert_eq!(chunk.as_ref(), b"id: test-cursor\n\n");
+    }
+
+    #[test]
+    fn encode_cursor_on_last_event() {
+        let chunk = encode_page(&page(2), EventsFormat::Native).unwrap();
+        let chunk = std::str::from_utf8(&chunk).unwrap();
+        let messages: Vec<&str> = chunk.split_terminator("\n\n").collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("event: change\ndata: {"));
+        assert!(!messages[0].contains("id:"));
+        assert!(messages[1].starts_with("id: test-cursor\nevent: change\ndata: {"));
+
+        let data = messages[1].split_once("data: ").unwrap().1;
+        let entry: EventEntry = serde_json::from_str(data).unwrap();
+        assert_eq!(entry.event.payload, 1);
+    }
+
+    #[test]
+    fn encode_cloudevents() {
+        let chunk = encode_page(&page(1), EventsFormat::CloudEvents).unwrap();
+        let chunk = std::str::from_utf8(&chunk).unwrap();
+        let data = chunk
+            .strip_suffix("\n\n")
+            .and_then(|message| message.split_once("data: "))
+            .unwrap()
+            .1;
+        let event: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(event["specversion"], "1.0");
+        assert_eq!(event["type"], "TEST_EVENT");
+    }
+}
@@ -9,9 +9,12 @@ use replisdk::utils::actix::error::Error;
 use replicore_injector::Injector;
 
 pub mod apply;
+pub mod catalog;
 pub mod constants;
 pub mod context;
+pub mod events;
 pub mod object;
+pub mod tasks;
 
 /// Successful (200) API response with no data returned to the client.
 #[inline]
@@ -31,7 +34,11 @@ pub fn configure(config: &mut ServiceConfig) {
     let scope = actix_web::web::scope("/api/v0")
         .app_data(Data::new(injector))
         .service(self::apply::apply)
-        .configure(self::object::configure);
+        .service(self::events::catalog)
+        .service(self::events::list)
+        .service(self::events::watch)
+        .configure(self::object::configure)
+        .configure(self::tasks::configure);
     config.service(scope);
 }
 
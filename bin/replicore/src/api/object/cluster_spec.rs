@@ -11,6 +11,7 @@ use replicore_cluster_view::ClusterView;
 use replicore_context::Context;
 use replicore_events::Event;
 use replicore_injector::Injector;
+use replicore_store::delete::DeleteWithEvents;
 
 use crate::api::constants::CLUSTER_SPEC_DELETED;
 use crate::api::Error;
@@ -24,10 +25,12 @@ pub async fn delete(
 ) -> Result<HttpResponse, Error> {
     let (ns_id, name) = path.into_inner();
     let id = replicore_store::ids::NamespacedResourceID { ns_id, name };
-    let event = Event::new_with_payload(CLUSTER_SPEC_DELETED, &id)?;
+    let event =
+        Event::new_with_payload(CLUSTER_SPEC_DELETED, &id)?.for_cluster(&id.ns_id, &id.name);
     let op = replicore_store::delete::DeleteClusterSpec(id);
-    injector.events.change(&context, event).await?;
-    injector.store.delete(&context, op).await?;
+    let op = DeleteWithEvents::new(op).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(injector.as_ref());
+    sdk.delete_with_events(&context, op).await?;
     Ok(crate::api::done())
 }
 
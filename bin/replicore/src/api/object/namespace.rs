@@ -11,6 +11,7 @@ use replisdk::core::models::namespace::NamespaceStatus;
 use replicore_context::Context;
 use replicore_events::Event;
 use replicore_injector::Injector;
+use replicore_store::persist::PersistWithEvents;
 
 use crate::api::constants::NAMESPACE_DELETE_REQUESTED;
 use crate::api::Error;
@@ -47,9 +48,11 @@ pub async fn delete(
     // Update namespace to the deleting state.
     let mut namespace = namespace;
     namespace.status = NamespaceStatus::Deleting;
-    let event = Event::new_with_payload(NAMESPACE_DELETE_REQUESTED, &namespace)?;
-    injector.events.change(&context, event).await?;
-    injector.store.persist(&context, namespace).await?;
+    let event = Event::new_with_payload(NAMESPACE_DELETE_REQUESTED, &namespace)?
+        .for_namespace(&namespace.id);
+    let op = PersistWithEvents::new(namespace).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(injector.as_ref());
+    sdk.persist_with_events(&context, op).await?;
     Ok(crate::api::done())
 }
 
@@ -10,6 +10,7 @@ use replisdk::core::models::api::PlatformList;
 use replicore_context::Context;
 use replicore_events::Event;
 use replicore_injector::Injector;
+use replicore_store::delete::DeleteWithEvents;
 
 use crate::api::constants::PLATFORM_DELETED;
 use crate::api::Error;
@@ -25,8 +26,9 @@ pub async fn delete(
     let id = replicore_store::ids::NamespacedResourceID { ns_id, name };
     let event = Event::new_with_payload(PLATFORM_DELETED, &id)?;
     let op = replicore_store::delete::DeletePlatform(id);
-    injector.events.change(&context, event).await?;
-    injector.store.delete(&context, op).await?;
+    let op = DeleteWithEvents::new(op).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(injector.as_ref());
+    sdk.delete_with_events(&context, op).await?;
     Ok(crate::api::done())
 }
 
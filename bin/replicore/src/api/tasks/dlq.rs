@@ -0,0 +1,71 @@
+//! API endpoints for handling tasks in the dead-letter queue.
+use actix_web::web::Data;
+use actix_web::web::Path;
+use actix_web::HttpResponse;
+
+use replicore_context::Context;
+use replicore_injector::Injector;
+use replicore_tasks_models::DeadLetterList;
+use replicore_tasks_models::DeadLetterPurged;
+
+use crate::api::Error;
+
+/// Get a task in the dead-letter queue by ID.
+#[actix_web::get("/tasks/dlq/{id}")]
+pub async fn get(
+    context: Context,
+    injector: Data<Injector>,
+    path: Path<String>,
+) -> Result<HttpResponse, Error> {
+    let id = path.into_inner();
+    let task = injector.tasks.dlq_lookup(&context, &id).await?;
+    match task {
+        None => Ok(crate::api::not_found()),
+        Some(task) => Ok(HttpResponse::Ok().json(task)),
+    }
+}
+
+/// List tasks in the dead-letter queue.
+#[actix_web::get("/tasks/dlq")]
+pub async fn list(context: Context, injector: Data<Injector>) -> Result<HttpResponse, Error> {
+    let items = injector.tasks.dlq_list(&context).await?;
+    let response = DeadLetterList { items };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Remove a task from the dead-letter queue.
+#[actix_web::delete("/tasks/dlq/{id}")]
+pub async fn purge(
+    context: Context,
+    injector: Data<Injector>,
+    path: Path<String>,
+) -> Result<HttpResponse, Error> {
+    let id = path.into_inner();
+    let count = injector.tasks.dlq_purge(&context, Some(&id)).await?;
+    if count == 0 {
+        return Ok(crate::api::not_found());
+    }
+    Ok(HttpResponse::Ok().json(DeadLetterPurged { count }))
+}
+
+/// Remove all tasks from the dead-letter queue.
+#[actix_web::delete("/tasks/dlq")]
+pub async fn purge_all(context: Context, injector: Data<Injector>) -> Result<HttpResponse, Error> {
+    let count = injector.tasks.dlq_purge(&context, None).await?;
+    Ok(HttpResponse::Ok().json(DeadLetterPurged { count }))
+}
+
+/// Move a task from the dead-letter queue back onto its queue for execution.
+#[actix_web::post("/tasks/dlq/{id}/requeue")]
+pub async fn requeue(
+    context: Context,
+    injector: Data<Injector>,
+    path: Path<String>,
+) -> Result<HttpResponse, Error> {
+    let id = path.into_inner();
+    let found = injector.tasks.dlq_requeue(&context, &id).await?;
+    match found {
+        false => Ok(crate::api::not_found()),
+        true => Ok(crate::api::done()),
+    }
+}
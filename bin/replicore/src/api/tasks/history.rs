@@ -0,0 +1,57 @@
+//! API endpoints to inspect the history of background task executions.
+use actix_web::web::Data;
+use actix_web::web::Query;
+use actix_web::HttpResponse;
+use futures_util::TryStreamExt;
+
+use replicore_context::Context;
+use replicore_injector::Injector;
+use replicore_store::query::ListTaskExecutions;
+use replicore_tasks_models::TaskExecution;
+use replicore_tasks_models::TaskExecutionList;
+
+use crate::api::Error;
+
+/// Default number of execution records returned when no limit is requested.
+const DEFAULT_LIMIT: usize = 20;
+
+/// Maximum number of execution records returned by a single request.
+const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, serde::Deserialize)]
+struct HistoryQueryArgs {
+    /// Number of the most recent execution records to return.
+    #[serde(default)]
+    limit: Option<usize>,
+
+    /// Only return executions of tasks received from this queue.
+    #[serde(default)]
+    queue: Option<String>,
+
+    /// Only return executions of the task with this ID.
+    #[serde(default)]
+    task_id: Option<String>,
+}
+
+/// List the most recent background task execution attempts, most recent first.
+#[actix_web::get("/tasks/history")]
+pub async fn list(
+    context: Context,
+    injector: Data<Injector>,
+    query: Query<HistoryQueryArgs>,
+) -> Result<HttpResponse, Error> {
+    let args = query.into_inner();
+    let limit = args.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let mut query = ListTaskExecutions::latest(limit);
+    if let Some(queue) = args.queue {
+        query = query.with_queue(queue);
+    }
+    if let Some(task_id) = args.task_id {
+        query = query.with_task_id(task_id);
+    }
+
+    let items = injector.store.query(&context, query).await?;
+    let items: Vec<TaskExecution> = items.try_collect().await?;
+    let response = TaskExecutionList { items };
+    Ok(HttpResponse::Ok().json(response))
+}
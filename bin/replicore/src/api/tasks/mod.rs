@@ -0,0 +1,18 @@
+//! API endpoints to inspect and manage background tasks.
+use actix_web::web::ServiceConfig;
+
+pub mod dlq;
+pub mod history;
+pub mod stats;
+
+/// Configure all API endpoints defined in this module.
+pub fn configure(config: &mut ServiceConfig) {
+    config
+        .service(self::dlq::get)
+        .service(self::dlq::list)
+        .service(self::dlq::purge)
+        .service(self::dlq::purge_all)
+        .service(self::dlq::requeue)
+        .service(self::history::list)
+        .service(self::stats::list);
+}
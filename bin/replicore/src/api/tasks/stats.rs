@@ -0,0 +1,33 @@
+//! API endpoints to inspect the state of background task queues.
+use actix_web::web::Data;
+use actix_web::web::Query;
+use actix_web::HttpResponse;
+
+use replicore_context::Context;
+use replicore_injector::Injector;
+use replicore_tasks_models::QueueStatsList;
+
+use crate::api::Error;
+
+/// Maximum number of task previews returned for each queue.
+const MAX_PREVIEWS: usize = 50;
+
+#[derive(Debug, serde::Deserialize)]
+struct StatsQueryArgs {
+    /// Number of the oldest tasks on each queue to include as previews.
+    #[serde(default)]
+    previews: usize,
+}
+
+/// Summarise the state of tasks on each background task queue.
+#[actix_web::get("/tasks")]
+pub async fn list(
+    context: Context,
+    injector: Data<Injector>,
+    query: Query<StatsQueryArgs>,
+) -> Result<HttpResponse, Error> {
+    let previews = query.previews.min(MAX_PREVIEWS);
+    let items = injector.tasks.stats(&context, previews).await?;
+    let response = QueueStatsList { items };
+    Ok(HttpResponse::Ok().json(response))
+}
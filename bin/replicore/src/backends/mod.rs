@@ -6,5 +6,7 @@
 //! This module is for `replicore` specific use cases that don't generalise.
 //! For example the [`EventsNull`] backend for use with the `sync` command.
 mod events_null;
+mod task_history_store;
 
 pub use self::events_null::EventsNull;
+pub use self::task_history_store::TaskHistoryStore;
@@ -0,0 +1,31 @@
+//! Home of the [`TaskHistoryStore`] backend implementation.
+use anyhow::Result;
+
+use replicore_context::Context;
+use replicore_store::persist::RecordTaskExecution;
+use replicore_store::Store;
+use replicore_tasks::execute::TaskHistoryBackend;
+use replicore_tasks_models::TaskExecution;
+
+/// Record task execution attempts in the persistent store.
+///
+/// The store retains at most `retain` records for each task, dropping the oldest first.
+pub struct TaskHistoryStore {
+    retain: usize,
+    store: Store,
+}
+
+impl TaskHistoryStore {
+    /// Record task executions in the given store, retaining a limited number for each task.
+    pub fn new(store: Store, retain: usize) -> TaskHistoryStore {
+        TaskHistoryStore { retain, store }
+    }
+}
+
+#[async_trait::async_trait]
+impl TaskHistoryBackend for TaskHistoryStore {
+    async fn record(&self, context: &Context, execution: TaskExecution) -> Result<()> {
+        let op = RecordTaskExecution::new(execution, self.retain);
+        self.store.persist(context, op).await
+    }
+}
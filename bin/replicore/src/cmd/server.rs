@@ -10,6 +10,8 @@ use crate::init::Server;
 pub async fn run(_cli: Cli, conf: Conf) -> Result<()> {
     Server::configure(conf)
         .await?
+        .register_core_event_codes()
+        .register_core_subscribers()
         .register_core_tasks()
         .register_default_backends()
         .register_default_clients()
@@ -44,10 +44,12 @@ impl ActixServer {
             .done();
 
         // Initialise and run actix server.
+        // Client certificates are attached to connections for mutual TLS authentication.
         let server = HttpServer::new(move || {
             let app = factory.initialise().wrap(context_middleware.clone());
             factory.finalise(app)
         })
+        .on_connect(replicore_auth::identity::actix_peer_certificate)
         .disable_signals();
         let server = self.conf.apply(server)?;
         Ok(server.run())
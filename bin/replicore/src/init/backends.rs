@@ -4,13 +4,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::Result;
+use replicore_auth::identity::AuthenticationFactory;
+use replicore_coordinator::CoordinatorFactory;
 use replicore_events::emit::EventsFactory;
+use replicore_events_tee::TeeFactory;
+use replicore_events_tee::TEE_BACKEND_ID;
 use replicore_store::StoreFactory;
 use replicore_tasks::factory::TasksFactory;
 
 /// Error looking for a specific backend implementation.
 #[derive(Debug, thiserror::Error)]
 pub enum BackendNotFound {
+    /// Authentication backend not recognised.
+    #[error("authentication backend '{0}' not recognised")]
+    // (id,)
+    Authentication(String),
+
+    /// Coordinator backend not recognised.
+    #[error("coordinator backend '{0}' not recognised")]
+    // (id,)
+    Coordinator(String),
+
     /// Events backend not recognised.
     #[error("events backend '{0}' not recognised")]
     // (id,)
@@ -28,6 +42,16 @@ pub enum BackendNotFound {
 }
 
 impl BackendNotFound {
+    /// Authentication backend not recognised.
+    pub fn authentication(id: &str) -> Self {
+        Self::Authentication(id.to_string())
+    }
+
+    /// Coordinator backend not recognised.
+    pub fn coordinator(id: &str) -> Self {
+        Self::Coordinator(id.to_string())
+    }
+
     /// Events backend not recognised.
     pub fn events(id: &str) -> Self {
         Self::Events(id.to_string())
@@ -47,9 +71,18 @@ impl BackendNotFound {
 /// Registers of backend factories for implementations supported by the process/build.
 #[derive(Clone, Default)]
 pub struct Backends {
+    /// Supported Authentication backends.
+    authentications: HashMap<String, Arc<dyn AuthenticationFactory>>,
+
+    /// Supported Distributed Coordination backends.
+    coordinators: HashMap<String, Arc<dyn CoordinatorFactory>>,
+
     // Supported Events Platform backends.
     events: HashMap<String, Arc<dyn EventsFactory>>,
 
+    /// Composite Events Platform backend, with all other Events Platform backends as children.
+    events_tee: TeeFactory,
+
     /// Supported Persistent Store backends.
     stores: HashMap<String, Arc<dyn StoreFactory>>,
 
@@ -58,8 +91,29 @@ pub struct Backends {
 }
 
 impl Backends {
+    /// Lookup an [`AuthenticationFactory`] by ID.
+    pub fn authentication(&self, id: &str) -> Result<&dyn AuthenticationFactory> {
+        let factory = self
+            .authentications
+            .get(id)
+            .ok_or_else(|| BackendNotFound::authentication(id))?;
+        Ok(factory.as_ref())
+    }
+
+    /// Lookup a [`CoordinatorFactory`] by ID.
+    pub fn coordinator(&self, id: &str) -> Result<&dyn CoordinatorFactory> {
+        let factory = self
+            .coordinators
+            .get(id)
+            .ok_or_else(|| BackendNotFound::coordinator(id))?;
+        Ok(factory.as_ref())
+    }
+
     /// Lookup an [`EventsFactory`] by ID.
     pub fn events(&self, id: &str) -> Result<&dyn EventsFactory> {
+        if id == TEE_BACKEND_ID {
+            return Ok(&self.events_tee);
+        }
         let factory = self
             .events
             .get(id)
@@ -67,25 +121,76 @@ impl Backends {
         Ok(factory.as_ref())
     }
 
+    /// Register a new factory for an Authentication implementation.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the identifier of the new Authentication backend is already in use.
+    pub fn register_authentication<B, S>(&mut self, id: S, backend: B) -> &mut Self
+    where
+        B: AuthenticationFactory + 'static,
+        S: Into<String>,
+    {
+        match self.authentications.entry(id.into()) {
+            Entry::Occupied(entry) => {
+                panic!(
+                    "an AuthenticationBackend with id '{}' is already registered",
+                    entry.key()
+                )
+            }
+            Entry::Vacant(entry) => entry.insert(Arc::new(backend)),
+        };
+        self
+    }
+
+    /// Register a new factory for a Distributed Coordination implementation.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the identifier of the new Coordinator backend is already in use.
+    pub fn register_coordinator<B, S>(&mut self, id: S, backend: B) -> &mut Self
+    where
+        B: CoordinatorFactory + 'static,
+        S: Into<String>,
+    {
+        match self.coordinators.entry(id.into()) {
+            Entry::Occupied(entry) => {
+                panic!(
+                    "a CoordinatorBackend with id '{}' is already registered",
+                    entry.key()
+                )
+            }
+            Entry::Vacant(entry) => entry.insert(Arc::new(backend)),
+        };
+        self
+    }
+
     /// Register a new factory for an Events Platform implementation.
     ///
     /// # Panics
     ///
     /// This method panics if the identifier of the new Events Platform backend is already in use.
+    /// The composite Events Platform backend identifier is always in use.
     pub fn register_events<B, S>(&mut self, id: S, backend: B) -> &mut Self
     where
         B: EventsFactory + 'static,
         S: Into<String>,
     {
-        match self.events.entry(id.into()) {
+        let id = id.into();
+        if id == TEE_BACKEND_ID {
+            panic!("an EventsBackend with id '{}' is already registered", id);
+        }
+        let backend: Arc<dyn EventsFactory> = Arc::new(backend);
+        match self.events.entry(id.clone()) {
             Entry::Occupied(entry) => {
                 panic!(
                     "an EventsBackend with id '{}' is already registered",
                     entry.key()
                 )
             }
-            Entry::Vacant(entry) => entry.insert(Arc::new(backend)),
+            Entry::Vacant(entry) => entry.insert(backend.clone()),
         };
+        self.events_tee.register(id, backend);
         self
     }
 
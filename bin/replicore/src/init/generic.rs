@@ -46,20 +46,59 @@ impl GenericInit {
     ///
     /// Supported dependencies can be tuned at compile time using crate features.
     pub fn register_default_backends(&mut self) -> &mut Self {
+        self.backends
+            .register_authentication("anonymous", replicore_auth_insecure::Anonymous);
+        #[cfg(feature = "replicore-auth-mtls")]
+        self.backends
+            .register_authentication("mtls", replicore_auth_mtls::MtlsFactory);
+        #[cfg(feature = "replicore-auth-token")]
+        self.backends
+            .register_authentication("token", replicore_auth_token::TokenFactory);
+        #[cfg(feature = "replicore-events-jsonl")]
+        self.backends
+            .register_events("jsonl", replicore_events_jsonl::JsonlFactory);
+        #[cfg(feature = "replicore-coordinator-memory")]
+        self.backends.register_coordinator(
+            "memory",
+            replicore_coordinator_memory::MemoryFactory::default(),
+        );
+        #[cfg(feature = "replicore-events-memory")]
+        self.backends
+            .register_events("memory", replicore_events_memory::MemoryFactory::default());
+        #[cfg(feature = "replicore-store-memory")]
+        self.backends
+            .register_store("memory", replicore_store_memory::MemoryFactory::default());
+        #[cfg(feature = "replicore-tasks-memory")]
+        self.backends
+            .register_tasks("memory", replicore_tasks_memory::MemoryFactory::default());
         #[cfg(feature = "replicore-events-sqlite")]
         self.backends
+            .register_coordinator("sqlite", replicore_coordinator_sqlite::SQLiteFactory)
             .register_events("sqlite", replicore_events_sqlite::emit::SQLiteFactory)
             .register_store("sqlite", replicore_store_sqlite::SQLiteFactory)
             .register_tasks("sqlite", replicore_tasks_sqlite::SQLiteFactory);
+        #[cfg(feature = "replicore-events-webhook")]
+        self.backends
+            .register_events("webhook", replicore_events_webhook::WebhookFactory);
         self
     }
 
     /// Register metrics for core crates and all selected backends.
     pub fn register_metrics(&self) -> Result<&Self> {
         // Required core crates.
+        replicore_coordinator::register_metrics(&self.telemetry.metrics)?;
+        replicore_events::register_metrics(&self.telemetry.metrics)?;
+        replicore_outbox::register_metrics(&self.telemetry.metrics)?;
+        replicore_scheduler::register_metrics(&self.telemetry.metrics)?;
         replicore_tasks::register_metrics(&self.telemetry.metrics)?;
 
         // Selected backends.
+        self.backends
+            .authentication(&self.conf.authentication.backend)?
+            .register_metrics(&self.telemetry.metrics)?;
+        self.backends
+            .coordinator(&self.conf.coordinator.backend)?
+            .register_metrics(&self.telemetry.metrics)?;
         self.backends
             .events(&self.conf.events.backend)?
             .register_metrics(&self.telemetry.metrics)?;
@@ -90,6 +129,12 @@ impl GenericInit {
 
     /// Validate the loaded configuration objects for the selected backends.
     pub fn validate_backends_conf(&self, context: &Context) -> Result<&Self> {
+        self.backends
+            .authentication(&self.conf.authentication.backend)?
+            .conf_check(context, &self.conf.authentication.options)?;
+        self.backends
+            .coordinator(&self.conf.coordinator.backend)?
+            .conf_check(context, &self.conf.coordinator.options)?;
         self.backends
             .events(&self.conf.events.backend)?
             .conf_check(context, &self.conf.events.options)?;
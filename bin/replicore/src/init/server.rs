@@ -1,14 +1,25 @@
 //! RepliCore Control Plane Server initialisation as a builder.
+use std::time::Duration;
+
 use anyhow::Result;
 
 use replisdk::runtime::shutdown::ShutdownManagerBuilder;
 
+use replicore_auth::identity::AuthenticationFactory;
+use replicore_auth::identity::AuthenticationFactoryArgs;
 use replicore_conf::Conf;
 use replicore_conf::TasksConf;
 use replicore_context::Context;
 use replicore_context::ContextBuilder;
+use replicore_coordinator::CoordinatorFactory;
+use replicore_coordinator::CoordinatorFactoryArgs;
+use replicore_coordinator::Election;
+use replicore_events::catalog::EventsCatalog;
+use replicore_events::catalog::EventsCatalogBuilder;
 use replicore_events::emit::EventsFactory;
 use replicore_events::emit::EventsFactoryArgs;
+use replicore_events::subscribe::Subscribers;
+use replicore_events::EventCode;
 use replicore_injector::Injector;
 use replicore_oaction::OActionMetadata;
 use replicore_oaction::OActionRegistry;
@@ -19,10 +30,18 @@ use replicore_tasks::execute::TasksExecutorBuilder;
 use replicore_tasks::factory::TasksFactory;
 use replicore_tasks::factory::TasksFactoryArgs;
 
+use crate::backends::TaskHistoryStore;
+
 use super::actix::ActixServerRunArgs;
 use super::backends::Backends;
 use super::generic::GenericInit;
 
+/// Name of the election for leader-only Control Plane components.
+const ELECTION_NAME: &str = "replicore";
+
+/// Time leadership is held for before it must be renewed.
+const ELECTION_TTL: Duration = Duration::from_secs(15);
+
 /// Process builder to initialise and run a RepliCore Control Plane instance.
 pub struct Server {
     /// Builder for the registries of clients needed to interact with remote components.
@@ -31,12 +50,18 @@ pub struct Server {
     /// Root context for the process.
     context: ContextBuilder,
 
+    /// Builder for the catalog of event codes emitted by the process.
+    event_codes: EventsCatalogBuilder,
+
     /// Process initialisation logic common to all RepliCore commands.
     generic: GenericInit,
 
     /// Builder for the registry of orchestrator actions available to the process.
     oactions: OActionRegistryBuilder,
 
+    /// In-process subscribers to notify of emitted events.
+    subscribers: Subscribers,
+
     /// Partial configuration of the background tasks executor component.
     tasks: TasksExecutorBuilder,
 }
@@ -46,12 +71,16 @@ impl Server {
     pub async fn configure(conf: Conf) -> Result<Self> {
         let generic = GenericInit::configure(conf).await?;
         let context = Context::root(generic.telemetry.logger.clone());
-        let tasks = TasksExecutorBuilder::new(generic.conf.tasks.executor.clone());
+        let mut tasks = TasksExecutorBuilder::new(generic.conf.tasks.executor.clone());
+        let grace = Duration::from_secs(generic.conf.runtime.shutdown_grace_sec);
+        tasks.drain_timeout(grace);
         let server = Self {
             clients: Default::default(),
             context,
+            event_codes: EventsCatalog::build(),
             generic,
             oactions: OActionRegistry::build(),
+            subscribers: Default::default(),
             tasks,
         };
         Ok(server)
@@ -70,6 +99,26 @@ impl Server {
         self
     }
 
+    /// Register the event codes emitted by all core control plane components.
+    pub fn register_core_event_codes(self) -> Self {
+        self.register_event_codes(crate::api::catalog::event_codes())
+            .register_event_codes(replicore_auth::access::event_codes())
+            .register_event_codes(replicore_sdk::catalog::event_codes())
+            .register_event_codes(replicore_task_discovery::events::event_codes())
+            .register_event_codes(replicore_task_orchestrate::event_codes())
+    }
+
+    /// Register all in-process event subscribers required by the control plane to operate.
+    pub fn register_core_subscribers(self) -> Self {
+        // Orchestrate clusters as soon as their spec changes instead of waiting for the scheduler.
+        let orchestrate = replicore_task_orchestrate::OrchestrateOnEvent::default();
+        self.subscribers.subscribe(
+            crate::api::apply::constants::APPLY_CLUSTER_SPEC,
+            orchestrate,
+        );
+        self
+    }
+
     /// Register all supported backends for all process dependencies.
     ///
     /// Supported dependencies can be tuned at compile time using crate features.
@@ -104,6 +153,20 @@ impl Server {
         self
     }
 
+    /// Register a new factory for a Distributed Coordination implementation.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the identifier of the new Coordinator backend is already in use.
+    pub fn register_coordinator<B, S>(mut self, id: S, backend: B) -> Self
+    where
+        B: CoordinatorFactory + 'static,
+        S: Into<String>,
+    {
+        self.generic.backends.register_coordinator(id, backend);
+        self
+    }
+
     /// Register a new factory for an Events Platform implementation.
     ///
     /// # Panics
@@ -118,6 +181,21 @@ impl Server {
         self
     }
 
+    /// Register the contract for event codes emitted by the process.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if an event code is already registered.
+    pub fn register_event_codes<I>(mut self, codes: I) -> Self
+    where
+        I: IntoIterator<Item = EventCode>,
+    {
+        for code in codes {
+            self.event_codes.register(code);
+        }
+        self
+    }
+
     /// Register metadata for handling of [`OAction`] records.
     ///
     /// [`OAction`]: replisdk::core::models::oaction::OAction
@@ -173,7 +251,9 @@ impl Server {
             &self.generic.conf,
             &self.generic.backends,
             self.clients,
+            self.event_codes.finish(),
             self.oactions.finish(),
+            self.subscribers,
         )
         .await?;
         Injector::set_global(injector);
@@ -189,15 +269,38 @@ impl Server {
                 context: injector.context,
             },
         )?;
+        election(
+            context.derive(),
+            &mut self.generic.shutdown,
+            Injector::global(),
+        );
+        events_maintenance(
+            context.derive(),
+            &self.generic.conf,
+            &self.generic.backends,
+            &mut self.generic.shutdown,
+            Injector::global(),
+        )
+        .await?;
         tasks_executor(
             context.derive(),
             &self.generic.conf.tasks,
             &self.generic.backends,
             &mut self.generic.shutdown,
             self.tasks,
+            Injector::global(),
         )
         .await?;
-        // TODO: Add other components
+        scheduler(
+            context.derive(),
+            &mut self.generic.shutdown,
+            Injector::global(),
+        );
+        outbox_relay(
+            context.derive(),
+            &mut self.generic.shutdown,
+            Injector::global(),
+        );
 
         // Run until user-requested exit or process error.
         self.generic.wait().await
@@ -219,21 +322,39 @@ pub async fn injector(
     conf: &Conf,
     backends: &Backends,
     clients: replicore_injector::Clients,
+    event_codes: EventsCatalog,
     oactions: OActionRegistry,
+    subscribers: Subscribers,
 ) -> Result<Injector> {
     // Grab all dependencies factories.
     let conf = conf.clone();
+    let authentication = backends.authentication(&conf.authentication.backend)?;
+    let coordinator = backends.coordinator(&conf.coordinator.backend)?;
     let events = backends.events(&conf.events.backend)?;
     let store = backends.store(&conf.store.backend)?;
     let tasks = backends.tasks(&conf.tasks.service.backend)?;
 
     // Initialise all dependencies.
+    let authenticator = authentication
+        .authenticator(AuthenticationFactoryArgs {
+            conf: &conf.authentication.options,
+            context,
+        })
+        .await?;
+    let coordinator = coordinator
+        .coordinator(CoordinatorFactoryArgs {
+            conf: &conf.coordinator.options,
+            context,
+        })
+        .await?;
     let events = events
         .events(EventsFactoryArgs {
             conf: &conf.events.options,
             context,
         })
-        .await?;
+        .await?
+        .with_catalog(event_codes)
+        .with_subscribers(subscribers);
     let store = store
         .store(StoreFactoryArgs {
             conf: &conf.store.options,
@@ -247,8 +368,9 @@ pub async fn injector(
         })
         .await?;
 
-    // Auth* is not currently configurable and just in place for the future.
-    let authenticator = replicore_auth_insecure::Anonymous.into();
+    let election = Election::new(coordinator.clone(), ELECTION_NAME, ELECTION_TTL);
+
+    // Authorisation is not currently configurable and just in place for the future.
     let authoriser = replicore_auth::access::Authoriser::wrap(
         replicore_auth_insecure::Unrestricted,
         events.clone(),
@@ -261,6 +383,8 @@ pub async fn injector(
         clients,
         conf,
         context: context.clone(),
+        coordinator,
+        election,
         events,
         oactions,
         store,
@@ -269,17 +393,148 @@ pub async fn injector(
     Ok(injector)
 }
 
+/// Start campaigning for leadership of the process election.
+pub fn election(
+    context: ContextBuilder,
+    shutdown: &mut ShutdownManagerBuilder<()>,
+    injector: Injector,
+) {
+    // Customise the root context for the election.
+    let context = context
+        .log_values(slog::o!("component" => "election"))
+        .build();
+
+    // Campaign for leadership in the background until shutdown.
+    let exit = shutdown.shutdown_notification();
+    shutdown.watch_tokio(tokio::spawn(async move {
+        injector.election.campaign(&context, exit).await
+    }));
+}
+
+/// Start background maintenance of the events backend, if it needs any.
+///
+/// Maintenance only runs in the process leading the election.
+pub async fn events_maintenance(
+    context: ContextBuilder,
+    conf: &Conf,
+    backends: &Backends,
+    shutdown: &mut ShutdownManagerBuilder<()>,
+    injector: Injector,
+) -> Result<()> {
+    // Customise the root context for events maintenance.
+    let context = context
+        .log_values(slog::o!("component" => "events-maintenance"))
+        .build();
+    let maintenance = backends
+        .events(&conf.events.backend)?
+        .maintenance(EventsFactoryArgs {
+            conf: &conf.events.options,
+            context: &context,
+        })
+        .await?;
+    let maintenance = match maintenance {
+        None => return Ok(()),
+        Some(maintenance) => maintenance,
+    };
+
+    // Maintain the events backend in the background, while leader, until shutdown.
+    let exit = shutdown.shutdown_notification();
+    shutdown.watch_tokio(tokio::spawn(async move {
+        let election = injector.election.clone();
+        election
+            .leader_only(&context, exit, |exit| {
+                let maintenance = maintenance.clone();
+                let context = context.clone();
+                async move { maintenance.run(&context, exit).await }
+            })
+            .await
+    }));
+    Ok(())
+}
+
+/// Start the relay publishing events from the transactional outbox, if enabled.
+///
+/// The relay only runs in the process leading the election.
+pub fn outbox_relay(
+    context: ContextBuilder,
+    shutdown: &mut ShutdownManagerBuilder<()>,
+    injector: Injector,
+) {
+    // Customise the root context for the outbox relay.
+    let context = context
+        .log_values(slog::o!("component" => "outbox-relay"))
+        .build();
+    if !injector.conf.outbox.enabled {
+        return;
+    }
+
+    // Publish outbox events in the background, while leader, until shutdown.
+    let exit = shutdown.shutdown_notification();
+    shutdown.watch_tokio(tokio::spawn(async move {
+        let election = injector.election.clone();
+        election
+            .leader_only(&context, exit, |exit| {
+                let relay = replicore_outbox::Relay::new(injector.clone());
+                let context = context.clone();
+                async move { relay.run(&context, exit).await }
+            })
+            .await
+    }));
+}
+
+/// Start the periodic discovery and orchestration scheduler component, if enabled.
+///
+/// The scheduler only runs in the process leading the election.
+pub fn scheduler(
+    context: ContextBuilder,
+    shutdown: &mut ShutdownManagerBuilder<()>,
+    injector: Injector,
+) {
+    // Customise the root context for the scheduler.
+    let context = context
+        .log_values(slog::o!("component" => "scheduler"))
+        .build();
+    if !injector.conf.scheduler.enabled {
+        slog::info!(
+            context.logger,
+            "Periodic scheduler disabled by configuration"
+        );
+        return;
+    }
+
+    // Schedule tasks in the background, while leader, until shutdown.
+    let exit = shutdown.shutdown_notification();
+    shutdown.watch_tokio(tokio::spawn(async move {
+        let election = injector.election.clone();
+        election
+            .leader_only(&context, exit, |exit| {
+                let mut scheduler = replicore_scheduler::Scheduler::new(injector.clone());
+                let context = context.clone();
+                async move { scheduler.run(&context, exit).await }
+            })
+            .await
+    }));
+}
+
 /// Configure and start the background task executor component.
 pub async fn tasks_executor(
     context: ContextBuilder,
     conf: &TasksConf,
     backends: &Backends,
     shutdown: &mut ShutdownManagerBuilder<()>,
-    builder: TasksExecutorBuilder,
+    mut builder: TasksExecutorBuilder,
+    injector: Injector,
 ) -> Result<()> {
     // Customise the root context for the tasks executor.
     let context = context.log_values(slog::o!("component" => "tasks")).build();
 
+    // Record task execution attempts in the store unless disabled.
+    let retain = conf.executor.history_limit;
+    if retain > 0 {
+        let history = TaskHistoryStore::new(injector.store, retain);
+        builder.history(history.into());
+    }
+
     // Initialise task polling and acknowledging backend.
     let tasks = backends.tasks(&conf.service.backend)?;
     let (source, ack) = tasks
@@ -4,6 +4,8 @@ use anyhow::Result;
 use replicore_conf::Conf;
 use replicore_context::Context;
 use replicore_context::ContextBuilder;
+use replicore_coordinator::CoordinatorFactory;
+use replicore_coordinator::CoordinatorFactorySyncArgs;
 use replicore_events::emit::EventsFactory;
 use replicore_events::emit::EventsFactorySyncArgs;
 use replicore_store::StoreFactory;
@@ -57,6 +59,20 @@ impl Sync {
         self
     }
 
+    /// Register a new factory for a Distributed Coordination implementation.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the identifier of the new Coordinator backend is already in use.
+    pub fn register_coordinator<B, S>(mut self, id: S, backend: B) -> Self
+    where
+        B: CoordinatorFactory + 'static,
+        S: Into<String>,
+    {
+        self.generic.backends.register_coordinator(id, backend);
+        self
+    }
+
     /// Register a new factory for an Events Platform implementation.
     ///
     /// # Panics
@@ -143,13 +159,26 @@ struct SyncArgs {
 /// Entrypoint to dependences synchronisation.
 async fn synchronise_dependencies(context: &Context, args: SyncArgs) -> Result<()> {
     slog::info!(context.logger, "Synchronising dependences");
-    // TODO: Synchronise election service.
+    // Leader election is backed by the coordinator and synchronised with it.
+    sync_coordinator(context, &args).await?;
     sync_events(context, &args).await?;
     sync_store(context, &args).await?;
     sync_tasks(context, &args).await?;
     Ok(())
 }
 
+async fn sync_coordinator(context: &Context, args: &SyncArgs) -> Result<()> {
+    slog::debug!(context.logger, "Synchronising coordinator backend");
+    let sync_args = CoordinatorFactorySyncArgs {
+        conf: &args.conf.coordinator.options,
+        context,
+    };
+    args.backends
+        .coordinator(&args.conf.coordinator.backend)?
+        .sync(sync_args)
+        .await
+}
+
 async fn sync_events(context: &Context, args: &SyncArgs) -> Result<()> {
     slog::debug!(context.logger, "Synchronising events backend");
     let sync_args = EventsFactorySyncArgs {
@@ -28,7 +28,7 @@ pub async fn run(globals: &Globals) -> Result<i32> {
             })
             .prompt()?;
 
-        let ca_bundle = Text::new("PEM Bundle of Certificate Authorities to trust:")
+        let ca_bundle = Text::new("Path to PEM Bundle of Certificate Authorities to trust:")
             .with_initial_value(context.connection.ca_bundle.as_deref().unwrap_or(""))
             .with_placeholder("Optional")
             .prompt()?;
@@ -37,10 +37,12 @@ pub async fn run(globals: &Globals) -> Result<i32> {
             bundle => Some(bundle),
         };
         context.connection.ca_bundle = ca_bundle;
-        let client_key = Text::new("Client Certificate's Private Key to send the Control Plane:")
-            .with_initial_value(context.connection.client_key.as_deref().unwrap_or(""))
-            .with_placeholder("Optional")
-            .prompt()?;
+        let client_key = Text::new(
+            "Path to Client Certificate and Private Key PEM Bundle to send the Control Plane:",
+        )
+        .with_initial_value(context.connection.client_key.as_deref().unwrap_or(""))
+        .with_placeholder("Optional")
+        .prompt()?;
         let client_key = match client_key {
             key if key.is_empty() => None,
             key => Some(key),
@@ -62,6 +64,7 @@ pub async fn run(globals: &Globals) -> Result<i32> {
 /// Create an empty context to use as a placeholder when a new context is needed.
 fn create_empty_context() -> Context {
     let connection = crate::context::Connection {
+        auth_token: None,
         ca_bundle: None,
         client_key: None,
         url: String::from(""),
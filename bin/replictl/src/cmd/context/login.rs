@@ -1,10 +1,39 @@
-//! Placeholder for future Replicante Control Plane authentication logic.
+//! Store the API token to authenticate with the Replicante Control Plane.
 use anyhow::Result;
+use inquire::Password;
+use inquire::PasswordDisplayMode;
 
+use crate::context::ContextStore;
 use crate::Globals;
 
-/// Placeholder for future Replicante Control Plane authentication logic.
-pub async fn run(_: &Globals) -> Result<i32> {
-    println!("Replicante Control Plane authentication is not available (or needed) yet");
+/// Store the API token to authenticate with the Replicante Control Plane.
+///
+/// Entering an empty token removes the stored token from the context.
+pub async fn run(globals: &Globals) -> Result<i32> {
+    let store = ContextStore::load(globals).await?;
+    let active = store.active_id(globals);
+    let context = store.get_active(globals)?;
+    println!("Authenticating with the Replicante Control Plane for context {active}");
+
+    let context = tokio::task::spawn_blocking(move || -> Result<_> {
+        let mut context = context;
+        let token = Password::new("API token:")
+            .with_display_mode(PasswordDisplayMode::Masked)
+            .with_help_message("Leave empty to remove the stored token")
+            .without_confirmation()
+            .prompt()?;
+        context.connection.auth_token = match token.trim() {
+            "" => None,
+            token => Some(token.to_string()),
+        };
+        Ok(context)
+    })
+    .await??;
+
+    // Update the contexts store and save it to disk.
+    let active = active.to_string();
+    let mut store = store;
+    store.upsert(active, context);
+    store.save(globals).await?;
     Ok(0)
 }
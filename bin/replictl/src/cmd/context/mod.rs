@@ -36,7 +36,7 @@ pub enum ContextCmd {
     /// List known RepliCore servers.
     List,
 
-    /// Placeholder for future Replicante Control Plane authentication logic.
+    /// Store the API token to authenticate with the RepliCore server.
     Login,
 
     /// Select the active context, the one used when none are specified.
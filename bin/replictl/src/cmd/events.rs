@@ -0,0 +1,249 @@
+//! Inspect events emitted by the Control Plane.
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsQuery;
+
+use crate::context::ContextStore;
+use crate::formatter::ops::EventListOp;
+use crate::Globals;
+
+/// Number of events requested from the server when paging through events.
+const PAGE_SIZE: usize = 500;
+
+/// Inspect events emitted by the Control Plane.
+#[derive(Debug, Parser)]
+pub struct EventsCli {
+    /// Select the `replictl events` command to run.
+    #[command(subcommand)]
+    pub command: EventsCmd,
+}
+
+/// Possible events commands to run.
+#[derive(Debug, Subcommand)]
+pub enum EventsCmd {
+    /// List events matching the filters, oldest first.
+    List(ListOpts),
+
+    /// Show the most recent events matching the filters, optionally following new events.
+    Tail(TailOpts),
+
+    /// Stream change events matching the filters as they are emitted.
+    Watch(WatchOpts),
+}
+
+/// Filters to select events with.
+///
+/// Events about specific namespaces or clusters are selected with
+/// the global `--namespace` and `--cluster` options.
+/// The namespace and cluster of the active context are NOT used to filter events.
+#[derive(Args, Debug)]
+pub struct EventsFilterOpts {
+    /// Only show events with this code.
+    #[arg(long)]
+    pub code: Option<String>,
+
+    /// Only show events that occurred at or after this RFC 3339 time.
+    #[arg(long, value_parser = parse_time)]
+    pub since: Option<OffsetDateTime>,
+
+    /// Only show events emitted onto this stream.
+    #[arg(long)]
+    pub stream: Option<StreamOpt>,
+
+    /// Only show events that occurred before this RFC 3339 time.
+    #[arg(long, value_parser = parse_time)]
+    pub until: Option<OffsetDateTime>,
+}
+
+impl EventsFilterOpts {
+    /// Build an [`EventsQuery`] from the filters.
+    fn query(&self, globals: &Globals, limit: usize) -> EventsQuery {
+        EventsQuery {
+            cluster_id: globals.cli.context.cluster.clone(),
+            code: self.code.clone(),
+            cursor: None,
+            latest: false,
+            limit,
+            ns_id: globals.cli.context.namespace.clone(),
+            since: self.since,
+            stream: self.stream.map(EventStream::from),
+            until: self.until,
+        }
+    }
+}
+
+/// List events matching the filters, oldest first.
+#[derive(Debug, Parser)]
+pub struct ListOpts {
+    /// Show events emitted after the cursor returned by a previous list command.
+    #[arg(long)]
+    pub cursor: Option<String>,
+
+    /// Filters to select events with.
+    #[command(flatten)]
+    pub filters: EventsFilterOpts,
+
+    /// Maximum number of events to list.
+    #[arg(long, default_value_t = 100)]
+    pub limit: usize,
+}
+
+/// Show the most recent events matching the filters, optionally following new events.
+#[derive(Debug, Parser)]
+pub struct TailOpts {
+    /// Filters to select events with.
+    #[command(flatten)]
+    pub filters: EventsFilterOpts,
+
+    /// Keep waiting for new events and show them as they are emitted.
+    #[arg(long, default_value_t = false)]
+    pub follow: bool,
+
+    /// Number of the most recent events to show.
+    #[arg(long, default_value_t = 20)]
+    pub lines: usize,
+
+    /// Seconds to wait between checks for new events in follow mode.
+    #[arg(long, default_value_t = 2)]
+    pub interval: u64,
+}
+
+/// Stream change events matching the filters as they are emitted.
+#[derive(Debug, Parser)]
+pub struct WatchOpts {
+    /// Only show events with this code.
+    #[arg(long)]
+    pub code: Option<String>,
+
+    /// Show events emitted after the cursor returned by a previous command.
+    #[arg(long)]
+    pub cursor: Option<String>,
+
+    /// Only show events that occurred at or after this RFC 3339 time.
+    #[arg(long, value_parser = parse_time)]
+    pub since: Option<OffsetDateTime>,
+}
+
+/// Events streams to select with CLI options.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StreamOpt {
+    /// Auditing events stream.
+    Audit,
+
+    /// Stream of changes to elements in the system.
+    Change,
+}
+
+impl From<StreamOpt> for EventStream {
+    fn from(value: StreamOpt) -> Self {
+        match value {
+            StreamOpt::Audit => EventStream::Audit,
+            StreamOpt::Change => EventStream::Change,
+        }
+    }
+}
+
+/// Execute the selected `replictl events` command.
+pub async fn run(globals: &Globals, cmd: &EventsCli) -> Result<i32> {
+    match &cmd.command {
+        EventsCmd::List(opts) => list(globals, opts).await,
+        EventsCmd::Tail(opts) => tail(globals, opts).await,
+        EventsCmd::Watch(opts) => watch(globals, opts).await,
+    }
+}
+
+async fn list(globals: &Globals, opts: &ListOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let mut query = opts.filters.query(globals, opts.limit);
+    query.cursor = opts.cursor.clone();
+    let page = client.events().query(&query).await?;
+    let mut formatter = globals.formatter.format(globals, EventListOp);
+    for entry in &page.items {
+        formatter.append(entry)?;
+    }
+    formatter.finish()?;
+
+    // Report the cursor on stderr to keep formatted output clean.
+    eprintln!("Continue listing events with --cursor {}", page.cursor);
+    Ok(0)
+}
+
+async fn tail(globals: &Globals, opts: &TailOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    // Fetch only the most recent events, then resume from them when following.
+    let mut query = opts.filters.query(globals, opts.lines);
+    query.latest = true;
+    let page = client.events().query(&query).await?;
+    query.cursor = Some(page.cursor);
+    query.latest = false;
+    query.limit = PAGE_SIZE;
+
+    let mut formatter = globals.formatter.format(globals, EventListOp);
+    for entry in &page.items {
+        formatter.append(entry)?;
+    }
+    if !opts.follow {
+        formatter.finish()?;
+        return Ok(0);
+    }
+
+    // Wait for new events until the user interrupts the command.
+    let interval = Duration::from_secs(opts.interval);
+    let mut wait = true;
+    loop {
+        if wait {
+            tokio::time::sleep(interval).await;
+        }
+        let page = client.events().query(&query).await?;
+        for entry in &page.items {
+            formatter.append(entry)?;
+        }
+        query.cursor = Some(page.cursor);
+
+        // Fetch the next page right away if more events may be available.
+        wait = page.items.len() < query.limit;
+    }
+}
+
+async fn watch(globals: &Globals, opts: &WatchOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let query = EventsQuery {
+        cluster_id: globals.cli.context.cluster.clone(),
+        code: opts.code.clone(),
+        cursor: opts.cursor.clone(),
+        latest: false,
+        limit: PAGE_SIZE,
+        ns_id: globals.cli.context.namespace.clone(),
+        since: opts.since,
+        stream: Some(EventStream::Change),
+        until: None,
+    };
+    let mut watch = client.events().watch(&query).await?;
+
+    // Show events until the user interrupts the command.
+    let mut formatter = globals.formatter.format(globals, EventListOp);
+    loop {
+        let entry = watch.next().await?;
+        formatter.append(&entry)?;
+    }
+}
+
+/// Parse RFC 3339 times from command line arguments.
+fn parse_time(value: &str) -> Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(value, &Rfc3339)
+}
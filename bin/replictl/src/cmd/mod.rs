@@ -5,10 +5,12 @@ use clap::Subcommand;
 pub mod apply;
 pub mod cluster_spec;
 pub mod context;
+pub mod events;
 pub mod naction;
 pub mod namespace;
 pub mod oaction;
 pub mod platform;
+pub mod tasks;
 
 use crate::context::ContextOpt;
 use crate::formatter::FormatOpts;
@@ -54,6 +56,10 @@ pub enum Command {
     /// Manage configuration of RepliCore servers to access.
     Context(context::ContextCli),
 
+    /// Inspect events emitted by the Control Plane.
+    #[command(alias = "event")]
+    Events(events::EventsCli),
+
     /// Inspect or manipulate node actions for a cluster.
     #[command(alias = "na", alias = "naction")]
     NAction(naction::NActionCli),
@@ -68,6 +74,10 @@ pub enum Command {
 
     /// Inspect, delete or manipulate platforms.
     Platform(platform::PlatformCli),
+
+    /// Inspect and manage background tasks.
+    #[command(alias = "task")]
+    Tasks(tasks::TasksCli),
 }
 
 #[cfg(test)]
@@ -0,0 +1,187 @@
+//! Inspect and manage background tasks.
+use anyhow::Result;
+use clap::Parser;
+use clap::Subcommand;
+
+use crate::context::ContextStore;
+use crate::formatter::ops::DeadLetterListOp;
+use crate::formatter::ops::QueueStatsListOp;
+use crate::formatter::ops::TaskExecutionListOp;
+use crate::Globals;
+
+/// Inspect and manage background tasks.
+#[derive(Debug, Parser)]
+pub struct TasksCli {
+    /// Select the `replictl tasks` command to run.
+    #[command(subcommand)]
+    pub command: TasksCmd,
+}
+
+/// Possible background tasks commands to run.
+#[derive(Debug, Subcommand)]
+pub enum TasksCmd {
+    /// Inspect and manage tasks that exhausted all delivery attempts.
+    Dlq(DlqCli),
+
+    /// List the most recent task execution attempts, most recent first.
+    History(HistoryOpts),
+
+    /// Summarise the state of tasks on each background task queue.
+    #[command(alias = "stats")]
+    Queues(QueuesOpts),
+}
+
+/// Inspect and manage tasks that exhausted all delivery attempts.
+#[derive(Debug, Parser)]
+pub struct DlqCli {
+    /// Select the `replictl tasks dlq` command to run.
+    #[command(subcommand)]
+    pub command: DlqCmd,
+}
+
+/// Possible dead-letter queue commands to run.
+#[derive(Debug, Subcommand)]
+pub enum DlqCmd {
+    /// List tasks in the dead-letter queue.
+    List,
+
+    /// Remove tasks from the dead-letter queue without executing them.
+    Purge(DlqPurgeOpts),
+
+    /// Move a task from the dead-letter queue back onto its queue for execution.
+    Requeue(DlqTaskOpts),
+
+    /// Lookup and display information about a task in the dead-letter queue.
+    #[command(alias = "get")]
+    Show(DlqTaskOpts),
+}
+
+/// Remove tasks from the dead-letter queue without executing them.
+#[derive(Debug, Parser)]
+pub struct DlqPurgeOpts {
+    /// Remove all tasks from the dead-letter queue.
+    #[arg(long, default_value_t = false, conflicts_with = "task_id")]
+    pub all: bool,
+
+    /// ID of the task to remove.
+    #[arg(required_unless_present = "all")]
+    pub task_id: Option<String>,
+}
+
+/// Select a task in the dead-letter queue.
+#[derive(Debug, Parser)]
+pub struct DlqTaskOpts {
+    /// ID of the task to select.
+    pub task_id: String,
+}
+
+/// List the most recent task execution attempts.
+#[derive(Debug, Parser)]
+pub struct HistoryOpts {
+    /// Maximum number of execution attempts to list.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Only list execution attempts of tasks received from this queue.
+    #[arg(long)]
+    pub queue: Option<String>,
+
+    /// Only list execution attempts of the task with this ID.
+    #[arg(long)]
+    pub task_id: Option<String>,
+}
+
+/// Summarise the state of tasks on each background task queue.
+#[derive(Debug, Parser)]
+pub struct QueuesOpts {
+    /// Number of the oldest tasks on each queue to preview.
+    #[arg(long, default_value_t = 0)]
+    pub previews: usize,
+}
+
+/// Execute the selected `replictl tasks` command.
+pub async fn run(globals: &Globals, cmd: &TasksCli) -> Result<i32> {
+    match &cmd.command {
+        TasksCmd::Dlq(cmd) => match &cmd.command {
+            DlqCmd::List => dlq_list(globals).await,
+            DlqCmd::Purge(opts) => dlq_purge(globals, opts).await,
+            DlqCmd::Requeue(opts) => dlq_requeue(globals, opts).await,
+            DlqCmd::Show(opts) => dlq_show(globals, opts).await,
+        },
+        TasksCmd::History(opts) => history(globals, opts).await,
+        TasksCmd::Queues(opts) => queues(globals, opts).await,
+    }
+}
+
+async fn dlq_list(globals: &Globals) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let tasks = client.tasks().dlq_list().await?;
+    let mut formatter = globals.formatter.format(globals, DeadLetterListOp);
+    for task in tasks {
+        formatter.append(&task)?;
+    }
+
+    formatter.finish()?;
+    Ok(0)
+}
+
+async fn dlq_purge(globals: &Globals, opts: &DlqPurgeOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let task_id = opts.task_id.as_deref();
+    let count = client.tasks().dlq_purge(task_id).await?;
+    println!("Removed {count} task(s) from the dead-letter queue");
+    Ok(0)
+}
+
+async fn dlq_requeue(globals: &Globals, opts: &DlqTaskOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let task_id = &opts.task_id;
+    client.tasks().dlq_requeue(task_id).await?;
+    println!("Task '{task_id}' moved back onto its queue for execution");
+    Ok(0)
+}
+
+async fn dlq_show(globals: &Globals, opts: &DlqTaskOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let task = client.tasks().dlq_get(&opts.task_id).await?;
+    globals.formatter.format(globals, task)?;
+    Ok(0)
+}
+
+async fn history(globals: &Globals, opts: &HistoryOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let queue = opts.queue.as_deref();
+    let task_id = opts.task_id.as_deref();
+    let executions = client.tasks().history(queue, task_id, opts.limit).await?;
+    let mut formatter = globals.formatter.format(globals, TaskExecutionListOp);
+    for execution in executions {
+        formatter.append(&execution)?;
+    }
+
+    formatter.finish()?;
+    Ok(0)
+}
+
+async fn queues(globals: &Globals, opts: &QueuesOpts) -> Result<i32> {
+    let context = ContextStore::active(globals).await?;
+    let client = crate::client(&context)?;
+
+    let stats = client.tasks().stats(opts.previews).await?;
+    let mut formatter = globals.formatter.format(globals, QueueStatsListOp);
+    for queue in stats {
+        formatter.append(&queue)?;
+    }
+
+    formatter.finish()?;
+    Ok(0)
+}
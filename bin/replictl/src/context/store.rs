@@ -114,13 +114,25 @@ impl ContextStore {
         let mut buffer = Vec::new();
         serde_yaml::to_writer(&mut buffer, self)
             .with_context(|| format!("unable to YAML encode contexts store to {}", &path))?;
-        let mut file = OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
+
+        // The store holds credentials so it must only be accessible to the current user.
+        let mut options = OpenOptions::new();
+        options.create(true).truncate(true).write(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options
             .open(&path)
             .await
             .with_context(|| format!("unable to open contexts store at {}", &path))?;
+        #[cfg(unix)]
+        {
+            // Restrict stores created before their permissions were enforced.
+            use std::os::unix::fs::PermissionsExt;
+            let permissions = std::fs::Permissions::from_mode(0o600);
+            file.set_permissions(permissions).await.with_context(|| {
+                format!("unable to restrict access to contexts store {}", &path)
+            })?;
+        }
         file.write_all(&buffer)
             .await
             .with_context(|| format!("unable to write contexts store to {}", &path))?;
@@ -8,11 +8,15 @@ use crate::errors::InvalidScope;
 /// Information needed to access the Replicante API.
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
 pub struct Connection {
-    /// Bundle of CA certificated to validate the API server with.
+    /// API token to authenticate requests with.
+    #[serde(default)]
+    pub auth_token: Option<String>,
+
+    /// Path to a bundle of CA certificated to validate the API server with.
     #[serde(default)]
     pub ca_bundle: Option<String>,
 
-    /// Client key and certificate PEM bundle for mutual TLS.
+    /// Path to a client key and certificate PEM bundle for mutual TLS.
     #[serde(default)]
     pub client_key: Option<String>,
 
@@ -44,3 +44,21 @@ Try adding --node or set one with 'replictl context change'"
     )]
     NodeNotSelected,
 }
+
+/// Error reading a TLS PEM file configured in a context.
+#[derive(thiserror::Error, Debug)]
+#[error("Unable to read TLS file at '{path}'")]
+pub struct TlsFileError {
+    path: String,
+}
+
+impl TlsFileError {
+    /// Create a TLS file error for the given path.
+    pub fn for_path<S>(path: S) -> TlsFileError
+    where
+        S: Into<String>,
+    {
+        let path = path.into();
+        TlsFileError { path }
+    }
+}
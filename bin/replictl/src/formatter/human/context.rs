@@ -17,6 +17,7 @@ impl ContextList {
             "ACTIVE",
             "NAME",
             "URL",
+            "AUTH TOKEN",
             "CA BUNDLE",
             "CLIENT KEY",
             "NAMESPACE",
@@ -29,12 +30,14 @@ impl ContextList {
 
 impl crate::formatter::ContextList for ContextList {
     fn append(&mut self, name: &str, context: &Context, active: bool) -> Result<()> {
+        let auth_token = crate::utils::set_or_not(&context.connection.auth_token);
         let ca_bundle = crate::utils::set_or_not(&context.connection.ca_bundle);
         let client_key = crate::utils::set_or_not(&context.connection.client_key);
         self.table.add_row(vec![
             if active { "*" } else { "" },
             name,
             &context.connection.url,
+            auth_token,
             ca_bundle,
             client_key,
             &value_or_not_set(&context.scope.namespace),
@@ -52,10 +55,12 @@ impl crate::formatter::ContextList for ContextList {
 
 /// Format the [`Context`] for users to inspect.
 pub fn show(context: &Context) {
+    let auth_token = crate::utils::set_or_not(&context.connection.auth_token);
     let ca_bundle = crate::utils::set_or_not(&context.connection.ca_bundle);
     let client_key = crate::utils::set_or_not(&context.connection.client_key);
     println!("Control Plane Connection:");
     println!("  URL: {}", context.connection.url);
+    println!("  Auth Token: {}", auth_token);
     println!("  CA Bundle: {}", ca_bundle);
     println!("  Client Key: {}", client_key);
     println!();
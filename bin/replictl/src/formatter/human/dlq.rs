@@ -0,0 +1,75 @@
+//! Format dead-letter queue related objects.
+use anyhow::Result;
+
+use replicore_tasks_models::DeadLetterTask;
+
+/// Format a list of [`DeadLetterTask`] objects into a table.
+#[derive(Default)]
+pub struct DeadLetterList {
+    table: comfy_table::Table,
+}
+
+impl DeadLetterList {
+    pub fn new() -> DeadLetterList {
+        let mut table = comfy_table::Table::new();
+        table.set_header(vec![
+            "TASK ID",
+            "QUEUE",
+            "ATTEMPTS",
+            "EXHAUSTED",
+            "LAST ERROR",
+        ]);
+        DeadLetterList { table }
+    }
+}
+
+impl crate::formatter::DeadLetterList for DeadLetterList {
+    fn append(&mut self, task: &DeadLetterTask) -> Result<()> {
+        let error = task
+            .last_error
+            .as_deref()
+            .and_then(|error| error.lines().next())
+            .unwrap_or_default();
+        self.table.add_row(vec![
+            task.id.clone(),
+            task.queue.clone(),
+            task.attempts.to_string(),
+            task.exhausted_time.format(super::TIME_FORMAT)?,
+            error.to_string(),
+        ]);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        println!("{}", self.table);
+        Ok(())
+    }
+}
+
+/// Format a [`DeadLetterTask`] for users to inspect.
+pub fn show(task: &DeadLetterTask) -> Result<()> {
+    println!("Task ID: {}", task.id);
+    println!("Queue: {}", task.queue);
+    println!("Attempts: {}", task.attempts);
+
+    let submitted = match task.submitted_time {
+        None => String::from("<Unknown>"),
+        Some(ts) => ts.format(super::TIME_FORMAT)?,
+    };
+    println!("Submitted at: {}", submitted);
+    let last_attempt = match task.last_attempt_time {
+        None => String::from("<Unknown>"),
+        Some(ts) => ts.format(super::TIME_FORMAT)?,
+    };
+    println!("Last attempt at: {}", last_attempt);
+    println!(
+        "Exhausted at: {}",
+        task.exhausted_time.format(super::TIME_FORMAT)?
+    );
+    println!();
+
+    let error = task.last_error.as_deref().unwrap_or("<No errors saved>");
+    println!("Last error: {}", error);
+    println!("Payload: {}", serde_json::to_string_pretty(&task.payload)?);
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//! Format events related objects.
+use anyhow::Result;
+
+use replicore_events_models::EventEntry;
+
+/// Format [`EventEntry`] objects into lines as they are appended.
+///
+/// Events are not collected into a table so new events can be shown as soon as they are emitted.
+pub struct EventLines;
+
+impl crate::formatter::EventList for EventLines {
+    fn append(&mut self, entry: &EventEntry) -> Result<()> {
+        let event = &entry.event;
+        let time = event.time.format(super::TIME_FORMAT)?;
+        let mut line = format!("{} [{}] {}", time, entry.stream, event.code);
+        for attribute in ["ns_id", "cluster_id"] {
+            if let Some(value) = event.payload.get(attribute).and_then(|v| v.as_str()) {
+                line.push_str(&format!(" {}={}", attribute, value));
+            }
+        }
+        println!("{}", line);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
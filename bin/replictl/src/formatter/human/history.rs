@@ -0,0 +1,47 @@
+//! Format background task execution history related objects.
+use anyhow::Result;
+
+use replicore_tasks_models::TaskExecution;
+
+/// Format a list of [`TaskExecution`] objects into a table.
+#[derive(Default)]
+pub struct TaskExecutionList {
+    table: comfy_table::Table,
+}
+
+impl TaskExecutionList {
+    pub fn new() -> TaskExecutionList {
+        let mut table = comfy_table::Table::new();
+        table.set_header(vec![
+            "TASK ID", "QUEUE", "ATTEMPT", "OUTCOME", "STARTED", "FINISHED", "ERROR", "TRACE",
+        ]);
+        TaskExecutionList { table }
+    }
+}
+
+impl crate::formatter::TaskExecutionList for TaskExecutionList {
+    fn append(&mut self, execution: &TaskExecution) -> Result<()> {
+        let error = execution
+            .error
+            .first()
+            .and_then(|error| error.lines().next())
+            .unwrap_or_default();
+        let trace = execution.trace_id.as_deref().unwrap_or("-");
+        self.table.add_row(vec![
+            execution.task_id.clone(),
+            execution.queue.clone(),
+            execution.attempt.to_string(),
+            execution.outcome.to_string(),
+            execution.started_time.format(super::TIME_FORMAT)?,
+            execution.finished_time.format(super::TIME_FORMAT)?,
+            error.to_string(),
+            trace.to_string(),
+        ]);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        println!("{}", self.table);
+        Ok(())
+    }
+}
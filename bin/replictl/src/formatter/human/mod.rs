@@ -8,10 +8,14 @@ use crate::globals::Globals;
 
 mod cluster_spec;
 mod context;
+mod dlq;
+mod events;
+mod history;
 mod naction;
 mod namespace;
 mod oaction;
 mod platform;
+mod queues;
 
 const TIME_FORMAT: &[BorrowedFormatItem<'static>] = time::macros::format_description!(
     "[year]-[month]-[day] [hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"
@@ -39,6 +43,12 @@ impl FormatterStrategy for HumanFormatter {
                 Responses::Success
             }
             Ops::ContextList => Responses::contexts(self::context::ContextList::new()),
+            Ops::DeadLetterTask(task) => match self::dlq::show(&task) {
+                Err(error) => Responses::Err(error),
+                Ok(()) => Responses::Success,
+            },
+            Ops::DeadLetterList => Responses::dead_letters(self::dlq::DeadLetterList::new()),
+            Ops::EventList => Responses::events(self::events::EventLines),
             Ops::NAction(action) => match self::naction::show(&action) {
                 Err(error) => Responses::Err(error),
                 Ok(()) => Responses::Success,
@@ -65,6 +75,10 @@ impl FormatterStrategy for HumanFormatter {
                 Responses::Success
             }
             Ops::PlatformList => Responses::platforms(self::platform::PlatformList::new()),
+            Ops::QueueStatsList => Responses::queue_stats(self::queues::QueueStatsList::new()),
+            Ops::TaskExecutionList => {
+                Responses::task_executions(self::history::TaskExecutionList::new())
+            }
         }
     }
 }
@@ -0,0 +1,77 @@
+//! Format background task queue related objects.
+use anyhow::Result;
+
+use replicore_tasks_models::QueueStats;
+
+/// Format a list of [`QueueStats`] objects into tables.
+///
+/// Queue summaries are presented in one table and task previews, if any, in a second table.
+#[derive(Default)]
+pub struct QueueStatsList {
+    previews: comfy_table::Table,
+    previews_count: usize,
+    table: comfy_table::Table,
+}
+
+impl QueueStatsList {
+    pub fn new() -> QueueStatsList {
+        let mut table = comfy_table::Table::new();
+        table.set_header(vec![
+            "QUEUE",
+            "PENDING",
+            "IN-FLIGHT",
+            "RETRYING",
+            "EXHAUSTED",
+            "OLDEST",
+        ]);
+        let mut previews = comfy_table::Table::new();
+        previews.set_header(vec!["TASK ID", "QUEUE", "ATTEMPTS", "SUBMITTED", "PAYLOAD"]);
+        QueueStatsList {
+            previews,
+            previews_count: 0,
+            table,
+        }
+    }
+}
+
+impl crate::formatter::QueueStatsList for QueueStatsList {
+    fn append(&mut self, stats: &QueueStats) -> Result<()> {
+        let oldest = match stats.oldest_task_age_secs {
+            None => String::from("-"),
+            Some(age) => format!("{}s", age),
+        };
+        self.table.add_row(vec![
+            stats.queue.clone(),
+            stats.pending.to_string(),
+            stats.in_flight.to_string(),
+            stats.retrying.to_string(),
+            stats.exhausted.to_string(),
+            oldest,
+        ]);
+
+        for preview in &stats.previews {
+            let submitted = match preview.submitted_time {
+                None => String::from("<Unknown>"),
+                Some(ts) => ts.format(super::TIME_FORMAT)?,
+            };
+            self.previews.add_row(vec![
+                preview.id.clone(),
+                stats.queue.clone(),
+                preview.attempts.to_string(),
+                submitted,
+                preview.payload.clone(),
+            ]);
+            self.previews_count += 1;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        println!("{}", self.table);
+        if self.previews_count > 0 {
+            println!();
+            println!("{}", self.previews);
+        }
+        Ok(())
+    }
+}
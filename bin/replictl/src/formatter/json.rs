@@ -7,12 +7,20 @@ use replisdk::core::models::api::NamespaceEntry;
 use replisdk::core::models::api::OActionEntry;
 use replisdk::core::models::api::PlatformEntry;
 
+use replicore_events_models::EventEntry;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
+use replicore_tasks_models::TaskExecution;
+
 use super::ops::Ops;
 use super::ops::Responses;
 use super::FormatterStrategy;
 use crate::context::Context;
 use crate::globals::Globals;
 
+/// Placeholder for secrets that must not be printed.
+const REDACTED: &str = "<redacted>";
+
 /// Format output to JSON.
 pub struct JsonFormatter;
 
@@ -22,8 +30,11 @@ impl FormatterStrategy for JsonFormatter {
             Ops::ClusterDiscovery(cluster_disc) => print_json(cluster_disc),
             Ops::ClusterSpec(cluster_spec) => print_json(cluster_spec),
             Ops::ClusterSpecList => Responses::cluster_specs(ClusterSpecList::default()),
-            Ops::Context(context) => print_json(context),
+            Ops::Context(context) => print_json(redact(context)),
             Ops::ContextList => Responses::contexts(ContextList::default()),
+            Ops::DeadLetterTask(task) => print_json(task),
+            Ops::DeadLetterList => Responses::dead_letters(DeadLetterList::default()),
+            Ops::EventList => Responses::events(EventLines),
             Ops::NAction(action) => print_json(action),
             Ops::NActionList => Responses::nactions(NActionList::default()),
             Ops::Namespace(namespace) => print_json(namespace),
@@ -33,6 +44,8 @@ impl FormatterStrategy for JsonFormatter {
             Ops::OrchestrateReport(report) => print_json(report),
             Ops::Platform(platform) => print_json(platform),
             Ops::PlatformList => Responses::platforms(PlatformList::default()),
+            Ops::QueueStatsList => Responses::queue_stats(QueueStatsList::default()),
+            Ops::TaskExecutionList => Responses::task_executions(TaskExecutionList::default()),
         }
     }
 }
@@ -76,6 +89,11 @@ list_serialiser!(
     crate::formatter::ClusterSpecList,
     ClusterSpecEntry
 );
+list_serialiser!(
+    DeadLetterList,
+    crate::formatter::DeadLetterList,
+    DeadLetterTask
+);
 list_serialiser!(NActionList, crate::formatter::NActionList, NActionEntry);
 list_serialiser!(
     NamespaceList,
@@ -84,6 +102,27 @@ list_serialiser!(
 );
 list_serialiser!(OActionList, crate::formatter::OActionList, OActionEntry);
 list_serialiser!(PlatformList, crate::formatter::PlatformList, PlatformEntry);
+list_serialiser!(QueueStatsList, crate::formatter::QueueStatsList, QueueStats);
+list_serialiser!(
+    TaskExecutionList,
+    crate::formatter::TaskExecutionList,
+    TaskExecution
+);
+
+/// Print events as JSON lines, one event per line, as they are appended.
+struct EventLines;
+
+impl crate::formatter::EventList for EventLines {
+    fn append(&mut self, entry: &EventEntry) -> Result<()> {
+        let value = serde_json::to_string(entry)?;
+        println!("{}", value);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
 
 /// Pretty print an list of context information.
 #[derive(Default)]
@@ -94,7 +133,7 @@ impl crate::formatter::ContextList for ContextList {
         self.0.push(ContextInfo {
             name: name.to_string(),
             active,
-            context: entry.clone(),
+            context: redact(entry.clone()),
         });
         Ok(())
     }
@@ -113,3 +152,11 @@ struct ContextInfo {
     active: bool,
     context: Context,
 }
+
+/// Replace secrets in a context with a placeholder so they are not printed.
+fn redact(mut context: Context) -> Context {
+    if context.connection.auth_token.is_some() {
+        context.connection.auth_token = Some(REDACTED.to_string());
+    }
+    context
+}
@@ -15,6 +15,11 @@ use replisdk::core::models::api::NamespaceEntry;
 use replisdk::core::models::api::OActionEntry;
 use replisdk::core::models::api::PlatformEntry;
 
+use replicore_events_models::EventEntry;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
+use replicore_tasks_models::TaskExecution;
+
 mod human;
 mod json;
 
@@ -41,6 +46,15 @@ pub trait ContextList {
     fn finish(&mut self) -> Result<()>;
 }
 
+/// Present a list of [`DeadLetterTask`]s to the user.
+pub trait DeadLetterList {
+    /// Append a new dead-letter task into the list being formatted.
+    fn append(&mut self, task: &DeadLetterTask) -> Result<()>;
+
+    /// Handle the now complete list of dead-letter tasks and emit it to standard output.
+    fn finish(&mut self) -> Result<()>;
+}
+
 /// List of available output formats.
 #[derive(Copy, Clone, Debug, Default, ValueEnum)]
 pub enum FormatId {
@@ -91,6 +105,17 @@ pub trait FormatterStrategy {
     fn format(&self, globals: &Globals, op: self::ops::Ops) -> self::ops::Responses;
 }
 
+/// Present a list of [`EventEntry`]s to the user.
+///
+/// Events are emitted as they are appended so lists can be followed as new events are emitted.
+pub trait EventList {
+    /// Append a new event into the list being formatted.
+    fn append(&mut self, entry: &EventEntry) -> Result<()>;
+
+    /// Handle the now complete list of events.
+    fn finish(&mut self) -> Result<()>;
+}
+
 /// Present a list of [`NActionEntry`]s to the user.
 pub trait NActionList {
     /// Append a new node action entry into the list being formatted.
@@ -127,6 +152,24 @@ pub trait PlatformList {
     fn finish(&mut self) -> Result<()>;
 }
 
+/// Present a list of [`QueueStats`] to the user.
+pub trait QueueStatsList {
+    /// Append a new queue summary into the list being formatted.
+    fn append(&mut self, stats: &QueueStats) -> Result<()>;
+
+    /// Handle the now complete list of queue summaries and emit it to standard output.
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// Present a list of [`TaskExecution`]s to the user.
+pub trait TaskExecutionList {
+    /// Append a new task execution record into the list being formatted.
+    fn append(&mut self, execution: &TaskExecution) -> Result<()>;
+
+    /// Handle the now complete list of task execution records and emit it to standard output.
+    fn finish(&mut self) -> Result<()>;
+}
+
 /// Instantiate a formatter based on CLI configuration.
 pub fn select(format: &FormatOpts) -> Formatter {
     let strategy: Box<dyn FormatterStrategy> = match format.format {
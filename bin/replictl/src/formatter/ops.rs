@@ -10,6 +10,10 @@ use replisdk::core::models::oaction::OAction;
 use replisdk::core::models::platform::Platform;
 
 use replicore_cluster_models::OrchestrateReport;
+use replicore_events_models::EventEntry;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
+use replicore_tasks_models::TaskExecution;
 
 use self::sealed::SealFormatOp;
 use crate::context::Context;
@@ -37,9 +41,18 @@ pub enum Ops {
     /// Request a formatter to emit [`Context`] lists.
     ContextList,
 
+    /// Format information about a [`DeadLetterTask`].
+    DeadLetterTask(DeadLetterTask),
+
+    /// Request a strategy to format [`DeadLetterTask`] lists.
+    DeadLetterList,
+
     /// Format information about a [`NAction`].
     NAction(NAction),
 
+    /// Request a strategy to format [`EventEntry`] lists.
+    EventList,
+
     /// Request a strategy to format `NActionEntry` lists.
     NActionList,
 
@@ -63,6 +76,12 @@ pub enum Ops {
 
     /// Format information about a [`Platform`].
     Platform(Platform),
+
+    /// Request a strategy to format [`QueueStats`] lists.
+    QueueStatsList,
+
+    /// Request a strategy to format [`TaskExecution`] lists.
+    TaskExecutionList,
 }
 
 /// All known responses from format operations.
@@ -73,9 +92,15 @@ pub enum Responses {
     /// Return a object to format a list of [`Context`]s.
     ContextList(Box<dyn super::ContextList>),
 
+    /// Return a object to format a list of [`DeadLetterTask`]s.
+    DeadLetterList(Box<dyn super::DeadLetterList>),
+
     /// Return an error back to the caller.
     Err(Error),
 
+    /// Return a object to format a list of [`EventEntry`]s.
+    EventList(Box<dyn super::EventList>),
+
     /// Return a object to format a list of `NActionEntry`s.
     NActionList(Box<dyn super::NActionList>),
 
@@ -88,8 +113,14 @@ pub enum Responses {
     /// Return a object to format a list of `PlatformEntry`s.
     PlatformList(Box<dyn super::PlatformList>),
 
+    /// Return a object to format a list of [`QueueStats`].
+    QueueStatsList(Box<dyn super::QueueStatsList>),
+
     /// The formatting operation was successful.
     Success,
+
+    /// Return a object to format a list of [`TaskExecution`]s.
+    TaskExecutionList(Box<dyn super::TaskExecutionList>),
 }
 
 impl Responses {
@@ -111,6 +142,24 @@ impl Responses {
         Self::ContextList(value)
     }
 
+    /// Wrap a [`DeadLetterList`](super::DeadLetterList) returned by the formatter.
+    pub fn dead_letters<L>(value: L) -> Self
+    where
+        L: super::DeadLetterList + 'static,
+    {
+        let value = Box::new(value);
+        Self::DeadLetterList(value)
+    }
+
+    /// Wrap an [`EventList`](super::EventList) returned by the formatter.
+    pub fn events<L>(value: L) -> Self
+    where
+        L: super::EventList + 'static,
+    {
+        let value = Box::new(value);
+        Self::EventList(value)
+    }
+
     /// Wrap an [`NActionList`](super::NActionList) returned by the formatter.
     pub fn nactions<L>(value: L) -> Self
     where
@@ -146,6 +195,24 @@ impl Responses {
         let value = Box::new(value);
         Self::PlatformList(value)
     }
+
+    /// Wrap a [`QueueStatsList`](super::QueueStatsList) returned by the formatter.
+    pub fn queue_stats<L>(value: L) -> Self
+    where
+        L: super::QueueStatsList + 'static,
+    {
+        let value = Box::new(value);
+        Self::QueueStatsList(value)
+    }
+
+    /// Wrap a [`TaskExecutionList`](super::TaskExecutionList) returned by the formatter.
+    pub fn task_executions<L>(value: L) -> Self
+    where
+        L: super::TaskExecutionList + 'static,
+    {
+        let value = Box::new(value);
+        Self::TaskExecutionList(value)
+    }
 }
 
 // --- Operation & return types -- //
@@ -155,6 +222,12 @@ pub struct ClusterSpecListOp;
 /// Request a formatter to emit [`Context`] lists.
 pub struct ContextListOp;
 
+/// Request a formatter to emit [`DeadLetterTask`] lists.
+pub struct DeadLetterListOp;
+
+/// Request a formatter to emit [`EventEntry`] lists.
+pub struct EventListOp;
+
 /// Request a formatter to emit `NActionEntry` lists.
 pub struct NActionListOp;
 
@@ -167,6 +240,12 @@ pub struct OActionListOp;
 /// Request a formatter to emit `PlatformEntry` lists.
 pub struct PlatformListOp;
 
+/// Request a formatter to emit [`QueueStats`] lists.
+pub struct QueueStatsListOp;
+
+/// Request a formatter to emit [`TaskExecution`] lists.
+pub struct TaskExecutionListOp;
+
 /// Private module to seal implementation details.
 mod sealed {
     /// Super-trait to seal the [`FormatOp`](super::FormatOp) trait.
@@ -224,6 +303,36 @@ impl FormatOp for ContextListOp {
     type Response = Box<dyn super::ContextList>;
 }
 
+impl SealFormatOp for DeadLetterTask {}
+impl From<DeadLetterTask> for Ops {
+    fn from(value: DeadLetterTask) -> Self {
+        Self::DeadLetterTask(value)
+    }
+}
+impl FormatOp for DeadLetterTask {
+    type Response = Result<()>;
+}
+
+impl SealFormatOp for DeadLetterListOp {}
+impl From<DeadLetterListOp> for Ops {
+    fn from(_: DeadLetterListOp) -> Self {
+        Self::DeadLetterList
+    }
+}
+impl FormatOp for DeadLetterListOp {
+    type Response = Box<dyn super::DeadLetterList>;
+}
+
+impl SealFormatOp for EventListOp {}
+impl From<EventListOp> for Ops {
+    fn from(_: EventListOp) -> Self {
+        Self::EventList
+    }
+}
+impl FormatOp for EventListOp {
+    type Response = Box<dyn super::EventList>;
+}
+
 impl SealFormatOp for NAction {}
 impl From<NAction> for Ops {
     fn from(value: NAction) -> Self {
@@ -314,6 +423,26 @@ impl FormatOp for PlatformListOp {
     type Response = Box<dyn super::PlatformList>;
 }
 
+impl SealFormatOp for QueueStatsListOp {}
+impl From<QueueStatsListOp> for Ops {
+    fn from(_: QueueStatsListOp) -> Self {
+        Self::QueueStatsList
+    }
+}
+impl FormatOp for QueueStatsListOp {
+    type Response = Box<dyn super::QueueStatsList>;
+}
+
+impl SealFormatOp for TaskExecutionListOp {}
+impl From<TaskExecutionListOp> for Ops {
+    fn from(_: TaskExecutionListOp) -> Self {
+        Self::TaskExecutionList
+    }
+}
+impl FormatOp for TaskExecutionListOp {
+    type Response = Box<dyn super::TaskExecutionList>;
+}
+
 // --- Implement Responses conversions on return types for transparent operations --- //
 impl From<Responses> for Box<dyn super::ClusterSpecList> {
     fn from(value: Responses) -> Self {
@@ -331,6 +460,22 @@ impl From<Responses> for Box<dyn super::ContextList> {
         }
     }
 }
+impl From<Responses> for Box<dyn super::DeadLetterList> {
+    fn from(value: Responses) -> Self {
+        match value {
+            Responses::DeadLetterList(value) => value,
+            _ => panic!("unexpected response type for formatter operation"),
+        }
+    }
+}
+impl From<Responses> for Box<dyn super::EventList> {
+    fn from(value: Responses) -> Self {
+        match value {
+            Responses::EventList(value) => value,
+            _ => panic!("unexpected response type for formatter operation"),
+        }
+    }
+}
 impl From<Responses> for Box<dyn super::NActionList> {
     fn from(value: Responses) -> Self {
         match value {
@@ -363,6 +508,22 @@ impl From<Responses> for Box<dyn super::PlatformList> {
         }
     }
 }
+impl From<Responses> for Box<dyn super::QueueStatsList> {
+    fn from(value: Responses) -> Self {
+        match value {
+            Responses::QueueStatsList(value) => value,
+            _ => panic!("unexpected response type for formatter operation"),
+        }
+    }
+}
+impl From<Responses> for Box<dyn super::TaskExecutionList> {
+    fn from(value: Responses) -> Self {
+        match value {
+            Responses::TaskExecutionList(value) => value,
+            _ => panic!("unexpected response type for formatter operation"),
+        }
+    }
+}
 impl From<Responses> for Result<()> {
     fn from(value: Responses) -> Self {
         match value {
@@ -1,4 +1,5 @@
 //! Inspect, manage and interact with RepliCore servers from a Command Line Interface.
+use anyhow::Context as AnyContext;
 use anyhow::Result;
 use clap::Parser;
 
@@ -13,16 +14,33 @@ mod utils;
 pub mod errors;
 
 use self::cmd::Cli;
+use self::errors::TlsFileError;
 use self::globals::Globals;
 
 /// Initialise an API client to interact with the control plane.
 fn client(context: &self::context::Context) -> Result<Client> {
-    let options = replicore_client::ClientOptions::url(&context.connection.url).client();
-    // TODO: implement TLS config options when supported.
+    let mut options = replicore_client::ClientOptions::url(&context.connection.url);
+    if let Some(token) = &context.connection.auth_token {
+        options.auth_token(token);
+    }
+    if let Some(path) = &context.connection.ca_bundle {
+        options.ca_bundle(read_tls_file(path)?);
+    }
+    if let Some(path) = &context.connection.client_key {
+        options.client_key(read_tls_file(path)?);
+    }
+    let options = options.client();
     let client = Client::with(options)?;
     Ok(client)
 }
 
+/// Read a PEM file configured in the context for TLS connections.
+fn read_tls_file(path: &str) -> Result<String> {
+    let path = self::utils::resolve_home(path)?;
+    let pem = std::fs::read_to_string(&path).with_context(|| TlsFileError::for_path(&path))?;
+    Ok(pem)
+}
+
 /// Initialise the replictl process and invoke a command implementation.
 pub async fn run() -> Result<i32> {
     let cli = Cli::parse();
@@ -32,10 +50,12 @@ pub async fn run() -> Result<i32> {
         cmd::Command::Apply(cmd) => cmd::apply::run(&globals, cmd).await,
         cmd::Command::Cluster(cmd) => cmd::cluster_spec::run(&globals, cmd).await,
         cmd::Command::Context(cmd) => cmd::context::run(&globals, cmd).await,
+        cmd::Command::Events(cmd) => cmd::events::run(&globals, cmd).await,
         cmd::Command::NAction(cmd) => cmd::naction::run(&globals, cmd).await,
         cmd::Command::Namespace(cmd) => cmd::namespace::run(&globals, cmd).await,
         cmd::Command::OAction(cmd) => cmd::oaction::run(&globals, cmd).await,
         cmd::Command::Platform(cmd) => cmd::platform::run(&globals, cmd).await,
+        cmd::Command::Tasks(cmd) => cmd::tasks::run(&globals, cmd).await,
     }
 }
 
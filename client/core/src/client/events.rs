@@ -0,0 +1,278 @@
+//! Implement the events methods for API clients.
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::Response;
+
+use repliclient_utils::EmptyResponse;
+use replicore_events_models::EventEntry;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
+
+use super::Client;
+
+/// Time after which watch requests are re-established, resuming from the last cursor.
+const WATCH_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The server reported an error while watching events.
+#[derive(Debug, thiserror::Error)]
+#[error("the server reported an error while watching events: {0}")]
+pub struct WatchError(pub String);
+
+/// Access events operations.
+pub struct EventsClient<'a> {
+    inner: &'a Client,
+}
+
+impl Client {
+    /// Events operations.
+    pub fn events(&self) -> EventsClient {
+        EventsClient { inner: self }
+    }
+}
+
+impl<'a> EventsClient<'a> {
+    /// Query a page of events matching the filters, oldest first.
+    ///
+    /// The returned page includes a cursor to query events emitted after the page.
+    /// Queries with the `latest` option return the most recent events instead.
+    pub async fn query(&'a self, query: &EventsQuery) -> Result<EventsPage> {
+        let url = format!("{}api/v0/events", self.inner.base);
+        let response = self.inner.client.get(url).query(query).send().await?;
+        let response = repliclient_utils::inspect::<EventsPage>(response).await?;
+        let response = response.ok_or(EmptyResponse)?;
+        Ok(response)
+    }
+
+    /// Watch change events matching the filters as they are emitted.
+    ///
+    /// Without a cursor or `since` filter the server picks the starting point
+    /// so only events emitted after the call are returned.
+    /// Interrupted watches are resumed from the last cursor sent by the server
+    /// so events may be returned more then once.
+    pub async fn watch(&self, query: &EventsQuery) -> Result<EventsWatch<'a>> {
+        let query = query.clone();
+        let mut watch = EventsWatch {
+            buffer: Vec::new(),
+            client: self.inner,
+            pending: VecDeque::new(),
+            query,
+            response: None,
+        };
+        watch.connect().await?;
+        Ok(watch)
+    }
+}
+
+/// Stream of change events returned by [`EventsClient::watch`].
+pub struct EventsWatch<'a> {
+    buffer: Vec<u8>,
+    client: &'a Client,
+    pending: VecDeque<EventEntry>,
+    query: EventsQuery,
+    response: Option<Response>,
+}
+
+impl<'a> EventsWatch<'a> {
+    /// Cursor to resume watching from after the events returned so far.
+    ///
+    /// The cursor is only updated at the end of each batch of events sent by the server
+    /// and when the server reports the starting point of a new watch.
+    pub fn cursor(&self) -> Option<&str> {
+        self.query.cursor.as_deref()
+    }
+
+    /// Wait for the next event emitted by the control plane.
+    pub async fn next(&mut self) -> Result<EventEntry> {
+        loop {
+            if let Some(entry) = self.pending.pop_front() {
+                return Ok(entry);
+            }
+
+            let response = match self.response.as_mut() {
+                Some(response) => response,
+                None => {
+                    self.connect().await?;
+                    continue;
+                }
+            };
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    self.buffer.extend_from_slice(&chunk);
+                    self.decode()?;
+                }
+                // Reconnect when the server closes the stream or the request times out.
+                Ok(None) => self.reset(),
+                Err(error) if error.is_timeout() => self.reset(),
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Start a new watch request from the last known cursor.
+    async fn connect(&mut self) -> Result<()> {
+        let url = format!("{}api/v0/events/watch", self.client.base);
+        let response = self
+            .client
+            .client
+            .get(url)
+            .query(&self.query)
+            .timeout(WATCH_TIMEOUT)
+            .send()
+            .await?;
+
+        // Decode errors the same way as other API requests.
+        if !response.status().is_success() {
+            repliclient_utils::inspect::<serde_json::Value>(response).await?;
+            anyhow::bail!(EmptyResponse);
+        }
+        self.response = Some(response);
+        Ok(())
+    }
+
+    /// Decode complete Server-Sent Events from the buffer.
+    ///
+    /// Messages are only decoded once fully received, so characters split across chunks
+    /// are never decoded partially.
+    fn decode(&mut self) -> Result<()> {
+        while let Some(end) = message_end(&self.buffer) {
+            let message: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let message = std::str::from_utf8(&message)?;
+            let mut data = String::new();
+            let mut event = "message";
+            let mut cursor = None;
+            for line in message.lines() {
+                if let Some(value) = line.strip_prefix("data:") {
+                    if !data.is_empty() {
+                        data.push('\n');
+                    }
+                    data.push_str(value.trim_start());
+                } else if let Some(value) = line.strip_prefix("event:") {
+                    event = value.trim();
+                } else if let Some(value) = line.strip_prefix("id:") {
+                    cursor = Some(value.trim().to_string());
+                }
+            }
+
+            if event == "error" {
+                anyhow::bail!(WatchError(data));
+            }
+            if !data.is_empty() {
+                let entry: EventEntry = serde_json::from_str(&data)?;
+                self.pending.push_back(entry);
+            }
+            if cursor.is_some() {
+                self.query.cursor = cursor;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the current watch request and any partially received event.
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.response = None;
+    }
+}
+
+/// Find the end of the first complete Server-Sent Event in the buffer, if any.
+fn message_end(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(2).position(|window| window == b"\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use repliclient_utils::ClientOptions;
+    use replicore_events_models::Event;
+    use replicore_events_models::EventEntry;
+    use replicore_events_models::EventStream;
+    use replicore_events_models::EventsQuery;
+
+    use super::EventsWatch;
+    use super::WatchError;
+    use crate::Client;
+
+    fn client() -> Client {
+        Client::with(ClientOptions::url("http://localhost:16016/")).unwrap()
+    }
+
+    fn message(name: &str, id: Option<&str>) -> String {
+        let event = Event::new_with_payload("TEST_EVENT", serde_json::json!({ "name": name }));
+        let entry = EventEntry {
+            event: event.unwrap(),
+            stream: EventStream::Change,
+        };
+        let data = serde_json::to_string(&entry).unwrap();
+        let id = id.map(|id| format!("id: {}\n", id)).unwrap_or_default();
+        format!("{}event: change\ndata: {}\n\n", id, data)
+    }
+
+    fn watch(client: &Client) -> EventsWatch {
+        EventsWatch {
+            buffer: Vec::new(),
+            client,
+            pending: VecDeque::new(),
+            query: EventsQuery::default(),
+            response: None,
+        }
+    }
+
+    #[test]
+    fn decode_chunks_split_in_characters() {
+        let client = client();
+        let mut watch = watch(&client);
+        let message = message("caf\u{e9} \u{2713}", None);
+        let split = message.find('\u{2713}').unwrap() + 1;
+        let (first, second) = message.as_bytes().split_at(split);
+
+        watch.buffer.extend_from_slice(first);
+        watch.decode().unwrap();
+        assert!(watch.pending.is_empty());
+
+        watch.buffer.extend_from_slice(second);
+        watch.decode().unwrap();
+        let entry = watch.pending.pop_front().unwrap();
+        assert_eq!(entry.event.payload["name"], "caf\u{e9} \u{2713}");
+        assert!(watch.buffer.is_empty());
+    }
+
+    #[test]
+    fn decode_error_event() {
+        let client = client();
+        let mut watch = watch(&client);
+        watch
+            .buffer
+            .extend_from_slice(b"event: error\ndata: store unavailable\n\n");
+        let error = watch.decode().unwrap_err();
+        let error = error.downcast::<WatchError>().unwrap();
+        assert_eq!(error.0, "store unavailable");
+    }
+
+    #[test]
+    fn decode_cursor_only_message() {
+        let client = client();
+        let mut watch = watch(&client);
+        watch.buffer.extend_from_slice(b"id: 0-42\n\n");
+        watch.decode().unwrap();
+        assert!(watch.pending.is_empty());
+        assert_eq!(watch.cursor(), Some("0-42"));
+    }
+
+    #[test]
+    fn decode_updates_cursor() {
+        let client = client();
+        let mut watch = watch(&client);
+        let chunk = format!(
+            ": keep-alive\n\n{}{}",
+            message("first", None),
+            message("last", Some("cursor-1")),
+        );
+        watch.buffer.extend_from_slice(chunk.as_bytes());
+        watch.decode().unwrap();
+        assert_eq!(watch.pending.len(), 2);
+        assert_eq!(watch.cursor(), Some("cursor-1"));
+    }
+}
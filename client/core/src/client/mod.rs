@@ -6,11 +6,16 @@ use repliclient_utils::ClientOptions;
 
 mod apply;
 mod cluster_spec;
+mod events;
 mod list;
 mod naction;
 mod namespace;
 mod oaction;
 mod platform;
+mod tasks;
+
+pub use self::events::EventsWatch;
+pub use self::events::WatchError;
 
 /// String to set as the user agent in HTTP request.
 static CLIENT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
@@ -0,0 +1,118 @@
+//! Implement the background tasks methods for API clients.
+use anyhow::Context;
+use anyhow::Result;
+
+use repliclient_utils::EmptyResponse;
+use repliclient_utils::ResourceIdentifier;
+use replicore_tasks_models::DeadLetterList;
+use replicore_tasks_models::DeadLetterPurged;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
+use replicore_tasks_models::QueueStatsList;
+use replicore_tasks_models::TaskExecution;
+use replicore_tasks_models::TaskExecutionList;
+
+use super::Client;
+
+/// Access background tasks operations.
+pub struct TasksClient<'a> {
+    inner: &'a Client,
+}
+
+impl Client {
+    /// Background tasks operations.
+    pub fn tasks(&self) -> TasksClient {
+        TasksClient { inner: self }
+    }
+}
+
+impl<'a> TasksClient<'a> {
+    /// Fetch a task in the dead-letter queue by ID.
+    pub async fn dlq_get(&'a self, id: &str) -> Result<DeadLetterTask> {
+        let url = format!("{}api/v0/tasks/dlq/{}", self.inner.base, id);
+        let response = self.inner.client.get(url).send().await?;
+        let response = repliclient_utils::inspect::<DeadLetterTask>(response)
+            .await
+            .with_context(|| ResourceIdentifier::reference("task", id))?;
+        let response = response.ok_or(EmptyResponse)?;
+        Ok(response)
+    }
+
+    /// List tasks in the dead-letter queue.
+    pub async fn dlq_list(&'a self) -> Result<Vec<DeadLetterTask>> {
+        let url = format!("{}api/v0/tasks/dlq", self.inner.base);
+        let response = self.inner.client.get(url).send().await?;
+        let response = repliclient_utils::inspect::<DeadLetterList>(response).await?;
+        let response = response.ok_or(EmptyResponse)?;
+        Ok(response.items)
+    }
+
+    /// Remove a task from the dead-letter queue, or all tasks if no ID is given.
+    ///
+    /// Returns the number of tasks removed from the dead-letter queue.
+    pub async fn dlq_purge(&'a self, id: Option<&str>) -> Result<u64> {
+        let url = match id {
+            None => format!("{}api/v0/tasks/dlq", self.inner.base),
+            Some(id) => format!("{}api/v0/tasks/dlq/{}", self.inner.base, id),
+        };
+        let response = self.inner.client.delete(url).send().await?;
+        let response = repliclient_utils::inspect::<DeadLetterPurged>(response)
+            .await
+            .with_context(|| {
+                let id = id.unwrap_or_default();
+                ResourceIdentifier::reference("task", id)
+            })?;
+        let response = response.ok_or(EmptyResponse)?;
+        Ok(response.count)
+    }
+
+    /// Move a task from the dead-letter queue back onto its queue for execution.
+    pub async fn dlq_requeue(&'a self, id: &str) -> Result<()> {
+        let url = format!("{}api/v0/tasks/dlq/{}/requeue", self.inner.base, id);
+        let response = self.inner.client.post(url).send().await?;
+        repliclient_utils::inspect::<serde_json::Value>(response)
+            .await
+            .with_context(|| ResourceIdentifier::reference("task", id))?;
+        Ok(())
+    }
+
+    /// List the most recent background task execution attempts, most recent first.
+    ///
+    /// Executions can be filtered to a specific queue and/or a specific task.
+    pub async fn history(
+        &'a self,
+        queue: Option<&str>,
+        task_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<TaskExecution>> {
+        let url = format!("{}api/v0/tasks/history", self.inner.base);
+        let mut query = vec![("limit", limit.to_string())];
+        if let Some(queue) = queue {
+            query.push(("queue", queue.to_string()));
+        }
+        if let Some(task_id) = task_id {
+            query.push(("task_id", task_id.to_string()));
+        }
+        let response = self.inner.client.get(url).query(&query).send().await?;
+        let response = repliclient_utils::inspect::<TaskExecutionList>(response).await?;
+        let response = response.ok_or(EmptyResponse)?;
+        Ok(response.items)
+    }
+
+    /// Summarise the state of tasks on each background task queue.
+    ///
+    /// Up to `previews` of the oldest tasks on each queue are included in the summaries.
+    pub async fn stats(&'a self, previews: usize) -> Result<Vec<QueueStats>> {
+        let url = format!("{}api/v0/tasks", self.inner.base);
+        let response = self
+            .inner
+            .client
+            .get(url)
+            .query(&[("previews", previews)])
+            .send()
+            .await?;
+        let response = repliclient_utils::inspect::<QueueStatsList>(response).await?;
+        let response = response.ok_or(EmptyResponse)?;
+        Ok(response.items)
+    }
+}
@@ -4,3 +4,5 @@ pub use repliclient_utils::ClientOptions;
 mod client;
 
 pub use self::client::Client;
+pub use self::client::EventsWatch;
+pub use self::client::WatchError;
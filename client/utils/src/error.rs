@@ -15,6 +15,26 @@ pub struct ClientError;
 #[error("the server returned an empty API response")]
 pub struct EmptyResponse;
 
+/// The client key PEM bundle must contain certificates and exactly one PKCS#8 private key.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidClientKey {
+    /// The bundle has no private key or more than one.
+    #[error("the client key PEM bundle must contain exactly one private key, found {0}")]
+    KeyCount(usize),
+
+    /// The bundle has no certificates.
+    #[error("the client key PEM bundle must contain at least one certificate")]
+    NoCertificates,
+
+    /// The private key is not an unencrypted PKCS#8 key.
+    #[error(
+        "the client key PEM bundle private key must be an unencrypted PKCS#8 key \
+        (BEGIN PRIVATE KEY) but found a '{0}' block; \
+        convert it with `openssl pkcs8 -topk8 -nocrypt`"
+    )]
+    UnsupportedKey(String),
+}
+
 /// Invalid API response received.
 #[derive(Debug, thiserror::Error)]
 #[error("invalid API response received: {response}")]
@@ -7,6 +7,7 @@ pub use self::config::ClientOptionsBuilder;
 pub use self::error::inspect;
 pub use self::error::ClientError;
 pub use self::error::EmptyResponse;
+pub use self::error::InvalidClientKey;
 pub use self::error::InvalidResponse;
 pub use self::error::ResourceIdentifier;
 pub use self::error::ResourceNotFound;
@@ -0,0 +1,184 @@
+//! Publish events from the transactional outbox to the events streaming platform.
+//!
+//! When the outbox is enabled, events describing a change are added to the outbox
+//! in the same store transaction as the change itself.
+//! The [`Relay`] then publishes outbox events, oldest first, and removes them once published.
+//!
+//! ## Delivery guarantees
+//!
+//! Events are removed from the outbox only after they are published, so they are published
+//! at-least-once: a failure after an event is published but before it is removed from the
+//! outbox results in the event being published again.
+//!
+//! Published events carry the outbox sequence number in their metadata, under the
+//! [`METADATA_OUTBOX_SEQ`] key, so consumers can detect duplicate deliveries.
+//!
+//! ## Failed events
+//!
+//! Events that fail to publish are retried, in order, on later relay runs.
+//! Once an event fails [`OutboxConf::max_attempts`] times it is dropped from the outbox
+//! so later events are not blocked forever: dropped events are logged in full and counted
+//! by the `replicore_outbox_dead_letter_count` metric so they can be recovered manually.
+//!
+//! Attempts are tracked by the relay process, so they reset when a new relay starts.
+//! The `replicore_outbox_oldest_event_age_seconds` metric reports how long the oldest event
+//! has been waiting in the outbox, so a stuck relay can be detected regardless.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::TryStreamExt;
+use time::OffsetDateTime;
+
+use replicore_conf::OutboxConf;
+use replicore_context::Context;
+use replicore_events_models::METADATA_OUTBOX_SEQ;
+use replicore_injector::Injector;
+use replicore_store::query::ListOutboxEvents;
+use replicore_store::query::OutboxEvent;
+
+mod telemetry;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::telemetry::register_metrics;
+
+/// Periodically publish events from the outbox, oldest first.
+pub struct Relay {
+    attempts: Mutex<HashMap<u64, u32>>,
+    conf: OutboxConf,
+    injector: Injector,
+}
+
+impl Relay {
+    /// Initialise a relay to publish outbox events with the given [`Injector`].
+    pub fn new(injector: Injector) -> Relay {
+        let conf = injector.conf.outbox.clone();
+        Relay {
+            attempts: Mutex::new(HashMap::new()),
+            conf,
+            injector,
+        }
+    }
+
+    /// Publish outbox events until the exit future resolves.
+    ///
+    /// Full batches are followed by the next one immediately, otherwise the relay waits
+    /// for the poll interval before checking the outbox again.
+    /// Errors during individual runs are reported but do not stop the relay.
+    pub async fn run(&self, context: &Context, exit: impl Future<Output = ()>) -> Result<()> {
+        // Pin the exit future so we can select it across loops.
+        tokio::pin!(exit);
+        let poll_interval = Duration::from_secs(self.conf.poll_interval_sec);
+
+        loop {
+            let delay = match self.relay(context).await {
+                Ok(published) if published >= self.conf.batch_size => Duration::ZERO,
+                Ok(_) => poll_interval,
+                Err(error) => {
+                    crate::telemetry::RELAY_ERR.inc();
+                    slog::error!(
+                        context.logger, "Failed to publish events from the outbox";
+                        replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                    );
+                    poll_interval
+                }
+            };
+
+            tokio::select! {
+                _ = &mut exit => break,
+                _ = tokio::time::sleep(delay) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Publish a batch of the oldest events in the outbox and return how many were published.
+    ///
+    /// Publishing stops at the first error so events are never published out of order,
+    /// unless the failed event exhausted its attempts and was dropped from the outbox.
+    pub(crate) async fn relay(&self, context: &Context) -> Result<usize> {
+        let query = ListOutboxEvents::oldest(self.conf.batch_size);
+        let events: Vec<OutboxEvent> = self
+            .injector
+            .store
+            .query(context, query)
+            .await?
+            .try_collect()
+            .await?;
+
+        // Report how long the oldest event has been waiting, so a stuck relay is visible.
+        let age = events
+            .first()
+            .map(|outbox| (OffsetDateTime::now_utc() - outbox.entry.event.time).as_seconds_f64())
+            .unwrap_or(0.0);
+        crate::telemetry::OLDEST_EVENT_AGE.set(age.max(0.0));
+
+        let mut published = 0;
+        for outbox in events {
+            let stream = outbox.entry.stream.to_string();
+            let mut entry = outbox.entry.clone();
+            entry
+                .event
+                .metadata
+                .insert(METADATA_OUTBOX_SEQ.to_string(), outbox.seq.to_string());
+            if let Err(error) = self.injector.events.publish(context, entry).await {
+                if !self.exhausted(outbox.seq) {
+                    return Err(error);
+                }
+                self.drop_event(context, &outbox, error).await?;
+                continue;
+            }
+            self.attempts_mut().remove(&outbox.seq);
+            self.injector.store.delete(context, &outbox).await?;
+            crate::telemetry::PUBLISHED_COUNT
+                .with_label_values(&[&stream])
+                .inc();
+            published += 1;
+        }
+        Ok(published)
+    }
+
+    /// Access the record of failed publish attempts for outbox events.
+    fn attempts_mut(&self) -> std::sync::MutexGuard<HashMap<u64, u32>> {
+        self.attempts
+            .lock()
+            .expect("outbox relay attempts lock poisoned")
+    }
+
+    /// Remove an event that exhausted its publish attempts from the outbox.
+    async fn drop_event(
+        &self,
+        context: &Context,
+        outbox: &OutboxEvent,
+        error: anyhow::Error,
+    ) -> Result<()> {
+        let entry = serde_json::to_string(&outbox.entry)?;
+        slog::error!(
+            context.logger, "Dropping event from the outbox after repeated publish failures";
+            "attempts" => self.conf.max_attempts,
+            "code" => &outbox.entry.event.code,
+            "entry" => entry,
+            "seq" => outbox.seq,
+            "stream" => %outbox.entry.stream,
+            replisdk::utils::error::slog::ErrorAttributes::from(&error),
+        );
+        self.injector.store.delete(context, outbox).await?;
+        self.attempts_mut().remove(&outbox.seq);
+        crate::telemetry::DEAD_LETTER_COUNT
+            .with_label_values(&[&outbox.entry.stream.to_string()])
+            .inc();
+        Ok(())
+    }
+
+    /// Record a failed attempt to publish an event and check if it should be dropped.
+    fn exhausted(&self, seq: u64) -> bool {
+        let mut attempts = self.attempts_mut();
+        let count = attempts.entry(seq).or_default();
+        *count += 1;
+        self.conf.max_attempts > 0 && *count >= self.conf.max_attempts
+    }
+}
@@ -0,0 +1,74 @@
+//! Telemetry related to the events outbox relay.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::Counter;
+use prometheus::CounterVec;
+use prometheus::Gauge;
+use prometheus::Opts;
+
+/// Total number of events dropped from the outbox after repeatedly failing to publish.
+pub static DEAD_LETTER_COUNT: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_outbox_dead_letter_count",
+            "Total number of events dropped from the outbox after repeatedly failing to publish",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise DEAD_LETTER_COUNT counter")
+});
+
+/// Age, in seconds, of the oldest event in the outbox when the relay last checked it.
+pub static OLDEST_EVENT_AGE: Lazy<Gauge> = Lazy::new(|| {
+    Gauge::new(
+        "replicore_outbox_oldest_event_age_seconds",
+        "Age, in seconds, of the oldest event in the outbox when the relay last checked it",
+    )
+    .expect("failed to initialise OLDEST_EVENT_AGE gauge")
+});
+
+/// Total number of events published from the outbox.
+pub static PUBLISHED_COUNT: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_outbox_published_count",
+            "Total number of events published from the outbox",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise PUBLISHED_COUNT counter")
+});
+
+/// Number of outbox relay runs that resulted in error.
+pub static RELAY_ERR: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_outbox_relay_error",
+        "Number of outbox relay runs that resulted in error",
+    )
+    .expect("failed to initialise RELAY_ERR counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the outbox relay metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 4] = [
+        Box::new(DEAD_LETTER_COUNT.clone()),
+        Box::new(OLDEST_EVENT_AGE.clone()),
+        Box::new(PUBLISHED_COUNT.clone()),
+        Box::new(RELAY_ERR.clone()),
+    ];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
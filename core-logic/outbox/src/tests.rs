@@ -0,0 +1,119 @@
+use anyhow::Result;
+use futures::TryStreamExt;
+
+use replisdk::core::models::namespace::Namespace;
+use replisdk::core::models::namespace::NamespaceStatus;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events::emit::EventsBackend;
+use replicore_events::emit::EventsFixtureBackend;
+use replicore_events::Event;
+use replicore_events_models::METADATA_OUTBOX_SEQ;
+use replicore_injector::Injector;
+use replicore_store::persist::PersistWithEvents;
+use replicore_store::query::ListOutboxEvents;
+
+use super::Relay;
+
+/// Events backend rejecting events with the `poison` code.
+struct RejectPoison(EventsFixtureBackend);
+
+#[async_trait::async_trait]
+impl EventsBackend for RejectPoison {
+    async fn audit(&self, context: &Context, event: Event) -> Result<()> {
+        self.0.audit(context, event).await
+    }
+
+    async fn change(&self, context: &Context, event: Event) -> Result<()> {
+        if event.code == "poison" {
+            anyhow::bail!("test backend rejects poison events");
+        }
+        self.0.change(context, event).await
+    }
+}
+
+fn mock_namespace(id: &str) -> Namespace {
+    Namespace {
+        id: id.into(),
+        tls: Default::default(),
+        settings: Default::default(),
+        status: NamespaceStatus::Active,
+    }
+}
+
+#[tokio::test]
+async fn relay_publishes_in_order() {
+    let mut fixture = Injector::fixture();
+    fixture.injector.conf.outbox.batch_size = 2;
+    let context = fixture.injector.context.clone();
+    for id in ["one", "two", "three"] {
+        let ns = mock_namespace(id);
+        let event = Event::new_with_payload(id, &ns).unwrap();
+        let op = PersistWithEvents::new(ns).change(event);
+        fixture.injector.store.persist(&context, op).await.unwrap();
+    }
+    let relay = Relay::new(fixture.injector.clone());
+
+    // Publish events in batches, oldest first.
+    assert_eq!(relay.relay(&context).await.unwrap(), 2);
+    assert_eq!(relay.relay(&context).await.unwrap(), 1);
+    assert_eq!(relay.relay(&context).await.unwrap(), 0);
+    let mut seqs = Vec::new();
+    for code in ["one", "two", "three"] {
+        let event = fixture.events.pop_change().await.unwrap();
+        assert_eq!(event.code, code);
+        let seq: u64 = event.metadata[METADATA_OUTBOX_SEQ].parse().unwrap();
+        seqs.push(seq);
+    }
+    assert!(seqs.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Published events are removed from the outbox.
+    let pending: Vec<_> = fixture
+        .injector
+        .store
+        .query(&context, ListOutboxEvents::oldest(10))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(pending.is_empty());
+}
+
+#[tokio::test]
+async fn relay_drops_events_after_max_attempts() {
+    let mut fixture = Injector::fixture();
+    fixture.injector.conf.outbox.max_attempts = 3;
+    fixture.injector.events = Events::from(RejectPoison(fixture.events.backend()));
+    let context = fixture.injector.context.clone();
+    for id in ["one", "poison", "three"] {
+        let ns = mock_namespace(id);
+        let event = Event::new_with_payload(id, &ns).unwrap();
+        let op = PersistWithEvents::new(ns).change(event);
+        fixture.injector.store.persist(&context, op).await.unwrap();
+    }
+    let relay = Relay::new(fixture.injector.clone());
+
+    // The failing event blocks later events until it exhausts its attempts.
+    relay.relay(&context).await.unwrap_err();
+    relay.relay(&context).await.unwrap_err();
+    assert_eq!(relay.relay(&context).await.unwrap(), 1);
+    assert_eq!(relay.relay(&context).await.unwrap(), 0);
+    for code in ["one", "three"] {
+        let event = fixture.events.pop_change().await.unwrap();
+        assert_eq!(event.code, code);
+    }
+
+    // The dropped event is no longer in the outbox.
+    let pending: Vec<_> = fixture
+        .injector
+        .store
+        .query(&context, ListOutboxEvents::oldest(10))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(pending.is_empty());
+}
@@ -0,0 +1,271 @@
+//! Periodic scheduling of platform discovery and cluster orchestration tasks.
+//!
+//! The [`Scheduler`] walks active namespaces, platforms and cluster specs from the
+//! persistent store and submits [`DiscoverPlatform`] and [`OrchestrateCluster`] tasks
+//! for them once they are due.
+//!
+//! ## Intervals and jitter
+//!
+//! Each platform and cluster is scheduled independently of others, with intervals that
+//! can be configured globally or overridden for individual namespaces.
+//!
+//! To avoid submitting tasks for all platforms and clusters at the same time, a random delay
+//! up to the configured jitter is added to every interval (including the first one).
+//!
+//! Intervals are checked for expiration periodically so the precision of the schedule
+//! is limited by the configured check interval.
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::TryStreamExt;
+use rand::Rng;
+use tokio::time::Instant;
+
+use replisdk::core::models::api::ClusterSpecEntry;
+use replisdk::core::models::api::NamespaceEntry;
+use replisdk::core::models::api::PlatformEntry;
+use replisdk::core::models::namespace::NamespaceStatus;
+
+use replicore_conf::SchedulerConf;
+use replicore_context::Context;
+use replicore_injector::Injector;
+use replicore_store::ids::NamespaceID;
+use replicore_store::query::ListClusterSpecs;
+use replicore_store::query::ListNamespaces;
+use replicore_store::query::ListPlatforms;
+use replicore_task_discovery::DiscoverPlatform;
+use replicore_task_orchestrate::OrchestrateCluster;
+
+mod telemetry;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::telemetry::register_metrics;
+
+/// Identify platforms and clusters the [`Scheduler`] submits tasks for.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum ScheduleKey {
+    /// Periodic discovery of a platform.
+    Discovery { ns_id: String, name: String },
+
+    /// Periodic orchestration of a cluster.
+    Orchestrate { ns_id: String, cluster_id: String },
+}
+
+impl ScheduleKey {
+    /// Label for the kind of task scheduled, used for telemetry.
+    fn kind(&self) -> &'static str {
+        match self {
+            ScheduleKey::Discovery { .. } => "discovery",
+            ScheduleKey::Orchestrate { .. } => "orchestrate",
+        }
+    }
+}
+
+/// Periodically submit platform discovery and cluster orchestration tasks.
+pub struct Scheduler {
+    conf: SchedulerConf,
+    due: HashMap<ScheduleKey, Instant>,
+    injector: Injector,
+}
+
+impl Scheduler {
+    /// Initialise a scheduler to submit tasks with the given [`Injector`].
+    pub fn new(injector: Injector) -> Scheduler {
+        let conf = injector.conf.scheduler.clone();
+        Scheduler {
+            conf,
+            due: HashMap::new(),
+            injector,
+        }
+    }
+
+    /// Scan the store and submit due tasks periodically, until the exit future resolves.
+    ///
+    /// Errors during individual scans are reported but do not stop the scheduler.
+    pub async fn run(&mut self, context: &Context, exit: impl Future<Output = ()>) -> Result<()> {
+        // Pin the exit future so we can select it across loops.
+        tokio::pin!(exit);
+        let check_interval = Duration::from_secs(self.conf.check_interval_sec);
+
+        loop {
+            let timer = crate::telemetry::SCAN_DURATION.start_timer();
+            let result = self.scan(context, Instant::now()).await;
+            timer.observe_duration();
+            if let Err(error) = result {
+                crate::telemetry::SCAN_ERR.inc();
+                slog::error!(
+                    context.logger, "Failed to scan for platforms and clusters to schedule";
+                    replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                );
+            }
+
+            tokio::select! {
+                _ = &mut exit => break,
+                _ = tokio::time::sleep(check_interval) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Walk active namespaces and submit tasks for platforms and clusters that are due.
+    pub(crate) async fn scan(&mut self, context: &Context, now: Instant) -> Result<()> {
+        let mut seen = HashSet::new();
+        let namespaces: Vec<NamespaceEntry> = self
+            .injector
+            .store
+            .query(context, ListNamespaces)
+            .await?
+            .try_collect()
+            .await?;
+
+        for namespace in namespaces {
+            if !matches!(namespace.status, NamespaceStatus::Active) {
+                continue;
+            }
+            if !self.conf.namespace_enabled(&namespace.id) {
+                continue;
+            }
+            self.scan_platforms(context, now, &namespace.id, &mut seen)
+                .await?;
+            self.scan_clusters(context, now, &namespace.id, &mut seen)
+                .await?;
+        }
+
+        // Forget platforms and clusters that are no longer active or have been deleted.
+        self.due.retain(|key, _| seen.contains(key));
+        Ok(())
+    }
+
+    /// Submit orchestration tasks for active clusters in the namespace, if they are due.
+    async fn scan_clusters(
+        &mut self,
+        context: &Context,
+        now: Instant,
+        ns_id: &str,
+        seen: &mut HashSet<ScheduleKey>,
+    ) -> Result<()> {
+        let query = ListClusterSpecs(NamespaceID {
+            id: ns_id.to_string(),
+        });
+        let clusters: Vec<ClusterSpecEntry> = self
+            .injector
+            .store
+            .query(context, query)
+            .await?
+            .try_collect()
+            .await?;
+
+        let interval = Duration::from_secs(self.conf.orchestrate_interval(ns_id));
+        for cluster in clusters {
+            if !cluster.active {
+                continue;
+            }
+            let key = ScheduleKey::Orchestrate {
+                ns_id: cluster.ns_id,
+                cluster_id: cluster.cluster_id,
+            };
+            seen.insert(key.clone());
+            self.submit_if_due(context, now, key, interval).await;
+        }
+        Ok(())
+    }
+
+    /// Submit discovery tasks for active platforms in the namespace, if they are due.
+    async fn scan_platforms(
+        &mut self,
+        context: &Context,
+        now: Instant,
+        ns_id: &str,
+        seen: &mut HashSet<ScheduleKey>,
+    ) -> Result<()> {
+        let query = ListPlatforms(NamespaceID {
+            id: ns_id.to_string(),
+        });
+        let platforms: Vec<PlatformEntry> = self
+            .injector
+            .store
+            .query(context, query)
+            .await?
+            .try_collect()
+            .await?;
+
+        let interval = Duration::from_secs(self.conf.discovery_interval(ns_id));
+        for platform in platforms {
+            if !platform.active {
+                continue;
+            }
+            let key = ScheduleKey::Discovery {
+                ns_id: ns_id.to_string(),
+                name: platform.name,
+            };
+            seen.insert(key.clone());
+            self.submit_if_due(context, now, key, interval).await;
+        }
+        Ok(())
+    }
+
+    /// Submit the task identified by the key if it is due and track when it is next due.
+    ///
+    /// Platforms and clusters seen for the first time are due after a random jitter.
+    /// Failed submissions are retried on the next scan.
+    async fn submit_if_due(
+        &mut self,
+        context: &Context,
+        now: Instant,
+        key: ScheduleKey,
+        interval: Duration,
+    ) {
+        let jitter = self.jitter();
+        let due = *self.due.entry(key.clone()).or_insert(now + jitter);
+        if due > now {
+            return;
+        }
+
+        let kind = key.kind();
+        crate::telemetry::SCHEDULE_COUNT
+            .with_label_values(&[kind])
+            .inc();
+        let result = match &key {
+            ScheduleKey::Discovery { ns_id, name } => {
+                let task = DiscoverPlatform::new(ns_id, name);
+                self.injector.tasks.submit(context, task).await
+            }
+            ScheduleKey::Orchestrate { ns_id, cluster_id } => {
+                let task = OrchestrateCluster::new(ns_id, cluster_id);
+                self.injector.tasks.submit(context, task).await
+            }
+        };
+
+        match result {
+            Err(error) => {
+                crate::telemetry::SCHEDULE_ERR
+                    .with_label_values(&[kind])
+                    .inc();
+                slog::warn!(
+                    context.logger, "Failed to submit scheduled task";
+                    "kind" => kind,
+                    replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                );
+            }
+            Ok(()) => {
+                let next = now + interval + self.jitter();
+                self.due.insert(key, next);
+            }
+        }
+    }
+
+    /// Random delay, up to the configured maximum, to spread tasks over time.
+    fn jitter(&self) -> Duration {
+        if self.conf.jitter_sec == 0 {
+            return Duration::ZERO;
+        }
+        let max = self.conf.jitter_sec * 1000;
+        let millis = rand::thread_rng().gen_range(0..=max);
+        Duration::from_millis(millis)
+    }
+}
@@ -0,0 +1,78 @@
+//! Telemetry related to periodic tasks scheduling.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::Counter;
+use prometheus::CounterVec;
+use prometheus::Histogram;
+use prometheus::HistogramOpts;
+use prometheus::Opts;
+
+/// Duration (in seconds) of scans for platforms and clusters due for scheduling.
+pub static SCAN_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    Histogram::with_opts(
+        HistogramOpts::new(
+            "replicore_scheduler_scan_duration",
+            "Duration (in seconds) of scans for platforms and clusters due for scheduling",
+        )
+        .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+    )
+    .expect("failed to initialise SCAN_DURATION histogram")
+});
+
+/// Number of scans for platforms and clusters that resulted in error.
+pub static SCAN_ERR: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_scheduler_scan_error",
+        "Number of scans for platforms and clusters that resulted in error",
+    )
+    .expect("failed to initialise SCAN_ERR counter")
+});
+
+/// Total number of tasks submitted by the scheduler.
+pub static SCHEDULE_COUNT: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_scheduler_schedule_count",
+            "Total number of tasks submitted by the scheduler",
+        ),
+        &["kind"],
+    )
+    .expect("failed to initialise SCHEDULE_COUNT counter")
+});
+
+/// Number of scheduler task submissions that resulted in error.
+pub static SCHEDULE_ERR: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_scheduler_schedule_error",
+            "Number of scheduler task submissions that resulted in error",
+        ),
+        &["kind"],
+    )
+    .expect("failed to initialise SCHEDULE_ERR counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the scheduler metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 4] = [
+        Box::new(SCAN_DURATION.clone()),
+        Box::new(SCAN_ERR.clone()),
+        Box::new(SCHEDULE_COUNT.clone()),
+        Box::new(SCHEDULE_ERR.clone()),
+    ];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use replisdk::core::models::cluster::ClusterSpec;
+use replisdk::core::models::namespace::Namespace;
+use replisdk::core::models::namespace::NamespaceStatus;
+use replisdk::core::models::platform::Platform;
+use replisdk::core::models::platform::PlatformTransport;
+use replisdk::core::models::platform::PlatformTransportUrl;
+
+use replicore_conf::SchedulerNamespaceConf;
+use replicore_injector::Injector;
+use replicore_task_discovery::DiscoverPlatform;
+use replicore_task_orchestrate::OrchestrateCluster;
+use replicore_tasks::submit::Tasks;
+use replicore_tasks::submit::TasksFixture;
+
+use super::Scheduler;
+
+const NO_TASK_TIMEOUT: Duration = Duration::from_millis(50);
+const TASK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Initialise a scheduler with a populated store and introspectable tasks.
+async fn fixture() -> (Scheduler, TasksFixture) {
+    let tasks = Tasks::fixture();
+    let mut fixture = Injector::fixture();
+    fixture.injector.tasks = tasks.backend().into();
+    fixture.injector.conf.scheduler.jitter_sec = 0;
+    fixture.injector.conf.scheduler.orchestrate_interval_sec = 10;
+    fixture.injector.conf.scheduler.discovery_interval_sec = 30;
+    fixed_db(&fixture.injector).await;
+    let scheduler = Scheduler::new(fixture.injector);
+    (scheduler, tasks)
+}
+
+/// Populate injected DB for tests.
+async fn fixed_db(injector: &Injector) {
+    let context = &injector.context;
+    for (id, status) in [
+        ("default", NamespaceStatus::Active),
+        ("test", NamespaceStatus::Inactive),
+    ] {
+        let ns = Namespace {
+            id: id.into(),
+            tls: Default::default(),
+            settings: Default::default(),
+            status,
+        };
+        injector.store.persist(context, ns).await.unwrap();
+
+        for (name, active) in [("active", true), ("inactive", false)] {
+            let platform = Platform {
+                ns_id: id.into(),
+                name: name.into(),
+                active,
+                discovery: Default::default(),
+                transport: PlatformTransport::Url(PlatformTransportUrl {
+                    base_url: "http://localhost:1234".into(),
+                    tls_ca_bundle: None,
+                    tls_insecure_skip_verify: false,
+                }),
+            };
+            injector.store.persist(context, platform).await.unwrap();
+
+            let mut spec = ClusterSpec::synthetic(id, name);
+            spec.active = active;
+            injector.store.persist(context, spec).await.unwrap();
+        }
+    }
+}
+
+/// Collect all tasks submitted so far, decoded by type.
+async fn submitted(tasks: &mut TasksFixture) -> (Vec<DiscoverPlatform>, Vec<OrchestrateCluster>) {
+    let mut discover = Vec::new();
+    let mut orchestrate = Vec::new();
+    while let Ok(task) = tasks.pop_task_timeout(NO_TASK_TIMEOUT).await {
+        match task.queue.queue.as_str() {
+            "platform_discovery" => discover.push(serde_json::from_value(task.payload).unwrap()),
+            "cluster_orchestrate" => {
+                orchestrate.push(serde_json::from_value(task.payload).unwrap())
+            }
+            queue => panic!("unexpected task on queue {queue}"),
+        }
+    }
+    (discover, orchestrate)
+}
+
+#[tokio::test]
+async fn schedule_active_resources_only() {
+    let (mut scheduler, mut tasks) = fixture().await;
+    let context = scheduler.injector.context.clone();
+    scheduler.scan(&context, Instant::now()).await.unwrap();
+
+    let (discover, orchestrate) = submitted(&mut tasks).await;
+    assert_eq!(discover, vec![DiscoverPlatform::new("default", "active")]);
+    assert_eq!(
+        orchestrate,
+        vec![OrchestrateCluster::new("default", "active")]
+    );
+}
+
+#[tokio::test]
+async fn schedule_again_once_interval_expires() {
+    let (mut scheduler, mut tasks) = fixture().await;
+    let context = scheduler.injector.context.clone();
+    let now = Instant::now();
+    scheduler.scan(&context, now).await.unwrap();
+    tasks.pop_task_timeout(TASK_TIMEOUT).await.unwrap();
+    tasks.pop_task_timeout(TASK_TIMEOUT).await.unwrap();
+
+    // Nothing is due before the interval expires.
+    scheduler
+        .scan(&context, now + Duration::from_secs(5))
+        .await
+        .unwrap();
+    let (discover, orchestrate) = submitted(&mut tasks).await;
+    assert!(discover.is_empty());
+    assert!(orchestrate.is_empty());
+
+    // Only the orchestrate interval has expired.
+    scheduler
+        .scan(&context, now + Duration::from_secs(10))
+        .await
+        .unwrap();
+    let (discover, orchestrate) = submitted(&mut tasks).await;
+    assert!(discover.is_empty());
+    assert_eq!(
+        orchestrate,
+        vec![OrchestrateCluster::new("default", "active")]
+    );
+}
+
+#[tokio::test]
+async fn namespace_overrides() {
+    let (mut scheduler, mut tasks) = fixture().await;
+    let context = scheduler.injector.context.clone();
+    scheduler.conf.namespaces.insert(
+        "default".into(),
+        SchedulerNamespaceConf {
+            discovery_interval_sec: Some(5),
+            ..Default::default()
+        },
+    );
+    let now = Instant::now();
+    scheduler.scan(&context, now).await.unwrap();
+    submitted(&mut tasks).await;
+
+    scheduler
+        .scan(&context, now + Duration::from_secs(5))
+        .await
+        .unwrap();
+    let (discover, orchestrate) = submitted(&mut tasks).await;
+    assert_eq!(discover, vec![DiscoverPlatform::new("default", "active")]);
+    assert!(orchestrate.is_empty());
+}
+
+#[tokio::test]
+async fn namespace_disabled() {
+    let (mut scheduler, mut tasks) = fixture().await;
+    let context = scheduler.injector.context.clone();
+    scheduler.conf.namespaces.insert(
+        "default".into(),
+        SchedulerNamespaceConf {
+            enabled: Some(false),
+            ..Default::default()
+        },
+    );
+    scheduler.scan(&context, Instant::now()).await.unwrap();
+
+    let (discover, orchestrate) = submitted(&mut tasks).await;
+    assert!(discover.is_empty());
+    assert!(orchestrate.is_empty());
+}
@@ -1,4 +1,6 @@
 //! Callback invoked when platform discovery task need to be executed.
+use std::time::Duration;
+
 use anyhow::Result;
 
 use replicore_context::Context;
@@ -8,6 +10,9 @@ use replicore_tasks::execute::TaskCallback;
 
 use crate::DiscoverPlatform;
 
+/// Time a platform discovery lease is held for before it must be renewed.
+const LEASE_TTL: Duration = Duration::from_secs(30);
+
 /// Callback to execute platform discovery tasks.
 pub struct Callback {
     pub(crate) injector: Injector,
@@ -28,8 +33,32 @@ impl TaskCallback for Callback {
             context.logger, "Reached platform discovery task callback";
             "request" => ?request,
         );
-        // TODO(locking): exit early if platform already under discovery.
 
-        crate::discover::discover(context, self, request).await
+        // Exit early if the platform is already under discovery by another process.
+        let lease = format!("platform_discovery/{}/{}", request.ns_id, request.name);
+        let coordinator = &self.injector.coordinator;
+        let guard = match coordinator.lease_hold(context, lease, LEASE_TTL).await? {
+            Some(guard) => guard,
+            None => {
+                slog::info!(
+                    context.logger, "Skipping discovery of platform already under discovery";
+                    "ns_id" => &request.ns_id,
+                    "name" => &request.name,
+                );
+                return Ok(());
+            }
+        };
+
+        let fence = guard.fence();
+        let result = crate::discover::discover(context, self, request, &fence).await;
+
+        // Leases expire on their own so failing to release them is not an error.
+        if let Err(error) = guard.release(context).await {
+            slog::warn!(
+                context.logger, "Failed to release platform discovery lease";
+                replisdk::utils::error::slog::ErrorAttributes::from(&error),
+            );
+        }
+        result
     }
 }
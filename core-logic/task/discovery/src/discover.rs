@@ -5,10 +5,12 @@ use replisdk::core::models::cluster::ClusterDiscovery;
 use replisdk::core::models::cluster::ClusterSpec;
 
 use replicore_context::Context;
+use replicore_coordinator::LeaseFence;
 use replicore_errors::NamespaceNotActive;
 use replicore_errors::NamespaceNotFound;
 use replicore_events::Event;
 use replicore_injector::Injector;
+use replicore_store::persist::PersistWithEvents;
 use replicore_store::query::LookupClusterSpec;
 use replicore_store::query::LookupNamespace;
 use replicore_store::query::LookupPlatform;
@@ -19,10 +21,14 @@ use crate::errors::PlatformNotFound;
 use crate::DiscoverPlatform;
 
 /// Process platform discovery requests.
+///
+/// The fence is checked before every change so discovery stops as soon as the
+/// platform discovery lease is lost to another process.
 pub async fn discover(
     context: &Context,
     callback: &Callback,
     request: DiscoverPlatform,
+    fence: &LeaseFence,
 ) -> Result<()> {
     // Lookup the namespace and ensure it is active.
     let op = LookupNamespace::from(request.ns_id.clone());
@@ -58,41 +64,54 @@ pub async fn discover(
             cluster_id: cluster.cluster_id,
             nodes: cluster.nodes,
         };
-        upsert(context, &callback.injector, cluster).await?;
+        upsert(context, &callback.injector, fence, cluster).await?;
     }
     Ok(())
 }
 
 /// Update or insert the cluster discovery record, emitting events as needed.
-async fn upsert(context: &Context, injector: &Injector, discovery: ClusterDiscovery) -> Result<()> {
+async fn upsert(
+    context: &Context,
+    injector: &Injector,
+    fence: &LeaseFence,
+    discovery: ClusterDiscovery,
+) -> Result<()> {
     // If the cluster has no ClusterSpec persist a synthetic one first.
+    let sdk = replicore_sdk::CoreSDK::from(injector);
     let spec = LookupClusterSpec::by(&discovery.ns_id, &discovery.cluster_id);
     let spec = injector.store.query(context, spec).await?;
     if spec.is_none() {
         let spec = ClusterSpec::synthetic(&discovery.ns_id, &discovery.cluster_id);
         let event = Event::new_with_payload(crate::events::EVENT_SYNTHETIC, &spec)?;
-        injector.events.change(context, event).await?;
-        injector.store.persist(context, spec).await?;
+        let op = PersistWithEvents::new(spec).change(event);
+        fence.check()?;
+        sdk.persist_with_events(context, op).await?;
     }
 
     // Lookup any previously stored discovery and emit events.
     let existing = injector.store.query(context, &discovery).await?;
-    match existing {
+    let event = match existing {
         None => {
             let event = Event::new_with_payload(crate::events::EVENT_NEW, &discovery)?;
-            injector.events.change(context, event).await?;
+            Some(event)
         }
         Some(existing) if existing != discovery => {
             let payload = crate::events::UpdatePayload {
                 before: existing,
                 after: discovery.clone(),
             };
-            let event = Event::new_with_payload(crate::events::EVENT_UPDATE, payload)?;
-            injector.events.change(context, event).await?;
+            let event = Event::new_with_payload(crate::events::EVENT_UPDATE, payload)?
+                .for_cluster(&discovery.ns_id, &discovery.cluster_id);
+            Some(event)
         }
-        _ => (),
-    }
+        _ => None,
+    };
 
     // Store the new/updated discovery record.
-    injector.store.persist(context, discovery).await
+    let mut op = PersistWithEvents::new(discovery);
+    if let Some(event) = event {
+        op = op.change(event);
+    }
+    fence.check()?;
+    sdk.persist_with_events(context, op).await
 }
@@ -4,6 +4,9 @@ use serde::Serialize;
 
 use replisdk::core::models::cluster::ClusterDiscovery;
 
+use replicore_events::EventCode;
+use replicore_sdk::catalog::CLUSTER_SPEC_SCHEMA;
+
 /// Event code emitted when a ClusterDiscovery for a new cluster is found.
 pub const EVENT_NEW: &str = "CLUSTER_DISCOVERY_NEW";
 
@@ -13,6 +16,36 @@ pub const EVENT_SYNTHETIC: &str = "CLUSTER_DISCOVERY_SYNTHETIC";
 /// Event code emitted when a ClusterDiscovery for a cluster is updated.
 pub const EVENT_UPDATE: &str = "CLUSTER_DISCOVERY_UPDATE";
 
+/// JSON Schema for events with a [`ClusterDiscovery`] payload.
+const CLUSTER_DISCOVERY_SCHEMA: &str = include_str!("catalog/cluster-discovery.schema.json");
+
+/// JSON Schema for events with an [`UpdatePayload`] payload.
+const UPDATE_PAYLOAD_SCHEMA: &str = include_str!("catalog/cluster-discovery-update.schema.json");
+
+/// Event codes emitted by cluster discovery.
+pub fn event_codes() -> Vec<EventCode> {
+    vec![
+        EventCode::change(
+            EVENT_NEW,
+            "ClusterDiscovery",
+            "ClusterDiscovery for a new cluster found",
+        )
+        .schema(CLUSTER_DISCOVERY_SCHEMA),
+        EventCode::change(
+            EVENT_SYNTHETIC,
+            "ClusterSpec",
+            "Synthetic ClusterSpec created for a discovered cluster",
+        )
+        .schema(CLUSTER_SPEC_SCHEMA),
+        EventCode::change(
+            EVENT_UPDATE,
+            "UpdatePayload",
+            "ClusterDiscovery for a cluster updated",
+        )
+        .schema(UPDATE_PAYLOAD_SCHEMA),
+    ]
+}
+
 /// Payload for [`ClusterDiscovery`] update events.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct UpdatePayload {
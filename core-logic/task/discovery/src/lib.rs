@@ -20,6 +20,7 @@ pub use self::callback::Callback;
 /// Background task queue for platform discovery requests.
 pub static DISCOVERY_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
     queue: String::from("platform_discovery"),
+    max_execution_time: Some(std::time::Duration::from_secs(5 * 60)),
     retry_count: 1,
     retry_timeout: std::time::Duration::from_secs(5),
 });
@@ -51,6 +52,9 @@ impl TryInto<TaskSubmission> for DiscoverPlatform {
     type Error = anyhow::Error;
 
     fn try_into(self) -> Result<TaskSubmission, Self::Error> {
-        TaskSubmission::new(&DISCOVERY_QUEUE, &self)
+        // Coalesce repeated requests to discover the same platform.
+        let dedup_key = format!("{}/{}", self.ns_id, self.name);
+        let task = TaskSubmission::new(&DISCOVERY_QUEUE, &self)?.dedup_key(dedup_key);
+        Ok(task)
     }
 }
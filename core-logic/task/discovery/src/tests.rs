@@ -11,12 +11,18 @@ use replisdk::core::models::platform::PlatformTransport;
 use replisdk::core::models::platform::PlatformTransportUrl;
 use replisdk::platform::models::ClusterDiscoveryNode;
 
+use replicore_clients_platform::UrlFactory;
 use replicore_context::Context;
+use replicore_coordinator::Coordinator;
+use replicore_coordinator::LeaseFence;
+use replicore_coordinator::LeaseLost;
 use replicore_errors::NamespaceNotActive;
 use replicore_errors::NamespaceNotFound;
 use replicore_injector::Injector;
 use replicore_injector::InjectorFixture;
 use replicore_store::query::LookupClusterDiscovery;
+use replicore_tasks::execute::ReceivedTask;
+use replicore_tasks::execute::TaskCallback;
 use repliplatform_client::Client;
 
 use super::discover::discover;
@@ -29,7 +35,7 @@ use crate::DiscoverPlatform;
 struct UnittestClientFactory;
 
 #[async_trait::async_trait]
-impl replicore_clients_platform::UrlFactory for UnittestClientFactory {
+impl UrlFactory for UnittestClientFactory {
     async fn init(&self, _: &Context, _: &PlatformTransportUrl) -> Result<Client> {
         let client = repliplatform_client::fixture::Client::default();
         client.append_node("cluster1", "node1");
@@ -40,6 +46,22 @@ impl replicore_clients_platform::UrlFactory for UnittestClientFactory {
     }
 }
 
+/// Factory marking the discovery lease as lost once the platform client is initialised.
+struct LeaseLostClientFactory(LeaseFence);
+
+#[async_trait::async_trait]
+impl UrlFactory for LeaseLostClientFactory {
+    async fn init(&self, context: &Context, transport: &PlatformTransportUrl) -> Result<Client> {
+        self.0.mark_lost();
+        UnittestClientFactory.init(context, transport).await
+    }
+}
+
+/// Fence for a platform discovery lease that is never lost.
+fn fence() -> LeaseFence {
+    LeaseFence::fixture("platform_discovery/default/unit")
+}
+
 /// Initialise a clients factory with unit test clients.
 async fn fixed_callback() -> (Callback, InjectorFixture) {
     let mut clients = replicore_clients_platform::PlatformClients::empty();
@@ -128,7 +150,7 @@ async fn ns_missing() {
     let (callback, _) = fixed_callback().await;
     let context = callback.injector.context.clone();
     let request = DiscoverPlatform::new("missing", "missing");
-    let result = discover(&context, &callback, request).await;
+    let result = discover(&context, &callback, request, &fence()).await;
     match result {
         Ok(()) => panic!("discovery expected to fail"),
         Err(error) if error.is::<NamespaceNotFound>() => (),
@@ -141,7 +163,7 @@ async fn ns_not_active() {
     let (callback, _) = fixed_callback().await;
     let context = callback.injector.context.clone();
     let request = DiscoverPlatform::new("test", "missing");
-    let result = discover(&context, &callback, request).await;
+    let result = discover(&context, &callback, request, &fence()).await;
     match result {
         Ok(()) => panic!("discovery expected to fail"),
         Err(error) if error.is::<NamespaceNotActive>() => (),
@@ -154,7 +176,7 @@ async fn platform_missing() {
     let (callback, _) = fixed_callback().await;
     let context = callback.injector.context.clone();
     let request = DiscoverPlatform::new("default", "missing");
-    let result = discover(&context, &callback, request).await;
+    let result = discover(&context, &callback, request, &fence()).await;
     match result {
         Ok(()) => panic!("discovery expected to fail"),
         Err(error) if error.is::<PlatformNotFound>() => (),
@@ -167,7 +189,7 @@ async fn platform_not_active() {
     let (callback, _) = fixed_callback().await;
     let context = callback.injector.context.clone();
     let request = DiscoverPlatform::new("default", "test");
-    let result = discover(&context, &callback, request).await;
+    let result = discover(&context, &callback, request, &fence()).await;
     match result {
         Ok(()) => panic!("discovery expected to fail"),
         Err(error) if error.is::<PlatformNotActive>() => (),
@@ -180,7 +202,7 @@ async fn clusters_are_discovered() {
     let (callback, fixture) = fixed_callback().await;
     let context = callback.injector.context.clone();
     let request = DiscoverPlatform::new("default", "unit");
-    discover(&context, &callback, request)
+    discover(&context, &callback, request, &fence())
         .await
         .expect("platform discovery unsuccessful");
 
@@ -222,6 +244,70 @@ async fn clusters_are_discovered() {
     };
 }
 
+#[tokio::test]
+async fn lease_lost_stops_discovery() {
+    let (mut callback, _) = fixed_callback().await;
+    let context = callback.injector.context.clone();
+    let fence = fence();
+    let mut clients = replicore_clients_platform::PlatformClients::empty();
+    clients.with_url_factory("unittest", LeaseLostClientFactory(fence.clone()));
+    callback.injector.clients.platform = clients;
+
+    let request = DiscoverPlatform::new("default", "unit");
+    let result = discover(&context, &callback, request, &fence).await;
+    match result {
+        Ok(()) => panic!("discovery expected to fail"),
+        Err(error) if error.is::<LeaseLost>() => (),
+        Err(error) => panic!("discovery failed with unexpected error: {:?}", error),
+    }
+
+    // Assert nothing was persisted once the lease was lost.
+    let cluster1 = callback
+        .injector
+        .store
+        .query(&context, LookupClusterDiscovery::by("default", "cluster1"))
+        .await
+        .unwrap();
+    assert!(cluster1.is_none());
+}
+
+#[tokio::test]
+async fn platform_under_discovery_is_skipped() {
+    let (callback, fixture) = fixed_callback().await;
+    let context = callback.injector.context.clone();
+
+    // Another process is already discovering the platform.
+    let other = Coordinator::from(fixture.coordinator.clone());
+    other
+        .lease_acquire(
+            &context,
+            "platform_discovery/default/unit",
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+    let task = ReceivedTask {
+        id: "1".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::to_value(DiscoverPlatform::new("default", "unit")).unwrap(),
+        queue: &crate::DISCOVERY_QUEUE,
+        run_as: None,
+        trace: None,
+    };
+    callback.execute(&context, &task).await.unwrap();
+
+    let cluster1 = callback
+        .injector
+        .store
+        .query(&context, LookupClusterDiscovery::by("default", "cluster1"))
+        .await
+        .unwrap();
+    assert!(cluster1.is_none());
+}
+
 fn assert_event(code: &str, payload: Value) {
     match code {
         crate::events::EVENT_NEW => assert_eq!(
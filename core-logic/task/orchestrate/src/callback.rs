@@ -1,14 +1,21 @@
 //! Callback invoked when cluster orchestration task need to be executed.
+use std::time::Duration;
+
 use anyhow::Result;
 
 use replicore_context::Context;
+use replicore_coordinator::LeaseFence;
 use replicore_events::Event;
 use replicore_injector::Injector;
+use replicore_store::persist::PersistWithEvents;
 use replicore_tasks::execute::ReceivedTask;
 use replicore_tasks::execute::TaskCallback;
 
 use super::OrchestrateCluster;
 
+/// Time a cluster orchestration lease is held for before it must be renewed.
+const LEASE_TTL: Duration = Duration::from_secs(30);
+
 /// Callback to execute cluster orchestration tasks.
 pub struct Callback {
     pub(crate) injector: Injector,
@@ -29,10 +36,52 @@ impl TaskCallback for Callback {
             context.logger, "Reached cluster orchestration task callback";
             "request" => ?request,
         );
-        // TODO: lock check to avoid concurrent execution.
 
+        // Exit early if the cluster is already orchestrated by another process.
+        let lease = format!(
+            "cluster_orchestrate/{}/{}",
+            request.ns_id, request.cluster_id
+        );
+        let coordinator = &self.injector.coordinator;
+        let guard = match coordinator.lease_hold(context, lease, LEASE_TTL).await? {
+            Some(guard) => guard,
+            None => {
+                slog::info!(
+                    context.logger, "Skipping orchestration of cluster already orchestrated";
+                    "ns_id" => &request.ns_id,
+                    "cluster_id" => &request.cluster_id,
+                );
+                return Ok(());
+            }
+        };
+
+        let result = self.orchestrate(context, guard.fence(), request).await;
+
+        // Leases expire on their own so failing to release them is not an error.
+        if let Err(error) = guard.release(context).await {
+            slog::warn!(
+                context.logger, "Failed to release cluster orchestration lease";
+                replisdk::utils::error::slog::ErrorAttributes::from(&error),
+            );
+        }
+        result
+    }
+}
+
+impl Callback {
+    /// Orchestrate the requested cluster, once exclusive access to it is ensured.
+    ///
+    /// The lease fence is checked before every change so orchestration stops as soon as
+    /// the cluster orchestration lease is lost to another process.
+    async fn orchestrate(
+        &self,
+        context: &Context,
+        fence: LeaseFence,
+        request: OrchestrateCluster,
+    ) -> Result<()> {
         // Initialise orchestration task.
-        let data = crate::init::InitData::load(context, self.injector.clone(), request).await?;
+        let injector = self.injector.clone();
+        let data = crate::init::InitData::load(context, injector, fence, request).await?;
         let data = crate::sync::SyncData::convert(data)?;
 
         // Sync cluster nodes and build current cluster view.
@@ -61,14 +110,33 @@ impl TaskCallback for Callback {
             crate::converge::run(context, &data).await?;
         }
 
+        // Decide on a follow-up orchestration if steps or actions asked for one.
+        let follow_up = crate::follow_up::decide(&data)?;
+
         // Emit the report as an event and save it to store.
         let report = data
             .report
             .into_inner()
             .expect("orchestrate task report lock poisoned");
         let event = Event::new_with_payload(crate::constants::ORCHESTRATE_REPORT, &report)?;
-        data.injector.events.change(context, event).await?;
-        data.injector.store.persist(context, report).await?;
+        let op = PersistWithEvents::new(report).change(event);
+        let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+        data.fence.check()?;
+        sdk.persist_with_events(context, op).await?;
+
+        // Submit the follow-up orchestration only once the report is safely stored.
+        //  The next periodic orchestration catches up if the submission fails.
+        if let Some(task) = follow_up {
+            let result = crate::follow_up::submit(context, &data.injector, &data.fence, task).await;
+            if let Err(error) = result {
+                slog::warn!(
+                    context.logger, "Failed to schedule follow-up cluster orchestration";
+                    "ns_id" => &data.ns.id,
+                    "cluster_id" => &data.cluster_new.spec.cluster_id,
+                    replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                );
+            }
+        }
 
         Ok(())
     }
@@ -0,0 +1,114 @@
+//! Event codes emitted by cluster orchestration and the contract of their payloads.
+use replicore_events::EventCode;
+use replicore_sdk::catalog::NACTION_SCHEMA;
+use replicore_sdk::catalog::OACTION_SCHEMA;
+
+use crate::constants::NACTION_SYNC_NEW;
+use crate::constants::NACTION_SYNC_UPDATE;
+use crate::constants::NODE_DELETE;
+use crate::constants::NODE_SYNC_NEW;
+use crate::constants::NODE_SYNC_UPDATE;
+use crate::constants::OACTION_FAIL;
+use crate::constants::OACTION_SUCCESS;
+use crate::constants::OACTION_UPDATE;
+use crate::constants::ORCHESTRATE_REPORT;
+use crate::constants::SHARD_SYNC_NEW;
+use crate::constants::SHARD_SYNC_UPDATE;
+use crate::constants::STORE_EXTRAS_SYNC_NEW;
+use crate::constants::STORE_EXTRAS_SYNC_UPDATE;
+
+/// JSON Schema for events with a `Node` payload.
+const NODE_SCHEMA: &str = include_str!("catalog/node.schema.json");
+
+/// JSON Schema for events with an `OrchestrateReport` payload.
+const ORCHESTRATE_REPORT_SCHEMA: &str = include_str!("catalog/orchestrate-report.schema.json");
+
+/// JSON Schema for events with a `Shard` payload.
+const SHARD_SCHEMA: &str = include_str!("catalog/shard.schema.json");
+
+/// JSON Schema for events with a `StoreExtras` payload.
+const STORE_EXTRAS_SCHEMA: &str = include_str!("catalog/store-extras.schema.json");
+
+/// Event codes emitted by cluster orchestration.
+///
+/// The `OACTION_CANCEL` code is shared with (and registered by) the [`replicore_sdk`].
+pub fn event_codes() -> Vec<EventCode> {
+    let change = |code: &str, payload: &str, description: &str, schema: &str| {
+        EventCode::change(code, payload, description).schema(schema)
+    };
+    vec![
+        change(
+            NACTION_SYNC_NEW,
+            "NAction",
+            "New node action first seen during sync",
+            NACTION_SCHEMA,
+        ),
+        change(
+            NACTION_SYNC_UPDATE,
+            "NAction",
+            "Node action updated during sync",
+            NACTION_SCHEMA,
+        ),
+        change(NODE_DELETE, "Node", "Terminated node deleted", NODE_SCHEMA),
+        change(
+            NODE_SYNC_NEW,
+            "Node",
+            "New node first seen during sync",
+            NODE_SCHEMA,
+        ),
+        change(
+            NODE_SYNC_UPDATE,
+            "Node",
+            "Node updated during sync",
+            NODE_SCHEMA,
+        ),
+        change(
+            OACTION_FAIL,
+            "OAction",
+            "Orchestrator action failed",
+            OACTION_SCHEMA,
+        ),
+        change(
+            OACTION_SUCCESS,
+            "OAction",
+            "Orchestrator action succeeded",
+            OACTION_SCHEMA,
+        ),
+        change(
+            OACTION_UPDATE,
+            "OAction",
+            "Orchestrator action updated",
+            OACTION_SCHEMA,
+        ),
+        change(
+            ORCHESTRATE_REPORT,
+            "OrchestrateReport",
+            "Orchestration cycle complete with its report",
+            ORCHESTRATE_REPORT_SCHEMA,
+        ),
+        change(
+            SHARD_SYNC_NEW,
+            "Shard",
+            "New shard first seen on a node during sync",
+            SHARD_SCHEMA,
+        ),
+        change(
+            SHARD_SYNC_UPDATE,
+            "Shard",
+            "Shard updated on a node during sync",
+            SHARD_SCHEMA,
+        ),
+        change(
+            STORE_EXTRAS_SYNC_NEW,
+            "StoreExtras",
+            "Store extras for a node first seen during sync",
+            STORE_EXTRAS_SCHEMA,
+        ),
+        change(
+            STORE_EXTRAS_SYNC_UPDATE,
+            "StoreExtras",
+            "Store extras for a node updated during sync",
+            STORE_EXTRAS_SCHEMA,
+        ),
+    ]
+}
@@ -52,7 +52,10 @@ impl ConvergeStep for ClusterExpand {
 
         // Skip if in expand grace period.
         let expand_grace = declaration.graces.expand;
-        if super::step::grace_check(STEP_ID_CLUSTER_EXPAND, &state.graces, expand_grace) {
+        if let Some(expire) =
+            super::step::grace_check(STEP_ID_CLUSTER_EXPAND, &state.graces, expand_grace)
+        {
+            data.follow_up.at(expire);
             slog::debug!(
                 context.logger, "Skip cluster expand while in grace period";
                 "ns_id" => data.ns_id(),
@@ -150,6 +153,7 @@ impl ConvergeStep for ClusterExpand {
         // Schedule cluster expand action based on mode.
         let spec = expand_naction(declaration, new_node, &target)?;
         let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+        data.fence.check()?;
         let action = sdk.naction_create(context, spec).await?;
         let mut note = OrchestrateReportNote::decision("Scheduled cluster expand action on node");
         note.for_node(&action.node_id)
@@ -164,7 +168,9 @@ impl ConvergeStep for ClusterExpand {
         );
 
         // Update convergence state.
-        super::step::grace_start(STEP_ID_CLUSTER_EXPAND, &mut state.graces);
+        let expire =
+            super::step::grace_start(STEP_ID_CLUSTER_EXPAND, &mut state.graces, expand_grace);
+        data.follow_up.at(expire);
         Ok(())
     }
 }
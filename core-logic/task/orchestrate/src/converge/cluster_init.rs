@@ -46,7 +46,11 @@ impl ConvergeStep for ClusterInit {
         }
 
         // Skip initialisation it the last attempt was too recent.
-        if super::step::grace_check(STEP_ID_CLUSTER_INIT, &state.graces, declaration.graces.init) {
+        let init_grace = declaration.graces.init;
+        if let Some(expire) =
+            super::step::grace_check(STEP_ID_CLUSTER_INIT, &state.graces, init_grace)
+        {
+            data.follow_up.at(expire);
             slog::debug!(
                 context.logger, "Skip cluster initialisation request while in grace period";
                 "ns_id" => data.ns_id(),
@@ -127,6 +131,7 @@ impl ConvergeStep for ClusterInit {
             metadata: Default::default(),
         };
         let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+        data.fence.check()?;
         let action = sdk.naction_create(context, spec).await?;
         let mut note = OrchestrateReportNote::decision("Scheduled cluster initialisation on node");
         note.for_node(&action.node_id)
@@ -141,7 +146,8 @@ impl ConvergeStep for ClusterInit {
         );
 
         // Update convergence state to make information available to the next loop.
-        super::step::grace_start(STEP_ID_CLUSTER_INIT, &mut state.graces);
+        let expire = super::step::grace_start(STEP_ID_CLUSTER_INIT, &mut state.graces, init_grace);
+        data.follow_up.at(expire);
         Ok(())
     }
 }
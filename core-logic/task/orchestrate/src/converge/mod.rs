@@ -13,6 +13,7 @@ use replicore_cluster_models::OrchestrateReport;
 use replicore_cluster_models::OrchestrateReportNote;
 use replicore_cluster_view::ClusterView;
 use replicore_context::Context;
+use replicore_coordinator::LeaseFence;
 use replicore_injector::Injector;
 
 mod cluster_expand;
@@ -23,6 +24,7 @@ mod node_scale_up;
 mod step;
 
 use self::step::ConvergeStep;
+use crate::follow_up::FollowUp;
 use crate::sync::SyncData;
 
 /// Ordered list of cluster convergence steps to perform.
@@ -48,6 +50,8 @@ static STEPS: Lazy<Vec<(&'static str, Box<dyn ConvergeStep>)>> = Lazy::new(|| {
 /// Data for the convergence step of cluster orchestration.
 pub struct ConvergeData {
     pub cluster_new: ClusterView,
+    pub fence: LeaseFence,
+    pub follow_up: FollowUp,
     pub injector: Injector,
     pub mode: OrchestrateMode,
     pub ns: Namespace,
@@ -79,6 +83,8 @@ impl ConvergeData {
         };
         let data = ConvergeData {
             cluster_new,
+            fence: value.fence,
+            follow_up: value.follow_up,
             injector: value.injector,
             mode: value.mode,
             ns: value.ns,
@@ -116,6 +122,7 @@ pub async fn run(context: &Context, data: &ConvergeData) -> Result<()> {
     // Execute convergence steps.
     let mut new_state = data.state.clone();
     for (step_id, step) in STEPS.iter() {
+        data.fence.check()?;
         let result = step.converge(context, data, &mut new_state).await;
         if let Err(error) = result {
             let message = "Cluster convergence step failed";
@@ -126,6 +133,7 @@ pub async fn run(context: &Context, data: &ConvergeData) -> Result<()> {
     }
 
     // Persist latest converge state updates.
+    data.fence.check()?;
     data.injector.store.persist(context, new_state).await?;
     Ok(())
 }
@@ -1,6 +1,5 @@
 //! Check the number of nodes in the cluster and create new ones if needed.
 use std::collections::HashMap;
-use std::time::Duration;
 
 use anyhow::Result;
 
@@ -49,17 +48,17 @@ impl ConvergeStep for NodeScaleUp {
         };
 
         // Skip step if last scale up triggered too recently.
-        if let Some(grace) = state.graces.get(STEP_ID_SCALE_UP) {
-            let grace_time = declaration.graces.scale_up;
-            let grace_time = Duration::from_secs(grace_time * 60);
-            if *grace + grace_time > time::OffsetDateTime::now_utc() {
-                slog::debug!(
-                    context.logger, "Skip node scale up while in grace period";
-                    "ns_id" => data.ns_id(),
-                    "cluster_id" => data.cluster_id(),
-                );
-                return Ok(());
-            }
+        let scale_up_grace = declaration.graces.scale_up;
+        if let Some(expire) =
+            super::step::grace_check(STEP_ID_SCALE_UP, &state.graces, scale_up_grace)
+        {
+            data.follow_up.at(expire);
+            slog::debug!(
+                context.logger, "Skip node scale up while in grace period";
+                "ns_id" => data.ns_id(),
+                "cluster_id" => data.cluster_id(),
+            );
+            return Ok(());
         }
         state.graces.remove(STEP_ID_SCALE_UP);
 
@@ -122,6 +121,7 @@ impl ConvergeStep for NodeScaleUp {
             timeout: None,
         };
         let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+        data.fence.check()?;
         let action = sdk.oaction_create(context, node_up).await?;
         let note = OrchestrateReportNote::decision("Cluster scale-up action scheduled");
         data.report_mut().notes.push(note);
@@ -133,10 +133,8 @@ impl ConvergeStep for NodeScaleUp {
         );
 
         // Update convergence state to make information available to the next loop.
-        state.graces.insert(
-            STEP_ID_SCALE_UP.to_string(),
-            time::OffsetDateTime::now_utc(),
-        );
+        let expire = super::step::grace_start(STEP_ID_SCALE_UP, &mut state.graces, scale_up_grace);
+        data.follow_up.at(expire);
         Ok(())
     }
 }
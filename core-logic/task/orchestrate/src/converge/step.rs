@@ -24,25 +24,34 @@ pub trait ConvergeStep: Send + Sync {
 
 /// Check the grace period of a convergence step.
 ///
-/// Returns `true` if the step is currently in the grace period.
+/// Returns the time the grace period expires if the step is currently in the grace period.
 pub fn grace_check(
     step_id: &str,
     graces: &HashMap<String, OffsetDateTime>,
     grace_time: u64,
-) -> bool {
-    let grace = match graces.get(step_id) {
-        None => return false,
-        Some(grace) => grace,
-    };
+) -> Option<OffsetDateTime> {
+    let grace = graces.get(step_id)?;
     let grace_time = Duration::from_secs(grace_time * 60);
     let grace_expire = *grace + grace_time;
-    grace_expire > time::OffsetDateTime::now_utc()
+    if grace_expire > time::OffsetDateTime::now_utc() {
+        Some(grace_expire)
+    } else {
+        None
+    }
 }
 
 /// Update the [`ConvergeData::graces`] to start the grace period for a step.
-pub fn grace_start<S>(step_id: S, graces: &mut HashMap<String, OffsetDateTime>)
+///
+/// Returns the time the new grace period expires.
+pub fn grace_start<S>(
+    step_id: S,
+    graces: &mut HashMap<String, OffsetDateTime>,
+    grace_time: u64,
+) -> OffsetDateTime
 where
     S: Into<String>,
 {
-    graces.insert(step_id.into(), time::OffsetDateTime::now_utc());
+    let now = time::OffsetDateTime::now_utc();
+    graces.insert(step_id.into(), now);
+    now + Duration::from_secs(grace_time * 60)
 }
@@ -0,0 +1,138 @@
+//! Track and submit follow-up orchestration requests.
+//!
+//! Convergence steps and orchestrator actions waiting for time to pass (such as the end
+//! of a grace period) can request the cluster is orchestrated again at a later time
+//! instead of waiting for the next periodic or user-requested orchestration.
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+
+use replicore_cluster_models::OrchestrateReportNote;
+use replicore_context::Context;
+use replicore_coordinator::LeaseFence;
+use replicore_injector::Injector;
+use replicore_tasks::submit::TaskSubmission;
+
+use crate::converge::ConvergeData;
+use crate::OrchestrateCluster;
+
+/// Earliest follow-up orchestration requested during an orchestration cycle.
+#[derive(Debug, Default)]
+pub struct FollowUp(Mutex<Option<OffsetDateTime>>);
+
+impl FollowUp {
+    /// Request a follow-up orchestration after the given delay from now.
+    pub fn after(&self, delay: Duration) {
+        self.at(OffsetDateTime::now_utc() + delay);
+    }
+
+    /// Request a follow-up orchestration at the given time.
+    ///
+    /// When multiple follow-ups are requested only the earliest one is kept.
+    pub fn at(&self, when: OffsetDateTime) {
+        let mut follow_up = self
+            .0
+            .lock()
+            .expect("orchestrate task follow_up lock poisoned");
+        match *follow_up {
+            Some(current) if current <= when => (),
+            _ => *follow_up = Some(when),
+        }
+    }
+
+    /// Take the requested follow-up time, if any, out of the tracker.
+    pub fn take(&self) -> Option<OffsetDateTime> {
+        self.0
+            .lock()
+            .expect("orchestrate task follow_up lock poisoned")
+            .take()
+    }
+}
+
+/// Take the requested follow-up, if any, and note the decision in the orchestration report.
+///
+/// The follow-up task is returned to be submitted once the report is persisted.
+pub fn decide(data: &ConvergeData) -> Result<Option<TaskSubmission>> {
+    let when = match data.follow_up.take() {
+        None => return Ok(None),
+        Some(when) => when,
+    };
+    let task = follow_up_task(data.ns_id(), data.cluster_id(), when)?;
+
+    let when = when.format(&time::format_description::well_known::Rfc3339)?;
+    let mut note = OrchestrateReportNote::decision("Scheduled follow-up cluster orchestration");
+    note.data.insert("not_before".into(), when.into());
+    data.report_mut().notes.push(note);
+    Ok(Some(task))
+}
+
+/// Submit a follow-up orchestration task decided on during the orchestration cycle.
+pub async fn submit(
+    context: &Context,
+    injector: &Injector,
+    fence: &LeaseFence,
+    task: TaskSubmission,
+) -> Result<()> {
+    let request: OrchestrateCluster = serde_json::from_value(task.payload.clone())?;
+    let when = task
+        .not_before
+        .map(|when| when.format(&time::format_description::well_known::Rfc3339))
+        .transpose()?;
+    fence.check()?;
+    injector.tasks.submit(context, task).await?;
+    slog::debug!(
+        context.logger, "Scheduled follow-up cluster orchestration";
+        "ns_id" => request.ns_id,
+        "cluster_id" => request.cluster_id,
+        "not_before" => when,
+    );
+    Ok(())
+}
+
+/// Build the delayed orchestration task for the cluster.
+fn follow_up_task(ns_id: &str, cluster_id: &str, when: OffsetDateTime) -> Result<TaskSubmission> {
+    let request = OrchestrateCluster::new(ns_id, cluster_id);
+    let task: TaskSubmission = request.try_into()?;
+    Ok(task.not_before(when))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use time::OffsetDateTime;
+
+    use super::FollowUp;
+
+    #[test]
+    fn earliest_follow_up_is_kept() {
+        let now = OffsetDateTime::now_utc();
+        let follow_up = FollowUp::default();
+        follow_up.at(now + Duration::from_secs(60));
+        follow_up.at(now + Duration::from_secs(10));
+        follow_up.at(now + Duration::from_secs(30));
+        assert_eq!(follow_up.take(), Some(now + Duration::from_secs(10)));
+        assert_eq!(follow_up.take(), None);
+    }
+
+    #[test]
+    fn follow_up_after_delay() {
+        let before = OffsetDateTime::now_utc();
+        let follow_up = FollowUp::default();
+        follow_up.after(Duration::from_secs(30));
+        let when = follow_up.take().unwrap();
+        assert!(when >= before + Duration::from_secs(30));
+        assert!(when <= OffsetDateTime::now_utc() + Duration::from_secs(30));
+    }
+
+    #[test]
+    fn follow_up_task_not_before() {
+        let when = OffsetDateTime::now_utc() + Duration::from_secs(30);
+        let task = super::follow_up_task("default", "cluster", when).unwrap();
+        assert_eq!(task.not_before, Some(when));
+        assert_eq!(task.dedup_key.as_deref(), Some("default/cluster"));
+        assert_eq!(task.queue.queue, crate::ORCHESTRATE_QUEUE.queue);
+    }
+}
@@ -8,6 +8,7 @@ use replicore_cluster_models::OrchestrateMode;
 use replicore_cluster_models::OrchestrateReport;
 use replicore_cluster_view::ClusterView;
 use replicore_context::Context;
+use replicore_coordinator::LeaseFence;
 use replicore_errors::ClusterNotActive;
 use replicore_errors::ClusterNotFound;
 use replicore_errors::NamespaceNotActive;
@@ -21,6 +22,7 @@ use crate::OrchestrateCluster;
 /// Initial data for cluster orchestration.
 pub struct InitData {
     pub cluster_current: ClusterView,
+    pub fence: LeaseFence,
     pub injector: Injector,
     pub mode: OrchestrateMode,
     pub ns: Namespace,
@@ -30,6 +32,7 @@ impl std::fmt::Debug for InitData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InitData")
             .field("cluster_current", &self.cluster_current)
+            .field("fence", &self.fence)
             .field("injector", &"Injector { ... }")
             .field("mode", &self.mode)
             .field("ns", &self.ns)
@@ -39,9 +42,12 @@ impl std::fmt::Debug for InitData {
 
 impl InitData {
     /// Fetch initial data from the store.
+    ///
+    /// The fence is carried through all orchestration phases and checked before every change.
     pub async fn load(
         context: &Context,
         injector: Injector,
+        fence: LeaseFence,
         request: OrchestrateCluster,
     ) -> Result<InitData> {
         // Lookup the namespace and ensure it is active.
@@ -79,6 +85,7 @@ impl InitData {
         // Collect all initial data and return it.
         let data = InitData {
             cluster_current,
+            fence,
             injector,
             mode,
             ns,
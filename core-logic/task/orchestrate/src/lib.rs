@@ -7,18 +7,24 @@ use replicore_tasks::conf::Queue;
 use replicore_tasks::submit::TaskSubmission;
 
 mod callback;
+mod catalog;
 mod constants;
 mod converge;
+mod follow_up;
 mod init;
 mod naction;
 mod oaction;
+mod subscriber;
 mod sync;
 
 pub use self::callback::Callback;
+pub use self::catalog::event_codes;
+pub use self::subscriber::OrchestrateOnEvent;
 
 /// Background task queue for cluster orchestration requests.
 pub static ORCHESTRATE_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
     queue: String::from("cluster_orchestrate"),
+    max_execution_time: Some(std::time::Duration::from_secs(10 * 60)),
     retry_count: 1,
     retry_timeout: std::time::Duration::from_secs(5),
 });
@@ -50,6 +56,9 @@ impl TryInto<TaskSubmission> for OrchestrateCluster {
     type Error = anyhow::Error;
 
     fn try_into(self) -> Result<TaskSubmission, Self::Error> {
-        TaskSubmission::new(&ORCHESTRATE_QUEUE, &self)
+        // Coalesce repeated requests to orchestrate the same cluster.
+        let dedup_key = format!("{}/{}", self.ns_id, self.cluster_id);
+        let task = TaskSubmission::new(&ORCHESTRATE_QUEUE, &self)?.dedup_key(dedup_key);
+        Ok(task)
     }
 }
@@ -104,6 +104,7 @@ async fn schedule_node(
     };
 
     // Submit the action to the node and update its status.
+    data.fence.check()?;
     let client = data
         .injector
         .clients
@@ -148,6 +149,7 @@ async fn schedule_node(
         true => data.cluster_new_mut().remove_naction(&action)?,
         false => data.cluster_new_mut().update_naction(action.clone())?,
     };
+    data.fence.check()?;
     data.injector.store.persist(context, action).await?;
     Ok(())
 }
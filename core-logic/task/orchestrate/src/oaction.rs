@@ -10,6 +10,7 @@ use replicore_events::Event;
 use replicore_oaction::OActionChangeValue;
 use replicore_oaction::OActionChanges;
 use replicore_oaction::OActionInvokeArgs;
+use replicore_store::persist::PersistWithEvents;
 
 use crate::sync::SyncData;
 
@@ -151,6 +152,7 @@ async fn execute(context: &Context, data: &SyncData, action: OAction) -> Result<
             .scheduled_ts
             .unwrap_or_else(time::OffsetDateTime::now_utc),
     );
+    data.fence.check()?;
     let changes = invoke(context, data, &action).await;
     match changes {
         Err(error) => {
@@ -187,6 +189,9 @@ async fn invoke(context: &Context, data: &SyncData, action: &OAction) -> Result<
         spec: &data.cluster_current.spec,
     };
     let mut changes = metadata.handler.invoke(context, &args).await?;
+    if let Some(delay) = changes.follow_up {
+        data.follow_up.after(delay);
+    }
 
     // If the action is running check to see if it timed out.
     if changes.state.is_running() {
@@ -263,9 +268,11 @@ async fn update(
         _ => panic!("unexpected oaction state for update"),
     };
     let event = Event::new_with_payload(event, action)?;
-    data.injector.events.change(context, event).await?;
 
     // Persist updated action.
-    data.injector.store.persist(context, action.clone()).await?;
+    let op = PersistWithEvents::new(action.clone()).change(event);
+    let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+    data.fence.check()?;
+    sdk.persist_with_events(context, op).await?;
     Ok(())
 }
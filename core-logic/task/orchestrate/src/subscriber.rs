@@ -0,0 +1,30 @@
+//! Event subscriber to orchestrate clusters as soon as their specification changes.
+use anyhow::Result;
+
+use replicore_context::Context;
+use replicore_events::subscribe::EventSubscriber;
+use replicore_events_models::EventEntry;
+use replicore_injector::Injector;
+use replicore_tasks::submit::TaskSubmission;
+
+use crate::OrchestrateCluster;
+
+/// Submit a cluster orchestration task for events with `ns_id` and `cluster_id` payload attributes.
+#[derive(Clone, Debug, Default)]
+pub struct OrchestrateOnEvent;
+
+#[async_trait::async_trait]
+impl EventSubscriber for OrchestrateOnEvent {
+    async fn handle(&self, context: &Context, entry: &EventEntry) -> Result<()> {
+        let request: OrchestrateCluster = entry.event.decode()?;
+        let task: TaskSubmission = request.clone().try_into()?;
+        Injector::global().tasks.submit(context, task).await?;
+        slog::debug!(
+            context.logger, "Requested cluster orchestration in response to event";
+            "code" => &entry.event.code,
+            "ns_id" => request.ns_id,
+            "cluster_id" => request.cluster_id,
+        );
+        Ok(())
+    }
+}
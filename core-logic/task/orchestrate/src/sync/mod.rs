@@ -22,8 +22,10 @@ use replicore_cluster_models::OrchestrateReportNote;
 use replicore_cluster_view::ClusterView;
 use replicore_cluster_view::ClusterViewBuilder;
 use replicore_context::Context;
+use replicore_coordinator::LeaseFence;
 use replicore_events::Event;
 use replicore_injector::Injector;
+use replicore_store::delete::DeleteWithEvents;
 
 mod error;
 mod nactions;
@@ -32,12 +34,15 @@ mod store;
 
 use self::error::NodeSpecificCheck;
 use self::error::NodeSpecificError;
+use crate::follow_up::FollowUp;
 use crate::init::InitData;
 
 /// Data used in the sync phase of cluster orchestration.
 pub struct SyncData {
     pub cluster_current: ClusterView,
     pub cluster_new: Mutex<ClusterViewBuilder>,
+    pub fence: LeaseFence,
+    pub follow_up: FollowUp,
     pub injector: Injector,
     pub mode: OrchestrateMode,
     pub ns: Namespace,
@@ -49,6 +54,8 @@ impl std::fmt::Debug for SyncData {
         f.debug_struct("SyncData")
             .field("cluster_current", &self.cluster_current)
             .field("cluster_new", &"ClusterViewBuilder { ... }")
+            .field("fence", &self.fence)
+            .field("follow_up", &self.follow_up)
             .field("injector", &"Injector { ... }")
             .field("mode", &self.mode)
             .field("ns", &self.ns)
@@ -77,6 +84,8 @@ impl SyncData {
         let data = Self {
             cluster_current: data.cluster_current,
             cluster_new: Mutex::new(cluster_new),
+            fence: data.fence,
+            follow_up: FollowUp::default(),
             injector: data.injector,
             mode: data.mode,
             ns: data.ns,
@@ -120,15 +129,18 @@ pub async fn nodes(context: &Context, data: &SyncData) -> Result<()> {
         .nodes
         .values()
         .filter(|node| !current_nodes.contains(&node.node_id));
+    let sdk = replicore_sdk::CoreSDK::from(&data.injector);
     for node in nodes {
-        let event = Event::new_with_payload(crate::constants::NODE_DELETE, node.as_ref().clone())?;
-        data.injector.events.change(context, event).await?;
-
         let node_id =
             replicore_store::ids::NodeID::by(&node.ns_id, &node.cluster_id, &node.node_id);
         let op = replicore_store::persist::NodeCancelAllActions::from(node_id.clone());
+        data.fence.check()?;
         data.injector.store.persist(context, op).await?;
-        data.injector.store.delete(context, node_id).await?;
+
+        // Emit the deletion event with the node record removal, once actions are cancelled.
+        let event = Event::new_with_payload(crate::constants::NODE_DELETE, node.as_ref().clone())?;
+        let op = DeleteWithEvents::new(node_id).change(event);
+        sdk.delete_with_events(context, op).await?;
     }
     Ok(())
 }
@@ -12,6 +12,7 @@ use repliagent_client::Client;
 use replicore_cluster_models::OrchestrateReportNote;
 use replicore_context::Context;
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 
 use super::error::NodeSpecificError;
 use crate::sync::SyncData;
@@ -131,16 +132,19 @@ async fn persist(context: &Context, data: &SyncData, action: NAction) -> Result<
         None => Some(crate::constants::NACTION_SYNC_NEW),
         _ => None,
     };
+    let mut op = PersistWithEvents::new(action.clone());
     if let Some(code) = code {
         let event = Event::new_with_payload(code, action.clone())?;
-        data.injector.events.change(context, event).await?;
+        op = op.change(event);
     }
 
     // Update view and store.
     if !action.state.phase.is_final() {
-        data.cluster_new_mut().naction(action.clone())?;
+        data.cluster_new_mut().naction(action)?;
     }
-    data.injector.store.persist(context, action).await?;
+    let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+    data.fence.check()?;
+    sdk.persist_with_events(context, op).await?;
     Ok(())
 }
 
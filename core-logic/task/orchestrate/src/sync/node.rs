@@ -11,6 +11,7 @@ use replisdk::platform::models::ClusterDiscoveryNode;
 
 use replicore_context::Context;
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 
 use crate::sync::SyncData;
 
@@ -26,14 +27,17 @@ pub async fn persist(context: &Context, data: &SyncData, node: Node) -> Result<(
         None => Some(crate::constants::NODE_SYNC_NEW),
         _ => None,
     };
+    let mut op = PersistWithEvents::new(node.clone());
     if let Some(code) = code {
         let event = Event::new_with_payload(code, node.clone())?;
-        data.injector.events.change(context, event).await?;
+        op = op.change(event);
     }
 
     // Update view and store.
-    data.cluster_new_mut().node_info(node.clone())?;
-    data.injector.store.persist(context, node).await?;
+    data.cluster_new_mut().node_info(node)?;
+    let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+    data.fence.check()?;
+    sdk.persist_with_events(context, op).await?;
     Ok(())
 }
 
@@ -7,6 +7,7 @@ use replisdk::platform::models::ClusterDiscoveryNode;
 
 use replicore_context::Context;
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 
 use crate::sync::SyncData;
 
@@ -26,14 +27,17 @@ pub async fn persist_extras(context: &Context, data: &SyncData, extras: StoreExt
         None => Some(crate::constants::STORE_EXTRAS_SYNC_NEW),
         _ => None,
     };
+    let mut op = PersistWithEvents::new(extras.clone());
     if let Some(code) = code {
         let event = Event::new_with_payload(code, extras.clone())?;
-        data.injector.events.change(context, event).await?;
+        op = op.change(event);
     }
 
     // Update view and store.
-    data.cluster_new_mut().store_extras(extras.clone())?;
-    data.injector.store.persist(context, extras).await?;
+    data.cluster_new_mut().store_extras(extras)?;
+    let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+    data.fence.check()?;
+    sdk.persist_with_events(context, op).await?;
     Ok(())
 }
 
@@ -117,13 +121,16 @@ async fn persist_shard(context: &Context, data: &SyncData, shard: Shard) -> Resu
         None => Some(crate::constants::SHARD_SYNC_NEW),
         _ => None,
     };
+    let mut op = PersistWithEvents::new(shard.clone());
     if let Some(code) = code {
         let event = Event::new_with_payload(code, shard.clone())?;
-        data.injector.events.change(context, event).await?;
+        op = op.change(event);
     }
 
     // Update view and store.
-    data.cluster_new_mut().shard(shard.clone())?;
-    data.injector.store.persist(context, shard).await?;
+    data.cluster_new_mut().shard(shard)?;
+    let sdk = replicore_sdk::CoreSDK::from(&data.injector);
+    data.fence.check()?;
+    sdk.persist_with_events(context, op).await?;
     Ok(())
 }
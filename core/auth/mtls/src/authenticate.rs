@@ -0,0 +1,133 @@
+//! Authenticate requests with client certificates verified with mutual TLS.
+use anyhow::Result;
+
+use replicore_auth::identity::Authentication;
+use replicore_auth::identity::IdentityReader;
+use replicore_auth::identity::Unauthenticated;
+use replicore_auth::Entity;
+use replicore_context::Context;
+
+use crate::rules::Rules;
+
+/// Implementation of the [`Authentication`] interface with mutual TLS client certificates.
+///
+/// Certificates are verified by the TLS layer before requests are handled:
+/// this implementation only maps the verified certificate to an [`Entity`].
+/// Requests without a client certificate are rejected unless anonymous requests are allowed,
+/// in which case they are authenticated as [`Entity::Anonymous`].
+pub struct MtlsAuthentication {
+    allow_anonymous: bool,
+    rules: Rules,
+}
+
+impl MtlsAuthentication {
+    /// Authenticate requests by matching client certificates against the given rules.
+    pub fn new(rules: Rules) -> MtlsAuthentication {
+        MtlsAuthentication {
+            allow_anonymous: false,
+            rules,
+        }
+    }
+
+    /// Authenticate requests without a certificate as [`Entity::Anonymous`] instead of failing.
+    pub fn allow_anonymous(&mut self, allow: bool) -> &mut Self {
+        self.allow_anonymous = allow;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Authentication for MtlsAuthentication {
+    async fn authenticate(&self, _: &Context, transport: &dyn IdentityReader) -> Result<Entity> {
+        let cert = match transport.peer_certificate() {
+            None if self.allow_anonymous => return Ok(Entity::Anonymous),
+            None => {
+                crate::telemetry::REJECTED_COUNT.inc();
+                anyhow::bail!(Unauthenticated::reason(
+                    "the request has no client certificate"
+                ))
+            }
+            Some(cert) => cert,
+        };
+        match self.rules.entity(cert) {
+            Some(entity) => Ok(entity),
+            None => {
+                crate::telemetry::REJECTED_COUNT.inc();
+                let error = Unauthenticated::reason("the client certificate matches no rule");
+                anyhow::bail!(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use replicore_auth::identity::Authentication;
+    use replicore_auth::identity::IdentityReader;
+    use replicore_auth::identity::PeerCertificate;
+    use replicore_auth::identity::Unauthenticated;
+    use replicore_auth::Entity;
+    use replicore_context::Context;
+
+    use super::MtlsAuthentication;
+    use crate::rules::Rules;
+    use crate::Conf;
+
+    /// Request with an optional client certificate for tests.
+    struct Request(Option<PeerCertificate>);
+
+    impl IdentityReader for Request {
+        fn metadata(&self, _: &str) -> Result<Option<&str>> {
+            Ok(None)
+        }
+
+        fn peer_certificate(&self) -> Option<&PeerCertificate> {
+            self.0.as_ref()
+        }
+    }
+
+    fn request(common_name: &str) -> Request {
+        Request(Some(PeerCertificate {
+            common_name: Some(common_name.into()),
+            ..Default::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn authenticate_certificates() {
+        let context = Context::fixture();
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "rules": [{
+                "attribute": "common_name",
+                "pattern": "(.+)@example\\.com",
+                "entity": {"kind": "user", "user_id": "$1"},
+            }],
+        }))
+        .unwrap();
+        let mut auth = MtlsAuthentication::new(Rules::compile(&conf).unwrap());
+
+        let entity = auth
+            .authenticate(&context, &request("alice@example.com"))
+            .await
+            .unwrap();
+        assert!(matches!(entity, Entity::User(_)));
+
+        let error = auth
+            .authenticate(&context, &request("mallory@evil.com"))
+            .await
+            .unwrap_err();
+        assert!(error.is::<Unauthenticated>());
+
+        // Requests without a certificate are only accepted when explicitly allowed.
+        let error = auth
+            .authenticate(&context, &Request(None))
+            .await
+            .unwrap_err();
+        assert!(error.is::<Unauthenticated>());
+        auth.allow_anonymous(true);
+        let entity = auth.authenticate(&context, &Request(None)).await.unwrap();
+        assert!(matches!(entity, Entity::Anonymous));
+    }
+}
@@ -0,0 +1,99 @@
+//! Configuration for the mutual TLS authentication backend.
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Mutual TLS specific configuration for the authentication interface.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// Authenticate requests without a client certificate as the anonymous entity.
+    ///
+    /// Such requests are rejected when this option is not set.
+    #[serde(default)]
+    pub allow_anonymous: bool,
+
+    /// Rules to map client certificates to entities, checked in order.
+    ///
+    /// Requests are authenticated as the entity of the first matching rule.
+    pub rules: Vec<Rule>,
+}
+
+impl Conf {
+    /// Check the configuration for errors that can't be detected while decoding it.
+    pub fn validate(&self) -> Result<(), RuleError> {
+        crate::rules::Rules::compile(self).map(|_| ())
+    }
+}
+
+/// Map client certificates with a matching attribute to an entity.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// Certificate attribute to match the rule against.
+    pub attribute: CertificateAttribute,
+
+    /// Regular expression the attribute must match in full for the rule to apply.
+    pub pattern: String,
+
+    /// Entity requests matching the rule are authenticated as.
+    pub entity: EntityRule,
+}
+
+/// Certificate attributes rules can match against.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum CertificateAttribute {
+    /// Common Name (CN) attribute of the certificate subject.
+    #[serde(rename = "common_name")]
+    CommonName,
+
+    /// Any DNS name in the Subject Alternative Name extension.
+    #[serde(rename = "dns_name")]
+    DnsName,
+
+    /// Any email address in the Subject Alternative Name extension.
+    #[serde(rename = "email")]
+    Email,
+
+    /// Any URI in the Subject Alternative Name extension.
+    #[serde(rename = "uri")]
+    Uri,
+}
+
+/// Entity to authenticate requests as when a rule matches.
+///
+/// Entity IDs can reference capture groups in the rule pattern,
+/// such as `$1` or `${name}`, to derive the ID from the certificate.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum EntityRule {
+    /// Authenticate requests as a service.
+    #[serde(rename = "service")]
+    Service { service_id: String },
+
+    /// Authenticate requests as a user.
+    #[serde(rename = "user")]
+    User { user_id: String },
+}
+
+/// The mutual TLS authentication configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the mutual TLS authentication configuration is not valid")]
+pub struct ConfError;
+
+/// A certificate mapping rule is not valid.
+#[derive(Debug, thiserror::Error)]
+pub enum RuleError {
+    /// Entity IDs must not be empty.
+    #[error("rule {0} must set a non-empty entity ID")]
+    EmptyEntityId(usize),
+
+    /// Rule patterns must be valid regular expressions.
+    #[error("rule {index} has an invalid pattern")]
+    InvalidPattern {
+        index: usize,
+        #[source]
+        source: regex::Error,
+    },
+
+    /// At least one rule is needed to authenticate any certificate.
+    #[error("at least one certificate mapping rule must be configured")]
+    NoRules,
+}
@@ -0,0 +1,41 @@
+//! Factory for the mutual TLS authentication backend.
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_auth::identity::AuthenticationFactory;
+use replicore_auth::identity::AuthenticationFactoryArgs;
+use replicore_auth::identity::Authenticator;
+use replicore_context::Context;
+
+use crate::rules::Rules;
+use crate::Conf;
+use crate::ConfError;
+use crate::MtlsAuthentication;
+
+/// Initialise mutual TLS client certificates authentication.
+pub struct MtlsFactory;
+
+#[async_trait::async_trait]
+impl AuthenticationFactory for MtlsFactory {
+    fn conf_check(&self, _: &Context, conf: &Json) -> Result<()> {
+        let conf: Conf = serde_json::from_value(conf.clone()).context(ConfError)?;
+        conf.validate().context(ConfError)?;
+        Ok(())
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        crate::telemetry::register_metrics(registry)
+    }
+
+    async fn authenticator<'a>(
+        &self,
+        args: AuthenticationFactoryArgs<'a>,
+    ) -> Result<Authenticator> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        let rules = Rules::compile(&conf).context(ConfError)?;
+        let mut authentication = MtlsAuthentication::new(rules);
+        authentication.allow_anonymous(conf.allow_anonymous);
+        Ok(Authenticator::from(authentication))
+    }
+}
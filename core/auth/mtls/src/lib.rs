@@ -0,0 +1,28 @@
+//! Authenticate requests to the Control Plane with client certificates.
+//!
+//! Client certificates are verified by the API server with mutual TLS,
+//! which requires the HTTP server to be configured with a clients CA bundle.
+//! Verified certificates are then mapped to the user or service entity they authenticate as
+//! with rules matching the certificate subject Common Name (CN)
+//! or Subject Alternative Name (SAN) entries.
+//!
+//! Requests with a certificate that matches no rule are always rejected.
+//! Rules whose entity ID template expands to an empty ID do not match the certificate.
+//! Requests without a certificate are also rejected unless `allow_anonymous` is set,
+//! for example to serve clients that can't present a certificate.
+//! Telemetry endpoints, such as `/metrics`, are served without authentication in either case.
+mod authenticate;
+mod conf;
+mod factory;
+mod rules;
+mod telemetry;
+
+pub use self::authenticate::MtlsAuthentication;
+pub use self::conf::CertificateAttribute;
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::conf::EntityRule;
+pub use self::conf::Rule;
+pub use self::conf::RuleError;
+pub use self::factory::MtlsFactory;
+pub use self::rules::Rules;
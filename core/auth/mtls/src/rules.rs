@@ -0,0 +1,219 @@
+//! Match client certificates against rules to determine the entity they authenticate as.
+use regex::Regex;
+
+use replicore_auth::identity::PeerCertificate;
+use replicore_auth::Entity;
+use replicore_auth::EntityService;
+use replicore_auth::EntityUser;
+
+use crate::conf::CertificateAttribute;
+use crate::conf::EntityRule;
+use crate::conf::RuleError;
+use crate::Conf;
+
+/// Certificate mapping rule with its pattern compiled.
+#[derive(Debug)]
+struct CompiledRule {
+    attribute: CertificateAttribute,
+    entity: EntityRule,
+    pattern: Regex,
+}
+
+impl CompiledRule {
+    /// Determine the entity for the certificate, if the rule matches it.
+    ///
+    /// Values that expand the entity ID template to an empty ID do not match the rule.
+    fn entity(&self, cert: &PeerCertificate) -> Option<Entity> {
+        let values = match self.attribute {
+            CertificateAttribute::CommonName => cert.common_name.as_slice(),
+            CertificateAttribute::DnsName => &cert.dns_names,
+            CertificateAttribute::Email => &cert.emails,
+            CertificateAttribute::Uri => &cert.uris,
+        };
+        let template = match &self.entity {
+            EntityRule::Service { service_id } => service_id,
+            EntityRule::User { user_id } => user_id,
+        };
+        let id = values.iter().find_map(|value| {
+            let captures = self.pattern.captures(value)?;
+            let mut id = String::new();
+            captures.expand(template, &mut id);
+            Some(id).filter(|id| !id.is_empty())
+        })?;
+        let entity = match &self.entity {
+            EntityRule::Service { .. } => Entity::Service(EntityService { service_id: id }),
+            EntityRule::User { .. } => Entity::User(EntityUser { user_id: id }),
+        };
+        Some(entity)
+    }
+}
+
+/// Ordered set of certificate mapping rules ready to match certificates.
+#[derive(Debug)]
+pub struct Rules {
+    rules: Vec<CompiledRule>,
+}
+
+impl Rules {
+    /// Compile the rules in the configuration, checking them for errors.
+    pub fn compile(conf: &Conf) -> Result<Rules, RuleError> {
+        if conf.rules.is_empty() {
+            return Err(RuleError::NoRules);
+        }
+        let mut rules = Vec::with_capacity(conf.rules.len());
+        for (index, rule) in conf.rules.iter().enumerate() {
+            let id = match &rule.entity {
+                EntityRule::Service { service_id } => service_id,
+                EntityRule::User { user_id } => user_id,
+            };
+            if id.is_empty() {
+                return Err(RuleError::EmptyEntityId(index));
+            }
+            // Anchor patterns so they must match the whole attribute.
+            let pattern = format!("^(?:{})$", rule.pattern);
+            let pattern = Regex::new(&pattern)
+                .map_err(|source| RuleError::InvalidPattern { index, source })?;
+            rules.push(CompiledRule {
+                attribute: rule.attribute,
+                entity: rule.entity.clone(),
+                pattern,
+            });
+        }
+        Ok(Rules { rules })
+    }
+
+    /// Determine the entity for the certificate from the first matching rule.
+    pub fn entity(&self, cert: &PeerCertificate) -> Option<Entity> {
+        self.rules.iter().find_map(|rule| rule.entity(cert))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_auth::identity::PeerCertificate;
+    use replicore_auth::Entity;
+
+    use super::Rules;
+    use crate::conf::RuleError;
+    use crate::Conf;
+
+    fn compile(rules: serde_json::Value) -> Result<Rules, RuleError> {
+        let conf: Conf = serde_json::from_value(serde_json::json!({ "rules": rules })).unwrap();
+        Rules::compile(&conf)
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let error = compile(serde_json::json!([])).unwrap_err();
+        assert!(matches!(error, RuleError::NoRules));
+
+        let error = compile(serde_json::json!([{
+            "attribute": "common_name",
+            "pattern": "(unclosed",
+            "entity": {"kind": "user", "user_id": "$1"},
+        }]))
+        .unwrap_err();
+        assert!(matches!(error, RuleError::InvalidPattern { index: 0, .. }));
+
+        let error = compile(serde_json::json!([{
+            "attribute": "common_name",
+            "pattern": ".*",
+            "entity": {"kind": "service", "service_id": ""},
+        }]))
+        .unwrap_err();
+        assert!(matches!(error, RuleError::EmptyEntityId(0)));
+    }
+
+    #[test]
+    fn empty_entity_id_does_not_match() {
+        let rules = compile(serde_json::json!([
+            {
+                "attribute": "common_name",
+                "pattern": "(.*)\\.users\\.example\\.com|(?<other>.+)",
+                "entity": {"kind": "user", "user_id": "$1"},
+            },
+            {
+                "attribute": "dns_name",
+                "pattern": "(?<service>[a-z]+)?\\.svc\\.example\\.com",
+                "entity": {"kind": "service", "service_id": "${service}"},
+            },
+            {
+                "attribute": "dns_name",
+                "pattern": ".*",
+                "entity": {"kind": "service", "service_id": "fallback"},
+            },
+        ]))
+        .unwrap();
+
+        // Empty captures fall through to later values and rules.
+        let cert = PeerCertificate {
+            common_name: Some(".users.example.com".into()),
+            dns_names: vec![".svc.example.com".into(), "ci.svc.example.com".into()],
+            ..Default::default()
+        };
+        match rules.entity(&cert) {
+            Some(Entity::Service(service)) => assert_eq!(service.service_id, "ci"),
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        // Groups that did not participate in the match expand to nothing.
+        let cert = PeerCertificate {
+            common_name: Some("alice".into()),
+            dns_names: vec![".svc.example.com".into()],
+            ..Default::default()
+        };
+        match rules.entity(&cert) {
+            Some(Entity::Service(service)) => assert_eq!(service.service_id, "fallback"),
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        let cert = PeerCertificate {
+            common_name: Some("alice".into()),
+            ..Default::default()
+        };
+        assert!(rules.entity(&cert).is_none());
+    }
+
+    #[test]
+    fn match_first_rule() {
+        let rules = compile(serde_json::json!([
+            {
+                "attribute": "uri",
+                "pattern": "spiffe://example.com/(?<service>[a-z]+)",
+                "entity": {"kind": "service", "service_id": "${service}"},
+            },
+            {
+                "attribute": "common_name",
+                "pattern": "(.+)\\.users\\.example\\.com",
+                "entity": {"kind": "user", "user_id": "$1"},
+            },
+        ]))
+        .unwrap();
+
+        let cert = PeerCertificate {
+            common_name: Some("alice.users.example.com".into()),
+            uris: vec!["https://other.com".into(), "spiffe://example.com/ci".into()],
+            ..Default::default()
+        };
+        match rules.entity(&cert) {
+            Some(Entity::Service(service)) => assert_eq!(service.service_id, "ci"),
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        let cert = PeerCertificate {
+            common_name: Some("alice.users.example.com".into()),
+            ..Default::default()
+        };
+        match rules.entity(&cert) {
+            Some(Entity::User(user)) => assert_eq!(user.user_id, "alice"),
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        // Patterns must match the whole attribute.
+        let cert = PeerCertificate {
+            common_name: Some("alice.users.example.com.evil.com".into()),
+            ..Default::default()
+        };
+        assert!(rules.entity(&cert).is_none());
+    }
+}
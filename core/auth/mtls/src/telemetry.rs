@@ -0,0 +1,30 @@
+//! Telemetry related to the mutual TLS authentication backend.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::Counter;
+
+/// Number of requests rejected because client certificates matched no rule.
+pub static REJECTED_COUNT: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_auth_mtls_rejected_count",
+        "Number of requests rejected because client certificates matched no rule",
+    )
+    .expect("failed to initialise REJECTED_COUNT counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the mutual TLS metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    reg.register(Box::new(REJECTED_COUNT.clone()))?;
+    Ok(())
+}
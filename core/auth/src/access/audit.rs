@@ -9,6 +9,7 @@ use serde::Serialize;
 use replisdk::core::models::auth::AuthContext;
 
 use replicore_events::Event;
+use replicore_events::EventCode;
 
 use super::Forbidden;
 use crate::Action;
@@ -18,6 +19,16 @@ use crate::Resource;
 /// Event code for audit authorisation events.
 pub const AUDIT_AUTHORISATION: &str = "AUDIT_AUTHORISATION";
 
+/// JSON Schema for [`Audit`] event payloads.
+const AUDIT_SCHEMA: &str = include_str!("audit.schema.json");
+
+/// Event codes emitted by the authorisation process.
+pub fn event_codes() -> Vec<EventCode> {
+    let audit = EventCode::audit(AUDIT_AUTHORISATION, "Audit", "Authorisation decision made")
+        .schema(AUDIT_SCHEMA);
+    vec![audit]
+}
+
 /// Payload for Audit events.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Audit {
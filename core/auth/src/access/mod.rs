@@ -12,6 +12,7 @@ mod audit;
 #[cfg(test)]
 mod test;
 
+pub use self::audit::event_codes;
 pub use self::audit::Audit;
 pub use self::audit::AuditDecision;
 pub use self::audit::AUDIT_AUTHORISATION;
@@ -8,6 +8,13 @@ use replicore_context::Context;
 
 use super::Entity;
 
+mod peer;
+
+pub use self::peer::PeerCertificate;
+
+#[cfg(all(feature = "actix-web", feature = "openssl"))]
+pub use self::peer::actix_peer_certificate;
+
 /// Operations implemented by Authentication mechanisms and services supported by Replicante Core.
 #[async_trait::async_trait]
 pub trait Authentication: Send + Sync {
@@ -44,6 +51,9 @@ pub trait AuthenticationFactory: Send + Sync {
 pub struct AuthenticationFactoryArgs<'a> {
     /// The configuration block for the backend to initialise.
     pub conf: &'a Json,
+
+    /// Container for operation scoped values.
+    pub context: &'a Context,
 }
 
 /// Determine the [`Entity`] requesting actions in a trusted way.
@@ -76,6 +86,28 @@ where
     }
 }
 
+/// Identity information is attached to a request but it is not valid.
+///
+/// [`Authentication`] implementations should return this error so requests can be rejected
+/// as unauthenticated instead of failing with a generic error.
+#[derive(Debug, thiserror::Error)]
+#[error("unable to authenticate the request: {reason}")]
+pub struct Unauthenticated {
+    reason: String,
+}
+
+impl Unauthenticated {
+    /// Reject a request as unauthenticated for the given reason.
+    pub fn reason<S>(reason: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Unauthenticated {
+            reason: reason.into(),
+        }
+    }
+}
+
 /// Read identity information to discover and verify [`Entity`]s from a variety of sources.
 pub trait IdentityReader {
     /// Look for a metadata value with the given key.
@@ -85,6 +117,13 @@ pub trait IdentityReader {
     ///
     /// For example in HTTP(S) requests metadata should be extracted from headers.
     fn metadata(&self, name: &str) -> Result<Option<&str>>;
+
+    /// Details about the verified certificate presented by the client, if any.
+    ///
+    /// For example in HTTPS requests this is the client certificate verified with mutual TLS.
+    fn peer_certificate(&self) -> Option<&PeerCertificate> {
+        None
+    }
 }
 
 #[cfg(feature = "actix-web")]
@@ -98,4 +137,8 @@ impl IdentityReader for actix_web::HttpRequest {
             }
         }
     }
+
+    fn peer_certificate(&self) -> Option<&PeerCertificate> {
+        self.conn_data::<PeerCertificate>()
+    }
 }
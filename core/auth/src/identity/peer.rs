@@ -0,0 +1,121 @@
+//! Details about the certificate presented by the peer of a TLS connection.
+
+/// Details about the verified certificate presented by a client over mutual TLS.
+///
+/// Certificate details are only available for requests received over TLS connections
+/// where the server was configured to verify client certificates.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PeerCertificate {
+    /// Common Name (CN) attribute of the certificate subject, if set.
+    pub common_name: Option<String>,
+
+    /// DNS names listed in the Subject Alternative Name (SAN) extension.
+    pub dns_names: Vec<String>,
+
+    /// Email addresses listed in the Subject Alternative Name (SAN) extension.
+    pub emails: Vec<String>,
+
+    /// URIs listed in the Subject Alternative Name (SAN) extension.
+    pub uris: Vec<String>,
+}
+
+#[cfg(feature = "openssl")]
+impl PeerCertificate {
+    /// Extract certificate details from an OpenSSL X509 certificate.
+    pub fn from_x509(cert: &openssl::x509::X509Ref) -> PeerCertificate {
+        let common_name = cert
+            .subject_name()
+            .entries_by_nid(openssl::nid::Nid::COMMONNAME)
+            .next()
+            .and_then(|entry| entry.data().as_utf8().ok())
+            .map(|name| name.to_string());
+        let mut peer = PeerCertificate {
+            common_name,
+            ..Default::default()
+        };
+        let names = match cert.subject_alt_names() {
+            None => return peer,
+            Some(names) => names,
+        };
+        for name in names.iter() {
+            if let Some(dns) = name.dnsname() {
+                peer.dns_names.push(dns.to_string());
+            }
+            if let Some(email) = name.email() {
+                peer.emails.push(email.to_string());
+            }
+            if let Some(uri) = name.uri() {
+                peer.uris.push(uri.to_string());
+            }
+        }
+        peer
+    }
+}
+
+/// Attach the client certificate of OpenSSL connections to the connection data.
+///
+/// Use this function with [`actix_web::HttpServer::on_connect`] so the certificate
+/// is available to [`Authentication`](super::Authentication) implementations
+/// with [`IdentityReader::peer_certificate`](super::IdentityReader::peer_certificate).
+#[cfg(all(feature = "actix-web", feature = "openssl"))]
+pub fn actix_peer_certificate(
+    connection: &dyn std::any::Any,
+    data: &mut actix_web::dev::Extensions,
+) {
+    type TlsStream = actix_tls::accept::openssl::TlsStream<actix_web::rt::net::TcpStream>;
+    let stream = match connection.downcast_ref::<TlsStream>() {
+        None => return,
+        Some(stream) => stream,
+    };
+    if let Some(cert) = stream.ssl().peer_certificate() {
+        data.insert(PeerCertificate::from_x509(&cert));
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::X509NameBuilder;
+    use openssl::x509::X509;
+
+    use super::PeerCertificate;
+
+    fn certificate() -> X509 {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "alice").unwrap();
+        let name = name.build();
+
+        let mut cert = X509::builder().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_subject_name(&name).unwrap();
+        cert.set_issuer_name(&name).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns("ci.example.com")
+            .email("alice@example.com")
+            .uri("spiffe://example.com/ci")
+            .build(&cert.x509v3_context(None, None))
+            .unwrap();
+        cert.append_extension(san).unwrap();
+        cert.sign(&key, MessageDigest::sha256()).unwrap();
+        cert.build()
+    }
+
+    #[test]
+    fn extract_x509_details() {
+        let peer = PeerCertificate::from_x509(&certificate());
+        assert_eq!(peer.common_name.as_deref(), Some("alice"));
+        assert_eq!(peer.dns_names, vec!["ci.example.com"]);
+        assert_eq!(peer.emails, vec!["alice@example.com"]);
+        assert_eq!(peer.uris, vec!["spiffe://example.com/ci"]);
+    }
+}
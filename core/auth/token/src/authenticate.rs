@@ -0,0 +1,277 @@
+//! Authenticate requests with static API tokens.
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::Weak;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::time::MissedTickBehavior;
+
+use replisdk::utils::error::slog::ErrorAttributes;
+
+use replicore_auth::identity::Authentication;
+use replicore_auth::identity::IdentityReader;
+use replicore_auth::identity::Unauthenticated;
+use replicore_auth::Entity;
+use replicore_context::Context;
+
+use crate::tokens::TokenSet;
+use crate::Conf;
+
+/// Request metadata carrying the API token.
+pub const AUTHORIZATION_HEADER: &str = "Authorization";
+
+/// Implementation of the [`Authentication`] interface with static API tokens.
+///
+/// Requests present tokens with the `Authorization: Bearer <token>` header.
+/// Requests without the header are rejected unless anonymous requests are allowed,
+/// in which case they are authenticated as [`Entity::Anonymous`].
+#[derive(Clone)]
+pub struct TokenAuthentication {
+    allow_anonymous: bool,
+    tokens: Arc<RwLock<TokenSet>>,
+}
+
+impl TokenAuthentication {
+    /// Authenticate requests against the given set of tokens.
+    pub fn new(tokens: TokenSet) -> TokenAuthentication {
+        let tokens = Arc::new(RwLock::new(tokens));
+        TokenAuthentication {
+            allow_anonymous: false,
+            tokens,
+        }
+    }
+
+    /// Authenticate requests without a token as [`Entity::Anonymous`] instead of rejecting them.
+    pub fn allow_anonymous(&mut self, allow: bool) -> &mut Self {
+        self.allow_anonymous = allow;
+        self
+    }
+
+    /// Reload tokens in the background when the tokens file changes.
+    ///
+    /// Reloading stops once all clones of the [`TokenAuthentication`] object are dropped.
+    /// Tokens that fail to load are reported and the previously loaded tokens are kept.
+    pub fn watch(&self, context: &Context, conf: Conf) {
+        if conf.file.is_none() {
+            return;
+        }
+        let context = context.clone();
+        let tokens = Arc::downgrade(&self.tokens);
+        tokio::spawn(reload(context, conf, tokens));
+    }
+}
+
+#[async_trait::async_trait]
+impl Authentication for TokenAuthentication {
+    async fn authenticate(&self, _: &Context, transport: &dyn IdentityReader) -> Result<Entity> {
+        let header = match transport.metadata(AUTHORIZATION_HEADER)? {
+            None if self.allow_anonymous => return Ok(Entity::Anonymous),
+            None => {
+                crate::telemetry::REJECTED_COUNT.inc();
+                anyhow::bail!(Unauthenticated::reason("the request has no API token"))
+            }
+            Some(header) => header,
+        };
+        let token = match header.split_once(' ') {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => token.trim(),
+            _ => {
+                crate::telemetry::REJECTED_COUNT.inc();
+                let error = Unauthenticated::reason("only bearer tokens are supported");
+                anyhow::bail!(error);
+            }
+        };
+
+        let tokens = self
+            .tokens
+            .read()
+            .expect("TokenAuthentication::tokens lock poisoned");
+        match tokens.verify(token) {
+            Some(entity) => Ok(entity.clone()),
+            None => {
+                crate::telemetry::REJECTED_COUNT.inc();
+                anyhow::bail!(Unauthenticated::reason("the API token is not valid"))
+            }
+        }
+    }
+}
+
+/// Periodically check the tokens file for changes and reload tokens when it does.
+async fn reload(context: Context, conf: Conf, tokens: Weak<RwLock<TokenSet>>) {
+    let path = match &conf.file {
+        None => return,
+        Some(path) => path.clone(),
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs(conf.reload_interval.max(1)));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let tokens = match tokens.upgrade() {
+            None => return,
+            Some(tokens) => tokens,
+        };
+        let loaded = tokens
+            .read()
+            .expect("TokenAuthentication::tokens lock poisoned")
+            .file_modified;
+
+        let result = async {
+            let modified = crate::tokens::modified(&path).await?;
+            if Some(modified) == loaded {
+                return Ok(false);
+            }
+            let update = TokenSet::load(&conf).await?;
+            *tokens
+                .write()
+                .expect("TokenAuthentication::tokens lock poisoned") = update;
+            Ok::<bool, anyhow::Error>(true)
+        };
+        match result.await {
+            Ok(false) => (),
+            Ok(true) => slog::info!(context.logger, "Reloaded API tokens"; "file" => &path),
+            Err(error) => {
+                crate::telemetry::RELOAD_ERR.inc();
+                slog::warn!(
+                    context.logger, "Unable to reload API tokens, previous tokens are kept";
+                    "file" => &path,
+                    ErrorAttributes::from(&error),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use anyhow::Result;
+
+    use replicore_auth::identity::Authentication;
+    use replicore_auth::identity::IdentityReader;
+    use replicore_auth::identity::Unauthenticated;
+    use replicore_auth::Entity;
+    use replicore_context::Context;
+
+    use super::TokenAuthentication;
+    use crate::tokens::tests::tokens_file;
+    use crate::tokens::tests::tokens_yaml;
+    use crate::tokens::TokenSet;
+    use crate::Conf;
+
+    /// Request metadata for tests.
+    struct Request(HashMap<String, String>);
+
+    impl Request {
+        fn bearer(token: &str) -> Request {
+            Request::with_header(&format!("Bearer {}", token))
+        }
+
+        fn with_header(header: &str) -> Request {
+            let mut metadata = HashMap::new();
+            metadata.insert(super::AUTHORIZATION_HEADER.to_string(), header.to_string());
+            Request(metadata)
+        }
+    }
+
+    impl IdentityReader for Request {
+        fn metadata(&self, name: &str) -> Result<Option<&str>> {
+            Ok(self.0.get(name).map(String::as_str))
+        }
+    }
+
+    async fn authentication(conf: serde_json::Value) -> TokenAuthentication {
+        let conf: Conf = serde_json::from_value(conf).unwrap();
+        let tokens = TokenSet::load(&conf).await.unwrap();
+        let auth = TokenAuthentication::new(tokens);
+        auth.watch(&Context::fixture(), conf);
+        auth
+    }
+
+    #[tokio::test]
+    async fn authenticate_tokens() {
+        let context = Context::fixture();
+        let conf = serde_json::json!({
+            "tokens": [{
+                "entity": {"kind": "service", "service_id": "ci"},
+                "hash": crate::hash_token("ci-token"),
+            }],
+        });
+        let auth = authentication(conf).await;
+
+        let entity = auth
+            .authenticate(&context, &Request::bearer("ci-token"))
+            .await
+            .unwrap();
+        assert!(matches!(entity, Entity::Service(_)));
+
+        let error = auth
+            .authenticate(&context, &Request::bearer("wrong-token"))
+            .await
+            .unwrap_err();
+        assert!(error.is::<Unauthenticated>());
+        let error = auth
+            .authenticate(&context, &Request::with_header("Basic Y2k6dG9rZW4="))
+            .await
+            .unwrap_err();
+        assert!(error.is::<Unauthenticated>());
+    }
+
+    #[tokio::test]
+    async fn missing_token() {
+        let context = Context::fixture();
+        let mut auth = authentication(serde_json::json!({})).await;
+        let error = auth
+            .authenticate(&context, &Request(HashMap::new()))
+            .await
+            .unwrap_err();
+        assert!(error.is::<Unauthenticated>());
+
+        auth.allow_anonymous(true);
+        let entity = auth
+            .authenticate(&context, &Request(HashMap::new()))
+            .await
+            .unwrap();
+        assert!(matches!(entity, Entity::Anonymous));
+    }
+
+    #[tokio::test]
+    async fn reload_changed_file() {
+        let context = Context::fixture();
+        let path = tokens_file();
+        std::fs::write(&path, tokens_yaml(&[("alice", "old-token")])).unwrap();
+        let conf = serde_json::json!({
+            "file": path.to_string_lossy(),
+            "reload_interval": 1,
+        });
+        let auth = authentication(conf).await;
+        let entity = auth
+            .authenticate(&context, &Request::bearer("old-token"))
+            .await
+            .unwrap();
+        assert!(matches!(entity, Entity::User(_)));
+
+        // Ensure the modification time changes even on coarse grained file systems.
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        std::fs::write(&path, tokens_yaml(&[("alice", "new-token")])).unwrap();
+        let reloaded = async {
+            loop {
+                let request = Request::bearer("new-token");
+                if auth.authenticate(&context, &request).await.is_ok() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), reloaded)
+            .await
+            .unwrap();
+        let error = auth
+            .authenticate(&context, &Request::bearer("old-token"))
+            .await
+            .unwrap_err();
+        assert!(error.is::<Unauthenticated>());
+        std::fs::remove_file(path).unwrap();
+    }
+}
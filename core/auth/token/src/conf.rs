@@ -0,0 +1,144 @@
+//! Configuration for the static API tokens authentication backend.
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use replicore_auth::Entity;
+
+/// Static API tokens specific configuration for the authentication interface.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// Authenticate requests without an API token as the anonymous entity.
+    ///
+    /// Requests without a token are rejected unless this is set.
+    #[serde(default)]
+    pub allow_anonymous: bool,
+
+    /// Path to a YAML file with a list of additional tokens, reloaded when it changes.
+    #[serde(default)]
+    pub file: Option<String>,
+
+    /// Seconds to wait between checks for changes to the tokens file.
+    #[serde(default = "Conf::default_reload_interval")]
+    pub reload_interval: u64,
+
+    /// Tokens accepted by the Control Plane.
+    #[serde(default)]
+    pub tokens: Vec<TokenConf>,
+}
+
+impl Conf {
+    fn default_reload_interval() -> u64 {
+        30
+    }
+
+    /// Check the configuration for errors that can't be detected while decoding it.
+    pub fn validate(&self) -> Result<(), TokenError> {
+        if matches!(&self.file, Some(file) if file.is_empty()) {
+            return Err(TokenError::EmptyFilePath);
+        }
+        validate_tokens(&self.tokens)
+    }
+}
+
+/// Token accepted by the Control Plane and the entity it authenticates as.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenConf {
+    /// Entity requests with the token are authenticated as.
+    ///
+    /// Only user and service entities can authenticate with tokens.
+    pub entity: Entity,
+
+    /// Hex encoded SHA-256 hash of the token.
+    pub hash: String,
+}
+
+impl TokenConf {
+    fn validate(&self) -> Result<(), TokenError> {
+        let valid_hash = self.hash.len() == 64 && self.hash.chars().all(|c| c.is_ascii_hexdigit());
+        if !valid_hash {
+            return Err(TokenError::InvalidHash(self.entity.to_string()));
+        }
+        if !matches!(self.entity, Entity::Service(_) | Entity::User(_)) {
+            return Err(TokenError::UnsupportedEntity(self.entity.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Check a list of tokens is valid, including across tokens.
+pub fn validate_tokens(tokens: &[TokenConf]) -> Result<(), TokenError> {
+    let mut hashes = HashSet::new();
+    for token in tokens {
+        token.validate()?;
+        if !hashes.insert(token.hash.to_ascii_lowercase()) {
+            return Err(TokenError::DuplicateHash(token.entity.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// The static API tokens authentication configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the static API tokens authentication configuration is not valid")]
+pub struct ConfError;
+
+/// A token configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+pub enum TokenError {
+    /// Each token must authenticate a single entity.
+    #[error("token for entity '{0}' has the same hash as another token")]
+    DuplicateHash(String),
+
+    /// A tokens file path was given but it is empty.
+    #[error("the tokens file path must not be empty")]
+    EmptyFilePath,
+
+    /// Token hashes must be hex encoded SHA-256 hashes.
+    #[error("token for entity '{0}' must have a hex encoded SHA-256 hash")]
+    InvalidHash(String),
+
+    /// Tokens can only authenticate users and services.
+    #[error("tokens can only authenticate users and services, not entity '{0}'")]
+    UnsupportedEntity(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Conf;
+    use super::TokenError;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn with_tokens(tokens: serde_json::Value) -> Conf {
+        serde_json::from_value(serde_json::json!({ "tokens": tokens })).unwrap()
+    }
+
+    #[test]
+    fn validate_tokens() {
+        let conf = with_tokens(serde_json::json!([
+            {"entity": {"kind": "user", "user_id": "alice"}, "hash": HASH},
+            {"entity": {"kind": "service", "service_id": "ci"}, "hash": HASH.to_uppercase()},
+        ]));
+        let error = conf.validate().unwrap_err();
+        assert!(matches!(error, TokenError::DuplicateHash(_)));
+
+        let conf = with_tokens(serde_json::json!([
+            {"entity": {"kind": "user", "user_id": "alice"}, "hash": "not-a-hash"},
+        ]));
+        let error = conf.validate().unwrap_err();
+        assert!(matches!(error, TokenError::InvalidHash(_)));
+
+        let conf = with_tokens(serde_json::json!([
+            {"entity": {"kind": "system", "component": "core"}, "hash": HASH},
+        ]));
+        let error = conf.validate().unwrap_err();
+        assert!(matches!(error, TokenError::UnsupportedEntity(_)));
+
+        let conf = with_tokens(serde_json::json!([
+            {"entity": {"kind": "user", "user_id": "alice"}, "hash": HASH},
+        ]));
+        assert!(conf.validate().is_ok());
+    }
+}
@@ -0,0 +1,42 @@
+//! Factory for the static API tokens authentication backend.
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_auth::identity::AuthenticationFactory;
+use replicore_auth::identity::AuthenticationFactoryArgs;
+use replicore_auth::identity::Authenticator;
+use replicore_context::Context;
+
+use crate::tokens::TokenSet;
+use crate::Conf;
+use crate::ConfError;
+use crate::TokenAuthentication;
+
+/// Initialise static API tokens authentication.
+pub struct TokenFactory;
+
+#[async_trait::async_trait]
+impl AuthenticationFactory for TokenFactory {
+    fn conf_check(&self, _: &Context, conf: &Json) -> Result<()> {
+        let conf: Conf = serde_json::from_value(conf.clone()).context(ConfError)?;
+        conf.validate().context(ConfError)?;
+        Ok(())
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        crate::telemetry::register_metrics(registry)
+    }
+
+    async fn authenticator<'a>(
+        &self,
+        args: AuthenticationFactoryArgs<'a>,
+    ) -> Result<Authenticator> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        let tokens = TokenSet::load(&conf).await?;
+        let mut authentication = TokenAuthentication::new(tokens);
+        authentication.allow_anonymous(conf.allow_anonymous);
+        authentication.watch(args.context, conf);
+        Ok(Authenticator::from(authentication))
+    }
+}
@@ -0,0 +1,33 @@
+//! Authenticate requests to the Control Plane with static API tokens.
+//!
+//! Clients present tokens as bearer tokens with the `Authorization` header.
+//! Tokens are verified against a list of SHA-256 hashes, each mapped to
+//! the user or service entity the token authenticates as.
+//! The Control Plane never needs to know the tokens themselves.
+//!
+//! Requests without an `Authorization` header are rejected as unauthenticated (HTTP 401).
+//! Set the `allow_anonymous` option to authenticate them as the anonymous entity instead,
+//! in which case access to the Control Plane is restricted only by the authorisation backend.
+//! Telemetry endpoints, such as `/metrics`, are served without authentication in either case.
+//!
+//! ## Tokens file
+//!
+//! Tokens can be listed in the configuration or in a YAML file with the same format.
+//! The file is checked for changes periodically and tokens are reloaded when it changes,
+//! so tokens can be issued and revoked without restarting the process.
+mod authenticate;
+mod conf;
+mod factory;
+mod telemetry;
+mod tokens;
+
+pub use self::authenticate::TokenAuthentication;
+pub use self::authenticate::AUTHORIZATION_HEADER;
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::conf::TokenConf;
+pub use self::conf::TokenError;
+pub use self::factory::TokenFactory;
+pub use self::tokens::hash_token;
+pub use self::tokens::TokenSet;
+pub use self::tokens::TokensFileError;
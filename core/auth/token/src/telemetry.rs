@@ -0,0 +1,45 @@
+//! Telemetry related to the static API tokens authentication backend.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::Counter;
+
+/// Number of requests rejected because of invalid API tokens.
+pub static REJECTED_COUNT: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_auth_token_rejected_count",
+        "Number of requests rejected because of invalid API tokens",
+    )
+    .expect("failed to initialise REJECTED_COUNT counter")
+});
+
+/// Number of attempts to reload the tokens file that resulted in error.
+pub static RELOAD_ERR: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_auth_token_reload_error",
+        "Number of attempts to reload the tokens file that resulted in error",
+    )
+    .expect("failed to initialise RELOAD_ERR counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the static API tokens metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 2] = [
+        Box::new(REJECTED_COUNT.clone()),
+        Box::new(RELOAD_ERR.clone()),
+    ];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! Set of accepted tokens and the entities they authenticate as.
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use sha2::Digest;
+use sha2::Sha256;
+
+use replicore_auth::Entity;
+
+use crate::conf::TokenConf;
+use crate::Conf;
+use crate::ConfError;
+
+/// Compute the hex encoded SHA-256 hash of a token, as expected in the configuration.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Accepted tokens, indexed by their hash, and the entities they authenticate as.
+#[derive(Debug, Default)]
+pub struct TokenSet {
+    /// Entities to authenticate requests as, by token hash.
+    entities: HashMap<String, Entity>,
+
+    /// Modification time of the tokens file when it was loaded, if a file is configured.
+    pub file_modified: Option<SystemTime>,
+}
+
+impl TokenSet {
+    /// Load all tokens from the configuration and the tokens file, if any.
+    pub async fn load(conf: &Conf) -> Result<TokenSet> {
+        let mut tokens = conf.tokens.clone();
+        let mut file_modified = None;
+        if let Some(path) = &conf.file {
+            file_modified = Some(modified(path).await?);
+            let file = tokio::fs::read(path)
+                .await
+                .with_context(|| TokensFileError(path.clone()))?;
+            let file: Vec<TokenConf> =
+                serde_yaml::from_slice(&file).with_context(|| TokensFileError(path.clone()))?;
+            tokens.extend(file);
+        }
+        crate::conf::validate_tokens(&tokens).context(ConfError)?;
+
+        let entities = tokens
+            .into_iter()
+            .map(|token| (token.hash.to_ascii_lowercase(), token.entity))
+            .collect();
+        Ok(TokenSet {
+            entities,
+            file_modified,
+        })
+    }
+
+    /// Lookup the entity a token authenticates as, if the token is accepted.
+    pub fn verify(&self, token: &str) -> Option<&Entity> {
+        self.entities.get(&hash_token(token))
+    }
+}
+
+/// Modification time of the tokens file, to detect changes.
+pub async fn modified(path: &str) -> Result<SystemTime> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .with_context(|| TokensFileError(path.to_string()))?;
+    let modified = metadata
+        .modified()
+        .with_context(|| TokensFileError(path.to_string()))?;
+    Ok(modified)
+}
+
+/// Unable to load tokens from the tokens file.
+#[derive(Debug, thiserror::Error)]
+#[error("unable to load tokens from file '{0}'")]
+pub struct TokensFileError(String);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::path::PathBuf;
+
+    use replicore_auth::Entity;
+
+    use super::hash_token;
+    use super::TokenSet;
+    use crate::Conf;
+
+    /// Create a unique path for a tokens file used by a test.
+    pub fn tokens_file() -> PathBuf {
+        let file = format!("replicore-auth-token-{}.yaml", uuid::Uuid::new_v4());
+        std::env::temp_dir().join(file)
+    }
+
+    /// Encode a tokens file with a user for each of the given tokens.
+    pub fn tokens_yaml(tokens: &[(&str, &str)]) -> String {
+        let mut yaml = String::new();
+        for (user, token) in tokens {
+            yaml.push_str(&format!(
+                "- entity: {{kind: user, user_id: {}}}\n  hash: {}\n",
+                user,
+                hash_token(token),
+            ));
+        }
+        yaml
+    }
+
+    #[test]
+    fn hash_tokens() {
+        assert_eq!(
+            hash_token("test"),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+        );
+    }
+
+    #[tokio::test]
+    async fn load_conf_and_file() {
+        let path = tokens_file();
+        std::fs::write(&path, tokens_yaml(&[("bob", "file-token")])).unwrap();
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "file": path.to_string_lossy(),
+            "tokens": [{
+                "entity": {"kind": "service", "service_id": "ci"},
+                "hash": hash_token("conf-token"),
+            }],
+        }))
+        .unwrap();
+
+        let tokens = TokenSet::load(&conf).await.unwrap();
+        assert!(tokens.file_modified.is_some());
+        assert!(matches!(
+            tokens.verify("conf-token"),
+            Some(Entity::Service(_))
+        ));
+        assert!(matches!(tokens.verify("file-token"), Some(Entity::User(_))));
+        assert!(tokens.verify("unknown-token").is_none());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,11 +1,16 @@
 //! Replicante Core configuration object and helpers.
 mod loading;
 mod object;
+mod outbox;
 mod runtime;
+mod scheduler;
 
 pub use self::loading::load;
 pub use self::loading::Error;
 pub use self::object::BackendConf;
 pub use self::object::Conf;
 pub use self::object::TasksConf;
+pub use self::outbox::OutboxConf;
 pub use self::runtime::RuntimeConf;
+pub use self::scheduler::SchedulerConf;
+pub use self::scheduler::SchedulerNamespaceConf;
@@ -7,11 +7,20 @@ use replisdk::runtime::telemetry::TelemetryConfig;
 
 use replicore_tasks::conf::TasksExecutorConf;
 
+use super::OutboxConf;
 use super::RuntimeConf;
+use super::SchedulerConf;
 
 /// Global configuration for the Replicante Core process.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Conf {
+    /// Authentication of API requests configuration.
+    #[serde(default = "Conf::default_authentication")]
+    pub authentication: BackendConf,
+
+    /// Distributed Coordination service configuration.
+    pub coordinator: BackendConf,
+
     /// Events Streaming Platform service configuration.
     pub events: BackendConf,
 
@@ -19,10 +28,18 @@ pub struct Conf {
     #[serde(default)]
     pub http: ServerConfig,
 
+    /// Transactional outbox for events emitted along with store changes.
+    #[serde(default)]
+    pub outbox: OutboxConf,
+
     /// Process runtime configuration.
     #[serde(default)]
     pub runtime: RuntimeConf,
 
+    /// Periodic scheduling of platform discovery and cluster orchestration.
+    #[serde(default)]
+    pub scheduler: SchedulerConf,
+
     /// Persistent Store service configuration.
     pub store: BackendConf,
 
@@ -34,6 +51,15 @@ pub struct Conf {
     pub telemetry: TelemetryConfig,
 }
 
+impl Conf {
+    fn default_authentication() -> BackendConf {
+        BackendConf {
+            backend: "anonymous".into(),
+            options: serde_json::Value::Object(Default::default()),
+        }
+    }
+}
+
 /// Unstructured configuration for runtime selected service backends.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BackendConf {
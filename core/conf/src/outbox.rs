@@ -0,0 +1,54 @@
+//! Configuration of the transactional outbox for events emitted along with store changes.
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Configuration of the transactional outbox for events emitted along with store changes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutboxConf {
+    /// Maximum number of outbox events published by each run of the relay loop.
+    #[serde(default = "OutboxConf::default_batch_size")]
+    pub batch_size: usize,
+
+    /// Add events to the outbox in the same store transaction as the change they describe.
+    ///
+    /// When disabled, events are emitted directly before the change is persisted.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Number of failed attempts to publish an event before it is dropped from the outbox.
+    ///
+    /// Dropped events are logged and counted so they can be recovered manually,
+    /// and the relay moves on to later events instead of blocking on them forever.
+    /// Set to 0 to retry failed events indefinitely.
+    #[serde(default = "OutboxConf::default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Interval, in seconds, between checks for outbox events when the outbox is empty.
+    #[serde(default = "OutboxConf::default_poll_interval")]
+    pub poll_interval_sec: u64,
+}
+
+impl Default for OutboxConf {
+    fn default() -> Self {
+        OutboxConf {
+            batch_size: OutboxConf::default_batch_size(),
+            enabled: false,
+            max_attempts: OutboxConf::default_max_attempts(),
+            poll_interval_sec: OutboxConf::default_poll_interval(),
+        }
+    }
+}
+
+impl OutboxConf {
+    fn default_batch_size() -> usize {
+        100
+    }
+
+    fn default_max_attempts() -> u32 {
+        10
+    }
+
+    fn default_poll_interval() -> u64 {
+        1
+    }
+}
@@ -0,0 +1,110 @@
+//! Configuration for the periodic scheduling of platform discovery and cluster orchestration.
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Configuration for the periodic scheduling of platform discovery and cluster orchestration.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerConf {
+    /// Interval, in seconds, between checks for platforms and clusters due for scheduling.
+    #[serde(default = "SchedulerConf::default_check_interval")]
+    pub check_interval_sec: u64,
+
+    /// Interval, in seconds, between discovery runs for each active platform.
+    #[serde(default = "SchedulerConf::default_discovery_interval")]
+    pub discovery_interval_sec: u64,
+
+    /// Enable periodic scheduling of platform discovery and cluster orchestration tasks.
+    #[serde(default = "SchedulerConf::default_enabled")]
+    pub enabled: bool,
+
+    /// Maximum random delay, in seconds, added to intervals to spread tasks over time.
+    #[serde(default = "SchedulerConf::default_jitter")]
+    pub jitter_sec: u64,
+
+    /// Per-namespace overrides of the scheduling configuration.
+    #[serde(default)]
+    pub namespaces: BTreeMap<String, SchedulerNamespaceConf>,
+
+    /// Interval, in seconds, between orchestration runs for each active cluster.
+    #[serde(default = "SchedulerConf::default_orchestrate_interval")]
+    pub orchestrate_interval_sec: u64,
+}
+
+impl Default for SchedulerConf {
+    fn default() -> Self {
+        SchedulerConf {
+            check_interval_sec: SchedulerConf::default_check_interval(),
+            discovery_interval_sec: SchedulerConf::default_discovery_interval(),
+            enabled: SchedulerConf::default_enabled(),
+            jitter_sec: SchedulerConf::default_jitter(),
+            namespaces: Default::default(),
+            orchestrate_interval_sec: SchedulerConf::default_orchestrate_interval(),
+        }
+    }
+}
+
+impl SchedulerConf {
+    fn default_check_interval() -> u64 {
+        10
+    }
+
+    fn default_discovery_interval() -> u64 {
+        300
+    }
+
+    fn default_enabled() -> bool {
+        true
+    }
+
+    fn default_jitter() -> u64 {
+        5
+    }
+
+    fn default_orchestrate_interval() -> u64 {
+        60
+    }
+
+    /// Interval, in seconds, between discovery runs of platforms in the given namespace.
+    pub fn discovery_interval(&self, ns_id: &str) -> u64 {
+        self.namespaces
+            .get(ns_id)
+            .and_then(|ns| ns.discovery_interval_sec)
+            .unwrap_or(self.discovery_interval_sec)
+    }
+
+    /// Check if periodic scheduling is enabled for the given namespace.
+    pub fn namespace_enabled(&self, ns_id: &str) -> bool {
+        self.namespaces
+            .get(ns_id)
+            .and_then(|ns| ns.enabled)
+            .unwrap_or(true)
+    }
+
+    /// Interval, in seconds, between orchestration runs of clusters in the given namespace.
+    pub fn orchestrate_interval(&self, ns_id: &str) -> u64 {
+        self.namespaces
+            .get(ns_id)
+            .and_then(|ns| ns.orchestrate_interval_sec)
+            .unwrap_or(self.orchestrate_interval_sec)
+    }
+}
+
+/// Namespace specific overrides of the scheduling configuration.
+///
+/// Options that are not set fall back to the global scheduler configuration.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerNamespaceConf {
+    /// Interval, in seconds, between discovery runs for each active platform in the namespace.
+    #[serde(default)]
+    pub discovery_interval_sec: Option<u64>,
+
+    /// Enable or disable periodic scheduling for the namespace.
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Interval, in seconds, between orchestration runs for each active cluster in the namespace.
+    #[serde(default)]
+    pub orchestrate_interval_sec: Option<u64>,
+}
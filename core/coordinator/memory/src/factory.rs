@@ -0,0 +1,41 @@
+//! Initialise the in-memory Coordinator backend.
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_coordinator::Coordinator;
+use replicore_coordinator::CoordinatorFactory;
+use replicore_coordinator::CoordinatorFactoryArgs;
+use replicore_coordinator::CoordinatorFactorySyncArgs;
+use replicore_coordinator::MemoryCoordinator;
+
+/// Initialise the in-memory Coordinator backend.
+///
+/// All [`Coordinator`]s created by the same factory share the same leases.
+#[derive(Clone, Default)]
+pub struct MemoryFactory {
+    coordinator: MemoryCoordinator,
+}
+
+#[async_trait::async_trait]
+impl CoordinatorFactory for MemoryFactory {
+    fn conf_check(&self, _: &Context, _: &Json) -> Result<()> {
+        Ok(())
+    }
+
+    async fn coordinator<'a>(&self, args: CoordinatorFactoryArgs<'a>) -> Result<Coordinator> {
+        slog::warn!(
+            args.context.logger,
+            "Using in-memory coordinator means only single process deployments are supported"
+        );
+        Ok(Coordinator::from(self.coordinator.clone()))
+    }
+
+    fn register_metrics(&self, _: &prometheus::Registry) -> Result<()> {
+        Ok(())
+    }
+
+    async fn sync<'a>(&self, _: CoordinatorFactorySyncArgs<'a>) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+//! Distributed coordination kept in the memory of the RepliCore process.
+//!
+//! This backend is intended for demos, tests and embedding the Control Plane:
+//!
+//! - Leases are lost as soon as the process terminates.
+//! - Leases are NOT shared across processes so only single process deployments are supported.
+//!
+//! The coordinator itself is implemented by [`replicore_coordinator::MemoryCoordinator`],
+//! which also backs the unit tests fixture.
+mod factory;
+
+pub use self::factory::MemoryFactory;
+pub use replicore_coordinator::MemoryCoordinator;
@@ -0,0 +1,25 @@
+//! Configuration for the SQLite coordinator backend.
+use serde::Deserialize;
+use serde::Serialize;
+
+/// SQLite specific configuration for the coordinator interface.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// Path to the SQLite DB file.
+    pub path: String,
+}
+
+impl Conf {
+    /// Initialise coordinator configuration with a SQLite path.
+    pub fn new<S>(path: S) -> Conf
+    where
+        S: Into<String>,
+    {
+        Conf { path: path.into() }
+    }
+}
+
+/// The SQLite coordinator backend configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the SQLite coordinator backend configuration is not valid")]
+pub struct ConfError;
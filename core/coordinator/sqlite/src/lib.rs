@@ -0,0 +1,10 @@
+//! Distributed coordination backend persisting leases in SQLite.
+mod conf;
+mod factory;
+mod schema;
+mod statements;
+mod telemetry;
+
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::factory::SQLiteFactory;
@@ -0,0 +1,10 @@
+-- Leases are never deleted, only expired, so fencing tokens keep increasing
+-- across holders even after a lease is released.
+CREATE TABLE IF NOT EXISTS coordinator_leases(
+  name TEXT PRIMARY KEY NOT NULL,
+  owner TEXT NOT NULL,
+  --  Fencing token, incremented every time the lease is acquired.
+  token INTEGER NOT NULL,
+  --  EPoc timestamp (in milliseconds) after which the lease can be acquired again.
+  expires_ms INTEGER NOT NULL
+);
@@ -0,0 +1,2 @@
+//! Coordinator schema utilities and migrations.
+refinery::embed_migrations!("src/migrations");
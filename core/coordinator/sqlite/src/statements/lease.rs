@@ -0,0 +1,338 @@
+//! Coordinator operations to acquire, renew and release leases.
+use std::time::Duration;
+
+use anyhow::Result;
+use opentelemetry_api::trace::FutureExt;
+use time::OffsetDateTime;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_coordinator::Lease;
+use replicore_coordinator::LeaseRequest;
+
+// Expired leases are taken over with a new fencing token, held leases are left untouched.
+const ACQUIRE_SQL: &str = r#"
+INSERT INTO coordinator_leases (name, owner, token, expires_ms)
+VALUES (?1, ?2, 1, ?4)
+ON CONFLICT(name) DO UPDATE SET
+    owner = excluded.owner,
+    token = coordinator_leases.token + 1,
+    expires_ms = excluded.expires_ms
+WHERE coordinator_leases.expires_ms <= ?3
+RETURNING name, owner, token, expires_ms;
+"#;
+
+const LOOKUP_SQL: &str = r#"
+SELECT name, owner, token, expires_ms
+FROM coordinator_leases
+WHERE
+    name = ?1 AND
+    expires_ms > ?2;
+"#;
+
+const RELEASE_SQL: &str = r#"
+UPDATE coordinator_leases
+SET expires_ms = 0
+WHERE
+    name = ?1 AND
+    owner = ?2 AND
+    token = ?3;
+"#;
+
+const RENEW_SQL: &str = r#"
+UPDATE coordinator_leases
+SET expires_ms = ?4
+WHERE
+    name = ?1 AND
+    owner = ?2 AND
+    token = ?3
+RETURNING name, owner, token, expires_ms;
+"#;
+
+/// SQL extracted lease object return from SQLite connection calls.
+#[derive(Debug)]
+struct SQLLease {
+    name: String,
+    owner: String,
+    token: u64,
+    expires_ms: i64,
+}
+
+impl SQLLease {
+    /// Extract a lease from an SQL result row.
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<SQLLease> {
+        let lease = SQLLease {
+            name: row.get("name")?,
+            owner: row.get("owner")?,
+            token: row.get("token")?,
+            expires_ms: row.get("expires_ms")?,
+        };
+        Ok(lease)
+    }
+
+    /// Decode SQL data into a [`Lease`] model.
+    fn decode(self) -> Result<Lease> {
+        let expires = i128::from(self.expires_ms) * 1_000_000;
+        let lease = Lease {
+            expires: OffsetDateTime::from_unix_timestamp_nanos(expires)?,
+            name: self.name,
+            owner: self.owner,
+            token: self.token,
+        };
+        Ok(lease)
+    }
+}
+
+/// Acquire a lease if it does not exist or has expired.
+pub async fn acquire(
+    _: &Context,
+    connection: &Connection,
+    request: LeaseRequest,
+) -> Result<Option<Lease>> {
+    let now = now_ms();
+    let expires = now + ttl_ms(request.ttl);
+    let (err_count, timer) = crate::telemetry::observe_op("lease.acquire");
+    let trace = crate::telemetry::trace_op("lease.acquire");
+    let lease = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(ACQUIRE_SQL)?;
+            let mut rows =
+                statement.query(rusqlite::params![request.name, request.owner, now, expires,])?;
+            let lease = match rows.next()? {
+                None => None,
+                Some(row) => Some(SQLLease::from_row(row)?),
+            };
+            Ok(lease)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    drop(timer);
+    lease.map(SQLLease::decode).transpose()
+}
+
+/// Lookup a lease by name, if it has not expired.
+pub async fn lookup(_: &Context, connection: &Connection, name: &str) -> Result<Option<Lease>> {
+    let name = name.to_string();
+    let now = now_ms();
+    let (err_count, timer) = crate::telemetry::observe_op("lease.lookup");
+    let trace = crate::telemetry::trace_op("lease.lookup");
+    let lease = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(LOOKUP_SQL)?;
+            let mut rows = statement.query(rusqlite::params![name, now])?;
+            let lease = match rows.next()? {
+                None => None,
+                Some(row) => Some(SQLLease::from_row(row)?),
+            };
+            Ok(lease)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    drop(timer);
+    lease.map(SQLLease::decode).transpose()
+}
+
+/// Release a lease, if still held by the same owner with the same fencing token.
+pub async fn release(_: &Context, connection: &Connection, lease: &Lease) -> Result<()> {
+    let lease = lease.clone();
+    let (err_count, _timer) = crate::telemetry::observe_op("lease.release");
+    let trace = crate::telemetry::trace_op("lease.release");
+    connection
+        .call(move |connection| {
+            connection.execute(
+                RELEASE_SQL,
+                rusqlite::params![lease.name, lease.owner, lease.token],
+            )?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+/// Extend a lease expiry, if still held by the same owner with the same fencing token.
+pub async fn renew(
+    _: &Context,
+    connection: &Connection,
+    lease: &Lease,
+    ttl: Duration,
+) -> Result<Option<Lease>> {
+    let lease = lease.clone();
+    let expires = now_ms() + ttl_ms(ttl);
+    let (err_count, timer) = crate::telemetry::observe_op("lease.renew");
+    let trace = crate::telemetry::trace_op("lease.renew");
+    let lease = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(RENEW_SQL)?;
+            let mut rows = statement.query(rusqlite::params![
+                lease.name,
+                lease.owner,
+                lease.token,
+                expires,
+            ])?;
+            let lease = match rows.next()? {
+                None => None,
+                Some(row) => Some(SQLLease::from_row(row)?),
+            };
+            Ok(lease)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    drop(timer);
+    lease.map(SQLLease::decode).transpose()
+}
+
+/// Current time as milliseconds since the UNIX epoch.
+fn now_ms() -> i64 {
+    let now = OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000;
+    now as i64
+}
+
+/// Lease TTL in milliseconds.
+fn ttl_ms(ttl: Duration) -> i64 {
+    i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use replicore_coordinator::Coordinator;
+
+    const TTL: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn acquire_lease() {
+        let backend = crate::statements::tests::sqlite_coordinator().await;
+        let context = replicore_context::Context::fixture();
+        let coordinator = Coordinator::from(backend);
+
+        let lease = coordinator
+            .lease_acquire(&context, "test", TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.name, "test");
+        assert_eq!(lease.owner, coordinator.owner());
+        assert_eq!(lease.token, 1);
+    }
+
+    #[tokio::test]
+    async fn acquire_held_lease() {
+        let backend = crate::statements::tests::sqlite_coordinator().await;
+        let context = replicore_context::Context::fixture();
+        let one = Coordinator::from(backend.clone());
+        let two = Coordinator::from(backend);
+
+        let lease = one.lease_acquire(&context, "test", TTL).await.unwrap();
+        assert!(lease.is_some());
+        let lease = one.lease_acquire(&context, "test", TTL).await.unwrap();
+        assert!(lease.is_none());
+        let lease = two.lease_acquire(&context, "test", TTL).await.unwrap();
+        assert!(lease.is_none());
+    }
+
+    #[tokio::test]
+    async fn acquire_expired_lease() {
+        let backend = crate::statements::tests::sqlite_coordinator().await;
+        let context = replicore_context::Context::fixture();
+        let one = Coordinator::from(backend.clone());
+        let two = Coordinator::from(backend);
+
+        let old = one
+            .lease_acquire(&context, "test", TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(TTL).await;
+        let new = two
+            .lease_acquire(&context, "test", TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(new.owner, two.owner());
+        assert_eq!(new.token, old.token + 1);
+
+        // The old holder has been fenced off.
+        let renewed = one.lease_renew(&context, &old, TTL).await.unwrap();
+        assert!(renewed.is_none());
+    }
+
+    #[tokio::test]
+    async fn lookup_lease() {
+        let backend = crate::statements::tests::sqlite_coordinator().await;
+        let context = replicore_context::Context::fixture();
+        let coordinator = Coordinator::from(backend);
+
+        let lease = coordinator.lease_lookup(&context, "test").await.unwrap();
+        assert!(lease.is_none());
+        let acquired = coordinator
+            .lease_acquire(&context, "test", TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        let lease = coordinator.lease_lookup(&context, "test").await.unwrap();
+        assert_eq!(lease, Some(acquired));
+
+        tokio::time::sleep(TTL).await;
+        let lease = coordinator.lease_lookup(&context, "test").await.unwrap();
+        assert!(lease.is_none());
+    }
+
+    #[tokio::test]
+    async fn renew_lease() {
+        let backend = crate::statements::tests::sqlite_coordinator().await;
+        let context = replicore_context::Context::fixture();
+        let one = Coordinator::from(backend.clone());
+        let two = Coordinator::from(backend);
+
+        let lease = one
+            .lease_acquire(&context, "test", TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        let renewed = one
+            .lease_renew(&context, &lease, TTL * 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(renewed.expires > lease.expires);
+        assert_eq!(renewed.token, lease.token);
+
+        tokio::time::sleep(TTL).await;
+        let lease = two.lease_acquire(&context, "test", TTL).await.unwrap();
+        assert!(lease.is_none());
+    }
+
+    #[tokio::test]
+    async fn release_lease() {
+        let backend = crate::statements::tests::sqlite_coordinator().await;
+        let context = replicore_context::Context::fixture();
+        let one = Coordinator::from(backend.clone());
+        let two = Coordinator::from(backend);
+
+        let lease = one
+            .lease_acquire(&context, "test", TTL * 10)
+            .await
+            .unwrap()
+            .unwrap();
+        one.lease_release(&context, &lease).await.unwrap();
+        let lease = two
+            .lease_acquire(&context, "test", TTL)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.token, 2);
+    }
+}
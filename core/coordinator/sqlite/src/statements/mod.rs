@@ -0,0 +1,78 @@
+//! SQL statements to implement the [`CoordinatorBackend`] with SQLite.
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio_rusqlite::Connection;
+
+use replicore_context::Context;
+use replicore_coordinator::CoordinatorBackend;
+use replicore_coordinator::Lease;
+use replicore_coordinator::LeaseRequest;
+
+mod lease;
+
+/// Implementation of the [`CoordinatorBackend`] interface using SQLite.
+#[derive(Clone)]
+pub struct SQLiteCoordinator {
+    /// Connection to the SQLite DB persisting data.
+    connection: Connection,
+}
+
+impl SQLiteCoordinator {
+    /// Initialise a new SQLite backed [`CoordinatorBackend`].
+    pub fn new(connection: Connection) -> Self {
+        SQLiteCoordinator { connection }
+    }
+}
+
+#[async_trait::async_trait]
+impl CoordinatorBackend for SQLiteCoordinator {
+    async fn lease_acquire(
+        &self,
+        context: &Context,
+        request: LeaseRequest,
+    ) -> Result<Option<Lease>> {
+        self::lease::acquire(context, &self.connection, request).await
+    }
+
+    async fn lease_lookup(&self, context: &Context, name: &str) -> Result<Option<Lease>> {
+        self::lease::lookup(context, &self.connection, name).await
+    }
+
+    async fn lease_release(&self, context: &Context, lease: &Lease) -> Result<()> {
+        self::lease::release(context, &self.connection, lease).await
+    }
+
+    async fn lease_renew(
+        &self,
+        context: &Context,
+        lease: &Lease,
+        ttl: Duration,
+    ) -> Result<Option<Lease>> {
+        self::lease::renew(context, &self.connection, lease, ttl).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SQLiteCoordinator;
+    use crate::factory::create_client;
+
+    /// Initialise an [`SQLiteCoordinator`] instance for unit tests.
+    pub async fn sqlite_coordinator() -> SQLiteCoordinator {
+        let context = replicore_context::Context::fixture();
+        let conf = crate::Conf::new(crate::factory::MEMORY_PATH);
+        let connection = create_client(&context, &conf).await.unwrap();
+        connection
+            .call(move |connection| {
+                crate::schema::migrations::runner()
+                    .set_migration_table_name(crate::factory::REFINERY_SCHEMA_TABLE_NAME)
+                    .run(connection)
+                    .unwrap();
+                Ok(())
+            })
+            .await
+            .unwrap();
+        SQLiteCoordinator::new(connection)
+    }
+}
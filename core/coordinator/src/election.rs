@@ -0,0 +1,254 @@
+//! Leader election for components that must only run in one process at a time.
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::FutureExt;
+use time::OffsetDateTime;
+use tokio::sync::watch;
+
+use replicore_context::Context;
+
+use super::Coordinator;
+use super::Lease;
+
+/// Future resolving when a leader-only component should stop running.
+///
+/// Resolves when the process loses leadership or when the process is shutting down.
+pub type LeaderExit = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Elect a single leader across Control Plane processes.
+///
+/// Processes campaign for leadership by acquiring a lease named after the election
+/// and keep leadership for as long as they are able to renew the lease.
+#[derive(Clone)]
+pub struct Election {
+    coordinator: Coordinator,
+    leader: Arc<watch::Sender<bool>>,
+    name: String,
+    ttl: Duration,
+}
+
+impl Election {
+    /// Campaign for leadership until the exit future resolves.
+    ///
+    /// Leadership is released, if held, before returning.
+    pub async fn campaign(&self, context: &Context, exit: impl Future<Output = ()>) -> Result<()> {
+        // Pin the exit future so we can select it across loops.
+        tokio::pin!(exit);
+        let interval = self.ttl / 3;
+        let mut lease: Option<Lease> = None;
+
+        loop {
+            lease = match lease {
+                None => self.acquire(context).await,
+                Some(current) => self.renew(context, current).await,
+            };
+            self.set_leader(context, lease.is_some());
+
+            tokio::select! {
+                _ = &mut exit => break,
+                _ = tokio::time::sleep(interval) => (),
+            }
+        }
+
+        // Step down to let other processes take over.
+        self.set_leader(context, false);
+        if let Some(lease) = lease {
+            self.coordinator.lease_release(context, &lease).await?;
+        }
+        Ok(())
+    }
+
+    /// Check if the current process is the leader of the election.
+    pub fn is_leader(&self) -> bool {
+        *self.leader.borrow()
+    }
+
+    /// Lookup the identifier of the process currently leading the election, if any.
+    pub async fn leader(&self, context: &Context) -> Result<Option<String>> {
+        let lease = self
+            .coordinator
+            .lease_lookup(context, &self.lease_name())
+            .await?;
+        Ok(lease.map(|lease| lease.owner))
+    }
+
+    /// Run a component only while the current process is the leader of the election.
+    ///
+    /// The component is started every time the process is elected and is given a [`LeaderExit`]
+    /// future to stop it when leadership is lost or the process shuts down.
+    /// The component is expected to return promptly once the [`LeaderExit`] future resolves.
+    ///
+    /// Returns when the exit future resolves or the component fails.
+    pub async fn leader_only<E, C, F>(
+        &self,
+        context: &Context,
+        exit: E,
+        mut component: C,
+    ) -> Result<()>
+    where
+        E: Future<Output = ()> + Send + 'static,
+        C: FnMut(LeaderExit) -> F,
+        F: Future<Output = Result<()>>,
+    {
+        let exit = exit.shared();
+        let mut leader = self.leader.subscribe();
+        loop {
+            // Wait to become the leader.
+            tokio::select! {
+                _ = exit.clone() => return Ok(()),
+                _ = leader.wait_for(|leader| *leader) => (),
+            }
+
+            // Run the component until leadership is lost or the process exits.
+            slog::info!(
+                context.logger, "Starting leader-only component";
+                "election" => &self.name,
+            );
+            let lost = self.lost();
+            let component_exit = exit.clone();
+            let component_exit: LeaderExit = Box::pin(async move {
+                tokio::select! {
+                    _ = component_exit => (),
+                    _ = lost => (),
+                }
+            });
+            component(component_exit).await?;
+            slog::info!(
+                context.logger, "Leader-only component stopped";
+                "election" => &self.name,
+            );
+
+            // Components that stop early are restarted only after a new election.
+            tokio::select! {
+                _ = exit.clone() => return Ok(()),
+                _ = self.lost() => (),
+            }
+        }
+    }
+
+    /// Name of the election.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Initialise an election for processes sharing the given [`Coordinator`].
+    ///
+    /// Leaders are elected for the given TTL and must renew leadership before it expires.
+    pub fn new<S>(coordinator: Coordinator, name: S, ttl: Duration) -> Election
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+        let (leader, _) = watch::channel(false);
+        crate::telemetry::ELECTION_LEADER
+            .with_label_values(&[&name])
+            .set(0);
+        Election {
+            coordinator,
+            leader: Arc::new(leader),
+            name,
+            ttl,
+        }
+    }
+
+    /// Subscribe to changes in leadership of the current process.
+    pub fn watch(&self) -> watch::Receiver<bool> {
+        self.leader.subscribe()
+    }
+}
+
+impl Election {
+    /// Attempt to acquire leadership, treating errors as a failed attempt.
+    async fn acquire(&self, context: &Context) -> Option<Lease> {
+        let result = self
+            .coordinator
+            .lease_acquire(context, self.lease_name(), self.ttl)
+            .await;
+        match result {
+            Ok(lease) => lease,
+            Err(error) => {
+                slog::warn!(
+                    context.logger, "Failed to campaign for leadership";
+                    "election" => &self.name,
+                    replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                );
+                None
+            }
+        }
+    }
+
+    /// Name of the lease backing the election.
+    fn lease_name(&self) -> String {
+        format!("election/{}", self.name)
+    }
+
+    /// Future resolving once the current process is not the leader.
+    fn lost(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut leader = self.leader.subscribe();
+        async move {
+            let _ = leader.wait_for(|leader| !*leader).await;
+        }
+    }
+
+    /// Renew leadership, stepping down if it can't be renewed before the lease expires.
+    async fn renew(&self, context: &Context, current: Lease) -> Option<Lease> {
+        let result = self
+            .coordinator
+            .lease_renew(context, &current, self.ttl)
+            .await;
+        match result {
+            Ok(lease) => lease,
+            Err(error) => {
+                slog::warn!(
+                    context.logger, "Failed to renew leadership";
+                    "election" => &self.name,
+                    replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                );
+                // Keep leadership only if the lease won't expire before the next attempt.
+                let next_attempt = OffsetDateTime::now_utc() + self.ttl / 3;
+                if current.expires > next_attempt {
+                    Some(current)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Update leadership status for the current process, reporting any changes.
+    fn set_leader(&self, context: &Context, leader: bool) {
+        let previous = self.leader.send_replace(leader);
+        if previous == leader {
+            return;
+        }
+
+        crate::telemetry::ELECTION_CHANGES
+            .with_label_values(&[&self.name])
+            .inc();
+        crate::telemetry::ELECTION_LEADER
+            .with_label_values(&[&self.name])
+            .set(i64::from(leader));
+        if leader {
+            slog::info!(context.logger, "Elected as leader"; "election" => &self.name);
+        } else {
+            slog::info!(context.logger, "Leadership lost"; "election" => &self.name);
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-fixture"))]
+impl Election {
+    /// Initialise an election that is not campaigning for unit tests.
+    pub fn fixture() -> Election {
+        Election::new(Coordinator::fixture(), "unittest", Duration::from_secs(60))
+    }
+
+    /// Force leadership of the current process for unit tests.
+    pub fn set_leader_fixture(&self, leader: bool) {
+        self.leader.send_replace(leader);
+    }
+}
@@ -0,0 +1,252 @@
+//! Leases to perform exclusive work for a limited amount of time.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tokio::task::JoinHandle;
+
+use replicore_context::Context;
+
+use super::Coordinator;
+
+/// Exclusive claim, held by a process, to perform some work until it expires.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lease {
+    /// Time after which the lease can be acquired by other processes.
+    pub expires: OffsetDateTime,
+
+    /// Name of the lease, identifying the work it grants exclusive access to.
+    pub name: String,
+
+    /// Identifier of the process holding the lease.
+    pub owner: String,
+
+    /// Fencing token, increased every time the lease is acquired by a new holder.
+    pub token: u64,
+}
+
+/// Request to acquire a lease passed to coordinator backends.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaseRequest {
+    /// Name of the lease to acquire.
+    pub name: String,
+
+    /// Identifier of the process requesting the lease.
+    pub owner: String,
+
+    /// Time the lease is held for, unless renewed.
+    pub ttl: Duration,
+}
+
+/// Exclusive work was interrupted because the lease granting it was lost.
+#[derive(Debug, thiserror::Error)]
+#[error("lease '{name}' was lost to another process")]
+pub struct LeaseLost {
+    /// Name of the lost lease.
+    pub name: String,
+}
+
+/// Handle to check a lease is still held while performing the work it grants access to.
+///
+/// Fences can be cloned and passed around to check the lease before each change is applied,
+/// even while the [`LeaseGuard`] they were created from is held elsewhere.
+#[derive(Clone)]
+pub struct LeaseFence {
+    lease: Arc<Mutex<Lease>>,
+    lost: Arc<AtomicBool>,
+}
+
+impl LeaseFence {
+    /// Fail with [`LeaseLost`] if the lease was lost or expired before it was renewed.
+    pub fn check(&self) -> Result<()> {
+        if self.is_lost() {
+            let name = self.lease().name;
+            anyhow::bail!(LeaseLost { name });
+        }
+        Ok(())
+    }
+
+    /// Check if the lease was lost or expired before it could be renewed.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst) || self.lease().expires <= OffsetDateTime::now_utc()
+    }
+
+    /// Current version of the held lease.
+    pub fn lease(&self) -> Lease {
+        self.lease
+            .lock()
+            .expect("LeaseGuard::lease lock poisoned")
+            .clone()
+    }
+
+    /// Fencing token of the held lease.
+    pub fn token(&self) -> u64 {
+        self.lease().token
+    }
+}
+
+impl std::fmt::Debug for LeaseFence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaseFence")
+            .field("lease", &self.lease())
+            .field("lost", &self.lost.load(Ordering::SeqCst))
+            .finish()
+    }
+}
+
+#[cfg(any(test, feature = "test-fixture"))]
+impl LeaseFence {
+    /// Fence for a lease that does not expire, unless marked as lost, for unit tests.
+    pub fn fixture<S: Into<String>>(name: S) -> Self {
+        let lease = Lease {
+            expires: OffsetDateTime::now_utc() + Duration::from_secs(3600),
+            name: name.into(),
+            owner: String::from("fixture"),
+            token: 1,
+        };
+        LeaseFence {
+            lease: Arc::new(Mutex::new(lease)),
+            lost: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Simulate the lease being lost to another process.
+    pub fn mark_lost(&self) {
+        self.lost.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Lease held by the current process and renewed in the background.
+///
+/// Renewal stops when the guard is released or dropped.
+/// Dropping the guard without releasing the lease leaves it held until it expires.
+pub struct LeaseGuard {
+    coordinator: Coordinator,
+    lease: Arc<Mutex<Lease>>,
+    lost: Arc<AtomicBool>,
+    renew: JoinHandle<()>,
+}
+
+impl LeaseGuard {
+    /// Fail with [`LeaseLost`] if the lease was lost or expired before it was renewed.
+    ///
+    /// Exclusive work should check the lease before each change it makes and abort on error.
+    pub fn check(&self) -> Result<()> {
+        self.fence().check()
+    }
+
+    /// Handle to check the lease is still held from code that does not own the guard.
+    pub fn fence(&self) -> LeaseFence {
+        LeaseFence {
+            lease: Arc::clone(&self.lease),
+            lost: Arc::clone(&self.lost),
+        }
+    }
+
+    /// Check if the lease was lost because it could not be renewed in time.
+    ///
+    /// Work performed under a lost lease may be running concurrently with other processes.
+    pub fn is_lost(&self) -> bool {
+        self.fence().is_lost()
+    }
+
+    /// Current version of the held lease.
+    pub fn lease(&self) -> Lease {
+        self.lease
+            .lock()
+            .expect("LeaseGuard::lease lock poisoned")
+            .clone()
+    }
+
+    /// Stop renewing the lease and release it for other processes to acquire.
+    pub async fn release(self, context: &Context) -> Result<()> {
+        self.renew.abort();
+        if self.is_lost() {
+            return Ok(());
+        }
+        let lease = self.lease();
+        self.coordinator.lease_release(context, &lease).await
+    }
+
+    /// Start renewing the given lease in the background.
+    pub(crate) fn start(
+        context: &Context,
+        coordinator: Coordinator,
+        lease: Lease,
+        ttl: Duration,
+    ) -> LeaseGuard {
+        let lease = Arc::new(Mutex::new(lease));
+        let lost = Arc::new(AtomicBool::new(false));
+        let renew = tokio::spawn(renew(
+            context.clone(),
+            coordinator.clone(),
+            Arc::clone(&lease),
+            Arc::clone(&lost),
+            ttl,
+        ));
+        LeaseGuard {
+            coordinator,
+            lease,
+            lost,
+            renew,
+        }
+    }
+}
+
+impl Drop for LeaseGuard {
+    fn drop(&mut self) {
+        self.renew.abort();
+    }
+}
+
+/// Periodically renew a lease until it is lost or the task is aborted.
+///
+/// Leases are renewed after a third of their TTL so a renewal error can be retried
+/// before the lease expires.
+async fn renew(
+    context: Context,
+    coordinator: Coordinator,
+    lease: Arc<Mutex<Lease>>,
+    lost: Arc<AtomicBool>,
+    ttl: Duration,
+) {
+    let interval = ttl / 3;
+    loop {
+        tokio::time::sleep(interval).await;
+        let current = lease
+            .lock()
+            .expect("LeaseGuard::lease lock poisoned")
+            .clone();
+        if current.expires <= OffsetDateTime::now_utc() {
+            slog::warn!(
+                context.logger, "Lease expired before it could be renewed";
+                "lease" => &current.name,
+            );
+            lost.store(true, Ordering::SeqCst);
+            return;
+        }
+
+        match coordinator.lease_renew(&context, &current, ttl).await {
+            Err(error) => slog::warn!(
+                context.logger, "Failed to renew lease";
+                "lease" => &current.name,
+                replisdk::utils::error::slog::ErrorAttributes::from(&error),
+            ),
+            Ok(None) => {
+                slog::warn!(
+                    context.logger, "Lease lost to another process";
+                    "lease" => &current.name,
+                );
+                lost.store(true, Ordering::SeqCst);
+                return;
+            }
+            Ok(Some(renewed)) => {
+                *lease.lock().expect("LeaseGuard::lease lock poisoned") = renewed;
+            }
+        }
+    }
+}
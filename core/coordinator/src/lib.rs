@@ -0,0 +1,207 @@
+//! Distributed coordination interface for RepliCore Control Plane.
+//!
+//! Control Plane processes can run as a group and some work must only be performed
+//! by one of them at a time (for example orchestrating a specific cluster).
+//! The [`Coordinator`] provides primitives for processes to agree on who performs this work.
+//!
+//! ## Leases
+//!
+//! Leases are named, exclusive, claims to perform some work for a limited time (TTL).
+//! Processes holding a lease must renew it before it expires or other processes
+//! can acquire it and start performing the same work.
+//!
+//! Every time a lease is acquired after expiring the backend assigns it a new fencing token.
+//! Fencing tokens strictly increase and can be attached to operations to reject any
+//! still performed by a process that lost the lease without realising it.
+//!
+//! ## Leader election
+//!
+//! Some components, such as periodic schedulers, must run in only one process at a time.
+//! An [`Election`] uses a lease to elect a leader among processes and
+//! [`Election::leader_only`] starts and stops components as leadership changes.
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+
+mod election;
+mod lease;
+mod memory;
+mod telemetry;
+
+/// In-memory coordinator backend for unit tests.
+#[cfg(any(test, feature = "test-fixture"))]
+pub type CoordinatorFixture = MemoryCoordinator;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::election::Election;
+pub use self::election::LeaderExit;
+pub use self::lease::Lease;
+pub use self::lease::LeaseFence;
+pub use self::lease::LeaseGuard;
+pub use self::lease::LeaseLost;
+pub use self::lease::LeaseRequest;
+pub use self::memory::MemoryCoordinator;
+pub use self::telemetry::register_metrics;
+
+/// Coordinate exclusive work across Control Plane processes.
+#[derive(Clone)]
+pub struct Coordinator {
+    backend: Arc<dyn CoordinatorBackend>,
+    owner: String,
+}
+
+impl Coordinator {
+    /// Attempt to acquire the named lease, if it is not held by anyone else.
+    ///
+    /// Returns `None` if the lease is currently held, including by this process.
+    pub async fn lease_acquire<S>(
+        &self,
+        context: &Context,
+        name: S,
+        ttl: Duration,
+    ) -> Result<Option<Lease>>
+    where
+        S: Into<String>,
+    {
+        let request = LeaseRequest {
+            name: name.into(),
+            owner: self.owner.clone(),
+            ttl,
+        };
+        self.backend.lease_acquire(context, request).await
+    }
+
+    /// Acquire the named lease and keep it renewed in the background until released.
+    ///
+    /// Returns `None` if the lease is currently held, including by this process.
+    pub async fn lease_hold<S>(
+        &self,
+        context: &Context,
+        name: S,
+        ttl: Duration,
+    ) -> Result<Option<LeaseGuard>>
+    where
+        S: Into<String>,
+    {
+        let lease = match self.lease_acquire(context, name, ttl).await? {
+            None => return Ok(None),
+            Some(lease) => lease,
+        };
+        let guard = LeaseGuard::start(context, self.clone(), lease, ttl);
+        Ok(Some(guard))
+    }
+
+    /// Lookup the current holder of the named lease, if it is held by any process.
+    pub async fn lease_lookup(&self, context: &Context, name: &str) -> Result<Option<Lease>> {
+        self.backend.lease_lookup(context, name).await
+    }
+
+    /// Release a lease held by this process so others can acquire it immediately.
+    pub async fn lease_release(&self, context: &Context, lease: &Lease) -> Result<()> {
+        self.backend.lease_release(context, lease).await
+    }
+
+    /// Extend the expiry time of a lease held by this process.
+    ///
+    /// Returns `None` if the lease was lost to another process.
+    pub async fn lease_renew(
+        &self,
+        context: &Context,
+        lease: &Lease,
+        ttl: Duration,
+    ) -> Result<Option<Lease>> {
+        self.backend.lease_renew(context, lease, ttl).await
+    }
+
+    /// Identifier of this process as a lease owner.
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+}
+
+impl<T> From<T> for Coordinator
+where
+    T: CoordinatorBackend + 'static,
+{
+    fn from(value: T) -> Self {
+        Coordinator {
+            backend: Arc::new(value),
+            owner: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// Operations implemented by Coordination Systems supported by Replicante Core.
+#[async_trait::async_trait]
+pub trait CoordinatorBackend: Send + Sync {
+    /// Acquire the requested lease if it does not exist or has expired.
+    ///
+    /// Acquiring a lease that exists but expired must assign it a new, greater, fencing token.
+    async fn lease_acquire(
+        &self,
+        context: &Context,
+        request: LeaseRequest,
+    ) -> Result<Option<Lease>>;
+
+    /// Lookup the named lease, if it exists and has not expired.
+    async fn lease_lookup(&self, context: &Context, name: &str) -> Result<Option<Lease>>;
+
+    /// Release the lease, if it is still held by the same owner with the same fencing token.
+    async fn lease_release(&self, context: &Context, lease: &Lease) -> Result<()>;
+
+    /// Extend the lease expiry, if it is still held by the same owner with the same fencing token.
+    async fn lease_renew(
+        &self,
+        context: &Context,
+        lease: &Lease,
+        ttl: Duration,
+    ) -> Result<Option<Lease>>;
+}
+
+/// Initialisation logic for the Coordination System and the client to access it.
+#[async_trait::async_trait]
+pub trait CoordinatorFactory: Send + Sync {
+    /// Validate the user provided configuration for the backend.
+    fn conf_check(&self, context: &Context, conf: &Json) -> Result<()>;
+
+    /// Instantiate a [`Coordinator`] object to coordinate work across processes.
+    async fn coordinator<'a>(&self, args: CoordinatorFactoryArgs<'a>) -> Result<Coordinator>;
+
+    /// Register backend specific metrics.
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()>;
+
+    /// Synchronise (initialise or migrate) the Coordination System for [`Coordinator`] use.
+    async fn sync<'a>(&self, args: CoordinatorFactorySyncArgs<'a>) -> Result<()>;
+}
+
+/// Arguments passed to the [`CoordinatorFactory`] client initialisation method.
+pub struct CoordinatorFactoryArgs<'a> {
+    /// The configuration block for the backend to initialise.
+    pub conf: &'a Json,
+
+    /// Container for operation scoped values.
+    pub context: &'a Context,
+}
+
+/// Arguments passed to the [`CoordinatorFactory`] client synchronisation method.
+pub struct CoordinatorFactorySyncArgs<'a> {
+    /// The configuration block for the backend to synchronise.
+    pub conf: &'a Json,
+
+    /// Container for operation scoped values.
+    pub context: &'a Context,
+}
+
+#[cfg(any(test, feature = "test-fixture"))]
+impl Coordinator {
+    /// Initialise a new coordinator backend fixture for unit tests.
+    pub fn fixture() -> Self {
+        Self::from(CoordinatorFixture::default())
+    }
+}
@@ -0,0 +1,97 @@
+//! In-memory coordinator backend for single process deployments and unit tests.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+
+use replicore_context::Context;
+
+use super::CoordinatorBackend;
+use super::Lease;
+use super::LeaseRequest;
+
+/// In-memory coordinator backend for single process deployments and unit tests.
+///
+/// Clones share leases so they can simulate multiple processes.
+#[derive(Clone, Default)]
+pub struct MemoryCoordinator {
+    leases: Arc<Mutex<HashMap<String, Lease>>>,
+}
+
+impl MemoryCoordinator {
+    /// Lookup the current state of a lease, if it was ever acquired.
+    pub fn lease(&self, name: &str) -> Option<Lease> {
+        self.leases
+            .lock()
+            .expect("MemoryCoordinator::leases lock poisoned")
+            .get(name)
+            .cloned()
+    }
+}
+
+#[async_trait::async_trait]
+impl CoordinatorBackend for MemoryCoordinator {
+    async fn lease_acquire(&self, _: &Context, request: LeaseRequest) -> Result<Option<Lease>> {
+        let now = OffsetDateTime::now_utc();
+        let mut leases = self
+            .leases
+            .lock()
+            .expect("MemoryCoordinator::leases lock poisoned");
+        let token = match leases.get(&request.name) {
+            Some(lease) if lease.expires > now => return Ok(None),
+            Some(lease) => lease.token + 1,
+            None => 1,
+        };
+        let lease = Lease {
+            expires: now + request.ttl,
+            name: request.name,
+            owner: request.owner,
+            token,
+        };
+        leases.insert(lease.name.clone(), lease.clone());
+        Ok(Some(lease))
+    }
+
+    async fn lease_lookup(&self, _: &Context, name: &str) -> Result<Option<Lease>> {
+        let now = OffsetDateTime::now_utc();
+        let lease = self.lease(name).filter(|lease| lease.expires > now);
+        Ok(lease)
+    }
+
+    async fn lease_release(&self, _: &Context, lease: &Lease) -> Result<()> {
+        let mut leases = self
+            .leases
+            .lock()
+            .expect("MemoryCoordinator::leases lock poisoned");
+        if let Some(current) = leases.get_mut(&lease.name) {
+            if current.owner == lease.owner && current.token == lease.token {
+                current.expires = OffsetDateTime::UNIX_EPOCH;
+            }
+        }
+        Ok(())
+    }
+
+    async fn lease_renew(
+        &self,
+        _: &Context,
+        lease: &Lease,
+        ttl: Duration,
+    ) -> Result<Option<Lease>> {
+        let mut leases = self
+            .leases
+            .lock()
+            .expect("MemoryCoordinator::leases lock poisoned");
+        let current = match leases.get_mut(&lease.name) {
+            None => return Ok(None),
+            Some(current) => current,
+        };
+        if current.owner != lease.owner || current.token != lease.token {
+            return Ok(None);
+        }
+        current.expires = OffsetDateTime::now_utc() + ttl;
+        Ok(Some(current.clone()))
+    }
+}
@@ -0,0 +1,53 @@
+//! Telemetry related to coordination of Control Plane processes.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::CounterVec;
+use prometheus::IntGaugeVec;
+use prometheus::Opts;
+
+/// Number of leadership changes observed by the process.
+pub static ELECTION_CHANGES: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_coordinator_election_changes",
+            "Number of leadership changes observed by the process",
+        ),
+        &["election"],
+    )
+    .expect("failed to initialise ELECTION_CHANGES counter")
+});
+
+/// Set to 1 while the process is the leader of the election, 0 otherwise.
+pub static ELECTION_LEADER: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "replicore_coordinator_election_leader",
+            "Set to 1 while the process is the leader of the election, 0 otherwise",
+        ),
+        &["election"],
+    )
+    .expect("failed to initialise ELECTION_LEADER gauge")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the coordinator metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 2] = [
+        Box::new(ELECTION_CHANGES.clone()),
+        Box::new(ELECTION_LEADER.clone()),
+    ];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
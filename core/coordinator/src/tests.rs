@@ -0,0 +1,190 @@
+use std::sync::atomic::AtomicU16;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::FutureExt;
+
+use replicore_context::Context;
+
+use super::Coordinator;
+use super::CoordinatorFixture;
+use super::Election;
+use super::LeaseLost;
+
+const TTL: Duration = Duration::from_millis(60);
+
+#[tokio::test]
+async fn lease_exclusive() {
+    let context = Context::fixture();
+    let fixture = CoordinatorFixture::default();
+    let one = Coordinator::from(fixture.clone());
+    let two = Coordinator::from(fixture);
+
+    let lease = one.lease_acquire(&context, "test", TTL).await.unwrap();
+    assert!(lease.is_some());
+    let lease = two.lease_acquire(&context, "test", TTL).await.unwrap();
+    assert!(lease.is_none());
+}
+
+#[tokio::test]
+async fn lease_hold_renews() {
+    let context = Context::fixture();
+    let fixture = CoordinatorFixture::default();
+    let one = Coordinator::from(fixture.clone());
+    let two = Coordinator::from(fixture);
+
+    let guard = one
+        .lease_hold(&context, "test", TTL)
+        .await
+        .unwrap()
+        .unwrap();
+    tokio::time::sleep(TTL * 2).await;
+    let lease = two.lease_acquire(&context, "test", TTL).await.unwrap();
+    assert!(lease.is_none());
+    assert!(!guard.is_lost());
+
+    guard.release(&context).await.unwrap();
+    let lease = two.lease_acquire(&context, "test", TTL).await.unwrap();
+    assert_eq!(lease.unwrap().token, 2);
+}
+
+#[tokio::test]
+async fn lease_takeover_fences_old_holder() {
+    let context = Context::fixture();
+    let fixture = CoordinatorFixture::default();
+    let one = Coordinator::from(fixture.clone());
+    let two = Coordinator::from(fixture);
+
+    let old = one
+        .lease_acquire(&context, "test", TTL)
+        .await
+        .unwrap()
+        .unwrap();
+    tokio::time::sleep(TTL).await;
+    let new = two
+        .lease_acquire(&context, "test", TTL)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(new.token > old.token);
+
+    let renewed = one.lease_renew(&context, &old, TTL).await.unwrap();
+    assert!(renewed.is_none());
+}
+
+#[tokio::test]
+async fn lease_lost_fails_fence_checks() {
+    let context = Context::fixture();
+    let fixture = CoordinatorFixture::default();
+    let one = Coordinator::from(fixture.clone());
+    let two = Coordinator::from(fixture);
+
+    let guard = one
+        .lease_hold(&context, "test", TTL)
+        .await
+        .unwrap()
+        .unwrap();
+    let fence = guard.fence();
+    fence.check().unwrap();
+
+    // Another process takes over the lease without the holder noticing.
+    one.lease_release(&context, &guard.lease()).await.unwrap();
+    two.lease_acquire(&context, "test", TTL)
+        .await
+        .unwrap()
+        .unwrap();
+    tokio::time::sleep(TTL).await;
+
+    assert!(guard.is_lost());
+    let error = fence.check().unwrap_err();
+    assert!(error.is::<LeaseLost>());
+}
+
+#[tokio::test]
+async fn election_single_leader() {
+    let context = Context::fixture();
+    let fixture = CoordinatorFixture::default();
+    let one = Election::new(Coordinator::from(fixture.clone()), "test", TTL);
+    let two = Election::new(Coordinator::from(fixture), "test", TTL);
+
+    let (exit_one, exit_one_rx) = tokio::sync::oneshot::channel::<()>();
+    let (exit_two, exit_two_rx) = tokio::sync::oneshot::channel::<()>();
+    let campaign_one = {
+        let context = context.clone();
+        let one = one.clone();
+        tokio::spawn(async move {
+            one.campaign(&context, async move {
+                let _ = exit_one_rx.await;
+            })
+            .await
+        })
+    };
+    tokio::time::sleep(TTL / 3).await;
+    let campaign_two = {
+        let context = context.clone();
+        let two = two.clone();
+        tokio::spawn(async move {
+            two.campaign(&context, async move {
+                let _ = exit_two_rx.await;
+            })
+            .await
+        })
+    };
+
+    tokio::time::sleep(TTL * 2).await;
+    assert!(one.is_leader());
+    assert!(!two.is_leader());
+
+    // Leadership moves once the leader steps down.
+    exit_one.send(()).unwrap();
+    campaign_one.await.unwrap().unwrap();
+    assert!(!one.is_leader());
+    tokio::time::sleep(TTL).await;
+    assert!(two.is_leader());
+
+    exit_two.send(()).unwrap();
+    campaign_two.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn leader_only_follows_leadership() {
+    let context = Context::fixture();
+    let election = Election::fixture();
+    let started = Arc::new(AtomicU16::new(0));
+    let (exit, exit_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let component = {
+        let context = context.clone();
+        let election = election.clone();
+        let started = Arc::clone(&started);
+        tokio::spawn(async move {
+            let exit = async move {
+                let _ = exit_rx.await;
+            };
+            election
+                .leader_only(&context, exit, |leader_exit| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    leader_exit.map(Ok)
+                })
+                .await
+        })
+    };
+
+    // Components start only once elected.
+    tokio::time::sleep(TTL).await;
+    assert_eq!(started.load(Ordering::SeqCst), 0);
+    election.set_leader_fixture(true);
+    tokio::time::sleep(TTL).await;
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+
+    // Components are restarted after leadership is lost and regained.
+    election.set_leader_fixture(false);
+    tokio::time::sleep(TTL).await;
+    election.set_leader_fixture(true);
+    tokio::time::sleep(TTL).await;
+    assert_eq!(started.load(Ordering::SeqCst), 2);
+
+    exit.send(()).unwrap();
+    component.await.unwrap().unwrap();
+}
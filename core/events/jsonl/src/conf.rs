@@ -0,0 +1,143 @@
+//! Configuration for the JSON Lines events backend.
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use replicore_events_models::EventsFormat;
+
+/// JSON Lines specific configuration for the events interface.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// Encoding of events written to the files.
+    ///
+    /// In the native format each line is an event, as the stream is known from the file.
+    #[serde(default)]
+    pub format: EventsFormat,
+
+    /// Directory to write events files into, created if missing.
+    pub path: String,
+
+    /// Rules to remove old rotated events files.
+    #[serde(default)]
+    pub retention: Retention,
+
+    /// Rules to rotate events files.
+    #[serde(default)]
+    pub rotation: Rotation,
+}
+
+impl Conf {
+    /// Check the events files directory can be used, without creating it.
+    pub fn validate(&self) -> Result<(), PathError> {
+        if self.path.is_empty() {
+            return Err(PathError::Empty);
+        }
+        let path = Path::new(&self.path);
+        if path.exists() && !path.is_dir() {
+            return Err(PathError::NotADirectory(self.path.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Rules to remove old rotated events files.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Retention {
+    /// Number of days to keep rotated files for, or 0 to keep files regardless of age.
+    #[serde(default = "Retention::default_age")]
+    pub age: u32,
+
+    /// Number of rotated files to keep for each stream, or 0 to keep any number of files.
+    #[serde(default = "Retention::default_files")]
+    pub files: u32,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention {
+            age: Self::default_age(),
+            files: Self::default_files(),
+        }
+    }
+}
+
+impl Retention {
+    fn default_age() -> u32 {
+        30
+    }
+
+    fn default_files() -> u32 {
+        10
+    }
+}
+
+/// Rules to rotate events files.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Rotation {
+    /// Hours after which events files are rotated, or 0 to disable time based rotation.
+    #[serde(default = "Rotation::default_max_age")]
+    pub max_age: u32,
+
+    /// Size in bytes events files can grow to, or 0 to disable size based rotation.
+    #[serde(default = "Rotation::default_max_size")]
+    pub max_size: u64,
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Rotation {
+            max_age: Self::default_max_age(),
+            max_size: Self::default_max_size(),
+        }
+    }
+}
+
+impl Rotation {
+    fn default_max_age() -> u32 {
+        24
+    }
+
+    fn default_max_size() -> u64 {
+        100 * 1024 * 1024
+    }
+}
+
+/// The JSON Lines events backend configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the JSON Lines events backend configuration is not valid")]
+pub struct ConfError;
+
+/// The events files directory can't be used.
+#[derive(Debug, thiserror::Error)]
+pub enum PathError {
+    /// No events files directory was given.
+    #[error("the events files directory path must not be empty")]
+    Empty,
+
+    /// The events files directory path exists but is not a directory.
+    #[error("the events files directory path '{0}' exists but is not a directory")]
+    NotADirectory(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Conf;
+    use super::PathError;
+
+    fn conf(path: &str) -> Conf {
+        serde_json::from_value(serde_json::json!({ "path": path })).unwrap()
+    }
+
+    #[test]
+    fn validate_paths() {
+        assert!(matches!(conf("").validate(), Err(PathError::Empty)));
+        let file = env!("CARGO_MANIFEST_DIR").to_string() + "/Cargo.toml";
+        assert!(matches!(
+            conf(&file).validate(),
+            Err(PathError::NotADirectory(_)),
+        ));
+        assert!(conf(env!("CARGO_MANIFEST_DIR")).validate().is_ok());
+        assert!(conf("missing/events/dir").validate().is_ok());
+    }
+}
@@ -0,0 +1,125 @@
+//! Emit events by appending them to JSON Lines files.
+use anyhow::Result;
+use tokio::sync::Mutex;
+
+use replicore_context::Context;
+use replicore_events::emit::EventsBackend;
+use replicore_events::Event;
+use replicore_events_models::EventEntry;
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsFormat;
+
+use crate::file::StreamFile;
+use crate::Conf;
+
+/// Implementation of the [`EventsBackend`] interface using JSON Lines files.
+pub struct JsonlEvents {
+    audit: Mutex<StreamFile>,
+    change: Mutex<StreamFile>,
+    format: EventsFormat,
+}
+
+impl JsonlEvents {
+    /// Write events to files in the configured directory.
+    pub fn new(conf: &Conf) -> JsonlEvents {
+        let stream = |stream| {
+            let file = StreamFile::new(
+                &conf.path,
+                stream,
+                conf.retention.clone(),
+                conf.rotation.clone(),
+            );
+            Mutex::new(file)
+        };
+        JsonlEvents {
+            audit: stream(EventStream::Audit),
+            change: stream(EventStream::Change),
+            format: conf.format,
+        }
+    }
+
+    /// Append the event to the file for its stream.
+    async fn emit(&self, stream: EventStream, event: Event) -> Result<()> {
+        let mut line = match self.format {
+            EventsFormat::CloudEvents => self.format.encode(&EventEntry { event, stream })?,
+            EventsFormat::Native => serde_json::to_vec(&event)?,
+        };
+        line.push(b'\n');
+        let file = match stream {
+            EventStream::Audit => &self.audit,
+            EventStream::Change => &self.change,
+        };
+        file.lock().await.write(&line).await
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsBackend for JsonlEvents {
+    async fn audit(&self, _: &Context, event: Event) -> Result<()> {
+        self.emit(EventStream::Audit, event).await
+    }
+
+    async fn change(&self, _: &Context, event: Event) -> Result<()> {
+        self.emit(EventStream::Change, event).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events::emit::Events;
+    use replicore_events::Event;
+    use replicore_events_models::CloudEvent;
+
+    use super::JsonlEvents;
+    use crate::file::tests::events_dir;
+    use crate::Conf;
+
+    #[tokio::test]
+    async fn emit_to_stream_files() {
+        let context = replicore_context::Context::fixture();
+        let dir = events_dir();
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "path": dir.to_string_lossy(),
+        }))
+        .unwrap();
+        let events = Events::from(JsonlEvents::new(&conf));
+
+        let event = Event::new_with_payload("TEST_AUDIT", 1).unwrap();
+        events.audit(&context, event.clone()).await.unwrap();
+        events.audit(&context, event.clone()).await.unwrap();
+        let change = Event::new_with_payload("TEST_CHANGE", 2).unwrap();
+        events.change(&context, change.clone()).await.unwrap();
+
+        let audit = std::fs::read_to_string(dir.join("audit.jsonl")).unwrap();
+        let audit: Vec<Event> = audit
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(audit, vec![event.clone(), event]);
+        let changes = std::fs::read_to_string(dir.join("change.jsonl")).unwrap();
+        let changes: Event = serde_json::from_str(changes.trim_end()).unwrap();
+        assert_eq!(changes, change);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn emit_cloudevents() {
+        let context = replicore_context::Context::fixture();
+        let dir = events_dir();
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "format": "cloudevents",
+            "path": dir.to_string_lossy(),
+        }))
+        .unwrap();
+        let events = Events::from(JsonlEvents::new(&conf));
+
+        let event = Event::new_with_payload("TEST_CHANGE", 2).unwrap();
+        events.change(&context, event).await.unwrap();
+
+        let changes = std::fs::read_to_string(dir.join("change.jsonl")).unwrap();
+        let changes: CloudEvent = serde_json::from_str(changes.trim_end()).unwrap();
+        assert_eq!(changes.source, "/replicore/change");
+        assert_eq!(changes.type_, "TEST_CHANGE");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,42 @@
+//! Factory for the JSON Lines events backend.
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events::emit::EventsFactory;
+use replicore_events::emit::EventsFactoryArgs;
+use replicore_events::emit::EventsFactorySyncArgs;
+
+use crate::Conf;
+use crate::ConfError;
+use crate::JsonlEvents;
+
+/// Initialise JSON Lines files Events streams.
+pub struct JsonlFactory;
+
+#[async_trait::async_trait]
+impl EventsFactory for JsonlFactory {
+    fn conf_check(&self, _: &Context, conf: &Json) -> Result<()> {
+        let conf: Conf = serde_json::from_value(conf.clone()).context(ConfError)?;
+        conf.validate().context(ConfError)?;
+        Ok(())
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        crate::telemetry::register_metrics(registry)
+    }
+
+    async fn events<'a>(&self, args: EventsFactoryArgs<'a>) -> Result<Events> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        tokio::fs::create_dir_all(&conf.path).await?;
+        Ok(Events::from(JsonlEvents::new(&conf)))
+    }
+
+    async fn sync<'a>(&self, args: EventsFactorySyncArgs<'a>) -> Result<()> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        tokio::fs::create_dir_all(&conf.path).await?;
+        Ok(())
+    }
+}
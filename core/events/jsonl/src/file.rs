@@ -0,0 +1,269 @@
+//! Append events to a stream file, rotating and cleaning up files as needed.
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use time::Duration;
+use time::OffsetDateTime;
+use tokio::fs::File;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+
+use replicore_events_models::EventStream;
+
+use crate::conf::Retention;
+use crate::conf::Rotation;
+
+/// Events file for a stream, opened on first write.
+pub struct StreamFile {
+    dir: PathBuf,
+    file: Option<File>,
+    opened: OffsetDateTime,
+    retention: Retention,
+    rotation: Rotation,
+    size: u64,
+    stream: EventStream,
+}
+
+impl StreamFile {
+    /// Manage the events file for a stream in the given directory.
+    pub fn new<P>(dir: P, stream: EventStream, retention: Retention, rotation: Rotation) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        StreamFile {
+            dir: dir.into(),
+            file: None,
+            opened: OffsetDateTime::now_utc(),
+            retention,
+            rotation,
+            size: 0,
+            stream,
+        }
+    }
+
+    /// Append a line to the events file, rotating it first if needed.
+    pub async fn write(&mut self, line: &[u8]) -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        if self.file.is_none() {
+            self.open(now).await?;
+        }
+        if self.should_rotate(now, line.len() as u64) {
+            self.rotate(now).await?;
+        }
+
+        let file = self.file.as_mut().expect("events file opened above");
+        file.write_all(line).await?;
+        file.flush().await?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Path of the events file currently written to.
+    fn active_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", self.stream))
+    }
+
+    /// Open the events file for appending, creating it if needed.
+    ///
+    /// The age of existing files is determined by their creation time, when available.
+    async fn open(&mut self, now: OffsetDateTime) -> Result<()> {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(self.active_path())
+            .await?;
+        let metadata = file.metadata().await?;
+        self.opened = metadata.created().map(OffsetDateTime::from).unwrap_or(now);
+        self.size = metadata.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Remove rotated files that exceed the retention rules, oldest first.
+    async fn enforce_retention(&self, now: OffsetDateTime) -> Result<()> {
+        let mut rotated = rotated_files(&self.dir, self.stream).await?;
+        let keep = self.retention.files as usize;
+        let excess = if keep > 0 {
+            rotated.len().saturating_sub(keep)
+        } else {
+            0
+        };
+        let cutoff = now - Duration::days(i64::from(self.retention.age));
+        let expired = rotated
+            .iter()
+            .filter(|(rotated, _)| self.retention.age > 0 && *rotated < cutoff)
+            .count();
+
+        let remove = excess.max(expired);
+        for (_, path) in rotated.drain(..remove) {
+            tokio::fs::remove_file(path).await?;
+            crate::telemetry::FILES_REMOVED
+                .with_label_values(&[&self.stream.to_string()])
+                .inc();
+        }
+        Ok(())
+    }
+
+    /// Rename the events file with its rotation time and open a new one.
+    ///
+    /// Files rotated within the same millisecond get an increasing sequence suffix
+    /// so they never replace each other.
+    async fn rotate(&mut self, now: OffsetDateTime) -> Result<()> {
+        self.file = None;
+        let millis = now.unix_timestamp_nanos() / 1_000_000;
+        let mut rotated = self
+            .dir
+            .join(format!("{}.{:015}.jsonl", self.stream, millis));
+        let mut sequence = 0;
+        while tokio::fs::try_exists(&rotated).await? {
+            sequence += 1;
+            rotated = self
+                .dir
+                .join(format!("{}.{:015}-{}.jsonl", self.stream, millis, sequence));
+        }
+        tokio::fs::rename(self.active_path(), rotated).await?;
+        crate::telemetry::ROTATIONS
+            .with_label_values(&[&self.stream.to_string()])
+            .inc();
+        self.enforce_retention(now).await?;
+        self.open(now).await
+    }
+
+    /// Check if the events file should be rotated before writing a line to it.
+    ///
+    /// Empty files are never rotated.
+    fn should_rotate(&self, now: OffsetDateTime, len: u64) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        let max_size = self.rotation.max_size;
+        let too_big = max_size > 0 && self.size + len > max_size;
+        let max_age = Duration::hours(i64::from(self.rotation.max_age));
+        let too_old = self.rotation.max_age > 0 && now - self.opened >= max_age;
+        too_big || too_old
+    }
+}
+
+/// List rotated files for a stream, with their rotation time, oldest first.
+async fn rotated_files(dir: &Path, stream: EventStream) -> Result<Vec<(OffsetDateTime, PathBuf)>> {
+    let prefix = format!("{}.", stream);
+    let mut rotated = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let suffix = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|name| name.strip_suffix(".jsonl"));
+        let suffix = match suffix {
+            None => continue,
+            Some(suffix) => suffix,
+        };
+        let (millis, sequence) = suffix.split_once('-').unwrap_or((suffix, "0"));
+        let sequence = match sequence.parse::<u64>() {
+            Err(_) => continue,
+            Ok(sequence) => sequence,
+        };
+        let time = millis
+            .parse::<i128>()
+            .ok()
+            .and_then(|millis| OffsetDateTime::from_unix_timestamp_nanos(millis * 1_000_000).ok());
+        if let Some(time) = time {
+            rotated.push((time, sequence, entry.path()));
+        }
+    }
+    rotated.sort();
+    let rotated = rotated
+        .into_iter()
+        .map(|(time, _, path)| (time, path))
+        .collect();
+    Ok(rotated)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::path::PathBuf;
+
+    use replicore_events_models::EventStream;
+
+    use super::rotated_files;
+    use super::StreamFile;
+    use crate::conf::Retention;
+    use crate::conf::Rotation;
+
+    /// Create a unique events directory for a test.
+    pub fn events_dir() -> PathBuf {
+        let dir = format!("replicore-events-jsonl-{}", uuid::Uuid::new_v4());
+        let dir = std::env::temp_dir().join(dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn rotate_on_size_and_keep_files() {
+        let dir = events_dir();
+        let retention = Retention { age: 0, files: 2 };
+        let rotation = Rotation {
+            max_age: 0,
+            max_size: 10,
+        };
+        let mut file = StreamFile::new(&dir, EventStream::Audit, retention, rotation);
+
+        // Each write exceeds the maximum size so all but the first rotate the file.
+        for _ in 0..5 {
+            file.write(b"0123456789\n").await.unwrap();
+        }
+        let rotated = rotated_files(&dir, EventStream::Audit).await.unwrap();
+        assert_eq!(rotated.len(), 2);
+        let active = std::fs::read_to_string(dir.join("audit.jsonl")).unwrap();
+        assert_eq!(active, "0123456789\n");
+        assert!(!dir.join("change.jsonl").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rotate_within_same_millisecond() {
+        let dir = events_dir();
+        let retention = Retention { age: 0, files: 0 };
+        let rotation = Rotation {
+            max_age: 0,
+            max_size: 1,
+        };
+        let mut file = StreamFile::new(&dir, EventStream::Change, retention, rotation);
+        let now = time::OffsetDateTime::now_utc();
+        for line in ["first\n", "second\n", "third\n"] {
+            file.write(line.as_bytes()).await.unwrap();
+            file.rotate(now).await.unwrap();
+        }
+
+        // Rotated files are all kept, in the order they were rotated.
+        let rotated = rotated_files(&dir, EventStream::Change).await.unwrap();
+        let contents: Vec<String> = rotated
+            .iter()
+            .map(|(_, path)| std::fs::read_to_string(path).unwrap())
+            .collect();
+        assert_eq!(contents, ["first\n", "second\n", "third\n"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn remove_expired_files() {
+        let dir = events_dir();
+        std::fs::write(dir.join("change.000000000001000.jsonl"), "old\n").unwrap();
+        std::fs::write(dir.join("audit.000000000001000.jsonl"), "other stream\n").unwrap();
+        let retention = Retention { age: 1, files: 0 };
+        let rotation = Rotation {
+            max_age: 0,
+            max_size: 1,
+        };
+        let mut file = StreamFile::new(&dir, EventStream::Change, retention, rotation);
+        file.write(b"first\n").await.unwrap();
+        file.write(b"second\n").await.unwrap();
+
+        let rotated = rotated_files(&dir, EventStream::Change).await.unwrap();
+        assert_eq!(rotated.len(), 1);
+        assert!(dir.join("audit.000000000001000.jsonl").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
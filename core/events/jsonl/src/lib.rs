@@ -0,0 +1,33 @@
+//! Event Streaming Platform writing events to JSON Lines files.
+//!
+//! This backend is intended for small installs and log-shipping pipelines:
+//!
+//! - Events are appended to `audit.jsonl` and `change.jsonl` in the configured directory,
+//!   one JSON encoded [`Event`](replicore_events::Event) per line.
+//! - Events are NOT shared across processes so only single process deployments are supported.
+//! - Querying events is not supported, use a log-shipping pipeline to consume events.
+//!
+//! ## Rotation and retention
+//!
+//! Events files are rotated when they would grow past the maximum size or once they
+//! are older than the maximum age, whichever happens first.
+//! Rotated files are renamed to `<stream>.<rotation unix time in milliseconds>.jsonl`
+//! so they sort by rotation time.
+//! Files rotated within the same millisecond are suffixed with a sequence number
+//! (for example `change.001700000000000-1.jsonl`) so none is overwritten.
+//!
+//! After each rotation the oldest rotated files of the stream are removed so that only
+//! a configured number of them, no older than a configured age, are kept.
+mod conf;
+mod events;
+mod factory;
+mod file;
+mod telemetry;
+
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::conf::PathError;
+pub use self::conf::Retention;
+pub use self::conf::Rotation;
+pub use self::events::JsonlEvents;
+pub use self::factory::JsonlFactory;
@@ -0,0 +1,50 @@
+//! Telemetry related to the JSON Lines events implementation.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::CounterVec;
+use prometheus::Opts;
+
+/// Number of rotated events files removed by retention rules.
+pub static FILES_REMOVED: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_jsonl_removed",
+            "Number of rotated events files removed by retention rules",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise FILES_REMOVED counter")
+});
+
+/// Number of events files rotations.
+pub static ROTATIONS: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_jsonl_rotations",
+            "Number of events files rotations",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise ROTATIONS counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the JSON Lines events backend metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 2] =
+        [Box::new(FILES_REMOVED.clone()), Box::new(ROTATIONS.clone())];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Configuration for the in-memory events backend.
+use serde::Deserialize;
+use serde::Serialize;
+
+/// In-memory specific configuration for the events interface.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// Maximum number of events to keep for each stream, older events are dropped first.
+    #[serde(default = "Conf::default_capacity")]
+    pub capacity: usize,
+}
+
+impl Default for Conf {
+    fn default() -> Self {
+        Conf {
+            capacity: Conf::default_capacity(),
+        }
+    }
+}
+
+impl Conf {
+    fn default_capacity() -> usize {
+        10_000
+    }
+}
+
+/// The in-memory events backend configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the in-memory events backend configuration is not valid")]
+pub struct ConfError;
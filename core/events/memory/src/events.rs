@@ -0,0 +1,226 @@
+//! Emit events to bounded in-memory streams.
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use anyhow::Result;
+
+use replicore_context::Context;
+use replicore_events::emit::EventsBackend;
+use replicore_events::Event;
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
+
+use crate::Conf;
+
+/// In-memory events implementation.
+///
+/// Clones of the backend emit events onto the same streams.
+#[derive(Clone)]
+pub struct MemoryEvents {
+    inner: Arc<Mutex<MemoryEventsState>>,
+}
+
+impl MemoryEvents {
+    /// List auditing events currently kept in memory, oldest first.
+    pub fn audit_events(&self) -> Vec<Event> {
+        let state = self.access();
+        state.audit.iter().map(|(_, event)| event.clone()).collect()
+    }
+
+    /// List change events currently kept in memory, oldest first.
+    pub fn change_events(&self) -> Vec<Event> {
+        let state = self.access();
+        state
+            .changes
+            .iter()
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+
+    /// Initialise in-memory event streams keeping up to `capacity` events each.
+    pub fn new(capacity: usize) -> MemoryEvents {
+        let state = MemoryEventsState {
+            audit: VecDeque::new(),
+            capacity,
+            changes: VecDeque::new(),
+            last_seq: 0,
+        };
+        MemoryEvents {
+            inner: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Update the maximum number of events kept for each stream.
+    ///
+    /// The oldest events are dropped if the streams exceed the new capacity.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut state = self.access();
+        state.capacity = capacity;
+        state.trim();
+    }
+}
+
+impl Default for MemoryEvents {
+    fn default() -> Self {
+        MemoryEvents::new(Conf::default().capacity)
+    }
+}
+
+impl MemoryEvents {
+    /// Lock and access the shared event streams.
+    fn access(&self) -> MutexGuard<MemoryEventsState> {
+        self.inner
+            .lock()
+            .expect("MemoryEvents::inner state lock poisoned")
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsBackend for MemoryEvents {
+    async fn audit(&self, _: &Context, event: Event) -> Result<()> {
+        let mut state = self.access();
+        let seq = state.next_seq();
+        state.audit.push_back((seq, event));
+        state.trim();
+        Ok(())
+    }
+
+    async fn change(&self, _: &Context, event: Event) -> Result<()> {
+        let mut state = self.access();
+        let seq = state.next_seq();
+        state.changes.push_back((seq, event));
+        state.trim();
+        Ok(())
+    }
+
+    async fn query(&self, _: &Context, query: EventsQuery) -> Result<EventsPage> {
+        let cursor = query.decode_cursor()?;
+        let state = self.access();
+        let select = |stream: EventStream, events: &VecDeque<(u64, Event)>| {
+            let matching = events
+                .iter()
+                .filter(|(seq, event)| *seq > cursor.get(stream) && query.matches(stream, event));
+            if !query.latest {
+                return matching.take(query.limit).cloned().collect::<Vec<_>>();
+            }
+            let mut latest: Vec<_> = matching.rev().take(query.limit).cloned().collect();
+            latest.reverse();
+            latest
+        };
+        let audit = select(EventStream::Audit, &state.audit);
+        let change = select(EventStream::Change, &state.changes);
+        match query.latest {
+            false => Ok(EventsPage::merge(cursor, audit, change, query.limit)),
+            true => Ok(EventsPage::merge_latest(cursor, audit, change, query.limit)),
+        }
+    }
+}
+
+/// Event streams kept in memory, with the sequence number assigned to each event.
+struct MemoryEventsState {
+    audit: VecDeque<(u64, Event)>,
+    capacity: usize,
+    changes: VecDeque<(u64, Event)>,
+    last_seq: u64,
+}
+
+impl MemoryEventsState {
+    /// Assign the next sequence number to an emitted event.
+    fn next_seq(&mut self) -> u64 {
+        self.last_seq += 1;
+        self.last_seq
+    }
+
+    /// Drop the oldest events from streams above capacity.
+    fn trim(&mut self) {
+        let capacity = self.capacity;
+        let streams = [("audit", &mut self.audit), ("change", &mut self.changes)];
+        for (stream, events) in streams {
+            let excess = events.len().saturating_sub(capacity);
+            if excess == 0 {
+                continue;
+            }
+            events.drain(..excess);
+            crate::telemetry::EVENTS_DROPPED
+                .with_label_values(&[stream])
+                .inc_by(excess as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events::emit::Events;
+    use replicore_events::Event;
+    use replicore_events_models::EventsQuery;
+
+    use super::MemoryEvents;
+
+    fn mock_event(code: &str) -> Event {
+        Event::new_with_payload(code, serde_json::Value::Null).unwrap()
+    }
+
+    #[tokio::test]
+    async fn emit_to_streams() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryEvents::new(10);
+        let events = Events::from(backend.clone());
+
+        events.audit(&context, mock_event("AUDIT")).await.unwrap();
+        events.change(&context, mock_event("CHANGE")).await.unwrap();
+
+        let audit: Vec<_> = backend.audit_events().into_iter().map(|e| e.code).collect();
+        assert_eq!(audit, ["AUDIT"]);
+        let changes: Vec<_> = backend
+            .change_events()
+            .into_iter()
+            .map(|e| e.code)
+            .collect();
+        assert_eq!(changes, ["CHANGE"]);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_over_capacity() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryEvents::new(2);
+        let events = Events::from(backend.clone());
+        for code in ["ONE", "TWO", "THREE"] {
+            events.change(&context, mock_event(code)).await.unwrap();
+        }
+
+        let changes: Vec<_> = backend
+            .change_events()
+            .into_iter()
+            .map(|e| e.code)
+            .collect();
+        assert_eq!(changes, ["TWO", "THREE"]);
+    }
+
+    #[tokio::test]
+    async fn query_resumes_from_cursor() {
+        let context = replicore_context::Context::fixture();
+        let events = Events::from(MemoryEvents::new(10));
+        events.audit(&context, mock_event("ONE")).await.unwrap();
+        events.change(&context, mock_event("TWO")).await.unwrap();
+
+        let query = EventsQuery {
+            limit: 1,
+            ..Default::default()
+        };
+        let page = events.query(&context, query.clone()).await.unwrap();
+        assert_eq!(page.items[0].event.code, "ONE");
+
+        events.change(&context, mock_event("THREE")).await.unwrap();
+        let query = EventsQuery {
+            cursor: Some(page.cursor),
+            limit: 10,
+            ..query
+        };
+        let page = events.query(&context, query).await.unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["TWO", "THREE"]);
+    }
+}
@@ -0,0 +1,55 @@
+//! Factory for the in-memory events backend.
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events::emit::EventsFactory;
+use replicore_events::emit::EventsFactoryArgs;
+use replicore_events::emit::EventsFactorySyncArgs;
+
+use crate::Conf;
+use crate::ConfError;
+use crate::MemoryEvents;
+
+/// Initialise in-memory Events streams.
+///
+/// All [`Events`] created by the same factory emit events onto the same streams.
+#[derive(Clone, Default)]
+pub struct MemoryFactory {
+    events: MemoryEvents,
+}
+
+impl MemoryFactory {
+    /// Access the events emitted by backends created with this factory.
+    pub fn emitted(&self) -> MemoryEvents {
+        self.events.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsFactory for MemoryFactory {
+    fn conf_check(&self, _: &Context, conf: &Json) -> Result<()> {
+        serde_json::from_value::<Conf>(conf.clone()).context(ConfError)?;
+        Ok(())
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        crate::telemetry::register_metrics(registry)
+    }
+
+    async fn events<'a>(&self, args: EventsFactoryArgs<'a>) -> Result<Events> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        slog::warn!(
+            args.context.logger,
+            "Using in-memory events means events will be lost once the process terminates"
+        );
+        self.events.set_capacity(conf.capacity);
+        Ok(Events::from(self.events.clone()))
+    }
+
+    async fn sync<'a>(&self, _: EventsFactorySyncArgs<'a>) -> Result<()> {
+        Ok(())
+    }
+}
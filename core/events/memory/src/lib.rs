@@ -0,0 +1,16 @@
+//! Event Streaming Platform kept in the memory of the RepliCore process.
+//!
+//! This backend is intended for demos, tests and embedding the Control Plane:
+//!
+//! - All events are lost as soon as the process terminates.
+//! - Only the most recent events are kept, up to a configurable capacity for each stream.
+//! - Events are NOT shared across processes so only single process deployments are supported.
+mod conf;
+mod events;
+mod factory;
+mod telemetry;
+
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::events::MemoryEvents;
+pub use self::factory::MemoryFactory;
@@ -0,0 +1,37 @@
+//! Telemetry related to the in-memory events implementation.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::CounterVec;
+use prometheus::Opts;
+
+/// Number of events dropped to make room for newer events.
+pub static EVENTS_DROPPED: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_memory_dropped",
+            "Number of events dropped to make room for newer events",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise EVENTS_DROPPED counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the in-memory events backend metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 1] = [Box::new(EVENTS_DROPPED.clone())];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Data models to describe event codes emitted by the Control Plane.
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value as Json;
+
+use crate::EventStream;
+
+/// Contract for events emitted with a specific code.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventCode {
+    /// Identifier of the event, as set in [`Event::code`](crate::Event::code).
+    pub code: String,
+
+    /// Human readable description of what the event means.
+    pub description: String,
+
+    /// Name of the type event payloads encode.
+    pub payload: String,
+
+    /// JSON Schema event payloads conform to.
+    pub schema: Json,
+
+    /// Stream events with this code are emitted onto.
+    pub stream: EventStream,
+}
+
+impl EventCode {
+    /// Describe an event emitted onto the audit stream.
+    pub fn audit<C, D, P>(code: C, payload: P, description: D) -> EventCode
+    where
+        C: Into<String>,
+        D: Into<String>,
+        P: Into<String>,
+    {
+        EventCode::new(code, EventStream::Audit, payload, description)
+    }
+
+    /// Describe an event emitted onto the change stream.
+    pub fn change<C, D, P>(code: C, payload: P, description: D) -> EventCode
+    where
+        C: Into<String>,
+        D: Into<String>,
+        P: Into<String>,
+    {
+        EventCode::new(code, EventStream::Change, payload, description)
+    }
+
+    /// Set the JSON Schema event payloads conform to from its JSON encoded source.
+    ///
+    /// Payloads accept any value until a schema is set.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the schema source is not valid JSON.
+    pub fn schema(mut self, source: &str) -> EventCode {
+        let error = format!("invalid JSON schema for event code {}", self.code);
+        self.schema = serde_json::from_str(source).expect(&error);
+        self
+    }
+
+    fn new<C, D, P>(code: C, stream: EventStream, payload: P, description: D) -> EventCode
+    where
+        C: Into<String>,
+        D: Into<String>,
+        P: Into<String>,
+    {
+        EventCode {
+            code: code.into(),
+            description: description.into(),
+            payload: payload.into(),
+            schema: serde_json::json!({}),
+            stream,
+        }
+    }
+}
+
+/// List of event codes known to the Control Plane, sorted by code.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventCodeList {
+    /// Event codes in the catalog.
+    pub items: Vec<EventCode>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventCode;
+    use crate::EventStream;
+
+    #[test]
+    fn schema_from_source() {
+        let code = EventCode::change("TEST", "Test", "Test event")
+            .schema(r#"{"type": "object", "required": ["id"]}"#);
+        assert_eq!(code.stream, EventStream::Change);
+        assert_eq!(code.schema["required"][0], "id");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid JSON schema for event code TEST")]
+    fn schema_invalid_source() {
+        EventCode::audit("TEST", "Test", "Test event").schema("not json");
+    }
+}
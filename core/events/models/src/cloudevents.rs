@@ -0,0 +1,247 @@
+//! Mapping of Control Plane events onto the CloudEvents 1.0 specification.
+//!
+//! Events are encoded in the CloudEvents structured JSON format:
+//!
+//! - The event code is the CloudEvent `type`.
+//! - The namespace and cluster the event is about are the CloudEvent `subject`.
+//! - The time the event was generated is the CloudEvent `time`.
+//! - The event payload is the CloudEvent `data`.
+//! - Trace context is carried with the CloudEvents distributed tracing extension.
+//!
+//! Refer to <https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/spec.md>
+//! for details about the CloudEvents specification.
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::Event;
+use crate::EventEntry;
+use crate::EventsPage;
+use crate::METADATA_TRACE_PARENT;
+use crate::METADATA_TRACE_STATE;
+
+/// Media type of events encoded in the CloudEvents structured JSON format.
+pub const CLOUDEVENTS_CONTENT_TYPE: &str = "application/cloudevents+json";
+
+/// Version of the CloudEvents specification events are mapped onto.
+pub const CLOUDEVENTS_SPEC_VERSION: &str = "1.0";
+
+/// Namespace to derive CloudEvents IDs from the content of events.
+const CLOUDEVENTS_ID_NAMESPACE: Uuid = Uuid::from_u128(0x2b1d0f2e_5a4c_4f7e_9c3a_7f1e6d8b4a90);
+
+/// Control Plane event mapped onto the CloudEvents 1.0 specification.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CloudEvent {
+    /// Version of the CloudEvents specification the event uses.
+    pub specversion: String,
+
+    /// Identifier of the event, derived from the event content.
+    ///
+    /// The same event always has the same ID, regardless of how it was read or delivered,
+    /// so consumers can detect duplicates.
+    pub id: String,
+
+    /// Context in which the event happened, based on the stream the event was emitted onto.
+    pub source: String,
+
+    /// Code of the event.
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Namespace and cluster the event is about, if the event payload identifies them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    /// Time the event was generated.
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+
+    /// Media type of the event data.
+    pub datacontenttype: String,
+
+    /// JSON encoded event payload.
+    #[serde(default)]
+    pub data: Value,
+
+    /// CloudEvents extension attributes, such as trace context.
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, String>,
+}
+
+impl CloudEvent {
+    /// Map an event emitted onto a stream to a CloudEvent.
+    pub fn from_entry(entry: &EventEntry) -> Result<CloudEvent, serde_json::Error> {
+        let id = serde_json::to_vec(entry)?;
+        let id = Uuid::new_v5(&CLOUDEVENTS_ID_NAMESPACE, &id).to_string();
+        let event = &entry.event;
+        let extensions = [METADATA_TRACE_PARENT, METADATA_TRACE_STATE]
+            .into_iter()
+            .filter_map(|key| {
+                let value = event.metadata.get(key)?;
+                Some((key.to_string(), value.clone()))
+            })
+            .collect();
+        Ok(CloudEvent {
+            specversion: CLOUDEVENTS_SPEC_VERSION.to_string(),
+            id,
+            source: format!("/replicore/{}", entry.stream),
+            type_: event.code.clone(),
+            subject: subject(event),
+            time: event.time,
+            datacontenttype: "application/json".to_string(),
+            data: event.payload.clone(),
+            extensions,
+        })
+    }
+}
+
+/// Page of events returned by a query, mapped onto CloudEvents.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CloudEventsPage {
+    /// Cursor to fetch events emitted after the last event in this page.
+    pub cursor: String,
+
+    /// Events matching the query.
+    pub items: Vec<CloudEvent>,
+}
+
+impl TryFrom<EventsPage> for CloudEventsPage {
+    type Error = serde_json::Error;
+
+    fn try_from(value: EventsPage) -> Result<Self, Self::Error> {
+        let items = value
+            .items
+            .iter()
+            .map(CloudEvent::from_entry)
+            .collect::<Result<_, _>>()?;
+        Ok(CloudEventsPage {
+            cursor: value.cursor,
+            items,
+        })
+    }
+}
+
+/// Encodings available to deliver or return events with.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EventsFormat {
+    /// Events are encoded in the CloudEvents 1.0 structured JSON format.
+    #[serde(rename = "cloudevents")]
+    CloudEvents,
+
+    /// Events are encoded as the Control Plane models them.
+    #[default]
+    #[serde(rename = "native")]
+    Native,
+}
+
+impl EventsFormat {
+    /// Media type of events encoded in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::CloudEvents => CLOUDEVENTS_CONTENT_TYPE,
+            Self::Native => "application/json",
+        }
+    }
+
+    /// Encode an event emitted onto a stream in this format.
+    pub fn encode(&self, entry: &EventEntry) -> Result<Vec<u8>, serde_json::Error> {
+        match self {
+            Self::CloudEvents => serde_json::to_vec(&CloudEvent::from_entry(entry)?),
+            Self::Native => serde_json::to_vec(entry),
+        }
+    }
+}
+
+/// Determine the CloudEvent subject from the namespace and cluster the event is about.
+///
+/// The scope recorded in event metadata is preferred over the event payload attributes.
+fn subject(event: &Event) -> Option<String> {
+    let attribute = |name: &str| event.payload.get(name).and_then(|v| v.as_str());
+    let ns_id = event.ns_id().or_else(|| attribute("ns_id"));
+    let cluster_id = event.cluster_id().or_else(|| attribute("cluster_id"));
+    match (ns_id, cluster_id) {
+        (Some(ns_id), Some(cluster_id)) => Some(format!("{}/{}", ns_id, cluster_id)),
+        (Some(ns_id), None) => Some(ns_id.to_string()),
+        (None, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CloudEvent;
+    use super::EventsFormat;
+    use crate::Event;
+    use crate::EventEntry;
+    use crate::EventStream;
+    use crate::METADATA_TRACE_PARENT;
+
+    const TRACE_PARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    fn entry(payload: serde_json::Value) -> EventEntry {
+        let mut event = Event::new_with_payload("CLUSTER_TEST", payload).unwrap();
+        event
+            .metadata
+            .insert(METADATA_TRACE_PARENT.into(), TRACE_PARENT.into());
+        event.metadata.insert("other".into(), "ignored".into());
+        EventEntry {
+            event,
+            stream: EventStream::Change,
+        }
+    }
+
+    #[test]
+    fn map_event_attributes() {
+        let entry = entry(serde_json::json!({"ns_id": "default", "cluster_id": "pg"}));
+        let cloud = CloudEvent::from_entry(&entry).unwrap();
+        assert_eq!(cloud.specversion, "1.0");
+        assert_eq!(cloud.source, "/replicore/change");
+        assert_eq!(cloud.type_, "CLUSTER_TEST");
+        assert_eq!(cloud.subject.as_deref(), Some("default/pg"));
+        assert_eq!(cloud.time, entry.event.time);
+        assert_eq!(cloud.data, entry.event.payload);
+        assert_eq!(cloud.extensions.len(), 1);
+        assert_eq!(cloud.extensions["traceparent"], TRACE_PARENT);
+
+        // IDs are stable for the same event.
+        let again = CloudEvent::from_entry(&entry).unwrap();
+        assert_eq!(cloud.id, again.id);
+    }
+
+    #[test]
+    fn subject_without_cluster() {
+        let ns = entry(serde_json::json!({"ns_id": "default"}));
+        let cloud = CloudEvent::from_entry(&ns).unwrap();
+        assert_eq!(cloud.subject.as_deref(), Some("default"));
+
+        let none = entry(serde_json::json!("no attributes"));
+        let cloud = CloudEvent::from_entry(&none).unwrap();
+        assert_eq!(cloud.subject, None);
+    }
+
+    #[test]
+    fn subject_from_metadata() {
+        let mut entry = entry(serde_json::json!({"id": "default"}));
+        entry.event = entry.event.for_cluster("default", "pg");
+        let cloud = CloudEvent::from_entry(&entry).unwrap();
+        assert_eq!(cloud.subject.as_deref(), Some("default/pg"));
+    }
+
+    #[test]
+    fn structured_json_encoding() {
+        let entry = entry(serde_json::json!({"ns_id": "default"}));
+        let encoded = EventsFormat::CloudEvents.encode(&entry).unwrap();
+        let encoded: serde_json::Value = serde_json::from_slice(&encoded).unwrap();
+        assert_eq!(encoded["type"], "CLUSTER_TEST");
+        assert_eq!(encoded["traceparent"], TRACE_PARENT);
+        assert_eq!(encoded["datacontenttype"], "application/json");
+        assert!(encoded.get("extensions").is_none());
+
+        let native = EventsFormat::Native.encode(&entry).unwrap();
+        let native: EventEntry = serde_json::from_slice(&native).unwrap();
+        assert_eq!(native, entry);
+    }
+}
@@ -1,8 +1,12 @@
-//! Errors returned by the replicore-events crate.
+//! Errors returned by the replicore-events-models crate.
 
 /// Errors dealing with events.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// The events query cursor is not valid.
+    #[error("the events query cursor '{0}' is not valid")]
+    InvalidCursor(String),
+
     /// Unable to decode event payload into the the specified type.
     #[error("unable to decode event payload into the the specified type")]
     PayloadDecode,
@@ -0,0 +1,152 @@
+//! Model of events emitted by the Control Plane.
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::Error;
+
+/// Event metadata key with the ID of the cluster the event is about.
+///
+/// Events queries filter by cluster against this attribute.
+pub const METADATA_CLUSTER_ID: &str = "core.replicante.io/cluster.id";
+
+/// Event metadata key with the ID of the namespace the event is about.
+///
+/// Events queries filter by namespace against this attribute.
+pub const METADATA_NS_ID: &str = "core.replicante.io/ns.id";
+
+/// Event metadata key with the sequence number of events published through the outbox.
+///
+/// Events are relayed from the outbox at-least-once: consumers can compare sequence numbers
+/// to detect events delivered more than once.
+pub const METADATA_OUTBOX_SEQ: &str = "core.replicante.io/outbox.seq";
+
+/// Event metadata key with the W3C `traceparent` of the operation that generated the event.
+///
+/// The key matches the CloudEvents distributed tracing extension attribute.
+pub const METADATA_TRACE_PARENT: &str = "traceparent";
+
+/// Event metadata key with the W3C `tracestate` of the operation that generated the event.
+pub const METADATA_TRACE_STATE: &str = "tracestate";
+
+/// An individual event emitted by the Control Plane.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    /// Identifier of the specific event (and its payload type).
+    pub code: String,
+
+    /// Additional unstructured metadata attached to the event.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+
+    /// JSON encoded event payload.
+    #[serde(default)]
+    pub payload: Value,
+
+    /// Time the event was generated.
+    #[serde(with = "time::serde::rfc3339")]
+    pub time: OffsetDateTime,
+}
+
+impl Event {
+    /// ID of the cluster the event is about, if any.
+    pub fn cluster_id(&self) -> Option<&str> {
+        self.metadata.get(METADATA_CLUSTER_ID).map(String::as_str)
+    }
+
+    /// Attempt to decode the event payload into the specified type.
+    pub fn decode<T>(&self) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        serde_json::from_value(self.payload.clone())
+            .context(Error::PayloadDecode)
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Record the cluster the event is about in the event metadata.
+    ///
+    /// Use this for events whose payload does not have top level `ns_id` and `cluster_id`
+    /// attributes, otherwise the scope is recorded from the payload when the event is emitted.
+    pub fn for_cluster<N, C>(mut self, ns_id: N, cluster_id: C) -> Event
+    where
+        N: Into<String>,
+        C: Into<String>,
+    {
+        self.metadata
+            .insert(METADATA_CLUSTER_ID.to_string(), cluster_id.into());
+        self.for_namespace(ns_id)
+    }
+
+    /// Record the namespace the event is about in the event metadata.
+    ///
+    /// Use this for events whose payload does not have a top level `ns_id` attribute,
+    /// otherwise the scope is recorded from the payload when the event is emitted.
+    pub fn for_namespace<N>(mut self, ns_id: N) -> Event
+    where
+        N: Into<String>,
+    {
+        self.metadata
+            .insert(METADATA_NS_ID.to_string(), ns_id.into());
+        self
+    }
+
+    /// Initialise a new [`Event`] with code and payload.
+    pub fn new_with_payload<C, P>(code: C, payload: P) -> Result<Event>
+    where
+        C: Into<String>,
+        P: Serialize,
+    {
+        let code = code.into();
+        let payload = serde_json::to_value(payload)?;
+        let event = Event {
+            code,
+            metadata: Default::default(),
+            payload,
+            time: OffsetDateTime::now_utc(),
+        };
+        Ok(event)
+    }
+
+    /// ID of the namespace the event is about, if any.
+    pub fn ns_id(&self) -> Option<&str> {
+        self.metadata.get(METADATA_NS_ID).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Event;
+
+    #[test]
+    fn decode_event() {
+        let event = Event {
+            code: "TEST".into(),
+            metadata: Default::default(),
+            payload: serde_json::json!("test string"),
+            time: time::OffsetDateTime::now_utc(),
+        };
+        let actual: String = event.decode().unwrap();
+        assert_eq!(actual, "test string");
+    }
+
+    #[test]
+    fn event_scope() {
+        let event = Event::new_with_payload("TEST", ()).unwrap();
+        assert_eq!(event.ns_id(), None);
+        assert_eq!(event.cluster_id(), None);
+
+        let event = event.for_namespace("ns");
+        assert_eq!(event.ns_id(), Some("ns"));
+        assert_eq!(event.cluster_id(), None);
+
+        let event = event.for_cluster("other", "cluster");
+        assert_eq!(event.ns_id(), Some("other"));
+        assert_eq!(event.cluster_id(), Some("cluster"));
+    }
+}
@@ -0,0 +1,26 @@
+//! Data models for RepliCore Control Plane events related operations.
+mod catalog;
+mod cloudevents;
+mod errors;
+mod event;
+mod query;
+
+pub use self::catalog::EventCode;
+pub use self::catalog::EventCodeList;
+pub use self::cloudevents::CloudEvent;
+pub use self::cloudevents::CloudEventsPage;
+pub use self::cloudevents::EventsFormat;
+pub use self::cloudevents::CLOUDEVENTS_CONTENT_TYPE;
+pub use self::cloudevents::CLOUDEVENTS_SPEC_VERSION;
+pub use self::errors::Error;
+pub use self::event::Event;
+pub use self::event::METADATA_CLUSTER_ID;
+pub use self::event::METADATA_NS_ID;
+pub use self::event::METADATA_OUTBOX_SEQ;
+pub use self::event::METADATA_TRACE_PARENT;
+pub use self::event::METADATA_TRACE_STATE;
+pub use self::query::EventEntry;
+pub use self::query::EventStream;
+pub use self::query::EventsCursor;
+pub use self::query::EventsPage;
+pub use self::query::EventsQuery;
@@ -0,0 +1,343 @@
+//! Data models to query events back from the streaming platform.
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::Error;
+use crate::Event;
+
+/// Streams events are emitted onto.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum EventStream {
+    /// Auditing events stream.
+    #[serde(rename = "audit")]
+    Audit,
+
+    /// Stream of changes to elements in the system.
+    #[serde(rename = "change")]
+    Change,
+}
+
+impl std::fmt::Display for EventStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Audit => write!(f, "audit"),
+            Self::Change => write!(f, "change"),
+        }
+    }
+}
+
+/// Event returned by a query along with the stream it was emitted onto.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EventEntry {
+    /// The event as emitted onto the stream.
+    pub event: Event,
+
+    /// The stream the event was emitted onto.
+    pub stream: EventStream,
+}
+
+/// Position in the events streams after which queries resume.
+///
+/// Backends assign increasing sequence numbers to events emitted on each stream
+/// and cursors track the sequence of the last event returned from each stream.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EventsCursor {
+    /// Sequence number of the last event returned from the audit stream.
+    pub audit: u64,
+
+    /// Sequence number of the last event returned from the change stream.
+    pub change: u64,
+}
+
+impl EventsCursor {
+    /// Decode a cursor returned by a previous query.
+    pub fn decode(cursor: &str) -> Result<EventsCursor, Error> {
+        let invalid = || Error::InvalidCursor(cursor.to_string());
+        let (audit, change) = cursor.split_once('-').ok_or_else(invalid)?;
+        let audit = audit.parse().map_err(|_| invalid())?;
+        let change = change.parse().map_err(|_| invalid())?;
+        Ok(EventsCursor { audit, change })
+    }
+
+    /// Encode the cursor for clients to resume queries from.
+    pub fn encode(&self) -> String {
+        format!("{}-{}", self.audit, self.change)
+    }
+
+    /// Sequence number of the last event returned from the given stream.
+    pub fn get(&self, stream: EventStream) -> u64 {
+        match stream {
+            EventStream::Audit => self.audit,
+            EventStream::Change => self.change,
+        }
+    }
+
+    /// Move the cursor for the given stream to the given sequence number.
+    pub fn set(&mut self, stream: EventStream, seq: u64) {
+        match stream {
+            EventStream::Audit => self.audit = seq,
+            EventStream::Change => self.change = seq,
+        }
+    }
+}
+
+/// Page of events returned by a query, oldest first.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EventsPage {
+    /// Cursor to fetch events emitted after the last event in this page.
+    ///
+    /// The cursor is returned even when no events match the query
+    /// so clients can wait for new events to be emitted.
+    pub cursor: String,
+
+    /// Events matching the query.
+    pub items: Vec<EventEntry>,
+}
+
+impl EventsPage {
+    /// Merge events from the audit and change streams into a page of up to `limit` events.
+    ///
+    /// Events for each stream must be given in sequence order along with their sequence number.
+    /// Streams are merged in event time order, without reordering events within a stream,
+    /// so the returned cursor never skips events that were not included in the page.
+    pub fn merge(
+        cursor: EventsCursor,
+        audit: Vec<(u64, Event)>,
+        change: Vec<(u64, Event)>,
+        limit: usize,
+    ) -> EventsPage {
+        let mut cursor = cursor;
+        let mut items = Vec::new();
+        let mut audit = audit.into_iter().peekable();
+        let mut change = change.into_iter().peekable();
+        while items.len() < limit {
+            let stream = match (audit.peek(), change.peek()) {
+                (None, None) => break,
+                (Some(_), None) => EventStream::Audit,
+                (None, Some(_)) => EventStream::Change,
+                (Some((_, a)), Some((_, c))) if a.time <= c.time => EventStream::Audit,
+                (Some(_), Some(_)) => EventStream::Change,
+            };
+            let next = match stream {
+                EventStream::Audit => audit.next(),
+                EventStream::Change => change.next(),
+            };
+            let (seq, event) = next.expect("peeked stream must have a next event");
+            cursor.set(stream, seq);
+            items.push(EventEntry { event, stream });
+        }
+        EventsPage {
+            cursor: cursor.encode(),
+            items,
+        }
+    }
+
+    /// Merge the most recent events from the audit and change streams into a page.
+    ///
+    /// Events for each stream must be the most recent matching events, in sequence order.
+    /// The page includes up to `limit` of the most recent events, still oldest first,
+    /// and the cursor moves past all given events so older events are not returned later.
+    pub fn merge_latest(
+        cursor: EventsCursor,
+        audit: Vec<(u64, Event)>,
+        change: Vec<(u64, Event)>,
+        limit: usize,
+    ) -> EventsPage {
+        let mut cursor = cursor;
+        if let Some((seq, _)) = audit.last() {
+            cursor.set(EventStream::Audit, *seq);
+        }
+        if let Some((seq, _)) = change.last() {
+            cursor.set(EventStream::Change, *seq);
+        }
+
+        // Merge streams from the newest event backwards, then restore the page order.
+        let mut items = Vec::new();
+        let mut audit = audit.into_iter().rev().peekable();
+        let mut change = change.into_iter().rev().peekable();
+        while items.len() < limit {
+            let stream = match (audit.peek(), change.peek()) {
+                (None, None) => break,
+                (Some(_), None) => EventStream::Audit,
+                (None, Some(_)) => EventStream::Change,
+                (Some((_, a)), Some((_, c))) if a.time > c.time => EventStream::Audit,
+                (Some(_), Some(_)) => EventStream::Change,
+            };
+            let next = match stream {
+                EventStream::Audit => audit.next(),
+                EventStream::Change => change.next(),
+            };
+            let (_, event) = next.expect("peeked stream must have a next event");
+            items.push(EventEntry { event, stream });
+        }
+        items.reverse();
+        EventsPage {
+            cursor: cursor.encode(),
+            items,
+        }
+    }
+}
+
+/// Filters and pagination options for events queries.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EventsQuery {
+    /// Only return events about the cluster with this ID.
+    ///
+    /// Clusters are matched against the cluster recorded in event metadata when emitted.
+    #[serde(default)]
+    pub cluster_id: Option<String>,
+
+    /// Only return events with this code.
+    #[serde(default)]
+    pub code: Option<String>,
+
+    /// Return the most recent matching events instead of the oldest ones.
+    ///
+    /// Events in the page are still ordered oldest first and the returned cursor
+    /// resumes after the most recent matching event.
+    #[serde(default)]
+    pub latest: bool,
+
+    /// Only return events emitted after the position returned by a previous query.
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Maximum number of events to return.
+    #[serde(default = "EventsQuery::default_limit")]
+    pub limit: usize,
+
+    /// Only return events about the namespace with this ID.
+    ///
+    /// Namespaces are matched against the namespace recorded in event metadata when emitted.
+    #[serde(default)]
+    pub ns_id: Option<String>,
+
+    /// Only return events that occurred at or after this time.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+
+    /// Only return events emitted onto this stream.
+    #[serde(default)]
+    pub stream: Option<EventStream>,
+
+    /// Only return events that occurred before this time.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub until: Option<OffsetDateTime>,
+}
+
+impl Default for EventsQuery {
+    fn default() -> Self {
+        EventsQuery {
+            cluster_id: None,
+            code: None,
+            cursor: None,
+            latest: false,
+            limit: EventsQuery::default_limit(),
+            ns_id: None,
+            since: None,
+            stream: None,
+            until: None,
+        }
+    }
+}
+
+impl EventsQuery {
+    /// Decode the query cursor, starting from the beginning of the streams if not set.
+    pub fn decode_cursor(&self) -> Result<EventsCursor, Error> {
+        match &self.cursor {
+            None => Ok(EventsCursor::default()),
+            Some(cursor) => EventsCursor::decode(cursor),
+        }
+    }
+
+    /// Check if events emitted onto the given stream should be queried at all.
+    pub fn includes(&self, stream: EventStream) -> bool {
+        self.stream.map(|filter| filter == stream).unwrap_or(true)
+    }
+
+    /// Check if an event matches the query filters, except for the cursor.
+    pub fn matches(&self, stream: EventStream, event: &Event) -> bool {
+        self.includes(stream)
+            && match_filter(&self.code, Some(event.code.as_str()))
+            && match_filter(&self.ns_id, event.ns_id())
+            && match_filter(&self.cluster_id, event.cluster_id())
+            && self.since.map(|since| event.time >= since).unwrap_or(true)
+            && self.until.map(|until| event.time < until).unwrap_or(true)
+    }
+
+    fn default_limit() -> usize {
+        100
+    }
+}
+
+/// Check an optional filter against an optional attribute value.
+fn match_filter(filter: &Option<String>, value: Option<&str>) -> bool {
+    match filter {
+        None => true,
+        Some(filter) => value == Some(filter.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+    use time::OffsetDateTime;
+
+    use super::EventStream;
+    use super::EventsCursor;
+    use super::EventsPage;
+    use crate::Event;
+
+    fn event_at(code: &str, time: OffsetDateTime) -> Event {
+        let mut event = Event::new_with_payload(code, serde_json::Value::Null).unwrap();
+        event.time = time;
+        event
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = EventsCursor {
+            audit: 4,
+            change: 2,
+        };
+        let decoded = EventsCursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded, cursor);
+        assert!(EventsCursor::decode("invalid").is_err());
+    }
+
+    #[test]
+    fn merge_in_time_order() {
+        let now = OffsetDateTime::now_utc();
+        let audit = vec![
+            (1, event_at("A1", now)),
+            (2, event_at("A2", now + Duration::seconds(2))),
+        ];
+        let change = vec![(7, event_at("C1", now + Duration::seconds(1)))];
+        let page = EventsPage::merge(EventsCursor::default(), audit, change, 2);
+
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["A1", "C1"]);
+        assert_eq!(page.items[1].stream, EventStream::Change);
+        assert_eq!(page.cursor, "1-7");
+    }
+
+    #[test]
+    fn merge_latest_in_time_order() {
+        let now = OffsetDateTime::now_utc();
+        let audit = vec![
+            (1, event_at("A1", now)),
+            (2, event_at("A2", now + Duration::seconds(2))),
+        ];
+        let change = vec![
+            (6, event_at("C1", now - Duration::seconds(1))),
+            (7, event_at("C2", now + Duration::seconds(1))),
+        ];
+        let page = EventsPage::merge_latest(EventsCursor::default(), audit, change, 3);
+
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["A1", "C2", "A2"]);
+        assert_eq!(page.items[1].stream, EventStream::Change);
+        assert_eq!(page.cursor, "2-7");
+    }
+}
@@ -0,0 +1,194 @@
+//! Periodically delete events older than the configured retention age.
+use std::time::Duration;
+
+use anyhow::Result;
+use opentelemetry_api::trace::FutureExt;
+use time::OffsetDateTime;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::encoding;
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_events::emit::EventsMaintenanceBackend;
+use replicore_events::emit::MaintenanceExit;
+
+use crate::conf::Retention;
+use crate::Conf;
+
+const CLEAN_AUDIT_SQL: &str = r#"
+DELETE FROM events_audit
+WHERE rowid IN (
+    SELECT rowid
+    FROM events_audit
+    WHERE time < ?1
+    ORDER BY time ASC
+    LIMIT ?2
+);
+"#;
+
+const CLEAN_CHANGE_SQL: &str = r#"
+DELETE FROM events_change
+WHERE rowid IN (
+    SELECT rowid
+    FROM events_change
+    WHERE time < ?1
+    ORDER BY time ASC
+    LIMIT ?2
+);
+"#;
+
+const OLDEST_AUDIT_SQL: &str = "SELECT MIN(time) FROM events_audit;";
+const OLDEST_CHANGE_SQL: &str = "SELECT MIN(time) FROM events_change;";
+
+/// Events streams to clean up, as (stream name, clean up SQL, oldest event SQL) tuples.
+const STREAMS: [(&str, &str, &str); 2] = [
+    ("audit", CLEAN_AUDIT_SQL, OLDEST_AUDIT_SQL),
+    ("change", CLEAN_CHANGE_SQL, OLDEST_CHANGE_SQL),
+];
+
+/// Delete events older than the retention age, in batches, at regular intervals.
+pub struct SQLiteHistoryClean {
+    connection: Connection,
+    retention: Retention,
+}
+
+impl SQLiteHistoryClean {
+    /// Initialise the SQLite events history clean up loop.
+    pub async fn new(context: &Context, conf: &Conf) -> Result<Self> {
+        let connection = crate::client::create(context, &conf.path).await?;
+        let retention = conf.retention.clone();
+        Ok(SQLiteHistoryClean {
+            connection,
+            retention,
+        })
+    }
+
+    /// Delete up to a batch of expired events from each stream.
+    pub async fn clean(&self, context: &Context) -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let cutoff = now - time::Duration::days(i64::from(self.retention.age));
+        let cutoff = encoding::encode_time_f64(cutoff)?;
+        let limit = self.retention.clean_batch;
+
+        for (stream, clean_sql, oldest_sql) in STREAMS {
+            let op = format!("clean.{}", stream);
+            let (err_count, _timer) = crate::telemetry::observe_op(&op);
+            let trace = crate::telemetry::trace_op(&op);
+            let (deleted, oldest) = self
+                .connection
+                .call(move |connection| {
+                    let deleted =
+                        connection.execute(clean_sql, rusqlite::params![cutoff, limit])?;
+                    let oldest: Option<f64> =
+                        connection.query_row(oldest_sql, [], |row| row.get(0))?;
+                    Ok((deleted, oldest))
+                })
+                .count_on_err(err_count)
+                .trace_on_err_with_status()
+                .with_context(trace)
+                .await?;
+
+            // Lag is how long the oldest event left in the stream is past its expiry.
+            let lag = oldest.map(|oldest| cutoff - oldest).unwrap_or_default();
+            crate::telemetry::CLEAN_DELETED
+                .with_label_values(&[stream])
+                .inc_by(deleted as f64);
+            crate::telemetry::CLEAN_LAG
+                .with_label_values(&[stream])
+                .set(lag.max(0.0));
+            slog::debug!(
+                context.logger, "Deleted expired events";
+                "stream" => stream,
+                "deleted" => deleted,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsMaintenanceBackend for SQLiteHistoryClean {
+    async fn run(&self, context: &Context, mut exit: MaintenanceExit) -> Result<()> {
+        let delay = Duration::from_secs(u64::from(self.retention.clean_delay) * 60);
+        loop {
+            // Errors are logged and retried on the next loop.
+            if let Err(error) = self.clean(context).await {
+                slog::warn!(
+                    context.logger, "Failed to delete expired events";
+                    replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                );
+            }
+
+            tokio::select! {
+                _ = &mut exit => return Ok(()),
+                _ = tokio::time::sleep(delay) => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_context::Context;
+
+    use super::SQLiteHistoryClean;
+    use crate::conf::Retention;
+
+    const INSERT_SQL: &str = "INSERT INTO events_audit (event, time) VALUES (?1, ?2);";
+    const COUNT_SQL: &str = "SELECT COUNT(*) FROM events_audit;";
+
+    async fn clean_fixture(clean_batch: u32) -> SQLiteHistoryClean {
+        let connection = crate::client::fixture().await;
+        let retention = Retention {
+            age: 1,
+            clean_batch,
+            clean_delay: 1,
+        };
+        SQLiteHistoryClean {
+            connection,
+            retention,
+        }
+    }
+
+    async fn insert_audit(clean: &SQLiteHistoryClean, age: time::Duration) {
+        let event = replicore_events::Event::new_with_payload("TEST", ()).unwrap();
+        let time = time::OffsetDateTime::now_utc() - age;
+        let time = replisdk::utils::encoding::encode_time_f64(time).unwrap();
+        let event = replisdk::utils::encoding::encode_serde(&event).unwrap();
+        clean
+            .connection
+            .call(move |connection| {
+                connection.execute(INSERT_SQL, rusqlite::params![event, time])?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn count_audit(clean: &SQLiteHistoryClean) -> u64 {
+        clean
+            .connection
+            .call(|connection| Ok(connection.query_row(COUNT_SQL, [], |row| row.get(0))?))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn clean_expired_in_batches() {
+        let context = Context::fixture();
+        let clean = clean_fixture(2).await;
+        for _ in 0..3 {
+            insert_audit(&clean, time::Duration::days(2)).await;
+        }
+        insert_audit(&clean, time::Duration::ZERO).await;
+
+        clean.clean(&context).await.unwrap();
+        assert_eq!(count_audit(&clean).await, 2);
+        clean.clean(&context).await.unwrap();
+        assert_eq!(count_audit(&clean).await, 1);
+        clean.clean(&context).await.unwrap();
+        assert_eq!(count_audit(&clean).await, 1);
+    }
+}
@@ -31,3 +31,20 @@ pub async fn create(context: &Context, path: &str) -> Result<Connection> {
     let connection = connection?;
     Ok(connection)
 }
+
+/// Create an in-memory SQLite DB [`Connection`] with the events schema for unit tests.
+#[cfg(test)]
+pub async fn fixture() -> Connection {
+    let connection = Connection::open_in_memory().await.unwrap();
+    connection
+        .call(|connection| {
+            crate::schema::migrations::runner()
+                .set_migration_table_name(REFINERY_SCHEMA_TABLE_NAME)
+                .run(connection)
+                .map_err(|error| tokio_rusqlite::Error::Other(Box::new(error)))?;
+            Ok(())
+        })
+        .await
+        .unwrap();
+    connection
+}
@@ -10,20 +10,22 @@ use replisdk::utils::trace::TraceFutureStdErrExt;
 use replicore_context::Context;
 use replicore_events::emit::EventsBackend;
 use replicore_events::Event;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
 
 use crate::Conf;
 
 const EMIT_AUDIT_SQL: &str = r#"
-INSERT INTO events_audit (event, time)
-VALUES (?1, ?2);
+INSERT INTO events_audit (event, time, ns_id, cluster_id)
+VALUES (?1, ?2, ?3, ?4);
 "#;
 
 const EMIT_CHANGE_SQL: &str = r#"
-INSERT INTO events_change (event, time)
-VALUES (?1, ?2);
+INSERT INTO events_change (event, time, ns_id, cluster_id)
+VALUES (?1, ?2, ?3, ?4);
 "#;
 
-/// SQLite backed events implementation/
+/// SQLite backed events implementation.
 pub struct SQLiteEvents {
     connection: Connection,
 }
@@ -41,14 +43,17 @@ impl EventsBackend for SQLiteEvents {
     async fn audit(&self, _: &Context, event: Event) -> Result<()> {
         // Serialise the event.
         let serialised = encoding::encode_serde(&event)?;
-        let time = encoding::encode_time(event.time)?;
+        let time = encoding::encode_time_f64(event.time)?;
+        let ns_id = event.ns_id().map(String::from);
+        let cluster_id = event.cluster_id().map(String::from);
 
         // Insert it into the DB.
         let (err_count, _timer) = crate::telemetry::observe_op("emit.audit");
         let trace = crate::telemetry::trace_op("emit.audit");
         self.connection
             .call(move |connection| {
-                connection.execute(EMIT_AUDIT_SQL, rusqlite::params![serialised, time])?;
+                let params = rusqlite::params![serialised, time, ns_id, cluster_id];
+                connection.execute(EMIT_AUDIT_SQL, params)?;
                 Ok(())
             })
             .count_on_err(err_count)
@@ -61,14 +66,17 @@ impl EventsBackend for SQLiteEvents {
     async fn change(&self, _: &Context, event: Event) -> Result<()> {
         // Serialise the event.
         let serialised = encoding::encode_serde(&event)?;
-        let time = encoding::encode_time(event.time)?;
+        let time = encoding::encode_time_f64(event.time)?;
+        let ns_id = event.ns_id().map(String::from);
+        let cluster_id = event.cluster_id().map(String::from);
 
         // Insert it into the DB.
         let (err_count, _timer) = crate::telemetry::observe_op("emit.change");
         let trace = crate::telemetry::trace_op("emit.change");
         self.connection
             .call(move |connection| {
-                connection.execute(EMIT_CHANGE_SQL, rusqlite::params![serialised, time])?;
+                let params = rusqlite::params![serialised, time, ns_id, cluster_id];
+                connection.execute(EMIT_CHANGE_SQL, params)?;
                 Ok(())
             })
             .count_on_err(err_count)
@@ -77,4 +85,8 @@ impl EventsBackend for SQLiteEvents {
             .await?;
         Ok(())
     }
+
+    async fn query(&self, context: &Context, query: EventsQuery) -> Result<EventsPage> {
+        crate::query::query(context, &self.connection, query).await
+    }
 }
@@ -8,8 +8,10 @@ use replicore_events::emit::Events;
 use replicore_events::emit::EventsFactory;
 use replicore_events::emit::EventsFactoryArgs;
 use replicore_events::emit::EventsFactorySyncArgs;
+use replicore_events::emit::EventsMaintenance;
 
 use super::events::SQLiteEvents;
+use crate::clean::SQLiteHistoryClean;
 use crate::Conf;
 
 /// The SQLite events backend configuration is not valid.
@@ -37,6 +39,15 @@ impl EventsFactory for SQLiteFactory {
         Ok(Events::from(events))
     }
 
+    async fn maintenance<'a>(
+        &self,
+        args: EventsFactoryArgs<'a>,
+    ) -> Result<Option<EventsMaintenance>> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).unwrap();
+        let clean = SQLiteHistoryClean::new(args.context, &conf).await?;
+        Ok(Some(EventsMaintenance::from(clean)))
+    }
+
     async fn sync<'a>(&self, args: EventsFactorySyncArgs<'a>) -> Result<()> {
         // Create the SQLite client.
         let conf: Conf = serde_json::from_value(args.conf.clone()).unwrap();
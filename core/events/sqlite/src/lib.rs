@@ -9,9 +9,19 @@
 //!
 //! - Table names are prefixed with `events_` to avoid clashes.
 //! - Schema migration data is kept in the `refinery_schema_history__events` table.
+//!
+//! ## History clean up
+//!
+//! Events older than the configured retention age are deleted by a background loop.
+//! Each run of the loop deletes at most `clean_batch` events from each events stream
+//! so large backlogs of expired events are worked through without long DB locks.
+//!
+//! The loop runs as events maintenance, only in the process leading the Control Plane election.
+mod clean;
 mod client;
 mod conf;
 pub mod emit;
+mod query;
 mod schema;
 mod telemetry;
 
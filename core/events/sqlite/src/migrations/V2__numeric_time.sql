@@ -0,0 +1,4 @@
+-- Event times were stored as RFC 3339 strings, which can't be compared to numeric timestamps.
+-- Convert them to UNIX timestamps (with sub-second precision) to support retention clean up.
+UPDATE events_audit SET time = unixepoch(time, 'subsec') WHERE typeof(time) = 'text';
+UPDATE events_change SET time = unixepoch(time, 'subsec') WHERE typeof(time) = 'text';
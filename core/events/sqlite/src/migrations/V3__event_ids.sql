@@ -0,0 +1,42 @@
+-- Assign events IDs that are never reused so queries can resume after the last event returned.
+-- SQLite can't add a primary key to existing tables so the tables are re-created.
+
+CREATE TABLE IF NOT EXISTS events_audit_ids(
+  -- Increasing ID of the event, never reused even once events are deleted.
+  event_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+  -- Event as a JSON blob.
+  event TEXT NOT NULL,
+
+  -- Manually managed normalised columns for indexes (where advanced types are involved).
+  time REAL NOT NULL,
+
+  -- Virtual columns to index events on.
+  code TEXT NOT NULL AS (json_extract(event, '$.code'))
+);
+INSERT INTO events_audit_ids (event, time)
+SELECT event, time FROM events_audit ORDER BY rowid ASC;
+DROP TABLE events_audit;
+ALTER TABLE events_audit_ids RENAME TO events_audit;
+CREATE INDEX events_audit_time ON events_audit(time, code);
+CREATE INDEX events_audit_code ON events_audit(code, event_id);
+
+CREATE TABLE IF NOT EXISTS events_change_ids(
+  -- Increasing ID of the event, never reused even once events are deleted.
+  event_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+  -- Event as a JSON blob.
+  event TEXT NOT NULL,
+
+  -- Manually managed normalised columns for indexes (where advanced types are involved).
+  time REAL NOT NULL,
+
+  -- Virtual columns to index events on.
+  code TEXT NOT NULL AS (json_extract(event, '$.code'))
+);
+INSERT INTO events_change_ids (event, time)
+SELECT event, time FROM events_change ORDER BY rowid ASC;
+DROP TABLE events_change;
+ALTER TABLE events_change_ids RENAME TO events_change;
+CREATE INDEX events_change_time ON events_change(time, code);
+CREATE INDEX events_change_code ON events_change(code, event_id);
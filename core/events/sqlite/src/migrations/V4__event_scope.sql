@@ -0,0 +1,40 @@
+-- Store the namespace and cluster events are about in indexed columns for queries to filter on.
+-- Values are set from the event metadata when events are emitted.
+ALTER TABLE events_audit ADD COLUMN ns_id TEXT;
+ALTER TABLE events_audit ADD COLUMN cluster_id TEXT;
+ALTER TABLE events_change ADD COLUMN ns_id TEXT;
+ALTER TABLE events_change ADD COLUMN cluster_id TEXT;
+
+-- Existing events may not have their scope in metadata so it is derived from payloads,
+-- including namespace events (payload `id`) and cluster discovery updates (payload `after`).
+UPDATE events_audit SET
+  ns_id = COALESCE(
+    json_extract(event, '$.metadata."core.replicante.io/ns.id"'),
+    json_extract(event, '$.payload.ns_id'),
+    json_extract(event, '$.payload.after.ns_id'),
+    CASE WHEN code LIKE 'NAMESPACE_%' THEN json_extract(event, '$.payload.id') END
+  ),
+  cluster_id = COALESCE(
+    json_extract(event, '$.metadata."core.replicante.io/cluster.id"'),
+    json_extract(event, '$.payload.cluster_id'),
+    json_extract(event, '$.payload.after.cluster_id'),
+    CASE WHEN code = 'CLUSTER_SPEC_DELETED' THEN json_extract(event, '$.payload.name') END
+  );
+UPDATE events_change SET
+  ns_id = COALESCE(
+    json_extract(event, '$.metadata."core.replicante.io/ns.id"'),
+    json_extract(event, '$.payload.ns_id'),
+    json_extract(event, '$.payload.after.ns_id'),
+    CASE WHEN code LIKE 'NAMESPACE_%' THEN json_extract(event, '$.payload.id') END
+  ),
+  cluster_id = COALESCE(
+    json_extract(event, '$.metadata."core.replicante.io/cluster.id"'),
+    json_extract(event, '$.payload.cluster_id'),
+    json_extract(event, '$.payload.after.cluster_id'),
+    CASE WHEN code = 'CLUSTER_SPEC_DELETED' THEN json_extract(event, '$.payload.name') END
+  );
+
+CREATE INDEX events_audit_ns ON events_audit(ns_id, event_id);
+CREATE INDEX events_audit_cluster ON events_audit(cluster_id, event_id);
+CREATE INDEX events_change_ns ON events_change(ns_id, event_id);
+CREATE INDEX events_change_cluster ON events_change(cluster_id, event_id);
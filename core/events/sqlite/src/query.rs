@@ -0,0 +1,295 @@
+//! Query events back from the SQLite store.
+use anyhow::Result;
+use opentelemetry_api::trace::FutureExt;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::encoding;
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_events::Event;
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
+
+const QUERY_AUDIT_SQL: &str = r#"
+SELECT event_id, event
+FROM events_audit
+WHERE
+    event_id > ?1
+    AND (?2 IS NULL OR code = ?2)
+    AND (?3 IS NULL OR time >= ?3)
+    AND (?4 IS NULL OR time < ?4)
+    AND (?5 IS NULL OR ns_id = ?5)
+    AND (?6 IS NULL OR cluster_id = ?6)
+ORDER BY event_id ASC
+LIMIT ?7;
+"#;
+
+const QUERY_CHANGE_SQL: &str = r#"
+SELECT event_id, event
+FROM events_change
+WHERE
+    event_id > ?1
+    AND (?2 IS NULL OR code = ?2)
+    AND (?3 IS NULL OR time >= ?3)
+    AND (?4 IS NULL OR time < ?4)
+    AND (?5 IS NULL OR ns_id = ?5)
+    AND (?6 IS NULL OR cluster_id = ?6)
+ORDER BY event_id ASC
+LIMIT ?7;
+"#;
+
+// Most recent events are selected newest first and returned to callers in sequence order.
+const QUERY_AUDIT_LATEST_SQL: &str = r#"
+SELECT event_id, event
+FROM events_audit
+WHERE
+    event_id > ?1
+    AND (?2 IS NULL OR code = ?2)
+    AND (?3 IS NULL OR time >= ?3)
+    AND (?4 IS NULL OR time < ?4)
+    AND (?5 IS NULL OR ns_id = ?5)
+    AND (?6 IS NULL OR cluster_id = ?6)
+ORDER BY event_id DESC
+LIMIT ?7;
+"#;
+
+const QUERY_CHANGE_LATEST_SQL: &str = r#"
+SELECT event_id, event
+FROM events_change
+WHERE
+    event_id > ?1
+    AND (?2 IS NULL OR code = ?2)
+    AND (?3 IS NULL OR time >= ?3)
+    AND (?4 IS NULL OR time < ?4)
+    AND (?5 IS NULL OR ns_id = ?5)
+    AND (?6 IS NULL OR cluster_id = ?6)
+ORDER BY event_id DESC
+LIMIT ?7;
+"#;
+
+/// Query events matching the filters, from the query cursor onwards.
+pub async fn query(_: &Context, connection: &Connection, query: EventsQuery) -> Result<EventsPage> {
+    let cursor = query.decode_cursor()?;
+    let audit = match query.includes(EventStream::Audit) {
+        false => Vec::new(),
+        true => query_stream(connection, &query, EventStream::Audit, cursor.audit).await?,
+    };
+    let change = match query.includes(EventStream::Change) {
+        false => Vec::new(),
+        true => query_stream(connection, &query, EventStream::Change, cursor.change).await?,
+    };
+    match query.latest {
+        false => Ok(EventsPage::merge(cursor, audit, change, query.limit)),
+        true => Ok(EventsPage::merge_latest(cursor, audit, change, query.limit)),
+    }
+}
+
+/// Query up to a page of matching events from a single stream, in sequence order.
+async fn query_stream(
+    connection: &Connection,
+    query: &EventsQuery,
+    stream: EventStream,
+    after: u64,
+) -> Result<Vec<(u64, Event)>> {
+    let (op, sql) = match (stream, query.latest) {
+        (EventStream::Audit, false) => ("query.audit", QUERY_AUDIT_SQL),
+        (EventStream::Audit, true) => ("query.audit", QUERY_AUDIT_LATEST_SQL),
+        (EventStream::Change, false) => ("query.change", QUERY_CHANGE_SQL),
+        (EventStream::Change, true) => ("query.change", QUERY_CHANGE_LATEST_SQL),
+    };
+    let code = query.code.clone();
+    let since = query.since.map(encoding::encode_time_f64).transpose()?;
+    let until = query.until.map(encoding::encode_time_f64).transpose()?;
+    let ns_id = query.ns_id.clone();
+    let cluster_id = query.cluster_id.clone();
+    let after = i64::try_from(after).unwrap_or(i64::MAX);
+    let limit = i64::try_from(query.limit).unwrap_or(i64::MAX);
+
+    let (err_count, _timer) = crate::telemetry::observe_op(op);
+    let trace = crate::telemetry::trace_op(op);
+    let rows = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(sql)?;
+            let mut rows = statement.query(rusqlite::params![
+                after, code, since, until, ns_id, cluster_id, limit,
+            ])?;
+            let mut events = Vec::new();
+            while let Some(row) = rows.next()? {
+                let event_id: i64 = row.get("event_id")?;
+                let event: String = row.get("event")?;
+                events.push((event_id, event));
+            }
+            Ok(events)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+
+    let mut events = Vec::with_capacity(rows.len());
+    for (event_id, event) in rows {
+        let event: Event = encoding::decode_serde(&event)?;
+        events.push((event_id as u64, event));
+    }
+    if query.latest {
+        events.reverse();
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_context::Context;
+    use replicore_events::Event;
+    use replicore_events_models::EventStream;
+    use replicore_events_models::EventsQuery;
+    use tokio_rusqlite::Connection;
+
+    const INSERT_AUDIT_SQL: &str =
+        "INSERT INTO events_audit (event, time, ns_id, cluster_id) VALUES (?1, ?2, ?3, ?4);";
+    const INSERT_CHANGE_SQL: &str =
+        "INSERT INTO events_change (event, time, ns_id, cluster_id) VALUES (?1, ?2, ?3, ?4);";
+
+    async fn insert(connection: &Connection, stream: EventStream, code: &str, ns_id: &str) {
+        let payload = serde_json::json!({"ns_id": ns_id});
+        let event = Event::new_with_payload(code, payload).unwrap();
+        insert_event(connection, stream, event).await;
+    }
+
+    async fn insert_event(connection: &Connection, stream: EventStream, mut event: Event) {
+        replicore_events::scope::attach_scope(&mut event);
+        let ns_id = event.ns_id().map(String::from);
+        let cluster_id = event.cluster_id().map(String::from);
+        let time = replisdk::utils::encoding::encode_time_f64(event.time).unwrap();
+        let event = replisdk::utils::encoding::encode_serde(&event).unwrap();
+        let sql = match stream {
+            EventStream::Audit => INSERT_AUDIT_SQL,
+            EventStream::Change => INSERT_CHANGE_SQL,
+        };
+        connection
+            .call(move |connection| {
+                let params = rusqlite::params![event, time, ns_id, cluster_id];
+                connection.execute(sql, params)?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn query_filter_and_resume() {
+        let context = Context::fixture();
+        let connection = crate::client::fixture().await;
+        insert(&connection, EventStream::Audit, "A", "ns1").await;
+        insert(&connection, EventStream::Change, "B", "ns1").await;
+        insert(&connection, EventStream::Change, "C", "ns2").await;
+        insert(&connection, EventStream::Change, "D", "ns1").await;
+
+        // Filter events by namespace and page through them.
+        let query = EventsQuery {
+            limit: 2,
+            ns_id: Some("ns1".into()),
+            ..Default::default()
+        };
+        let page = super::query(&context, &connection, query.clone())
+            .await
+            .unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["A", "B"]);
+        assert_eq!(page.items[0].stream, EventStream::Audit);
+
+        let query = EventsQuery {
+            cursor: Some(page.cursor),
+            ..query
+        };
+        let page = super::query(&context, &connection, query.clone())
+            .await
+            .unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["D"]);
+
+        // Resuming from the last cursor returns only new events.
+        let query = EventsQuery {
+            cursor: Some(page.cursor),
+            ..query
+        };
+        let page = super::query(&context, &connection, query).await.unwrap();
+        assert!(page.items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn query_latest() {
+        let context = Context::fixture();
+        let connection = crate::client::fixture().await;
+        insert(&connection, EventStream::Change, "A", "ns1").await;
+        insert(&connection, EventStream::Audit, "B", "ns1").await;
+        insert(&connection, EventStream::Change, "C", "ns1").await;
+
+        // Return the most recent events, oldest first.
+        let query = EventsQuery {
+            latest: true,
+            limit: 2,
+            ..Default::default()
+        };
+        let page = super::query(&context, &connection, query).await.unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["B", "C"]);
+
+        // Resuming from the cursor skips all older events.
+        insert(&connection, EventStream::Change, "D", "ns1").await;
+        let query = EventsQuery {
+            cursor: Some(page.cursor),
+            ..Default::default()
+        };
+        let page = super::query(&context, &connection, query).await.unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["D"]);
+    }
+
+    #[tokio::test]
+    async fn query_filter_by_scope() {
+        let context = Context::fixture();
+        let connection = crate::client::fixture().await;
+        let namespace = serde_json::json!({"id": "ns1"});
+        let event = Event::new_with_payload("NAMESPACE_APPLY", namespace)
+            .unwrap()
+            .for_namespace("ns1");
+        insert_event(&connection, EventStream::Change, event).await;
+        let update = serde_json::json!({
+            "before": {"ns_id": "ns1", "cluster_id": "pg"},
+            "after": {"ns_id": "ns1", "cluster_id": "pg"},
+        });
+        let event = Event::new_with_payload("CLUSTER_DISCOVERY_UPDATE", update)
+            .unwrap()
+            .for_cluster("ns1", "pg");
+        insert_event(&connection, EventStream::Change, event).await;
+        let payload = serde_json::json!({"ns_id": "ns1", "cluster_id": "other"});
+        let event = Event::new_with_payload("NODE_NEW", payload).unwrap();
+        insert_event(&connection, EventStream::Change, event).await;
+        insert(&connection, EventStream::Change, "NAMESPACE_APPLY", "ns2").await;
+
+        // Events are matched on the scope recorded when they are emitted, not their payload.
+        let query = EventsQuery {
+            ns_id: Some("ns1".into()),
+            ..Default::default()
+        };
+        let page = super::query(&context, &connection, query).await.unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            ["NAMESPACE_APPLY", "CLUSTER_DISCOVERY_UPDATE", "NODE_NEW"]
+        );
+
+        let query = EventsQuery {
+            ns_id: Some("ns1".into()),
+            cluster_id: Some("pg".into()),
+            ..Default::default()
+        };
+        let page = super::query(&context, &connection, query).await.unwrap();
+        let codes: Vec<_> = page.items.iter().map(|e| e.event.code.as_str()).collect();
+        assert_eq!(codes, ["CLUSTER_DISCOVERY_UPDATE"]);
+    }
+}
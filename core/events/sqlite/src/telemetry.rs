@@ -12,11 +12,36 @@ use opentelemetry_api::trace::TracerProvider;
 use opentelemetry_api::Context;
 use prometheus::Counter;
 use prometheus::CounterVec;
+use prometheus::GaugeVec;
 use prometheus::HistogramOpts;
 use prometheus::HistogramTimer;
 use prometheus::HistogramVec;
 use prometheus::Opts;
 
+/// Number of expired events deleted by the history clean loop.
+pub static CLEAN_DELETED: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_sqlite_clean_deleted",
+            "Number of expired events deleted by the history clean loop",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise CLEAN_DELETED counter")
+});
+
+/// Time (in seconds) the oldest event in the DB is past its retention age.
+pub static CLEAN_LAG: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new(
+            "replicore_events_sqlite_clean_lag",
+            "Time (in seconds) the oldest event in the DB is past its retention age",
+        ),
+        &["stream"],
+    )
+    .expect("failed to initialise CLEAN_LAG gauge")
+});
+
 /// Duration (in seconds) of SQLite operations.
 pub static OPS_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
     HistogramVec::new(
@@ -62,8 +87,12 @@ pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
         return Ok(());
     }
 
-    let collectors: [Box<dyn prometheus::core::Collector>; 2] =
-        [Box::new(OPS_DURATION.clone()), Box::new(OPS_ERR.clone())];
+    let collectors: [Box<dyn prometheus::core::Collector>; 4] = [
+        Box::new(CLEAN_DELETED.clone()),
+        Box::new(CLEAN_LAG.clone()),
+        Box::new(OPS_DURATION.clone()),
+        Box::new(OPS_ERR.clone()),
+    ];
     for collector in collectors {
         reg.register(collector)?;
     }
@@ -0,0 +1,194 @@
+//! Catalog of event codes emitted by the Control Plane and the contract of their payloads.
+//!
+//! Components register the event codes they emit so consumers can rely on a documented
+//! stream and payload shape for each code.
+//! In debug builds [`Events`](crate::emit::Events) validates emitted events against the catalog.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use jsonschema::Validator;
+
+use replicore_events_models::EventCode;
+use replicore_events_models::EventStream;
+
+use crate::Event;
+
+/// Registered event code along with its compiled payload schema.
+struct CatalogEntry {
+    code: EventCode,
+    schema: Validator,
+}
+
+/// Collection of [`EventCode`]s known to the Control Plane.
+///
+/// Empty catalogs accept all events, which is useful for unit tests.
+#[derive(Clone, Default)]
+pub struct EventsCatalog {
+    /// Map of event code to its catalog entry.
+    entries: Arc<BTreeMap<String, CatalogEntry>>,
+}
+
+impl EventsCatalog {
+    /// Begin building an empty [`EventsCatalog`] instance.
+    pub fn build() -> EventsCatalogBuilder {
+        EventsCatalogBuilder::default()
+    }
+
+    /// Iterate over all event codes in the catalog, sorted by code.
+    pub fn codes(&self) -> impl Iterator<Item = &EventCode> {
+        self.entries.values().map(|entry| &entry.code)
+    }
+
+    /// Lookup the contract for an event code.
+    pub fn lookup(&self, code: &str) -> Option<&EventCode> {
+        self.entries.get(code).map(|entry| &entry.code)
+    }
+
+    /// Check an event emitted onto a stream matches the contract for its code.
+    pub fn validate(&self, stream: EventStream, event: &Event) -> Result<()> {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+        let entry = self
+            .entries
+            .get(&event.code)
+            .ok_or_else(|| CatalogError::UnknownCode(event.code.clone()))?;
+        if entry.code.stream != stream {
+            let error = CatalogError::WrongStream {
+                code: event.code.clone(),
+                expected: entry.code.stream,
+                actual: stream,
+            };
+            anyhow::bail!(error);
+        }
+        if let Err(errors) = entry.schema.validate(&event.payload) {
+            let violations: Vec<String> = errors.map(|error| error.to_string()).collect();
+            let error = CatalogError::InvalidPayload {
+                code: event.code.clone(),
+                violations: violations.join("; "),
+            };
+            anyhow::bail!(error);
+        }
+        Ok(())
+    }
+}
+
+/// Incrementally build [`EventsCatalog`]s.
+#[derive(Default)]
+pub struct EventsCatalogBuilder {
+    entries: BTreeMap<String, CatalogEntry>,
+}
+
+impl EventsCatalogBuilder {
+    /// Complete building the catalog instance.
+    pub fn finish(self) -> EventsCatalog {
+        EventsCatalog {
+            entries: Arc::new(self.entries),
+        }
+    }
+
+    /// Register the contract for a new event code.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the code is already registered or its schema is not valid.
+    pub fn register(&mut self, code: EventCode) -> &mut Self {
+        if self.entries.contains_key(&code.code) {
+            panic!(
+                "event code {} cannot be registered more then once",
+                code.code
+            );
+        }
+        let error = format!("invalid JSON schema for event code {}", code.code);
+        let schema = Validator::new(&code.schema).expect(&error);
+        let entry = CatalogEntry { code, schema };
+        self.entries.insert(entry.code.code.clone(), entry);
+        self
+    }
+}
+
+/// Errors validating events against the [`EventsCatalog`].
+#[derive(Debug, thiserror::Error)]
+pub enum CatalogError {
+    /// The payload of the event does not match the schema for its code.
+    #[error("payload of event {code} does not match its schema: {violations}")]
+    InvalidPayload { code: String, violations: String },
+
+    /// The event code is not registered in the catalog.
+    #[error("event code {0} is not registered in the events catalog")]
+    UnknownCode(String),
+
+    /// The event was emitted onto a different stream then the one registered for its code.
+    #[error("event {code} must be emitted onto the {expected} stream, not the {actual} stream")]
+    WrongStream {
+        code: String,
+        expected: EventStream,
+        actual: EventStream,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events_models::EventCode;
+    use replicore_events_models::EventStream;
+
+    use super::CatalogError;
+    use super::EventsCatalog;
+    use crate::Event;
+
+    fn catalog() -> EventsCatalog {
+        let mut catalog = EventsCatalog::build();
+        catalog.register(
+            EventCode::change("TEST", "Test", "Test event")
+                .schema(r#"{"type": "object", "required": ["id"]}"#),
+        );
+        catalog.finish()
+    }
+
+    #[test]
+    fn empty_catalog_accepts_all() {
+        let event = Event::new_with_payload("TEST", 42).unwrap();
+        EventsCatalog::default()
+            .validate(EventStream::Audit, &event)
+            .unwrap();
+    }
+
+    #[test]
+    fn validate_event() {
+        let event = Event::new_with_payload("TEST", serde_json::json!({"id": "a"})).unwrap();
+        catalog().validate(EventStream::Change, &event).unwrap();
+    }
+
+    #[test]
+    fn validate_invalid_payload() {
+        let event = Event::new_with_payload("TEST", serde_json::json!({"other": "a"})).unwrap();
+        let error = catalog().validate(EventStream::Change, &event).unwrap_err();
+        let error = error.downcast::<CatalogError>().unwrap();
+        assert!(matches!(error, CatalogError::InvalidPayload { .. }));
+    }
+
+    #[test]
+    fn validate_unknown_code() {
+        let event = Event::new_with_payload("OTHER", serde_json::json!({"id": "a"})).unwrap();
+        let error = catalog().validate(EventStream::Change, &event).unwrap_err();
+        let error = error.downcast::<CatalogError>().unwrap();
+        assert!(matches!(error, CatalogError::UnknownCode(code) if code == "OTHER"));
+    }
+
+    #[test]
+    fn validate_wrong_stream() {
+        let event = Event::new_with_payload("TEST", serde_json::json!({"id": "a"})).unwrap();
+        let error = catalog().validate(EventStream::Audit, &event).unwrap_err();
+        let error = error.downcast::<CatalogError>().unwrap();
+        assert!(matches!(error, CatalogError::WrongStream { .. }));
+    }
+
+    #[test]
+    #[should_panic(expected = "event code TEST cannot be registered more then once")]
+    fn register_twice() {
+        let mut catalog = EventsCatalog::build();
+        catalog.register(EventCode::change("TEST", "Test", "Test event"));
+        catalog.register(EventCode::change("TEST", "Test", "Test event"));
+    }
+}
@@ -1,26 +1,133 @@
 //! Interfaces to emit events to a streaming platform.
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use anyhow::Result;
 use serde_json::Value as Json;
 
 use replicore_context::Context;
+use replicore_events_models::EventEntry;
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
 
 use super::Event;
+use crate::catalog::EventsCatalog;
+use crate::subscribe::EventSubscriber;
+use crate::subscribe::Subscribers;
 
 /// Emit events to the backing events streaming platform.
+///
+/// In-process subscribers are notified of events after they are successfully emitted,
+/// unless the caller defers notification until the change they describe is committed.
+/// Clones of an [`Events`] object share the same subscribers.
+///
+/// In debug builds events are checked against the [`EventsCatalog`] before they are emitted.
 #[derive(Clone)]
-pub struct Events(Arc<dyn EventsBackend>);
+pub struct Events {
+    backend: Arc<dyn EventsBackend>,
+    catalog: EventsCatalog,
+    subscribers: Subscribers,
+}
 
 impl Events {
     /// Emit an auditing event.
     pub async fn audit(&self, context: &Context, event: Event) -> Result<()> {
-        self.0.audit(context, event).await
+        self.emit(context, EventStream::Audit, event).await
+    }
+
+    /// Catalog of event codes known to the process.
+    pub fn catalog(&self) -> &EventsCatalog {
+        &self.catalog
     }
 
     /// Emit an event about a change to an element in the system.
     pub async fn change(&self, context: &Context, event: Event) -> Result<()> {
-        self.0.change(context, event).await
+        self.emit(context, EventStream::Change, event).await
+    }
+
+    /// Emit an event onto the stream it was recorded for, such as events relayed from an outbox.
+    pub async fn publish(&self, context: &Context, entry: EventEntry) -> Result<()> {
+        self.emit(context, entry.stream, entry.event).await
+    }
+
+    /// Notify in-process subscribers of an event emitted with [`Events::publish_without_notify`].
+    pub async fn notify(&self, context: &Context, entry: &EventEntry) {
+        if self.subscribers.has(&entry.event.code) {
+            self.subscribers.notify(context, entry).await;
+        }
+    }
+
+    /// Emit an event onto the stream it was recorded for without notifying subscribers.
+    ///
+    /// Callers must [`Events::notify`] subscribers once the change the event describes
+    /// is committed, so subscribers never observe changes that are not yet visible.
+    pub async fn publish_without_notify(&self, context: &Context, entry: EventEntry) -> Result<()> {
+        let event = self.prepare(entry.stream, entry.event)?;
+        self.send(context, entry.stream, event).await
+    }
+
+    /// Query events back from the streaming platform, oldest first.
+    pub async fn query(&self, context: &Context, query: EventsQuery) -> Result<EventsPage> {
+        self.backend.query(context, query).await
+    }
+
+    /// Register an in-process subscriber for events with the given code.
+    pub fn subscribe<S, E>(&self, code: S, subscriber: E)
+    where
+        S: Into<String>,
+        E: EventSubscriber + 'static,
+    {
+        self.subscribers.subscribe(code, subscriber)
+    }
+
+    /// Replace the catalog of event codes known to the process.
+    pub fn with_catalog(mut self, catalog: EventsCatalog) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Replace the in-process subscribers notified of emitted events.
+    pub fn with_subscribers(mut self, subscribers: Subscribers) -> Self {
+        self.subscribers = subscribers;
+        self
+    }
+
+    /// Emit an event onto a stream and notify subscribers once emitted.
+    ///
+    /// The trace context of the current operation is attached to events that don't have one.
+    async fn emit(&self, context: &Context, stream: EventStream, event: Event) -> Result<()> {
+        let event = self.prepare(stream, event)?;
+
+        // Only copy events when someone is interested in them.
+        let entry = self.subscribers.has(&event.code).then(|| EventEntry {
+            event: event.clone(),
+            stream,
+        });
+        self.send(context, stream, event).await?;
+        if let Some(entry) = entry {
+            self.subscribers.notify(context, &entry).await;
+        }
+        Ok(())
+    }
+
+    /// Attach the event scope and current trace context, then check the event is known.
+    fn prepare(&self, stream: EventStream, mut event: Event) -> Result<Event> {
+        crate::scope::attach_scope(&mut event);
+        crate::trace::attach_trace_context(&mut event);
+        if cfg!(debug_assertions) {
+            self.catalog.validate(stream, &event)?;
+        }
+        Ok(event)
+    }
+
+    /// Send an event to the backend for the stream.
+    async fn send(&self, context: &Context, stream: EventStream, event: Event) -> Result<()> {
+        match stream {
+            EventStream::Audit => self.backend.audit(context, event).await,
+            EventStream::Change => self.backend.change(context, event).await,
+        }
     }
 }
 
@@ -29,10 +136,44 @@ where
     T: EventsBackend + 'static,
 {
     fn from(value: T) -> Self {
-        Events(Arc::new(value))
+        Events {
+            backend: Arc::new(value),
+            catalog: Default::default(),
+            subscribers: Default::default(),
+        }
+    }
+}
+
+/// Future resolving when background maintenance of the streaming platform should stop.
+pub type MaintenanceExit = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Background maintenance of the events streaming platform, such as removing expired events.
+#[derive(Clone)]
+pub struct EventsMaintenance(Arc<dyn EventsMaintenanceBackend>);
+
+impl EventsMaintenance {
+    /// Run maintenance operations until the exit future resolves.
+    pub async fn run(&self, context: &Context, exit: MaintenanceExit) -> Result<()> {
+        self.0.run(context, exit).await
+    }
+}
+
+impl<T> From<T> for EventsMaintenance
+where
+    T: EventsMaintenanceBackend + 'static,
+{
+    fn from(value: T) -> Self {
+        EventsMaintenance(Arc::new(value))
     }
 }
 
+/// Maintenance operations for Event Streaming Platforms that need them.
+#[async_trait::async_trait]
+pub trait EventsMaintenanceBackend: Send + Sync {
+    /// Run maintenance operations until the exit future resolves.
+    async fn run(&self, context: &Context, exit: MaintenanceExit) -> Result<()>;
+}
+
 /// Operations implemented by Event Streaming Platforms supported by Replicante Core.
 #[async_trait::async_trait]
 pub trait EventsBackend: Send + Sync {
@@ -41,8 +182,20 @@ pub trait EventsBackend: Send + Sync {
 
     /// Emit an event about a change to an element in the system.
     async fn change(&self, context: &Context, event: Event) -> Result<()>;
+
+    /// Query events back from the streaming platform, oldest first.
+    ///
+    /// Backends that can't read events back don't need to implement this method.
+    async fn query(&self, _context: &Context, _query: EventsQuery) -> Result<EventsPage> {
+        anyhow::bail!(QueryNotSupported)
+    }
 }
 
+/// The events backend does not support reading events back.
+#[derive(Debug, thiserror::Error)]
+#[error("the events backend does not support reading events back")]
+pub struct QueryNotSupported;
+
 /// Initialisation logic for the event streaming platform and the client to access it.
 #[async_trait::async_trait]
 pub trait EventsFactory: Send + Sync {
@@ -55,6 +208,17 @@ pub trait EventsFactory: Send + Sync {
     /// Instantiate an [`Events`] object to emit events to the streaming platform.
     async fn events<'a>(&self, args: EventsFactoryArgs<'a>) -> Result<Events>;
 
+    /// Instantiate an [`EventsMaintenance`] object for backends that need background maintenance.
+    ///
+    /// Maintenance is run by only one Control Plane process at a time.
+    /// Backends that need no maintenance don't need to implement this method.
+    async fn maintenance<'a>(
+        &self,
+        _args: EventsFactoryArgs<'a>,
+    ) -> Result<Option<EventsMaintenance>> {
+        Ok(None)
+    }
+
     /// Synchronise (initialise or migrate) the streaming platform to handle RepliCore [`Event`]s.
     async fn sync<'a>(&self, args: EventsFactorySyncArgs<'a>) -> Result<()>;
 }
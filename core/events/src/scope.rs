@@ -0,0 +1,61 @@
+//! Record the namespace and cluster events are about so backends can filter on them.
+use replicore_events_models::Event;
+use replicore_events_models::METADATA_CLUSTER_ID;
+use replicore_events_models::METADATA_NS_ID;
+
+/// Record the namespace and cluster of the event in its metadata, if not already set.
+///
+/// Events that do not set their scope explicitly (see [`Event::for_namespace`] and
+/// [`Event::for_cluster`]) are scoped by the top level `ns_id` and `cluster_id`
+/// attributes of their payload, when present.
+pub fn attach_scope(event: &mut Event) {
+    for (key, attribute) in [
+        (METADATA_NS_ID, "ns_id"),
+        (METADATA_CLUSTER_ID, "cluster_id"),
+    ] {
+        if event.metadata.contains_key(key) {
+            continue;
+        }
+        let value = event
+            .payload
+            .get(attribute)
+            .and_then(|value| value.as_str());
+        if let Some(value) = value {
+            event.metadata.insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events_models::Event;
+
+    use super::attach_scope;
+
+    #[test]
+    fn scope_from_payload() {
+        let payload = serde_json::json!({"ns_id": "ns", "cluster_id": "cluster"});
+        let mut event = Event::new_with_payload("TEST", payload).unwrap();
+        attach_scope(&mut event);
+        assert_eq!(event.ns_id(), Some("ns"));
+        assert_eq!(event.cluster_id(), Some("cluster"));
+    }
+
+    #[test]
+    fn keep_explicit_scope() {
+        let payload = serde_json::json!({"id": "ns", "ns_id": "other"});
+        let mut event = Event::new_with_payload("TEST", payload)
+            .unwrap()
+            .for_namespace("ns");
+        attach_scope(&mut event);
+        assert_eq!(event.ns_id(), Some("ns"));
+        assert_eq!(event.cluster_id(), None);
+    }
+
+    #[test]
+    fn skip_without_scope() {
+        let mut event = Event::new_with_payload("TEST", "scope").unwrap();
+        attach_scope(&mut event);
+        assert!(event.metadata.is_empty());
+    }
+}
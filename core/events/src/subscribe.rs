@@ -0,0 +1,200 @@
+//! Handle events emitted by the Control Plane process from within the process.
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use anyhow::Result;
+use futures::FutureExt;
+
+use replicore_context::Context;
+use replicore_events_models::EventEntry;
+use replisdk::utils::error::slog::ErrorAttributes;
+
+/// Handler of events emitted by the Control Plane process.
+///
+/// Handlers are invoked after the event is successfully emitted, before the emit operation
+/// returns, so they should be quick (for example submit a background task for slow work).
+///
+/// Events persisted along with a record are only handled once the record is committed.
+#[async_trait::async_trait]
+pub trait EventSubscriber: Send + Sync {
+    /// Handle an event emitted onto a stream.
+    async fn handle(&self, context: &Context, entry: &EventEntry) -> Result<()>;
+}
+
+/// Registry of in-process [`EventSubscriber`]s, by event code.
+///
+/// Clones of the registry share the same subscribers.
+#[derive(Clone, Default)]
+pub struct Subscribers {
+    by_code: Arc<RwLock<HashMap<String, Vec<Arc<dyn EventSubscriber>>>>>,
+}
+
+impl Subscribers {
+    /// Check if any subscriber is registered for an event code.
+    pub fn has(&self, code: &str) -> bool {
+        self.by_code
+            .read()
+            .expect("Subscribers::by_code lock poisoned")
+            .contains_key(code)
+    }
+
+    /// Invoke all subscribers registered for the event code, in registration order.
+    ///
+    /// Subscriber errors and panics are logged and counted but otherwise ignored
+    /// so they never fail the operation that emitted the event.
+    pub async fn notify(&self, context: &Context, entry: &EventEntry) {
+        let code = entry.event.code.as_str();
+        let subscribers = self
+            .by_code
+            .read()
+            .expect("Subscribers::by_code lock poisoned")
+            .get(code)
+            .cloned()
+            .unwrap_or_default();
+        for subscriber in subscribers {
+            let handle = AssertUnwindSafe(subscriber.handle(context, entry));
+            let error = match handle.catch_unwind().await {
+                Ok(Ok(())) => continue,
+                Ok(Err(error)) => error,
+                Err(_) => anyhow::anyhow!(SubscriberPanic),
+            };
+            crate::telemetry::SUBSCRIBER_ERR
+                .with_label_values(&[code])
+                .inc();
+            slog::warn!(
+                context.logger, "Event subscriber failed to handle event";
+                "code" => code,
+                ErrorAttributes::from(&error),
+            );
+        }
+    }
+
+    /// Register a subscriber for events with the given code.
+    pub fn subscribe<S, E>(&self, code: S, subscriber: E)
+    where
+        S: Into<String>,
+        E: EventSubscriber + 'static,
+    {
+        self.by_code
+            .write()
+            .expect("Subscribers::by_code lock poisoned")
+            .entry(code.into())
+            .or_default()
+            .push(Arc::new(subscriber));
+    }
+}
+
+/// An event subscriber panicked while handling an event.
+#[derive(Debug, thiserror::Error)]
+#[error("an event subscriber panicked while handling an event")]
+pub struct SubscriberPanic;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use anyhow::Result;
+
+    use replicore_context::Context;
+    use replicore_events_models::EventEntry;
+    use replicore_events_models::EventStream;
+
+    use super::EventSubscriber;
+    use crate::emit::Events;
+    use crate::emit::EventsFixture;
+    use crate::Event;
+
+    /// Count handled events, failing or panicking if requested.
+    #[derive(Clone, Default)]
+    struct Counter {
+        handled: Arc<AtomicUsize>,
+        fail: bool,
+        panic: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Counter {
+        async fn handle(&self, _: &Context, entry: &EventEntry) -> Result<()> {
+            if entry.stream != EventStream::Change {
+                anyhow::bail!("unexpected event stream");
+            }
+            self.handled.fetch_add(1, Ordering::SeqCst);
+            if self.panic {
+                panic!("test subscriber panic");
+            }
+            if self.fail {
+                anyhow::bail!("test subscriber failure");
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn notify_subscribers_by_code() {
+        let context = Context::fixture();
+        let fixture = EventsFixture::new();
+        let events = Events::from(fixture.backend());
+        let counter = Counter::default();
+        events.subscribe("TEST", counter.clone());
+
+        let event = Event::new_with_payload("TEST", 1).unwrap();
+        events.change(&context, event).await.unwrap();
+        let event = Event::new_with_payload("OTHER", 2).unwrap();
+        events.change(&context, event).await.unwrap();
+        assert_eq!(counter.handled.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn notify_subscribers_deferred() {
+        let context = Context::fixture();
+        let mut fixture = EventsFixture::new();
+        let events = Events::from(fixture.backend());
+        let counter = Counter::default();
+        events.subscribe("TEST", counter.clone());
+
+        let entry = EventEntry {
+            event: Event::new_with_payload("TEST", 1).unwrap(),
+            stream: EventStream::Change,
+        };
+        events
+            .publish_without_notify(&context, entry.clone())
+            .await
+            .unwrap();
+        let event = fixture.pop_change().await.unwrap();
+        assert_eq!(event.code, "TEST");
+        assert_eq!(counter.handled.load(Ordering::SeqCst), 0);
+
+        events.notify(&context, &entry).await;
+        assert_eq!(counter.handled.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn subscriber_failures_isolated() {
+        let context = Context::fixture();
+        let fixture = EventsFixture::new();
+        let events = Events::from(fixture.backend());
+        let failing = Counter {
+            fail: true,
+            ..Default::default()
+        };
+        let panicking = Counter {
+            panic: true,
+            ..Default::default()
+        };
+        let counter = Counter::default();
+        events.subscribe("TEST", failing.clone());
+        events.subscribe("TEST", panicking.clone());
+        events.subscribe("TEST", counter.clone());
+
+        // Subscribers registered on clones are shared.
+        let event = Event::new_with_payload("TEST", 1).unwrap();
+        events.clone().change(&context, event).await.unwrap();
+        assert_eq!(failing.handled.load(Ordering::SeqCst), 1);
+        assert_eq!(panicking.handled.load(Ordering::SeqCst), 1);
+        assert_eq!(counter.handled.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,37 @@
+//! Telemetry related to the events platform interface.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::CounterVec;
+use prometheus::Opts;
+
+/// Number of events in-process subscribers failed to handle.
+pub static SUBSCRIBER_ERR: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_subscriber_error",
+            "Number of events in-process subscribers failed to handle",
+        ),
+        &["code"],
+    )
+    .expect("failed to initialise SUBSCRIBER_ERR counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the events interface metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 1] = [Box::new(SUBSCRIBER_ERR.clone())];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
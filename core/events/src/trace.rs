@@ -0,0 +1,92 @@
+//! Attach trace context to events so consumers can link them to the operation emitting them.
+use opentelemetry_api::trace::TraceContextExt;
+use opentelemetry_api::Context as OTelContext;
+
+use replicore_events_models::Event;
+use replicore_events_models::METADATA_TRACE_PARENT;
+use replicore_events_models::METADATA_TRACE_STATE;
+
+/// Record the W3C trace context of the current OpenTelemetry span in the event metadata.
+///
+/// Events that already carry a trace context, or generated outside of a trace, are unchanged.
+/// This ensures events relayed by other operations (such as from an outbox)
+/// keep linking to the operation that originally generated them.
+pub fn attach_trace_context(event: &mut Event) {
+    if event.metadata.contains_key(METADATA_TRACE_PARENT) {
+        return;
+    }
+    let context = OTelContext::current();
+    let span = context.span();
+    let span_context = span.span_context();
+    if !span_context.is_valid() {
+        return;
+    }
+
+    let parent = format!(
+        "00-{}-{}-{:02x}",
+        span_context.trace_id(),
+        span_context.span_id(),
+        span_context.trace_flags().to_u8(),
+    );
+    event
+        .metadata
+        .insert(METADATA_TRACE_PARENT.to_string(), parent);
+    let state = span_context.trace_state().header();
+    if !state.is_empty() {
+        event
+            .metadata
+            .insert(METADATA_TRACE_STATE.to_string(), state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_api::trace::SpanContext;
+    use opentelemetry_api::trace::SpanId;
+    use opentelemetry_api::trace::TraceContextExt;
+    use opentelemetry_api::trace::TraceFlags;
+    use opentelemetry_api::trace::TraceId;
+    use opentelemetry_api::trace::TraceState;
+    use opentelemetry_api::Context as OTelContext;
+
+    use replicore_events_models::Event;
+    use replicore_events_models::METADATA_TRACE_PARENT;
+    use replicore_events_models::METADATA_TRACE_STATE;
+
+    use super::attach_trace_context;
+
+    const TRACE_PARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    #[test]
+    fn attach_current_trace() {
+        let span = SpanContext::new(
+            TraceId::from_hex("0af7651916cd43dd8448eb211c80319c").unwrap(),
+            SpanId::from_hex("b7ad6b7169203331").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let _guard = OTelContext::new().with_remote_span_context(span).attach();
+        let mut event = Event::new_with_payload("TEST", "trace").unwrap();
+        attach_trace_context(&mut event);
+        assert_eq!(event.metadata[METADATA_TRACE_PARENT], TRACE_PARENT);
+        assert!(!event.metadata.contains_key(METADATA_TRACE_STATE));
+    }
+
+    #[test]
+    fn keep_existing_trace() {
+        let mut event = Event::new_with_payload("TEST", "trace").unwrap();
+        event
+            .metadata
+            .insert(METADATA_TRACE_PARENT.to_string(), "original".to_string());
+        attach_trace_context(&mut event);
+        assert_eq!(event.metadata[METADATA_TRACE_PARENT], "original");
+    }
+
+    #[test]
+    fn skip_without_trace() {
+        let mut event = Event::new_with_payload("TEST", "trace").unwrap();
+        attach_trace_context(&mut event);
+        assert!(event.metadata.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! Configuration for the composite events backend.
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value as Json;
+
+/// Composite specific configuration for the events interface.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// Child backends to emit events to.
+    pub backends: Vec<ChildConf>,
+}
+
+impl Conf {
+    /// Check the configuration for errors that can't be detected while decoding it.
+    pub fn validate(&self) -> Result<(), ChildConfError> {
+        if self.backends.is_empty() {
+            return Err(ChildConfError::NoChildren);
+        }
+        let mut names = HashSet::new();
+        for child in &self.backends {
+            if !names.insert(child.name()) {
+                return Err(ChildConfError::DuplicateName(child.name().to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of a child backend of the composite backend.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChildConf {
+    /// ID of the events backend implementing the child.
+    pub backend: String,
+
+    /// Name of the child used in logs and metrics, defaults to the backend ID.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// How failures of the child are handled.
+    #[serde(default)]
+    pub policy: FailurePolicy,
+
+    /// Backend specific configuration options.
+    #[serde(default, flatten)]
+    pub options: Json,
+}
+
+impl ChildConf {
+    /// Name of the child used in logs and metrics.
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.backend)
+    }
+}
+
+/// How failures of a child backend are handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FailurePolicy {
+    /// Failures are logged and counted but otherwise ignored.
+    #[serde(rename = "best-effort")]
+    BestEffort,
+
+    /// Failures of the child fail the operation.
+    #[default]
+    #[serde(rename = "required")]
+    Required,
+}
+
+impl std::fmt::Display for FailurePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::BestEffort => write!(f, "best-effort"),
+            Self::Required => write!(f, "required"),
+        }
+    }
+}
+
+/// The composite events backend configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the composite events backend configuration is not valid")]
+pub struct ConfError;
+
+/// The configuration of the composite backend children is not valid.
+#[derive(Debug, thiserror::Error)]
+pub enum ChildConfError {
+    /// Child names are used to tell children apart in logs and metrics.
+    #[error("composite events child name '{0}' is used more than once")]
+    DuplicateName(String),
+
+    /// At least one child is needed to emit events to.
+    #[error("composite events backend needs at least one child backend")]
+    NoChildren,
+
+    /// The child backend is not available to composite backends.
+    #[error("events backend '{0}' is not available as a composite events child")]
+    UnknownBackend(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChildConfError;
+    use super::Conf;
+    use super::FailurePolicy;
+
+    #[test]
+    fn decode_child_options() {
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "backends": [
+                {"backend": "sqlite", "path": "events.sqlite"},
+                {"backend": "webhook", "name": "alerts", "policy": "best-effort", "endpoints": []},
+            ],
+        }))
+        .unwrap();
+        assert_eq!(conf.backends[0].name(), "sqlite");
+        assert_eq!(conf.backends[0].policy, FailurePolicy::Required);
+        assert_eq!(
+            conf.backends[0].options,
+            serde_json::json!({"path": "events.sqlite"}),
+        );
+        assert_eq!(conf.backends[1].name(), "alerts");
+        assert_eq!(conf.backends[1].policy, FailurePolicy::BestEffort);
+        assert_eq!(
+            conf.backends[1].options,
+            serde_json::json!({"endpoints": []}),
+        );
+    }
+
+    #[test]
+    fn validate_unique_names() {
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "backends": [{"backend": "memory"}, {"backend": "memory"}],
+        }))
+        .unwrap();
+        let error = conf.validate().unwrap_err();
+        assert!(matches!(error, ChildConfError::DuplicateName(name) if name == "memory"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Emit events to all children of the composite backend.
+use anyhow::Result;
+use futures::FutureExt;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events::emit::EventsBackend;
+use replicore_events::emit::EventsMaintenance;
+use replicore_events::emit::EventsMaintenanceBackend;
+use replicore_events::emit::MaintenanceExit;
+use replicore_events::emit::QueryNotSupported;
+use replicore_events::Event;
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsPage;
+use replicore_events_models::EventsQuery;
+use replisdk::utils::error::slog::ErrorAttributes;
+
+use crate::FailurePolicy;
+
+/// Operation on a composite events child failed.
+#[derive(Debug, thiserror::Error)]
+#[error("operation on composite events child '{0}' failed")]
+pub struct ChildFailed(pub String);
+
+/// Child of a composite backend along with its failure policy.
+pub(crate) struct TeeChild<T> {
+    /// Name of the child used in logs and metrics.
+    pub name: String,
+
+    /// How failures of the child are handled.
+    pub policy: FailurePolicy,
+
+    /// Backend to forward operations to.
+    pub inner: T,
+}
+
+impl<T> TeeChild<T> {
+    /// Handle the result of an operation on the child according to its failure policy.
+    ///
+    /// Best-effort failures are logged and reported as successes.
+    fn check(&self, context: &Context, result: Result<()>) -> Result<()> {
+        let error = match result {
+            Ok(()) => return Ok(()),
+            Err(error) => error.context(ChildFailed(self.name.clone())),
+        };
+        match self.policy {
+            FailurePolicy::Required => Err(error),
+            FailurePolicy::BestEffort => {
+                slog::warn!(
+                    context.logger, "Ignoring failure of best-effort composite events child";
+                    "child" => &self.name,
+                    ErrorAttributes::from(&error),
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Implementation of the [`EventsBackend`] interface emitting events to multiple children.
+pub struct TeeEvents {
+    children: Vec<TeeChild<Events>>,
+}
+
+impl TeeEvents {
+    /// Emit events to the given children.
+    pub(crate) fn new(children: Vec<TeeChild<Events>>) -> TeeEvents {
+        TeeEvents { children }
+    }
+
+    /// Emit the event to all children concurrently and check results against policies.
+    ///
+    /// Returns the error of the first required child that failed, if any.
+    async fn emit(&self, context: &Context, stream: EventStream, event: Event) -> Result<()> {
+        let stream_label = stream.to_string();
+        let emits = self.children.iter().map(|child| {
+            let event = event.clone();
+            let stream_label = stream_label.as_str();
+            async move {
+                let _timer = crate::telemetry::EMIT_DURATION
+                    .with_label_values(&[&child.name, stream_label])
+                    .start_timer();
+                let result = match stream {
+                    EventStream::Audit => child.inner.audit(context, event).await,
+                    EventStream::Change => child.inner.change(context, event).await,
+                };
+                if result.is_err() {
+                    let policy = child.policy.to_string();
+                    crate::telemetry::EMIT_ERR
+                        .with_label_values(&[&child.name, stream_label, &policy])
+                        .inc();
+                }
+                result
+            }
+        });
+        let results = futures::future::join_all(emits).await;
+
+        let mut failure = None;
+        for (child, result) in self.children.iter().zip(results) {
+            if let Err(error) = child.check(context, result) {
+                failure.get_or_insert(error);
+            }
+        }
+        match failure {
+            None => Ok(()),
+            Some(error) => Err(error),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsBackend for TeeEvents {
+    async fn audit(&self, context: &Context, event: Event) -> Result<()> {
+        self.emit(context, EventStream::Audit, event).await
+    }
+
+    async fn change(&self, context: &Context, event: Event) -> Result<()> {
+        self.emit(context, EventStream::Change, event).await
+    }
+
+    async fn query(&self, context: &Context, query: EventsQuery) -> Result<EventsPage> {
+        for child in &self.children {
+            match child.inner.query(context, query.clone()).await {
+                Err(error) if error.is::<QueryNotSupported>() => continue,
+                result => {
+                    return result.map_err(|error| error.context(ChildFailed(child.name.clone())))
+                }
+            }
+        }
+        anyhow::bail!(QueryNotSupported)
+    }
+}
+
+/// Run maintenance of all composite backend children that need it.
+pub struct TeeMaintenance {
+    children: Vec<TeeChild<EventsMaintenance>>,
+}
+
+impl TeeMaintenance {
+    /// Run maintenance of the given children.
+    pub(crate) fn new(children: Vec<TeeChild<EventsMaintenance>>) -> TeeMaintenance {
+        TeeMaintenance { children }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsMaintenanceBackend for TeeMaintenance {
+    async fn run(&self, context: &Context, exit: MaintenanceExit) -> Result<()> {
+        let exit = exit.shared();
+        let runs = self.children.iter().map(|child| {
+            let exit = Box::pin(exit.clone());
+            child.inner.run(context, exit)
+        });
+        let results = futures::future::join_all(runs).await;
+
+        let mut failure = None;
+        for (child, result) in self.children.iter().zip(results) {
+            if let Err(error) = child.check(context, result) {
+                failure.get_or_insert(error);
+            }
+        }
+        match failure {
+            None => Ok(()),
+            Some(error) => Err(error),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Factory for the composite events backend.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events::emit::EventsFactory;
+use replicore_events::emit::EventsFactoryArgs;
+use replicore_events::emit::EventsFactorySyncArgs;
+use replicore_events::emit::EventsMaintenance;
+use replisdk::utils::error::slog::ErrorAttributes;
+
+use crate::events::TeeChild;
+use crate::events::TeeMaintenance;
+use crate::ChildConf;
+use crate::ChildConfError;
+use crate::ChildFailed;
+use crate::Conf;
+use crate::ConfError;
+use crate::FailurePolicy;
+use crate::TeeEvents;
+
+/// ID composite events backends are registered with.
+pub const TEE_BACKEND_ID: &str = "tee";
+
+/// Initialise events backends that emit events to multiple child backends.
+///
+/// Children can be any events backend registered with the factory.
+#[derive(Clone, Default)]
+pub struct TeeFactory {
+    factories: HashMap<String, Arc<dyn EventsFactory>>,
+}
+
+impl TeeFactory {
+    /// Make an events backend available as a child of composite backends.
+    pub fn register<S>(&mut self, id: S, factory: Arc<dyn EventsFactory>) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.factories.insert(id.into(), factory);
+        self
+    }
+
+    /// Lookup the factory for a child backend.
+    fn factory(&self, child: &ChildConf) -> Result<&dyn EventsFactory> {
+        let factory = self
+            .factories
+            .get(&child.backend)
+            .ok_or_else(|| ChildConfError::UnknownBackend(child.backend.clone()))?;
+        Ok(factory.as_ref())
+    }
+
+    /// Handle the failure to initialise a child according to its policy.
+    ///
+    /// Best-effort children that fail to initialise are logged and skipped.
+    fn init_failed(context: &Context, child: &ChildConf, error: anyhow::Error) -> Result<()> {
+        let error = error.context(ChildFailed(child.name().to_string()));
+        match child.policy {
+            FailurePolicy::Required => Err(error),
+            FailurePolicy::BestEffort => {
+                slog::warn!(
+                    context.logger, "Skipping best-effort events child that failed to initialise";
+                    "child" => child.name(),
+                    ErrorAttributes::from(&error),
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsFactory for TeeFactory {
+    fn conf_check(&self, context: &Context, conf: &Json) -> Result<()> {
+        let conf: Conf = serde_json::from_value(conf.clone()).context(ConfError)?;
+        conf.validate().context(ConfError)?;
+        for child in &conf.backends {
+            self.factory(child)
+                .context(ConfError)?
+                .conf_check(context, &child.options)
+                .context(ChildFailed(child.name().to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        // Children are selected by configuration so register metrics for all candidates.
+        crate::telemetry::register_metrics(registry)?;
+        for factory in self.factories.values() {
+            factory.register_metrics(registry)?;
+        }
+        Ok(())
+    }
+
+    async fn events<'a>(&self, args: EventsFactoryArgs<'a>) -> Result<Events> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        let mut children = Vec::new();
+        for child in &conf.backends {
+            let child_args = EventsFactoryArgs {
+                conf: &child.options,
+                context: args.context,
+            };
+            match self.factory(child)?.events(child_args).await {
+                Ok(events) => children.push(TeeChild {
+                    name: child.name().to_string(),
+                    policy: child.policy,
+                    inner: events,
+                }),
+                Err(error) => Self::init_failed(args.context, child, error)?,
+            }
+        }
+        Ok(Events::from(TeeEvents::new(children)))
+    }
+
+    async fn maintenance<'a>(
+        &self,
+        args: EventsFactoryArgs<'a>,
+    ) -> Result<Option<EventsMaintenance>> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        let mut children = Vec::new();
+        for child in &conf.backends {
+            let child_args = EventsFactoryArgs {
+                conf: &child.options,
+                context: args.context,
+            };
+            match self.factory(child)?.maintenance(child_args).await {
+                Ok(None) => (),
+                Ok(Some(maintenance)) => children.push(TeeChild {
+                    name: child.name().to_string(),
+                    policy: child.policy,
+                    inner: maintenance,
+                }),
+                Err(error) => Self::init_failed(args.context, child, error)?,
+            }
+        }
+        if children.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(EventsMaintenance::from(TeeMaintenance::new(children))))
+    }
+
+    async fn sync<'a>(&self, args: EventsFactorySyncArgs<'a>) -> Result<()> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        for child in &conf.backends {
+            let child_args = EventsFactorySyncArgs {
+                conf: &child.options,
+                context: args.context,
+            };
+            if let Err(error) = self.factory(child)?.sync(child_args).await {
+                Self::init_failed(args.context, child, error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use serde_json::Value as Json;
+
+    use replicore_context::Context;
+    use replicore_events::emit::Events;
+    use replicore_events::emit::EventsBackend;
+    use replicore_events::emit::EventsFactory;
+    use replicore_events::emit::EventsFactoryArgs;
+    use replicore_events::emit::EventsFactorySyncArgs;
+    use replicore_events::emit::EventsFixture;
+    use replicore_events::Event;
+
+    use super::TeeFactory;
+    use crate::ChildFailed;
+
+    const POP_TIMEOUT: Duration = Duration::from_millis(50);
+
+    /// Backend that fails all emit operations.
+    struct Failing;
+
+    #[async_trait::async_trait]
+    impl EventsBackend for Failing {
+        async fn audit(&self, _: &Context, _: Event) -> Result<()> {
+            anyhow::bail!("test failure")
+        }
+
+        async fn change(&self, _: &Context, _: Event) -> Result<()> {
+            anyhow::bail!("test failure")
+        }
+    }
+
+    /// Factory for test backends: fixtures, if one is given, or failing backends otherwise.
+    struct TestFactory(Option<EventsFixture>);
+
+    #[async_trait::async_trait]
+    impl EventsFactory for TestFactory {
+        fn conf_check(&self, _: &Context, _: &Json) -> Result<()> {
+            Ok(())
+        }
+
+        fn register_metrics(&self, _: &prometheus::Registry) -> Result<()> {
+            Ok(())
+        }
+
+        async fn events<'a>(&self, _: EventsFactoryArgs<'a>) -> Result<Events> {
+            match &self.0 {
+                None => Ok(Events::from(Failing)),
+                Some(fixture) => Ok(Events::from(fixture.backend())),
+            }
+        }
+
+        async fn sync<'a>(&self, _: EventsFactorySyncArgs<'a>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn tee(fixture: &EventsFixture, failing_policy: &str) -> Events {
+        let mut factory = TeeFactory::default();
+        factory
+            .register("fixture", Arc::new(TestFactory(Some(fixture.clone()))))
+            .register("failing", Arc::new(TestFactory(None)));
+        let conf = serde_json::json!({
+            "backends": [
+                {"backend": "fixture"},
+                {"backend": "failing", "policy": failing_policy},
+            ],
+        });
+        let context = Context::fixture();
+        let args = EventsFactoryArgs {
+            conf: &conf,
+            context: &context,
+        };
+        factory.events(args).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn best_effort_failures_ignored() {
+        let context = Context::fixture();
+        let mut fixture = EventsFixture::new();
+        let events = tee(&fixture, "best-effort").await;
+
+        let event = Event::new_with_payload("TEST", "payload").unwrap();
+        events.change(&context, event).await.unwrap();
+        let event = fixture.pop_change_timeout(POP_TIMEOUT).await.unwrap();
+        assert_eq!(event.code, "TEST");
+    }
+
+    #[tokio::test]
+    async fn required_failures_returned() {
+        let context = Context::fixture();
+        let mut fixture = EventsFixture::new();
+        let events = tee(&fixture, "required").await;
+
+        let event = Event::new_with_payload("TEST", "payload").unwrap();
+        let error = events.audit(&context, event).await.unwrap_err();
+        let child = error.downcast_ref::<ChildFailed>().unwrap();
+        assert_eq!(child.0, "failing");
+
+        // Other children still receive the event.
+        let event = fixture.pop_audit_timeout(POP_TIMEOUT).await.unwrap();
+        assert_eq!(event.code, "TEST");
+    }
+
+    #[test]
+    fn conf_check_unknown_backend() {
+        let factory = TeeFactory::default();
+        let conf = serde_json::json!({"backends": [{"backend": "missing"}]});
+        let result = factory.conf_check(&Context::fixture(), &conf);
+        assert!(result.is_err());
+    }
+}
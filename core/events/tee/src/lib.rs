@@ -0,0 +1,34 @@
+//! Event Streaming Platform emitting events to multiple child backends at once.
+//!
+//! Composite backends wrap any number of other registered events backends (children)
+//! so events can be kept for querying while also being streamed to other sinks.
+//!
+//! ## Failure policies
+//!
+//! Each child is configured with a failure policy:
+//!
+//! - `required`: failing to emit an event onto the child fails the emit operation.
+//! - `best-effort`: failures are logged and counted but otherwise ignored.
+//!   Best-effort children that fail to initialise are skipped.
+//!
+//! Events are emitted to all children concurrently, even when a required child fails,
+//! so best-effort sinks may receive events whose emit operation ultimately failed.
+//!
+//! ## Queries and maintenance
+//!
+//! Queries are served by the first child, in configuration order, that supports them.
+//! Children that need background maintenance have it run alongside each other.
+mod conf;
+mod events;
+mod factory;
+mod telemetry;
+
+pub use self::conf::ChildConf;
+pub use self::conf::ChildConfError;
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::conf::FailurePolicy;
+pub use self::events::ChildFailed;
+pub use self::events::TeeEvents;
+pub use self::factory::TeeFactory;
+pub use self::factory::TEE_BACKEND_ID;
@@ -0,0 +1,52 @@
+//! Telemetry related to the composite events implementation.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::CounterVec;
+use prometheus::HistogramOpts;
+use prometheus::HistogramVec;
+use prometheus::Opts;
+
+/// Duration of emit operations on each composite events child.
+pub static EMIT_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new(
+            "replicore_events_tee_emit_duration",
+            "Duration of emit operations on each composite events child",
+        ),
+        &["child", "stream"],
+    )
+    .expect("failed to initialise EMIT_DURATION histogram")
+});
+
+/// Number of failed emit operations on each composite events child.
+pub static EMIT_ERR: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_tee_emit_error",
+            "Number of failed emit operations on each composite events child",
+        ),
+        &["child", "stream", "policy"],
+    )
+    .expect("failed to initialise EMIT_ERR counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the composite events backend metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 2] =
+        [Box::new(EMIT_DURATION.clone()), Box::new(EMIT_ERR.clone())];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,257 @@
+//! Configuration for the webhook events backend.
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use replicore_events_models::EventStream;
+use replicore_events_models::EventsFormat;
+
+/// Webhook specific configuration for the events interface.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Conf {
+    /// HTTP endpoints to POST events to.
+    pub endpoints: Vec<Endpoint>,
+
+    /// Maximum number of events waiting in memory for delivery to each endpoint.
+    ///
+    /// Events emitted while the queue is full are spooled (or dropped) instead.
+    #[serde(default = "Conf::default_queue_size")]
+    pub queue_size: usize,
+
+    /// Retry rules for failed deliveries.
+    #[serde(default)]
+    pub retry: Retry,
+
+    /// Spool undeliverable events to disk for later delivery.
+    ///
+    /// Undeliverable events are dropped when no spool is configured.
+    #[serde(default)]
+    pub spool: Option<SpoolConf>,
+
+    /// Seconds to wait for endpoints to respond to a delivery attempt.
+    #[serde(default = "Conf::default_timeout")]
+    pub timeout: u64,
+}
+
+impl Conf {
+    fn default_queue_size() -> usize {
+        1000
+    }
+
+    fn default_timeout() -> u64 {
+        10
+    }
+
+    /// Check the configuration for errors that can't be detected while decoding it.
+    pub fn validate(&self) -> Result<(), EndpointError> {
+        let mut names = HashSet::new();
+        for endpoint in &self.endpoints {
+            endpoint.validate()?;
+            if !names.insert(endpoint.name.as_str()) {
+                return Err(EndpointError::DuplicateName(endpoint.name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// HTTP endpoint to POST events to.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Endpoint {
+    /// Unique name of the endpoint, used in logs, metrics and spool paths.
+    pub name: String,
+
+    /// URL to POST events to.
+    pub url: String,
+
+    /// Only deliver events with these codes, or all events if empty.
+    #[serde(default)]
+    pub codes: Vec<String>,
+
+    /// Encoding of events delivered to the endpoint.
+    #[serde(default)]
+    pub format: EventsFormat,
+
+    /// Shared secret to sign requests with, requests are not signed if not set.
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Only deliver events emitted onto these streams, or all streams if empty.
+    #[serde(default)]
+    pub streams: Vec<EventStream>,
+}
+
+impl Endpoint {
+    /// Check if an event should be delivered to the endpoint.
+    pub fn matches(&self, stream: EventStream, code: &str) -> bool {
+        let stream = self.streams.is_empty() || self.streams.contains(&stream);
+        let code = self.codes.is_empty() || self.codes.iter().any(|filter| filter == code);
+        stream && code
+    }
+
+    fn validate(&self) -> Result<(), EndpointError> {
+        if self.name.is_empty() {
+            return Err(EndpointError::EmptyName);
+        }
+        let valid_name = self
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(EndpointError::InvalidName(self.name.clone()));
+        }
+        match reqwest::Url::parse(&self.url) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => (),
+            _ => return Err(EndpointError::InvalidUrl(self.name.clone())),
+        };
+        if matches!(&self.secret, Some(secret) if secret.is_empty()) {
+            return Err(EndpointError::EmptySecret(self.name.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Retry rules for failed deliveries.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Retry {
+    /// Maximum number of attempts to deliver an event before it is spooled.
+    #[serde(default = "Retry::default_attempts")]
+    pub attempts: u32,
+
+    /// Milliseconds to wait after the first failed attempt, doubled after each failure.
+    #[serde(default = "Retry::default_initial_delay")]
+    pub initial_delay: u64,
+
+    /// Maximum milliseconds to wait between delivery attempts.
+    #[serde(default = "Retry::default_max_delay")]
+    pub max_delay: u64,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry {
+            attempts: Self::default_attempts(),
+            initial_delay: Self::default_initial_delay(),
+            max_delay: Self::default_max_delay(),
+        }
+    }
+}
+
+impl Retry {
+    fn default_attempts() -> u32 {
+        5
+    }
+
+    fn default_initial_delay() -> u64 {
+        500
+    }
+
+    fn default_max_delay() -> u64 {
+        30_000
+    }
+}
+
+/// Spool undeliverable events to disk for later delivery.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SpoolConf {
+    /// Directory to spool events into, with a sub-directory for each endpoint.
+    pub path: String,
+
+    /// Seconds to wait between attempts to redeliver spooled events.
+    #[serde(default = "SpoolConf::default_replay_delay")]
+    pub replay_delay: u64,
+}
+
+impl SpoolConf {
+    fn default_replay_delay() -> u64 {
+        60
+    }
+}
+
+/// The webhook events backend configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+#[error("the webhook events backend configuration is not valid")]
+pub struct ConfError;
+
+/// A webhook endpoint configuration is not valid.
+#[derive(Debug, thiserror::Error)]
+pub enum EndpointError {
+    /// Endpoint names are used in spool paths so they must be unique.
+    #[error("webhook endpoint name '{0}' is used more than once")]
+    DuplicateName(String),
+
+    /// Endpoints must have a name.
+    #[error("webhook endpoint names must not be empty")]
+    EmptyName,
+
+    /// Signing requests with empty secrets provides no protection.
+    #[error("webhook endpoint '{0}' has an empty secret")]
+    EmptySecret(String),
+
+    /// Endpoint names are used in spool paths so only a safe set of characters is allowed.
+    #[error("webhook endpoint name '{0}' must only contain ASCII letters, digits, '-' and '_'")]
+    InvalidName(String),
+
+    /// Endpoints must have a valid HTTP(S) URL.
+    #[error("webhook endpoint '{0}' does not have a valid HTTP(S) URL")]
+    InvalidUrl(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_events_models::EventStream;
+
+    use super::Conf;
+    use super::EndpointError;
+
+    fn conf(endpoints: serde_json::Value) -> Conf {
+        serde_json::from_value(serde_json::json!({ "endpoints": endpoints })).unwrap()
+    }
+
+    #[test]
+    fn endpoint_matches_filters() {
+        let conf = conf(serde_json::json!([{
+            "name": "alerts",
+            "url": "http://localhost/",
+            "codes": ["OACTION_FAIL", "NODE_DELETE"],
+            "streams": ["change"],
+        }]));
+        let endpoint = &conf.endpoints[0];
+        assert!(endpoint.matches(EventStream::Change, "OACTION_FAIL"));
+        assert!(!endpoint.matches(EventStream::Audit, "OACTION_FAIL"));
+        assert!(!endpoint.matches(EventStream::Change, "ORCHESTRATE_REPORT"));
+    }
+
+    #[test]
+    fn endpoint_matches_all_without_filters() {
+        let conf = conf(serde_json::json!([{"name": "all", "url": "http://localhost/"}]));
+        let endpoint = &conf.endpoints[0];
+        assert!(endpoint.matches(EventStream::Audit, "ANYTHING"));
+        assert!(endpoint.matches(EventStream::Change, "ANYTHING"));
+    }
+
+    #[test]
+    fn validate_endpoints() {
+        let conf = conf(serde_json::json!([
+            {"name": "alerts", "url": "http://localhost/"},
+            {"name": "alerts", "url": "http://localhost/"},
+        ]));
+        let error = conf.validate().unwrap_err();
+        assert!(matches!(error, EndpointError::DuplicateName(name) if name == "alerts"));
+
+        let conf = conf_one("../alerts", "http://localhost/");
+        assert!(matches!(
+            conf.validate(),
+            Err(EndpointError::InvalidName(_))
+        ));
+        let conf = conf_one("alerts", "ftp://localhost/");
+        assert!(matches!(conf.validate(), Err(EndpointError::InvalidUrl(_))));
+        let conf = conf_one("alerts", "https://localhost/");
+        assert!(conf.validate().is_ok());
+    }
+
+    fn conf_one(name: &str, url: &str) -> Conf {
+        conf(serde_json::json!([{"name": name, "url": url}]))
+    }
+}
@@ -0,0 +1,417 @@
+//! Deliver events to webhook endpoints with retries and spooling.
+use std::time::Duration;
+
+use anyhow::Result;
+use hmac::Hmac;
+use hmac::Mac;
+use sha2::Sha256;
+use slog::Logger;
+use tokio::sync::mpsc::Receiver;
+use tokio::time::MissedTickBehavior;
+
+use replisdk::utils::error::slog::ErrorAttributes;
+
+use crate::conf::Endpoint;
+use crate::conf::Retry;
+use crate::spool::Spool;
+
+/// HTTP header carrying the signature of signed requests.
+pub const SIGNATURE_HEADER: &str = "X-Replicore-Signature";
+
+/// HTTP header carrying the UNIX timestamp, in seconds, signed along with the request body.
+pub const TIMESTAMP_HEADER: &str = "X-Replicore-Timestamp";
+
+/// Compute the signature of a request body, sent at the given time, with an endpoint secret.
+///
+/// Signatures are formatted as `sha256=<hex encoded HMAC-SHA256 of "{timestamp}.{body}">`.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC-SHA256 accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Deliver events to a single webhook endpoint.
+pub struct Deliver {
+    client: reqwest::Client,
+    endpoint: Endpoint,
+    logger: Logger,
+    retry: Retry,
+    spool: Option<Spool>,
+}
+
+impl Deliver {
+    /// Initialise delivery of events to the given endpoint.
+    pub fn new(
+        client: reqwest::Client,
+        endpoint: Endpoint,
+        logger: Logger,
+        retry: Retry,
+        spool: Option<Spool>,
+    ) -> Deliver {
+        Deliver {
+            client,
+            endpoint,
+            logger,
+            retry,
+            spool,
+        }
+    }
+
+    /// Deliver events from the queue, and periodically redeliver spooled events,
+    /// until all senders of the queue are dropped.
+    pub async fn run(self, mut queue: Receiver<Vec<u8>>, replay_delay: Duration) {
+        let mut replay = tokio::time::interval(replay_delay);
+        replay.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                body = queue.recv() => match body {
+                    None => return,
+                    Some(body) => self.deliver_or_spool(body).await,
+                },
+                _ = replay.tick(), if self.spool.is_some() => self.replay().await,
+            }
+        }
+    }
+
+    /// Deliver an event, retrying failed attempts with exponential backoff.
+    ///
+    /// Returns the error of the last attempt once all attempts failed.
+    pub async fn deliver(&self, body: &[u8]) -> Result<()> {
+        let max_delay = Duration::from_millis(self.retry.max_delay);
+        let mut delay = Duration::from_millis(self.retry.initial_delay).min(max_delay);
+        let mut attempt = 1;
+        loop {
+            match self.send(body).await {
+                Ok(()) => return Ok(()),
+                Err(error) if attempt >= self.retry.attempts => return Err(error),
+                Err(error) => {
+                    slog::debug!(
+                        self.logger, "Retrying failed event delivery to webhook endpoint";
+                        "endpoint" => &self.endpoint.name,
+                        "attempt" => attempt,
+                        ErrorAttributes::from(&error),
+                    );
+                }
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(max_delay);
+            attempt += 1;
+        }
+    }
+
+    /// Deliver an event, spooling it (or dropping it) if delivery fails.
+    pub async fn deliver_or_spool(&self, body: Vec<u8>) {
+        if let Err(error) = self.deliver(&body).await {
+            slog::warn!(
+                self.logger, "Unable to deliver event to webhook endpoint";
+                "endpoint" => &self.endpoint.name,
+                ErrorAttributes::from(&error),
+            );
+            spool_or_drop(
+                &self.logger,
+                &self.endpoint.name,
+                self.spool.as_ref(),
+                &body,
+            )
+            .await;
+        }
+    }
+
+    /// Redeliver spooled events, oldest first, stopping at the first failure.
+    ///
+    /// Spooled events are attempted once per replay as the endpoint is likely still
+    /// unavailable after a failure and new events should not wait on retries.
+    pub async fn replay(&self) {
+        let spool = match &self.spool {
+            None => return,
+            Some(spool) => spool,
+        };
+        let result = async {
+            for path in spool.pending().await? {
+                let body = tokio::fs::read(&path).await?;
+                self.send(&body).await?;
+                tokio::fs::remove_file(&path).await?;
+            }
+            Ok::<(), anyhow::Error>(())
+        };
+        if let Err(error) = result.await {
+            slog::warn!(
+                self.logger, "Unable to redeliver spooled events to webhook endpoint";
+                "endpoint" => &self.endpoint.name,
+                ErrorAttributes::from(&error),
+            );
+        }
+    }
+
+    /// Make a single attempt to deliver an event to the endpoint.
+    async fn send(&self, body: &[u8]) -> Result<()> {
+        let name = self.endpoint.name.as_str();
+        let _timer = crate::telemetry::DELIVERY_DURATION
+            .with_label_values(&[name])
+            .start_timer();
+        let mut request = self
+            .client
+            .post(&self.endpoint.url)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                self.endpoint.format.content_type(),
+            )
+            .body(body.to_vec());
+        if let Some(secret) = &self.endpoint.secret {
+            // Sign every attempt with the current time so retries and replays stay fresh.
+            let timestamp = time::OffsetDateTime::now_utc().unix_timestamp();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(SIGNATURE_HEADER, sign(secret, timestamp, body));
+        }
+
+        let result = async move { request.send().await?.error_for_status() }.await;
+        match result {
+            Ok(_) => {
+                crate::telemetry::EVENTS_DELIVERED
+                    .with_label_values(&[name])
+                    .inc();
+                Ok(())
+            }
+            Err(error) => {
+                crate::telemetry::DELIVERY_ERR
+                    .with_label_values(&[name])
+                    .inc();
+                Err(error.into())
+            }
+        }
+    }
+}
+
+/// Store an undeliverable event in the spool, or drop it if that is not possible.
+pub async fn spool_or_drop(logger: &Logger, endpoint: &str, spool: Option<&Spool>, body: &[u8]) {
+    let spool = match spool {
+        None => {
+            crate::telemetry::EVENTS_DROPPED
+                .with_label_values(&[endpoint])
+                .inc();
+            return;
+        }
+        Some(spool) => spool,
+    };
+    match spool.store(body).await {
+        Ok(()) => crate::telemetry::EVENTS_SPOOLED
+            .with_label_values(&[endpoint])
+            .inc(),
+        Err(error) => {
+            crate::telemetry::EVENTS_DROPPED
+                .with_label_values(&[endpoint])
+                .inc();
+            slog::error!(
+                logger, "Unable to spool undeliverable event, the event is dropped";
+                "endpoint" => endpoint,
+                ErrorAttributes::from(&error),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+    use tokio::net::TcpStream;
+
+    use super::sign;
+    use super::Deliver;
+    use crate::conf::Endpoint;
+    use crate::conf::Retry;
+    use crate::spool::Spool;
+
+    /// Request received by a [`Stub`] server.
+    pub struct StubRequest {
+        pub body: Vec<u8>,
+        pub headers: HashMap<String, String>,
+    }
+
+    /// Minimal HTTP server to receive webhook requests in tests.
+    ///
+    /// The server fails the configured number of requests before accepting them.
+    #[derive(Clone)]
+    pub struct Stub {
+        pub addr: SocketAddr,
+        pub failures: Arc<AtomicUsize>,
+        pub requests: Arc<Mutex<Vec<StubRequest>>>,
+    }
+
+    impl Stub {
+        /// Start a stub server listening on a random local port.
+        pub async fn start(failures: usize) -> Stub {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let stub = Stub {
+                addr: listener.local_addr().unwrap(),
+                failures: Arc::new(AtomicUsize::new(failures)),
+                requests: Default::default(),
+            };
+            let server = stub.clone();
+            tokio::spawn(async move {
+                loop {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    server.handle(stream).await;
+                }
+            });
+            stub
+        }
+
+        /// Number of requests the stub server accepted.
+        pub fn accepted(&self) -> usize {
+            self.requests.lock().unwrap().len()
+        }
+
+        /// URL to send requests to the stub server.
+        pub fn url(&self) -> String {
+            format!("http://{}/hook", self.addr)
+        }
+
+        async fn handle(&self, mut stream: TcpStream) {
+            // Read the request head and body, as sized by the content-length header.
+            let mut buffer = Vec::new();
+            let head_end = loop {
+                let mut chunk = [0; 1024];
+                let read = stream.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..read]);
+                if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+            let headers: HashMap<String, String> = head
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.split_once(": "))
+                .map(|(name, value)| (name.to_lowercase(), value.to_string()))
+                .collect();
+            let length: usize = headers
+                .get("content-length")
+                .map(|length| length.parse().unwrap())
+                .unwrap_or(0);
+            while buffer.len() < head_end + length {
+                let mut chunk = [0; 1024];
+                let read = stream.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..read]);
+            }
+
+            // Fail requests while failures are left, accept them after that.
+            let fail = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| f.checked_sub(1))
+                .is_ok();
+            let status = if fail {
+                "500 Internal Server Error"
+            } else {
+                let body = buffer[head_end..head_end + length].to_vec();
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .push(StubRequest { body, headers });
+                "200 OK"
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+    }
+
+    /// Create a unique spool directory for a test.
+    pub fn spool_dir() -> PathBuf {
+        let dir = format!("replicore-events-webhook-{}", uuid::Uuid::new_v4());
+        std::env::temp_dir().join(dir)
+    }
+
+    fn deliver(stub: &Stub, spool: Option<Spool>) -> Deliver {
+        let context = replicore_context::Context::fixture();
+        let endpoint = Endpoint {
+            name: "test".into(),
+            url: stub.url(),
+            codes: Vec::new(),
+            format: Default::default(),
+            secret: Some("secret".into()),
+            streams: Vec::new(),
+        };
+        let retry = Retry {
+            attempts: 3,
+            initial_delay: 1,
+            max_delay: 5,
+        };
+        Deliver::new(
+            reqwest::Client::new(),
+            endpoint,
+            context.logger,
+            retry,
+            spool,
+        )
+    }
+
+    #[test]
+    fn sign_body() {
+        let signature = sign("Jefe", 1700000000, b"what do ya want for nothing?");
+        assert_eq!(
+            signature,
+            "sha256=1cdd0650c8be1cb0974b1788d458b1e781206cfef59b85faafc582d2e182c57e",
+        );
+
+        // The timestamp is part of the signature.
+        let later = sign("Jefe", 1700000001, b"what do ya want for nothing?");
+        assert_ne!(signature, later);
+    }
+
+    #[tokio::test]
+    async fn deliver_after_retries() {
+        let stub = Stub::start(2).await;
+        let deliver = deliver(&stub, None);
+        let before = time::OffsetDateTime::now_utc().unix_timestamp();
+        deliver.deliver(br#"{"test":true}"#).await.unwrap();
+        let after = time::OffsetDateTime::now_utc().unix_timestamp();
+
+        let requests = stub.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body, br#"{"test":true}"#);
+        let timestamp: i64 = requests[0].headers["x-replicore-timestamp"]
+            .parse()
+            .unwrap();
+        assert!(before <= timestamp && timestamp <= after);
+        assert_eq!(
+            requests[0].headers.get("x-replicore-signature"),
+            Some(&sign("secret", timestamp, br#"{"test":true}"#)),
+        );
+    }
+
+    #[tokio::test]
+    async fn spool_and_replay_undeliverable() {
+        let stub = Stub::start(3).await;
+        let dir = spool_dir();
+        let spool = Spool::new(&dir, "test");
+        let deliver = deliver(&stub, Some(spool.clone()));
+
+        // All attempts fail so the event is spooled.
+        deliver.deliver_or_spool(b"{}".to_vec()).await;
+        assert_eq!(stub.accepted(), 0);
+        assert_eq!(spool.pending().await.unwrap().len(), 1);
+
+        // Spooled events are removed once redelivered.
+        deliver.replay().await;
+        assert_eq!(stub.accepted(), 1);
+        assert!(spool.pending().await.unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
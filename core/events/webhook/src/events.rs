@@ -0,0 +1,234 @@
+//! Emit events by queueing them for delivery to webhook endpoints.
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::Sender;
+
+use replicore_context::Context;
+use replicore_events::emit::EventsBackend;
+use replicore_events::Event;
+use replicore_events_models::EventEntry;
+use replicore_events_models::EventStream;
+
+use crate::conf::Endpoint;
+use crate::deliver::spool_or_drop;
+use crate::deliver::Deliver;
+use crate::spool::Spool;
+use crate::Conf;
+
+/// String to set as the user agent in webhook requests.
+static CLIENT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Queue of events waiting for delivery to an endpoint.
+struct Route {
+    endpoint: Endpoint,
+    queue: Sender<Vec<u8>>,
+    spool: Option<Spool>,
+}
+
+/// Implementation of the [`EventsBackend`] interface delivering events to webhook endpoints.
+///
+/// Events are queued for background delivery so emitting events does not wait on endpoints.
+pub struct WebhookEvents {
+    routes: Vec<Route>,
+}
+
+impl WebhookEvents {
+    /// Start background delivery of events to the configured endpoints.
+    ///
+    /// Delivery stops once the [`WebhookEvents`] object is dropped and queued events are handled.
+    pub fn start(context: &Context, conf: &Conf) -> Result<WebhookEvents> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(conf.timeout))
+            .user_agent(CLIENT_USER_AGENT)
+            .build()?;
+        let replay_delay = conf
+            .spool
+            .as_ref()
+            .map(|spool| spool.replay_delay)
+            .unwrap_or_default();
+        let replay_delay = Duration::from_secs(replay_delay.max(1));
+
+        let mut routes = Vec::new();
+        for endpoint in &conf.endpoints {
+            let spool = conf
+                .spool
+                .as_ref()
+                .map(|spool| Spool::new(&spool.path, &endpoint.name));
+            let (queue, receiver) = tokio::sync::mpsc::channel(conf.queue_size.max(1));
+            let deliver = Deliver::new(
+                client.clone(),
+                endpoint.clone(),
+                context.logger.clone(),
+                conf.retry.clone(),
+                spool.clone(),
+            );
+            tokio::spawn(deliver.run(receiver, replay_delay));
+            routes.push(Route {
+                endpoint: endpoint.clone(),
+                queue,
+                spool,
+            });
+        }
+        Ok(WebhookEvents { routes })
+    }
+
+    /// Queue the event for delivery to all endpoints it matches.
+    ///
+    /// Events that don't fit in an endpoint queue are spooled (or dropped) immediately.
+    async fn emit(&self, context: &Context, stream: EventStream, event: Event) -> Result<()> {
+        let routes: Vec<&Route> = self
+            .routes
+            .iter()
+            .filter(|route| route.endpoint.matches(stream, &event.code))
+            .collect();
+        if routes.is_empty() {
+            return Ok(());
+        }
+
+        let entry = EventEntry { event, stream };
+        for route in routes {
+            let body = route.endpoint.format.encode(&entry)?;
+            match route.queue.try_send(body) {
+                Ok(()) => (),
+                Err(TrySendError::Closed(body)) | Err(TrySendError::Full(body)) => {
+                    let name = &route.endpoint.name;
+                    spool_or_drop(&context.logger, name, route.spool.as_ref(), &body).await;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsBackend for WebhookEvents {
+    async fn audit(&self, context: &Context, event: Event) -> Result<()> {
+        self.emit(context, EventStream::Audit, event).await
+    }
+
+    async fn change(&self, context: &Context, event: Event) -> Result<()> {
+        self.emit(context, EventStream::Change, event).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use replicore_events::Event;
+    use replicore_events_models::CloudEvent;
+    use replicore_events_models::EventEntry;
+    use replicore_events_models::EventStream;
+
+    use super::WebhookEvents;
+    use crate::deliver::tests::spool_dir;
+    use crate::deliver::tests::Stub;
+    use crate::Conf;
+
+    async fn wait_accepted(stub: &Stub, count: usize) {
+        let wait = async {
+            while stub.accepted() < count {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(2), wait)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn deliver_matching_events() {
+        let context = replicore_context::Context::fixture();
+        let alerts = Stub::start(0).await;
+        let all = Stub::start(0).await;
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "endpoints": [
+                {"name": "alerts", "url": alerts.url(), "codes": ["OACTION_FAIL"]},
+                {"name": "all", "url": all.url()},
+            ],
+        }))
+        .unwrap();
+        let events = WebhookEvents::start(&context, &conf).unwrap();
+
+        let event = Event::new_with_payload("NODE_DELETE", "test").unwrap();
+        events
+            .emit(&context, EventStream::Change, event)
+            .await
+            .unwrap();
+        let event = Event::new_with_payload("OACTION_FAIL", "test").unwrap();
+        events
+            .emit(&context, EventStream::Audit, event)
+            .await
+            .unwrap();
+        wait_accepted(&all, 2).await;
+        wait_accepted(&alerts, 1).await;
+
+        let requests = alerts.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let entry: EventEntry = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(entry.event.code, "OACTION_FAIL");
+        assert_eq!(entry.stream, EventStream::Audit);
+    }
+
+    #[tokio::test]
+    async fn deliver_cloudevents() {
+        let context = replicore_context::Context::fixture();
+        let stub = Stub::start(0).await;
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "endpoints": [{"name": "cloud", "url": stub.url(), "format": "cloudevents"}],
+        }))
+        .unwrap();
+        let events = WebhookEvents::start(&context, &conf).unwrap();
+
+        let payload = serde_json::json!({"ns_id": "default", "cluster_id": "pg"});
+        let event = Event::new_with_payload("OACTION_FAIL", payload).unwrap();
+        events
+            .emit(&context, EventStream::Change, event)
+            .await
+            .unwrap();
+        wait_accepted(&stub, 1).await;
+
+        let requests = stub.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].headers["content-type"],
+            "application/cloudevents+json",
+        );
+        let cloud: CloudEvent = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(cloud.type_, "OACTION_FAIL");
+        assert_eq!(cloud.subject.as_deref(), Some("default/pg"));
+    }
+
+    #[tokio::test]
+    async fn spooled_events_delivered_on_start() {
+        let context = replicore_context::Context::fixture();
+        let stub = Stub::start(0).await;
+        let dir = spool_dir();
+        let spool = crate::spool::Spool::new(&dir, "test");
+        spool.store(br#"{"spooled":true}"#).await.unwrap();
+
+        let conf: Conf = serde_json::from_value(serde_json::json!({
+            "endpoints": [{"name": "test", "url": stub.url()}],
+            "spool": {"path": dir.to_string_lossy()},
+        }))
+        .unwrap();
+        let _events = WebhookEvents::start(&context, &conf).unwrap();
+        wait_accepted(&stub, 1).await;
+        assert_eq!(
+            stub.requests.lock().unwrap()[0].body,
+            br#"{"spooled":true}"#
+        );
+
+        // Delivered events are removed from the spool.
+        let removed = async {
+            while !spool.pending().await.unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(2), removed)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
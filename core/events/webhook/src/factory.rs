@@ -0,0 +1,45 @@
+//! Factory for the webhook events backend.
+use anyhow::Context as AnyContext;
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_events::emit::Events;
+use replicore_events::emit::EventsFactory;
+use replicore_events::emit::EventsFactoryArgs;
+use replicore_events::emit::EventsFactorySyncArgs;
+
+use crate::Conf;
+use crate::ConfError;
+use crate::WebhookEvents;
+
+/// Initialise delivery of events to webhook endpoints.
+pub struct WebhookFactory;
+
+#[async_trait::async_trait]
+impl EventsFactory for WebhookFactory {
+    fn conf_check(&self, _: &Context, conf: &Json) -> Result<()> {
+        let conf: Conf = serde_json::from_value(conf.clone()).context(ConfError)?;
+        conf.validate().context(ConfError)?;
+        Ok(())
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        crate::telemetry::register_metrics(registry)
+    }
+
+    async fn events<'a>(&self, args: EventsFactoryArgs<'a>) -> Result<Events> {
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        let events = WebhookEvents::start(args.context, &conf)?;
+        Ok(Events::from(events))
+    }
+
+    async fn sync<'a>(&self, args: EventsFactorySyncArgs<'a>) -> Result<()> {
+        // Ensure the spool directory can be created before events need spooling.
+        let conf: Conf = serde_json::from_value(args.conf.clone()).context(ConfError)?;
+        if let Some(spool) = &conf.spool {
+            tokio::fs::create_dir_all(&spool.path).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Event Streaming Platform delivering events to HTTP webhook endpoints.
+//!
+//! Events are POSTed as JSON [`EventEntry`](replicore_events_models::EventEntry) objects
+//! to each configured endpoint they match (by event code and stream).
+//!
+//! This backend only pushes events out of the Control Plane: querying events is not supported.
+//!
+//! ## Delivery
+//!
+//! Each endpoint has its own in-memory queue and delivery loop so slow or unavailable
+//! endpoints do not hold back the Control Plane or other endpoints:
+//!
+//! - Failed deliveries are retried with exponential backoff, up to a configurable limit.
+//! - Events that could not be delivered, or that do not fit in the queue, are spooled to disk.
+//! - Spooled events are periodically redelivered, oldest first, and removed once delivered.
+//! - Events are dropped if they can't be delivered and no spool directory is configured.
+//!
+//! Events still queued in memory when the process terminates are lost.
+//! Because spooled events are redelivered in the background, events may reach endpoints
+//! out of order and endpoints should use the event time to order them.
+//!
+//! ## Signatures
+//!
+//! Endpoints configured with a secret receive two additional headers with each request:
+//!
+//! - `X-Replicore-Timestamp`: the UNIX time, in seconds, the request was sent at.
+//! - `X-Replicore-Signature`: set to `sha256=<hex encoded HMAC-SHA256 of "{timestamp}.{body}">`,
+//!   where `{timestamp}` is the value of the `X-Replicore-Timestamp` header
+//!   and `{body}` is the raw request body.
+//!
+//! Endpoints should recompute the signature with the shared secret, compare it in constant
+//! time and reject mismatches.
+//! To prevent captured requests from being replayed, endpoints should also reject requests
+//! whose timestamp differs from their own clock by more than a tolerance window:
+//! five minutes is recommended, which allows for reasonable clock skew.
+//!
+//! Every delivery attempt, including retries and redelivery of spooled events,
+//! is signed with the time of the attempt.
+mod conf;
+mod deliver;
+mod events;
+mod factory;
+mod spool;
+mod telemetry;
+
+pub use self::conf::Conf;
+pub use self::conf::ConfError;
+pub use self::conf::Endpoint;
+pub use self::conf::EndpointError;
+pub use self::conf::Retry;
+pub use self::conf::SpoolConf;
+pub use self::deliver::sign;
+pub use self::deliver::SIGNATURE_HEADER;
+pub use self::deliver::TIMESTAMP_HEADER;
+pub use self::events::WebhookEvents;
+pub use self::factory::WebhookFactory;
@@ -0,0 +1,54 @@
+//! Store undeliverable events on disk for later delivery.
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+
+/// Events waiting on disk for delivery to a webhook endpoint.
+///
+/// Each event is stored in its own file, named so that sorting files by name
+/// lists events in the order they were spooled.
+#[derive(Clone, Debug)]
+pub struct Spool {
+    dir: PathBuf,
+}
+
+impl Spool {
+    /// Spool events for the named endpoint into a sub-directory of `root`.
+    pub fn new<P: AsRef<Path>>(root: P, endpoint: &str) -> Spool {
+        let dir = root.as_ref().join(endpoint);
+        Spool { dir }
+    }
+
+    /// List spooled events, oldest first.
+    pub async fn pending(&self) -> Result<Vec<PathBuf>> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            result => result?,
+        };
+        let mut pending = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+                pending.push(path);
+            }
+        }
+        pending.sort();
+        Ok(pending)
+    }
+
+    /// Store an event request body for later delivery.
+    ///
+    /// Events are written to a temporary file first and renamed once complete
+    /// so partially written events are never delivered.
+    pub async fn store(&self, body: &[u8]) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let now = OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let name = format!("{:020}-{}", now, uuid::Uuid::new_v4());
+        let partial = self.dir.join(format!("{}.partial", name));
+        tokio::fs::write(&partial, body).await?;
+        tokio::fs::rename(&partial, self.dir.join(format!("{}.json", name))).await?;
+        Ok(())
+    }
+}
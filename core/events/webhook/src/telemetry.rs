@@ -0,0 +1,93 @@
+//! Telemetry related to the webhook events implementation.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::CounterVec;
+use prometheus::HistogramOpts;
+use prometheus::HistogramVec;
+use prometheus::Opts;
+
+/// Duration of requests to deliver events to webhook endpoints.
+pub static DELIVERY_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new(
+            "replicore_events_webhook_delivery_duration",
+            "Duration of requests to deliver events to webhook endpoints",
+        ),
+        &["endpoint"],
+    )
+    .expect("failed to initialise DELIVERY_DURATION histogram")
+});
+
+/// Number of failed requests to deliver events to webhook endpoints.
+pub static DELIVERY_ERR: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_webhook_delivery_error",
+            "Number of failed requests to deliver events to webhook endpoints",
+        ),
+        &["endpoint"],
+    )
+    .expect("failed to initialise DELIVERY_ERR counter")
+});
+
+/// Number of events delivered to webhook endpoints.
+pub static EVENTS_DELIVERED: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_webhook_delivered",
+            "Number of events delivered to webhook endpoints",
+        ),
+        &["endpoint"],
+    )
+    .expect("failed to initialise EVENTS_DELIVERED counter")
+});
+
+/// Number of undeliverable events dropped.
+pub static EVENTS_DROPPED: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_webhook_dropped",
+            "Number of undeliverable events dropped",
+        ),
+        &["endpoint"],
+    )
+    .expect("failed to initialise EVENTS_DROPPED counter")
+});
+
+/// Number of undeliverable events spooled to disk.
+pub static EVENTS_SPOOLED: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_events_webhook_spooled",
+            "Number of undeliverable events spooled to disk",
+        ),
+        &["endpoint"],
+    )
+    .expect("failed to initialise EVENTS_SPOOLED counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the webhook events backend metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 5] = [
+        Box::new(DELIVERY_DURATION.clone()),
+        Box::new(DELIVERY_ERR.clone()),
+        Box::new(EVENTS_DELIVERED.clone()),
+        Box::new(EVENTS_DROPPED.clone()),
+        Box::new(EVENTS_SPOOLED.clone()),
+    ];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
@@ -7,6 +7,8 @@ use replicore_auth::access::Authoriser;
 use replicore_auth::identity::Authenticator;
 use replicore_conf::Conf;
 use replicore_context::Context;
+use replicore_coordinator::Coordinator;
+use replicore_coordinator::Election;
 use replicore_events::emit::Events;
 use replicore_oaction::OActionRegistry;
 use replicore_store::Store;
@@ -37,6 +39,12 @@ pub struct Injector {
     /// Process global context to derive scoped contexts from.
     pub context: Context,
 
+    /// Interface to coordinate exclusive work across Control Plane processes.
+    pub coordinator: Coordinator,
+
+    /// Leader election for components that must only run in one process at a time.
+    pub election: Election,
+
     /// Interface to emit system events.
     pub events: Events,
 
@@ -94,6 +102,7 @@ impl Injector {
 #[cfg(any(test, feature = "test-fixture"))]
 pub struct InjectorFixture {
     pub injector: Injector,
+    pub coordinator: replicore_coordinator::CoordinatorFixture,
     pub events: replicore_events::emit::EventsFixture,
 }
 
@@ -101,18 +110,29 @@ pub struct InjectorFixture {
 impl Injector {
     /// [`Injector`] instance to be used with unit tests.
     pub fn fixture() -> InjectorFixture {
+        let coordinator = replicore_coordinator::CoordinatorFixture::default();
         let events = replicore_events::emit::EventsFixture::new();
         let authoriser = Authoriser::wrap(
             replicore_auth_insecure::Unrestricted,
             events.backend().into(),
         );
         let conf = Conf {
+            authentication: replicore_conf::BackendConf {
+                backend: "unittest".into(),
+                options: Default::default(),
+            },
+            coordinator: replicore_conf::BackendConf {
+                backend: "unittest".into(),
+                options: Default::default(),
+            },
             events: replicore_conf::BackendConf {
                 backend: "unittest".into(),
                 options: Default::default(),
             },
             http: Default::default(),
+            outbox: Default::default(),
             runtime: Default::default(),
+            scheduler: Default::default(),
             store: replicore_conf::BackendConf {
                 backend: "unittest".into(),
                 options: Default::default(),
@@ -132,11 +152,17 @@ impl Injector {
             clients: Clients::empty(),
             conf,
             context: Context::fixture(),
+            coordinator: coordinator.clone().into(),
+            election: Election::fixture(),
             events: events.backend().into(),
             oactions: OActionRegistry::build().finish(),
             store: Store::fixture(),
             tasks: Tasks::fixture().backend().into(),
         };
-        InjectorFixture { injector, events }
+        InjectorFixture {
+            injector,
+            coordinator,
+            events,
+        }
     }
 }
@@ -1,4 +1,6 @@
 //! Interface for implementation of orchestrator action execution handling.
+use std::time::Duration;
+
 use anyhow::Result;
 
 use replisdk::core::models::cluster::ClusterDiscovery;
@@ -27,6 +29,12 @@ pub struct OActionChanges {
     /// Optionally change the action error data.
     pub error: OActionChangeValue,
 
+    /// Request a follow-up orchestration of the cluster after the given delay.
+    ///
+    /// Actions waiting on external progress can use this to be invoked again
+    /// without waiting for the next periodic or user-requested orchestration.
+    pub follow_up: Option<Duration>,
+
     /// Optionally change the action payload data.
     pub payload: OActionChangeValue,
 
@@ -47,6 +55,12 @@ impl OActionChanges {
         self
     }
 
+    /// Request a follow-up orchestration of the cluster after the given delay.
+    pub fn follow_up(mut self, delay: Duration) -> Self {
+        self.follow_up = Some(delay);
+        self
+    }
+
     /// Update or reset the orchestrator action payload data.
     pub fn payload<P>(mut self, payload: P) -> Self
     where
@@ -63,6 +77,7 @@ impl OActionChanges {
     pub fn to(state: OActionState) -> OActionChanges {
         OActionChanges {
             error: Default::default(),
+            follow_up: None,
             payload: Default::default(),
             state,
         }
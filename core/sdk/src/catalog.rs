@@ -0,0 +1,46 @@
+//! Event codes emitted by the SDK and the contract of their payloads.
+use replicore_events::EventCode;
+
+use crate::constants::NACTION_APPROVE;
+use crate::constants::NACTION_CANCEL;
+use crate::constants::NACTION_CREATE;
+use crate::constants::NACTION_REJECT;
+use crate::constants::OACTION_APPROVE;
+use crate::constants::OACTION_CANCEL;
+use crate::constants::OACTION_CREATE;
+use crate::constants::OACTION_REJECT;
+
+/// JSON Schema for events with a `ClusterSpec` payload.
+pub const CLUSTER_SPEC_SCHEMA: &str = include_str!("catalog/cluster-spec.schema.json");
+
+/// JSON Schema for events with an `NAction` payload.
+pub const NACTION_SCHEMA: &str = include_str!("catalog/naction.schema.json");
+
+/// JSON Schema for events with an `OAction` payload.
+pub const OACTION_SCHEMA: &str = include_str!("catalog/oaction.schema.json");
+
+/// Event codes emitted by the SDK.
+pub fn event_codes() -> Vec<EventCode> {
+    let naction = |code: &str, description: &str| {
+        EventCode::change(code, "NAction", description).schema(NACTION_SCHEMA)
+    };
+    let oaction = |code: &str, description: &str| {
+        EventCode::change(code, "OAction", description).schema(OACTION_SCHEMA)
+    };
+    vec![
+        naction(NACTION_APPROVE, "Node Action approved for scheduling"),
+        naction(NACTION_CANCEL, "Node Action cancelled"),
+        naction(NACTION_CREATE, "Node Action created"),
+        naction(NACTION_REJECT, "Node Action rejected to prevent scheduling"),
+        oaction(
+            OACTION_APPROVE,
+            "Orchestrator Action approved for scheduling",
+        ),
+        oaction(OACTION_CANCEL, "Orchestrator Action cancelled"),
+        oaction(OACTION_CREATE, "Orchestrator Action created"),
+        oaction(
+            OACTION_REJECT,
+            "Orchestrator Action rejected to prevent scheduling",
+        ),
+    ]
+}
@@ -3,7 +3,9 @@ use replicore_injector::Injector;
 
 mod naction;
 mod oaction;
+mod persist;
 
+pub mod catalog;
 pub mod constants;
 pub mod errors;
 
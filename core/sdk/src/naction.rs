@@ -9,6 +9,7 @@ use replisdk::core::models::naction::NActionState;
 
 use replicore_context::Context;
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 use replicore_store::query::LookupNAction;
 
 use super::CoreSDK;
@@ -18,8 +19,8 @@ impl CoreSDK {
     pub async fn naction_approve(&self, context: &Context, mut action: NAction) -> Result<()> {
         action.state.phase = NActionPhase::PendingSchedule;
         let event = Event::new_with_payload(crate::constants::NACTION_APPROVE, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(())
     }
 
@@ -27,8 +28,8 @@ impl CoreSDK {
     pub async fn naction_cancel(&self, context: &Context, mut action: NAction) -> Result<()> {
         action.finish(NActionPhase::Cancelled);
         let event = Event::new_with_payload(crate::constants::NACTION_CANCEL, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(())
     }
 
@@ -77,8 +78,8 @@ impl CoreSDK {
 
         // Apply the cluster spec.
         let event = Event::new_with_payload(crate::constants::NACTION_CREATE, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(action_ref)
     }
 
@@ -86,8 +87,8 @@ impl CoreSDK {
     pub async fn naction_reject(&self, context: &Context, mut action: NAction) -> Result<()> {
         action.finish(NActionPhase::Cancelled);
         let event = Event::new_with_payload(crate::constants::NACTION_REJECT, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(())
     }
 }
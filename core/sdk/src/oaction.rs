@@ -8,6 +8,7 @@ use replisdk::core::models::oaction::OActionState;
 
 use replicore_context::Context;
 use replicore_events::Event;
+use replicore_store::persist::PersistWithEvents;
 use replicore_store::query::LookupOAction;
 
 use super::CoreSDK;
@@ -17,8 +18,8 @@ impl CoreSDK {
     pub async fn oaction_approve(&self, context: &Context, mut action: OAction) -> Result<()> {
         action.state = OActionState::PendingSchedule;
         let event = Event::new_with_payload(crate::constants::OACTION_APPROVE, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(())
     }
 
@@ -26,8 +27,8 @@ impl CoreSDK {
     pub async fn oaction_cancel(&self, context: &Context, mut action: OAction) -> Result<()> {
         action.finish(OActionState::Cancelled);
         let event = Event::new_with_payload(crate::constants::OACTION_CANCEL, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(())
     }
 
@@ -72,8 +73,8 @@ impl CoreSDK {
 
         // Apply the cluster spec.
         let event = Event::new_with_payload(crate::constants::OACTION_CREATE, &oaction)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, oaction).await?;
+        let op = PersistWithEvents::new(oaction).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(action_ref)
     }
 
@@ -81,8 +82,8 @@ impl CoreSDK {
     pub async fn oaction_reject(&self, context: &Context, mut action: OAction) -> Result<()> {
         action.finish(OActionState::Cancelled);
         let event = Event::new_with_payload(crate::constants::OACTION_REJECT, &action)?;
-        self.injector.events.change(context, event).await?;
-        self.injector.store.persist(context, action).await?;
+        let op = PersistWithEvents::new(action).change(event);
+        self.persist_with_events(context, op).await?;
         Ok(())
     }
 }
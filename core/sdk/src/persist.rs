@@ -0,0 +1,86 @@
+//! Persist or delete records along with the events describing the change.
+use anyhow::Result;
+
+use replicore_context::Context;
+use replicore_events_models::EventEntry;
+use replicore_store::delete::DeleteWithEvents;
+use replicore_store::persist::PersistWithEvents;
+
+use super::CoreSDK;
+
+impl CoreSDK {
+    /// Delete a record and emit the events describing the change.
+    ///
+    /// Events are handled as described for [`CoreSDK::persist_with_events`].
+    pub async fn delete_with_events(
+        &self,
+        context: &Context,
+        mut op: DeleteWithEvents,
+    ) -> Result<()> {
+        self.prepare_events(&mut op.events)?;
+        if !self.injector.conf.outbox.enabled {
+            self.publish_events(context, &op.events).await?;
+            self.injector.store.delete(context, op.op).await?;
+            self.notify_events(context, &op.events).await;
+            return Ok(());
+        }
+        self.injector.store.delete(context, op).await
+    }
+
+    /// Persist a record and emit the events describing the change.
+    ///
+    /// When the events outbox is enabled, events are added to the outbox in the same store
+    /// transaction as the record and the outbox relay publishes them once committed.
+    /// Otherwise events are emitted before the record is persisted.
+    ///
+    /// Either way in-process subscribers are only notified once the record is persisted.
+    pub async fn persist_with_events(
+        &self,
+        context: &Context,
+        mut op: PersistWithEvents,
+    ) -> Result<()> {
+        self.prepare_events(&mut op.events)?;
+        if !self.injector.conf.outbox.enabled {
+            self.publish_events(context, &op.events).await?;
+            self.injector.store.persist(context, op.op).await?;
+            self.notify_events(context, &op.events).await;
+            return Ok(());
+        }
+        self.injector.store.persist(context, op).await
+    }
+
+    /// Notify in-process subscribers of events once the change they describe is committed.
+    async fn notify_events(&self, context: &Context, events: &[EventEntry]) {
+        for entry in events {
+            self.injector.events.notify(context, entry).await;
+        }
+    }
+
+    /// Attach the trace context to events and check them before the change is made.
+    fn prepare_events(&self, events: &mut [EventEntry]) -> Result<()> {
+        // Attach the trace context now, as events are published or handled later.
+        for entry in events.iter_mut() {
+            replicore_events::trace::attach_trace_context(&mut entry.event);
+        }
+
+        // Catch invalid events before they are committed, since they would block the outbox.
+        if cfg!(debug_assertions) && self.injector.conf.outbox.enabled {
+            let catalog = self.injector.events.catalog();
+            for entry in events.iter() {
+                catalog.validate(entry.stream, &entry.event)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit events, without notifying subscribers, before the change they describe is made.
+    async fn publish_events(&self, context: &Context, events: &[EventEntry]) -> Result<()> {
+        for entry in events {
+            self.injector
+                .events
+                .publish_without_notify(context, entry.clone())
+                .await?;
+        }
+        Ok(())
+    }
+}
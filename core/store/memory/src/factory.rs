@@ -0,0 +1,41 @@
+//! Initialise the in-memory Persistent Store.
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_store::MemoryStore;
+use replicore_store::Store;
+use replicore_store::StoreFactory;
+use replicore_store::StoreFactoryArgs;
+use replicore_store::StoreFactorySyncArgs;
+
+/// Initialise the in-memory Persistent Store.
+///
+/// All [`Store`]s created by the same factory share the same records.
+#[derive(Clone, Default)]
+pub struct MemoryFactory {
+    store: MemoryStore,
+}
+
+#[async_trait::async_trait]
+impl StoreFactory for MemoryFactory {
+    fn conf_check(&self, _: &Context, _: &Json) -> Result<()> {
+        Ok(())
+    }
+
+    fn register_metrics(&self, _: &prometheus::Registry) -> Result<()> {
+        Ok(())
+    }
+
+    async fn store<'a>(&self, args: StoreFactoryArgs<'a>) -> Result<Store> {
+        slog::warn!(
+            args.context.logger,
+            "Using in-memory store means data will be lost once the process terminates"
+        );
+        Ok(Store::from(self.store.clone()))
+    }
+
+    async fn sync<'a>(&self, _: StoreFactorySyncArgs<'a>) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+//! Persistent store kept in the memory of the RepliCore process.
+//!
+//! This backend is intended for demos, tests and embedding the Control Plane:
+//!
+//! - All data is lost as soon as the process terminates.
+//! - Data is NOT shared across processes so only single process deployments are supported.
+//! - Records are returned in the same order as the SQLite store.
+//!
+//! The store itself is implemented by [`replicore_store::MemoryStore`],
+//! which also backs the unit tests fixture.
+mod factory;
+
+pub use self::factory::MemoryFactory;
+pub use replicore_store::MemoryStore;
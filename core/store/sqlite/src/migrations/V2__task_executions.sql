@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS store_task_execution(
+  -- Increasing ID of the record to order executions from the most recent.
+  record_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+  -- Task Execution object as a JSON blob.
+  execution TEXT NOT NULL,
+
+  -- Manually managed normalised columns for indexes (where virtual columns can't be used).
+  queue_id TEXT NOT NULL,
+  task_id TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS store_task_execution_queue ON store_task_execution(queue_id, record_id);
+CREATE INDEX IF NOT EXISTS store_task_execution_task ON store_task_execution(task_id, record_id);
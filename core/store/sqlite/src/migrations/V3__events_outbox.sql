@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS store_events_outbox(
+  -- Increasing sequence number of the event, assigned in the order events are added.
+  seq INTEGER PRIMARY KEY AUTOINCREMENT,
+
+  -- Event and the stream to publish it onto as a JSON blob.
+  entry TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Key grouping executions of the same task, or recurring tasks, for history retention.
+--  Tasks with a de-duplication key are grouped by it, other tasks by their ID.
+ALTER TABLE store_task_execution ADD COLUMN history_key TEXT DEFAULT NULL;
+UPDATE store_task_execution SET history_key = task_id WHERE history_key IS NULL;
+CREATE INDEX IF NOT EXISTS store_task_execution_history
+  ON store_task_execution(queue_id, history_key, record_id);
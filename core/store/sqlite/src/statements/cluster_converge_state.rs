@@ -11,6 +11,8 @@ use replicore_context::Context;
 use replicore_store::delete::DeleteClusterConvergeState;
 use replicore_store::ids::NamespacedResourceID;
 
+use super::Statement;
+
 const DELETE_SQL: &str = r#"
 DELETE FROM store_cluster_converge_state
 WHERE
@@ -40,14 +42,12 @@ pub async fn delete(
     connection: &Connection,
     cluster: DeleteClusterConvergeState,
 ) -> Result<()> {
+    let statement = delete_statement(cluster);
     let (err_count, _timer) = crate::telemetry::observe_op("clusterConvergeState.delete");
     let trace = crate::telemetry::trace_op("clusterConvergeState.delete");
     connection
         .call(move |connection| {
-            connection.execute(
-                DELETE_SQL,
-                rusqlite::params![cluster.0.ns_id, cluster.0.name],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -57,6 +57,18 @@ pub async fn delete(
     Ok(())
 }
 
+/// Prepare the statement to delete records, so it can also run as part of a transaction.
+pub fn delete_statement(cluster: DeleteClusterConvergeState) -> Statement {
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            DELETE_SQL,
+            rusqlite::params![cluster.0.ns_id, cluster.0.name],
+        )?;
+        Ok(())
+    };
+    Box::new(statement)
+}
+
 /// Lookup a cluster convergence state from the store, if one is available.
 pub async fn lookup(
     _: &Context,
@@ -95,15 +107,12 @@ pub async fn lookup(
 
 /// Persist a new or updated [`ConvergeState`]` into the store.
 pub async fn persist(_: &Context, connection: &Connection, cluster: ConvergeState) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&cluster)?;
+    let statement = persist_statement(cluster)?;
     let (err_count, _timer) = crate::telemetry::observe_op("clusterConvergeState.persist");
     let trace = crate::telemetry::trace_op("clusterConvergeState.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![cluster.ns_id, cluster.cluster_id, record],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -112,3 +121,16 @@ pub async fn persist(_: &Context, connection: &Connection, cluster: ConvergeStat
         .await?;
     Ok(())
 }
+
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(cluster: ConvergeState) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&cluster)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![cluster.ns_id, cluster.cluster_id, record],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
@@ -10,6 +10,8 @@ use replisdk::utils::trace::TraceFutureStdErrExt;
 use replicore_context::Context;
 use replicore_store::ids::NamespacedResourceID;
 
+use super::Statement;
+
 const LOOKUP_SQL: &str = r#"
 SELECT cluster_disc
 FROM store_cluster_disc
@@ -68,15 +70,12 @@ pub async fn persist(
     connection: &Connection,
     cluster: ClusterDiscovery,
 ) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&cluster)?;
+    let statement = persist_statement(cluster)?;
     let (err_count, _timer) = crate::telemetry::observe_op("clusterDiscovery.persist");
     let trace = crate::telemetry::trace_op("clusterDiscovery.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![cluster.ns_id, cluster.cluster_id, record],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -85,3 +84,16 @@ pub async fn persist(
         .await?;
     Ok(())
 }
+
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(cluster: ClusterDiscovery) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&cluster)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![cluster.ns_id, cluster.cluster_id, record],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
@@ -14,6 +14,8 @@ use replicore_store::ids::NamespaceID;
 use replicore_store::ids::NamespacedResourceID;
 use replicore_store::query::ClusterSpecEntryStream;
 
+use super::Statement;
+
 const DELETE_SQL: &str = r#"
 DELETE FROM store_cluster_spec
 WHERE
@@ -50,14 +52,12 @@ pub async fn delete(
     connection: &Connection,
     cluster: DeleteClusterSpec,
 ) -> Result<()> {
+    let statement = delete_statement(cluster);
     let (err_count, _timer) = crate::telemetry::observe_op("clusterSpec.delete");
     let trace = crate::telemetry::trace_op("clusterSpec.delete");
     connection
         .call(move |connection| {
-            connection.execute(
-                DELETE_SQL,
-                rusqlite::params![cluster.0.ns_id, cluster.0.name],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -67,6 +67,18 @@ pub async fn delete(
     Ok(())
 }
 
+/// Prepare the statement to delete records, so it can also run as part of a transaction.
+pub fn delete_statement(cluster: DeleteClusterSpec) -> Statement {
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            DELETE_SQL,
+            rusqlite::params![cluster.0.ns_id, cluster.0.name],
+        )?;
+        Ok(())
+    };
+    Box::new(statement)
+}
+
 /// Return a list of known [`ClusterSpec`] IDs in the given namespace.
 pub async fn list(
     _: &Context,
@@ -139,15 +151,12 @@ pub async fn lookup(
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, cluster: ClusterSpec) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&cluster)?;
+    let statement = persist_statement(cluster)?;
     let (err_count, _timer) = crate::telemetry::observe_op("clusterSpec.persist");
     let trace = crate::telemetry::trace_op("clusterSpec.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![cluster.ns_id, cluster.cluster_id, record],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -157,6 +166,19 @@ pub async fn persist(_: &Context, connection: &Connection, cluster: ClusterSpec)
     Ok(())
 }
 
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(cluster: ClusterSpec) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&cluster)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![cluster.ns_id, cluster.cluster_id, record],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
+
 #[cfg(test)]
 mod tests {
     use futures::TryStreamExt;
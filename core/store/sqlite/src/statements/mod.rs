@@ -19,9 +19,15 @@ mod naction;
 mod namespace;
 mod oaction;
 mod orchestrate_report;
+mod outbox;
 mod platform;
 mod shards;
 mod store_extras;
+mod task_execution;
+
+/// SQL statement prepared for execution on a connection, either directly or in a transaction.
+pub type Statement =
+    Box<dyn FnOnce(&rusqlite::Connection) -> rusqlite::Result<()> + Send + 'static>;
 
 /// Implementation of the [`StoreBackend`] interface using SQLite.
 pub struct SQLiteStore {
@@ -59,9 +65,17 @@ impl StoreBackend for SQLiteStore {
                 self::cluster_node::delete(context, &self.connection, node).await?;
                 Ok(DeleteResponses::Success)
             }
+            DeleteOps::OutboxEvent(event) => self::outbox::delete(context, &self.connection, event)
+                .await
+                .map(|_| DeleteResponses::Success),
             DeleteOps::Platform(pl) => self::platform::delete(context, &self.connection, pl)
                 .await
                 .map(|_| DeleteResponses::Success),
+            DeleteOps::WithEvents(op) => {
+                self::outbox::delete_with_events(context, &self.connection, *op)
+                    .await
+                    .map(|_| DeleteResponses::Success)
+            }
         }
     }
 
@@ -101,6 +115,10 @@ impl StoreBackend for SQLiteStore {
                 let list = self::oaction::list(context, &self.connection, query).await?;
                 Ok(QueryResponses::OActionEntries(list))
             }
+            QueryOps::ListOutboxEvents(query) => {
+                let list = self::outbox::list(context, &self.connection, query).await?;
+                Ok(QueryResponses::OutboxEvents(list))
+            }
             QueryOps::ListPlatforms(ns) => {
                 let list = self::platform::list(context, &self.connection, ns).await?;
                 Ok(QueryResponses::PlatformEntries(list))
@@ -113,6 +131,10 @@ impl StoreBackend for SQLiteStore {
                 let list = self::store_extras::list(context, &self.connection, query).await?;
                 Ok(QueryResponses::StoreExtrasList(list))
             }
+            QueryOps::ListTaskExecutions(query) => {
+                let list = self::task_execution::list(context, &self.connection, query).await?;
+                Ok(QueryResponses::TaskExecutions(list))
+            }
             QueryOps::NAction(query) => {
                 let action = self::naction::lookup(context, &self.connection, query).await?;
                 Ok(QueryResponses::NAction(action))
@@ -198,6 +220,14 @@ impl StoreBackend for SQLiteStore {
                     .await
                     .map(|_| PersistResponses::Success)
             }
+            PersistOps::TaskExecution(record) => {
+                self::task_execution::persist(context, &self.connection, record)
+                    .await
+                    .map(|_| PersistResponses::Success)
+            }
+            PersistOps::WithEvents(op) => self::outbox::persist(context, &self.connection, *op)
+                .await
+                .map(|_| PersistResponses::Success),
         }
     }
 }
@@ -16,6 +16,8 @@ use replicore_store::query::LookupNAction;
 use replicore_store::query::NActionEntryStream;
 use replicore_store::query::NActionStream;
 
+use super::Statement;
+
 const CANCEL_FOR_NODE_SQL: &str = r#"
 UPDATE store_naction
 SET
@@ -81,24 +83,12 @@ pub async fn cancel_for_node(
     connection: &Connection,
     node: replicore_store::ids::NodeID,
 ) -> Result<()> {
-    let finished_time = time::OffsetDateTime::now_utc();
-    let finished_num = replisdk::utils::encoding::encode_time_f64(finished_time)?;
-    let finished_str = replisdk::utils::encoding::encode_time(finished_time)?;
-
+    let statement = cancel_for_node_statement(node)?;
     let (err_count, _timer) = crate::telemetry::observe_op("naction.cancelForNode");
     let trace = crate::telemetry::trace_op("naction.cancelForNode");
     connection
         .call(move |connection| {
-            connection.execute(
-                CANCEL_FOR_NODE_SQL,
-                rusqlite::params![
-                    finished_str,
-                    finished_num,
-                    node.ns_id,
-                    node.cluster_id,
-                    node.node_id,
-                ],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -108,6 +98,27 @@ pub async fn cancel_for_node(
     Ok(())
 }
 
+/// Prepare the statement to cancel actions for a node, so it can also run in a transaction.
+pub fn cancel_for_node_statement(node: replicore_store::ids::NodeID) -> Result<Statement> {
+    let finished_time = time::OffsetDateTime::now_utc();
+    let finished_num = replisdk::utils::encoding::encode_time_f64(finished_time)?;
+    let finished_str = replisdk::utils::encoding::encode_time(finished_time)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            CANCEL_FOR_NODE_SQL,
+            rusqlite::params![
+                finished_str,
+                finished_num,
+                node.ns_id,
+                node.cluster_id,
+                node.node_id,
+            ],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
+
 /// Return a list of known [`NActionEntry`]s in the given cluster.
 pub async fn list(
     _: &Context,
@@ -199,28 +210,12 @@ pub async fn lookup(
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, action: NAction) -> Result<()> {
-    // Serialise special types into stings for the DB.
-    let created_time = encoding::encode_time(action.created_time)?;
-    let finished_time = encoding::encode_time_option_f64(action.finished_time)?;
-
-    // Execute the statement.
-    let record = replisdk::utils::encoding::encode_serde(&action)?;
+    let statement = persist_statement(action)?;
     let (err_count, _timer) = crate::telemetry::observe_op("naction.persist");
     let trace = crate::telemetry::trace_op("naction.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![
-                    action.ns_id,
-                    action.cluster_id,
-                    action.node_id,
-                    action.action_id.to_string(),
-                    created_time,
-                    finished_time,
-                    record,
-                ],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -230,6 +225,31 @@ pub async fn persist(_: &Context, connection: &Connection, action: NAction) -> R
     Ok(())
 }
 
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(action: NAction) -> Result<Statement> {
+    // Serialise special types into stings for the DB.
+    let created_time = encoding::encode_time(action.created_time)?;
+    let finished_time = encoding::encode_time_option_f64(action.finished_time)?;
+
+    let record = replisdk::utils::encoding::encode_serde(&action)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![
+                action.ns_id,
+                action.cluster_id,
+                action.node_id,
+                action.action_id.to_string(),
+                created_time,
+                finished_time,
+                record,
+            ],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
+
 /// Iterate over unfinished node actions.
 pub async fn unfinished(
     _: &Context,
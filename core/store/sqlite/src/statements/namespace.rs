@@ -15,6 +15,8 @@ use replicore_store::delete::DeleteNamespace;
 use replicore_store::query::LookupNamespace;
 use replicore_store::query::NamespaceEntryStream;
 
+use super::Statement;
+
 const DELETE_SQL: &str = r#"
 DELETE FROM store_namespace
 WHERE id = ?1;
@@ -42,11 +44,12 @@ DO UPDATE SET
 
 /// Delete a namespace from the store, ignoring missing namespaces.
 pub async fn delete(_: &Context, connection: &Connection, ns: DeleteNamespace) -> Result<()> {
+    let statement = delete_statement(ns);
     let (err_count, _timer) = crate::telemetry::observe_op("namespace.delete");
     let trace = crate::telemetry::trace_op("namespace.delete");
     connection
         .call(move |connection| {
-            connection.execute(DELETE_SQL, rusqlite::params![ns.0.id])?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -56,6 +59,15 @@ pub async fn delete(_: &Context, connection: &Connection, ns: DeleteNamespace) -
     Ok(())
 }
 
+/// Prepare the statement to delete records, so it can also run as part of a transaction.
+pub fn delete_statement(ns: DeleteNamespace) -> Statement {
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(DELETE_SQL, rusqlite::params![ns.0.id])?;
+        Ok(())
+    };
+    Box::new(statement)
+}
+
 /// Return a list of known [`Namespace`] IDs.
 pub async fn list(_: &Context, connection: &Connection) -> Result<NamespaceEntryStream> {
     let (err_count, timer) = crate::telemetry::observe_op("namespace.listIds");
@@ -127,12 +139,12 @@ pub async fn lookup(
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, ns: Namespace) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&ns)?;
+    let statement = persist_statement(ns)?;
     let (err_count, _timer) = crate::telemetry::observe_op("namespace.persist");
     let trace = crate::telemetry::trace_op("namespace.persist");
     connection
         .call(move |connection| {
-            connection.execute(PERSIST_SQL, rusqlite::params![ns.id, record])?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -142,6 +154,16 @@ pub async fn persist(_: &Context, connection: &Connection, ns: Namespace) -> Res
     Ok(())
 }
 
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(ns: Namespace) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&ns)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(PERSIST_SQL, rusqlite::params![ns.id, record])?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
+
 #[cfg(test)]
 mod tests {
     use futures::TryStreamExt;
@@ -16,6 +16,8 @@ use replicore_store::query::LookupOAction;
 use replicore_store::query::OActionEntryStream;
 use replicore_store::query::OActionStream;
 
+use super::Statement;
+
 const LIST_ALL_SQL: &str = r#"
 SELECT oaction
 FROM store_oaction
@@ -139,27 +141,12 @@ pub async fn lookup(
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, oaction: OAction) -> Result<()> {
-    // Serialise special types into stings for the DB.
-    let created_ts = encoding::encode_time(oaction.created_ts)?;
-    let finished_ts = encoding::encode_time_option_f64(oaction.finished_ts)?;
-
-    // Execute the statement.
-    let record = replisdk::utils::encoding::encode_serde(&oaction)?;
+    let statement = persist_statement(oaction)?;
     let (err_count, _timer) = crate::telemetry::observe_op("oaction.persist");
     let trace = crate::telemetry::trace_op("oaction.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![
-                    oaction.ns_id,
-                    oaction.cluster_id,
-                    oaction.action_id.to_string(),
-                    created_ts,
-                    finished_ts,
-                    record,
-                ],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -169,6 +156,30 @@ pub async fn persist(_: &Context, connection: &Connection, oaction: OAction) ->
     Ok(())
 }
 
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(oaction: OAction) -> Result<Statement> {
+    // Serialise special types into stings for the DB.
+    let created_ts = encoding::encode_time(oaction.created_ts)?;
+    let finished_ts = encoding::encode_time_option_f64(oaction.finished_ts)?;
+
+    let record = replisdk::utils::encoding::encode_serde(&oaction)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![
+                oaction.ns_id,
+                oaction.cluster_id,
+                oaction.action_id.to_string(),
+                created_ts,
+                finished_ts,
+                record,
+            ],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
+
 /// Iterate over unfinished orchestrator actions.
 pub async fn unfinished(
     _: &Context,
@@ -10,6 +10,8 @@ use replicore_cluster_models::OrchestrateReport;
 use replicore_context::Context;
 use replicore_store::ids::NamespacedResourceID;
 
+use super::Statement;
+
 const LOOKUP_SQL: &str = r#"
 SELECT report
 FROM store_orchestrate_report
@@ -68,15 +70,12 @@ pub async fn persist(
     connection: &Connection,
     report: OrchestrateReport,
 ) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&report)?;
+    let statement = persist_statement(report)?;
     let (err_count, _timer) = crate::telemetry::observe_op("orchestrateReport.persist");
     let trace = crate::telemetry::trace_op("orchestrateReport.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![report.ns_id, report.cluster_id, record],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -85,3 +84,16 @@ pub async fn persist(
         .await?;
     Ok(())
 }
+
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(report: OrchestrateReport) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&report)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![report.ns_id, report.cluster_id, record],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
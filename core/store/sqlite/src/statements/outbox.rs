@@ -0,0 +1,325 @@
+//! Persistent store operations on the events outbox.
+use anyhow::Result;
+use futures::StreamExt;
+use opentelemetry_api::trace::FutureExt;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_events_models::EventEntry;
+use replicore_store::delete::DeleteOps;
+use replicore_store::delete::DeleteOutboxEvent;
+use replicore_store::delete::DeleteWithEvents;
+use replicore_store::persist::PersistOps;
+use replicore_store::persist::PersistWithEvents;
+use replicore_store::query::ListOutboxEvents;
+use replicore_store::query::OutboxEvent;
+use replicore_store::query::OutboxEventStream;
+
+use super::Statement;
+
+const DELETE_SQL: &str = r#"
+DELETE FROM store_events_outbox
+WHERE seq = ?1;
+"#;
+
+const LIST_SQL: &str = r#"
+SELECT seq, entry
+FROM store_events_outbox
+ORDER BY seq ASC
+LIMIT ?1;
+"#;
+
+const PERSIST_SQL: &str = r#"
+INSERT INTO store_events_outbox (entry)
+VALUES (?1)
+;"#;
+
+/// Delete a published event from the outbox, ignoring missing events.
+pub async fn delete(_: &Context, connection: &Connection, event: DeleteOutboxEvent) -> Result<()> {
+    let seq = i64::try_from(event.0).unwrap_or(i64::MAX);
+    let (err_count, _timer) = crate::telemetry::observe_op("outbox.delete");
+    let trace = crate::telemetry::trace_op("outbox.delete");
+    connection
+        .call(move |connection| {
+            connection.execute(DELETE_SQL, rusqlite::params![seq])?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+/// Delete a record and add its events to the outbox in a single transaction.
+pub async fn delete_with_events(
+    _: &Context,
+    connection: &Connection,
+    op: DeleteWithEvents,
+) -> Result<()> {
+    let statement = delete_statement(op.op)?;
+    let transaction = with_events(statement, op.events)?;
+    let (err_count, _timer) = crate::telemetry::observe_op("outbox.deleteWithEvents");
+    let trace = crate::telemetry::trace_op("outbox.deleteWithEvents");
+    connection
+        .call(move |connection| {
+            transaction(connection)?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+/// Return the oldest events in the outbox, in the order they were added.
+pub async fn list(
+    _: &Context,
+    connection: &Connection,
+    query: ListOutboxEvents,
+) -> Result<OutboxEventStream> {
+    let limit = i64::try_from(query.limit).unwrap_or(i64::MAX);
+    let (err_count, _timer) = crate::telemetry::observe_op("outbox.list");
+    let trace = crate::telemetry::trace_op("outbox.list");
+    let events = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(LIST_SQL)?;
+            let mut rows = statement.query(rusqlite::params![limit])?;
+
+            let mut events = Vec::new();
+            while let Some(row) = rows.next()? {
+                let seq: i64 = row.get("seq")?;
+                let entry: String = row.get("entry")?;
+                events.push((seq, entry));
+            }
+            Ok(events)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+
+    let events = futures::stream::iter(events)
+        .map(|(seq, entry)| {
+            let entry = replisdk::utils::encoding::decode_serde(&entry)?;
+            let seq = u64::try_from(seq)?;
+            Ok(OutboxEvent { entry, seq })
+        })
+        .boxed();
+    Ok(events)
+}
+
+/// Persist a record and add its events to the outbox in a single transaction.
+pub async fn persist(_: &Context, connection: &Connection, op: PersistWithEvents) -> Result<()> {
+    let statement = persist_statement(op.op)?;
+    let transaction = with_events(statement, op.events)?;
+    let (err_count, _timer) = crate::telemetry::observe_op("outbox.persist");
+    let trace = crate::telemetry::trace_op("outbox.persist");
+    connection
+        .call(move |connection| {
+            transaction(connection)?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+/// Prepare the statement for a record delete operation that supports outbox events.
+fn delete_statement(op: DeleteOps) -> Result<Statement> {
+    let statement = match op {
+        DeleteOps::ClusterConvergeState(cluster) => {
+            super::cluster_converge_state::delete_statement(cluster)
+        }
+        DeleteOps::ClusterSpec(cluster) => super::cluster_spec::delete_statement(cluster),
+        DeleteOps::Namespace(ns) => super::namespace::delete_statement(ns),
+        DeleteOps::Node(node) => {
+            let shards = super::shards::delete_on_node_statement(node.clone());
+            let extras = super::store_extras::delete_statement(node.clone());
+            let node = super::cluster_node::delete_statement(node);
+            let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+                shards(connection)?;
+                extras(connection)?;
+                node(connection)
+            };
+            Box::new(statement)
+        }
+        DeleteOps::Platform(pl) => super::platform::delete_statement(pl),
+        DeleteOps::OutboxEvent(_) | DeleteOps::WithEvents(_) => {
+            anyhow::bail!(UnsupportedOutboxOp)
+        }
+    };
+    Ok(statement)
+}
+
+/// Prepare the statement for a record persist operation that supports outbox events.
+fn persist_statement(op: PersistOps) -> Result<Statement> {
+    match op {
+        PersistOps::ClusterConvergeState(state) => {
+            super::cluster_converge_state::persist_statement(state)
+        }
+        PersistOps::ClusterDiscovery(disc) => super::cluster_discovery::persist_statement(disc),
+        PersistOps::ClusterSpec(spec) => super::cluster_spec::persist_statement(spec),
+        PersistOps::NAction(action) => super::naction::persist_statement(action),
+        PersistOps::Namespace(ns) => super::namespace::persist_statement(ns),
+        PersistOps::Node(node) => super::cluster_node::persist_statement(node),
+        PersistOps::NodeCancelAllActions(node) => super::naction::cancel_for_node_statement(node),
+        PersistOps::OAction(oaction) => super::oaction::persist_statement(oaction),
+        PersistOps::OrchestrateReport(report) => {
+            super::orchestrate_report::persist_statement(report)
+        }
+        PersistOps::Platform(pl) => super::platform::persist_statement(pl),
+        PersistOps::Shard(shard) => super::shards::persist_statement(shard),
+        PersistOps::StoreExtras(extras) => super::store_extras::persist_statement(extras),
+        PersistOps::TaskExecution(_) | PersistOps::WithEvents(_) => {
+            anyhow::bail!(UnsupportedOutboxOp)
+        }
+    }
+}
+
+/// Wrap a record statement to run in a transaction that also adds events to the outbox.
+fn with_events(
+    statement: Statement,
+    events: Vec<EventEntry>,
+) -> Result<impl FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<()> + Send + 'static> {
+    let mut entries = Vec::with_capacity(events.len());
+    for entry in events {
+        let entry = replisdk::utils::encoding::encode_serde(&entry)?;
+        entries.push(entry);
+    }
+    let transaction = move |connection: &mut rusqlite::Connection| -> rusqlite::Result<()> {
+        let transaction = connection.transaction()?;
+        statement(&transaction)?;
+        for entry in entries {
+            transaction.execute(PERSIST_SQL, rusqlite::params![entry])?;
+        }
+        transaction.commit()
+    };
+    Ok(transaction)
+}
+
+/// The store operation can't add events to the outbox.
+#[derive(Debug, thiserror::Error)]
+#[error("the store operation can't add events to the outbox")]
+pub struct UnsupportedOutboxOp;
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use replisdk::core::models::namespace::Namespace;
+    use replisdk::core::models::namespace::NamespaceStatus;
+
+    use replicore_events_models::Event;
+    use replicore_events_models::EventStream;
+    use replicore_store::delete::DeleteOutboxEvent;
+    use replicore_store::delete::DeleteWithEvents;
+    use replicore_store::ids::NodeID;
+    use replicore_store::persist::NodeCancelAllActions;
+    use replicore_store::persist::PersistWithEvents;
+    use replicore_store::query::ListOutboxEvents;
+    use replicore_store::query::LookupNamespace;
+    use replicore_store::query::OutboxEvent;
+    use replicore_store::Store;
+
+    fn mock_namespace() -> Namespace {
+        Namespace {
+            id: "test".into(),
+            tls: Default::default(),
+            settings: Default::default(),
+            status: NamespaceStatus::Active,
+        }
+    }
+
+    async fn pending(store: &Store) -> Vec<OutboxEvent> {
+        let context = replicore_context::Context::fixture();
+        store
+            .query(&context, ListOutboxEvents::oldest(10))
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn persist_with_events() {
+        let context = replicore_context::Context::fixture();
+        let store = crate::statements::tests::store().await;
+        let ns = mock_namespace();
+        let event = Event::new_with_payload("NAMESPACE_TEST", &ns).unwrap();
+        let op = PersistWithEvents::new(ns)
+            .audit(event.clone())
+            .change(event.clone());
+        store.persist(&context, op).await.unwrap();
+
+        let ns = store
+            .query(&context, LookupNamespace::from("test"))
+            .await
+            .unwrap();
+        assert!(ns.is_some());
+        let events = pending(&store).await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].entry.event, event);
+        assert_eq!(events[0].entry.stream, EventStream::Audit);
+        assert_eq!(events[1].entry.stream, EventStream::Change);
+        assert!(events[0].seq < events[1].seq);
+
+        store.delete(&context, &events[0]).await.unwrap();
+        let remaining = pending(&store).await;
+        assert_eq!(remaining, events[1..]);
+    }
+
+    #[tokio::test]
+    async fn delete_with_events() {
+        let context = replicore_context::Context::fixture();
+        let store = crate::statements::tests::store().await;
+        let ns = mock_namespace();
+        store.persist(&context, ns.clone()).await.unwrap();
+
+        let event = Event::new_with_payload("NAMESPACE_TEST", &ns).unwrap();
+        let op = DeleteWithEvents::new(&ns).change(event.clone());
+        store.delete(&context, op).await.unwrap();
+
+        let ns = store
+            .query(&context, LookupNamespace::from("test"))
+            .await
+            .unwrap();
+        assert!(ns.is_none());
+        let events = pending(&store).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].entry.event, event);
+    }
+
+    #[tokio::test]
+    async fn node_cancel_all_actions_with_events() {
+        let context = replicore_context::Context::fixture();
+        let store = crate::statements::tests::store().await;
+        let node = NodeID {
+            ns_id: "ns".into(),
+            cluster_id: "cluster".into(),
+            node_id: "node".into(),
+        };
+        let event = Event::new_with_payload("NODE_TEST", "node").unwrap();
+        let op = PersistWithEvents::new(NodeCancelAllActions(node)).change(event);
+        store.persist(&context, op).await.unwrap();
+        assert_eq!(pending(&store).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unsupported_op_adds_no_events() {
+        let context = replicore_context::Context::fixture();
+        let store = crate::statements::tests::store().await;
+        let event = Event::new_with_payload("OUTBOX_TEST", 1).unwrap();
+        let op = DeleteWithEvents::new(DeleteOutboxEvent(1)).change(event);
+        let error = store.delete(&context, op).await.unwrap_err();
+        assert!(error.is::<super::UnsupportedOutboxOp>());
+        assert!(pending(&store).await.is_empty());
+    }
+}
@@ -15,6 +15,8 @@ use replicore_store::ids::NamespaceID;
 use replicore_store::ids::NamespacedResourceID;
 use replicore_store::query::PlatformEntryStream;
 
+use super::Statement;
+
 const DELETE_SQL: &str = r#"
 DELETE FROM store_platform
 WHERE
@@ -47,14 +49,12 @@ DO UPDATE SET
 
 /// Delete a platform from the store, ignoring missing platforms.
 pub async fn delete(_: &Context, connection: &Connection, platform: DeletePlatform) -> Result<()> {
+    let statement = delete_statement(platform);
     let (err_count, _timer) = crate::telemetry::observe_op("platform.delete");
     let trace = crate::telemetry::trace_op("platform.delete");
     connection
         .call(move |connection| {
-            connection.execute(
-                DELETE_SQL,
-                rusqlite::params![platform.0.ns_id, platform.0.name],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -64,6 +64,18 @@ pub async fn delete(_: &Context, connection: &Connection, platform: DeletePlatfo
     Ok(())
 }
 
+/// Prepare the statement to delete records, so it can also run as part of a transaction.
+pub fn delete_statement(platform: DeletePlatform) -> Statement {
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            DELETE_SQL,
+            rusqlite::params![platform.0.ns_id, platform.0.name],
+        )?;
+        Ok(())
+    };
+    Box::new(statement)
+}
+
 /// Return a list of known [`Platform`] IDs in the given namespace.
 pub async fn list(
     _: &Context,
@@ -133,15 +145,12 @@ pub async fn lookup(
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, platform: Platform) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&platform)?;
+    let statement = persist_statement(platform)?;
     let (err_count, _timer) = crate::telemetry::observe_op("platform.persist");
     let trace = crate::telemetry::trace_op("platform.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![platform.ns_id, platform.name, record],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -151,6 +160,19 @@ pub async fn persist(_: &Context, connection: &Connection, platform: Platform) -
     Ok(())
 }
 
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(platform: Platform) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&platform)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![platform.ns_id, platform.name, record],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
+
 #[cfg(test)]
 mod tests {
     use futures::TryStreamExt;
@@ -13,6 +13,8 @@ use replicore_store::ids::NodeID;
 use replicore_store::query::ListShards;
 use replicore_store::query::ShardsStream;
 
+use super::Statement;
+
 const DELETE_SQL: &str = r#"
 DELETE FROM store_cluster_shard
 WHERE
@@ -50,14 +52,12 @@ DO UPDATE SET
 
 /// Delete all shards located on a node.
 pub async fn delete_on_node(_: &Context, connection: &Connection, node: NodeID) -> Result<()> {
+    let statement = delete_on_node_statement(node);
     let (err_count, _timer) = crate::telemetry::observe_op("shard.delete");
     let trace = crate::telemetry::trace_op("shard.delete");
     connection
         .call(move |connection| {
-            connection.execute(
-                DELETE_SQL,
-                rusqlite::params![node.ns_id, node.cluster_id, node.node_id],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -67,6 +67,18 @@ pub async fn delete_on_node(_: &Context, connection: &Connection, node: NodeID)
     Ok(())
 }
 
+/// Prepare the statement to delete records, so it can also run as part of a transaction.
+pub fn delete_on_node_statement(node: NodeID) -> Statement {
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            DELETE_SQL,
+            rusqlite::params![node.ns_id, node.cluster_id, node.node_id],
+        )?;
+        Ok(())
+    };
+    Box::new(statement)
+}
+
 /// Return a list of known [`Shard`]s in the given cluster.
 pub async fn list(_: &Context, connection: &Connection, query: ListShards) -> Result<ShardsStream> {
     let (err_count, _timer) = crate::telemetry::observe_op("shard.list");
@@ -106,21 +118,12 @@ pub async fn list(_: &Context, connection: &Connection, query: ListShards) -> Re
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, shard: Shard) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&shard)?;
+    let statement = persist_statement(shard)?;
     let (err_count, _timer) = crate::telemetry::observe_op("shard.persist");
     let trace = crate::telemetry::trace_op("shard.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![
-                    shard.ns_id,
-                    shard.cluster_id,
-                    shard.node_id,
-                    shard.shard_id,
-                    record,
-                ],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -129,3 +132,22 @@ pub async fn persist(_: &Context, connection: &Connection, shard: Shard) -> Resu
         .await?;
     Ok(())
 }
+
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(shard: Shard) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&shard)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![
+                shard.ns_id,
+                shard.cluster_id,
+                shard.node_id,
+                shard.shard_id,
+                record,
+            ],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
@@ -13,6 +13,8 @@ use replicore_store::ids::NamespacedResourceID;
 use replicore_store::ids::NodeID;
 use replicore_store::query::StoreExtrasStream;
 
+use super::Statement;
+
 const DELETE_SQL: &str = r#"
 DELETE FROM store_cluster_store_extras
 WHERE
@@ -40,14 +42,12 @@ DO UPDATE SET
 
 /// Delete the [`StoreExtras`] record for a node.
 pub async fn delete(_: &Context, connection: &Connection, node: NodeID) -> Result<()> {
+    let statement = delete_statement(node);
     let (err_count, _timer) = crate::telemetry::observe_op("storeExtra.delete");
     let trace = crate::telemetry::trace_op("storeExtra.delete");
     connection
         .call(move |connection| {
-            connection.execute(
-                DELETE_SQL,
-                rusqlite::params![node.ns_id, node.cluster_id, node.node_id],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -57,6 +57,18 @@ pub async fn delete(_: &Context, connection: &Connection, node: NodeID) -> Resul
     Ok(())
 }
 
+/// Prepare the statement to delete records, so it can also run as part of a transaction.
+pub fn delete_statement(node: NodeID) -> Statement {
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            DELETE_SQL,
+            rusqlite::params![node.ns_id, node.cluster_id, node.node_id],
+        )?;
+        Ok(())
+    };
+    Box::new(statement)
+}
+
 /// Return a list of known [`StoreExtras`]s in the given cluster.
 pub async fn list(
     _: &Context,
@@ -93,15 +105,12 @@ pub async fn list(
 
 /// Persist a new or updated record into the store.
 pub async fn persist(_: &Context, connection: &Connection, extras: StoreExtras) -> Result<()> {
-    let record = replisdk::utils::encoding::encode_serde(&extras)?;
+    let statement = persist_statement(extras)?;
     let (err_count, _timer) = crate::telemetry::observe_op("storeExtras.persist");
     let trace = crate::telemetry::trace_op("storeExtras.persist");
     connection
         .call(move |connection| {
-            connection.execute(
-                PERSIST_SQL,
-                rusqlite::params![extras.ns_id, extras.cluster_id, extras.node_id, record],
-            )?;
+            statement(connection)?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -110,3 +119,16 @@ pub async fn persist(_: &Context, connection: &Connection, extras: StoreExtras)
         .await?;
     Ok(())
 }
+
+/// Prepare the statement to persist a record, so it can also run as part of a transaction.
+pub fn persist_statement(extras: StoreExtras) -> Result<Statement> {
+    let record = replisdk::utils::encoding::encode_serde(&extras)?;
+    let statement = move |connection: &rusqlite::Connection| -> rusqlite::Result<()> {
+        connection.execute(
+            PERSIST_SQL,
+            rusqlite::params![extras.ns_id, extras.cluster_id, extras.node_id, record],
+        )?;
+        Ok(())
+    };
+    Ok(Box::new(statement))
+}
@@ -0,0 +1,187 @@
+//! Persistent store operations on Task Execution records.
+use anyhow::Result;
+use futures::StreamExt;
+use opentelemetry_api::trace::FutureExt;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_store::persist::RecordTaskExecution;
+use replicore_store::query::ListTaskExecutions;
+use replicore_store::query::TaskExecutionStream;
+
+const LIST_SQL: &str = r#"
+SELECT execution
+FROM store_task_execution
+WHERE
+    (?1 IS NULL OR queue_id = ?1)
+    AND (?2 IS NULL OR task_id = ?2)
+ORDER BY record_id DESC
+LIMIT ?3;
+"#;
+
+const PERSIST_SQL: &str = r#"
+INSERT INTO store_task_execution (queue_id, task_id, history_key, execution)
+VALUES (?1, ?2, ?3, ?4)
+;"#;
+
+// Keep only the most recent records for the task the new record was added for.
+//  Records are grouped by history key so recurring tasks share a bounded history.
+const PRUNE_SQL: &str = r#"
+DELETE FROM store_task_execution
+WHERE record_id IN (
+    SELECT record_id
+    FROM store_task_execution
+    WHERE queue_id = ?1 AND history_key = ?2
+    ORDER BY record_id DESC
+    LIMIT -1 OFFSET ?3
+)
+;"#;
+
+/// Return a list of recorded task executions, most recent first.
+pub async fn list(
+    _: &Context,
+    connection: &Connection,
+    query: ListTaskExecutions,
+) -> Result<TaskExecutionStream> {
+    let limit = i64::try_from(query.limit).unwrap_or(i64::MAX);
+    let (err_count, _timer) = crate::telemetry::observe_op("taskExecution.list");
+    let trace = crate::telemetry::trace_op("taskExecution.list");
+    let executions = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(LIST_SQL)?;
+            let mut rows = statement.query(rusqlite::params![query.queue, query.task_id, limit])?;
+
+            let mut executions = Vec::new();
+            while let Some(row) = rows.next()? {
+                let execution: String = row.get("execution")?;
+                executions.push(execution);
+            }
+            Ok(executions)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+
+    let executions = futures::stream::iter(executions)
+        .map(|execution| {
+            let execution = replisdk::utils::encoding::decode_serde(&execution)?;
+            Ok(execution)
+        })
+        .boxed();
+    Ok(executions)
+}
+
+/// Persist a task execution record and prune older records for its task.
+pub async fn persist(
+    _: &Context,
+    connection: &Connection,
+    record: RecordTaskExecution,
+) -> Result<()> {
+    let execution = replisdk::utils::encoding::encode_serde(&record.execution)?;
+    let history_key = record.execution.history_key().to_string();
+    let queue = record.execution.queue;
+    let task_id = record.execution.task_id;
+    let retain = i64::try_from(record.retain).unwrap_or(i64::MAX);
+    let (err_count, _timer) = crate::telemetry::observe_op("taskExecution.persist");
+    let trace = crate::telemetry::trace_op("taskExecution.persist");
+    connection
+        .call(move |connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute(
+                PERSIST_SQL,
+                rusqlite::params![queue, task_id, history_key, execution],
+            )?;
+            transaction.execute(PRUNE_SQL, rusqlite::params![queue, history_key, retain])?;
+            transaction.commit()?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use time::OffsetDateTime;
+
+    use replicore_store::persist::RecordTaskExecution;
+    use replicore_store::query::ListTaskExecutions;
+    use replicore_tasks_models::TaskExecution;
+    use replicore_tasks_models::TaskOutcome;
+
+    /// Return a [`TaskExecution`] record to use in tests.
+    fn mock_execution(queue: &str, task_id: &str, dedup_key: Option<&str>) -> TaskExecution {
+        TaskExecution {
+            task_id: task_id.into(),
+            attempt: 1,
+            dedup_key: dedup_key.map(String::from),
+            error: vec![String::from("test error")],
+            finished_time: OffsetDateTime::now_utc(),
+            outcome: TaskOutcome::Failed,
+            payload: String::from("null"),
+            queue: queue.into(),
+            started_time: OffsetDateTime::now_utc(),
+            trace_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn persist_list_retain() {
+        let context = replicore_context::Context::fixture();
+        let store = crate::statements::tests::store().await;
+        let records = [
+            ("A", "1", Some("x")),
+            ("A", "2", Some("x")),
+            ("B", "3", None),
+            ("A", "4", Some("x")),
+            ("A", "5", None),
+        ];
+        for (queue, task_id, dedup_key) in records {
+            let execution = mock_execution(queue, task_id, dedup_key);
+            let record = RecordTaskExecution::new(execution, 2);
+            store.persist(&context, record).await.unwrap();
+        }
+
+        // Only the most recent records for each task are kept.
+        let list: Vec<TaskExecution> = store
+            .query(&context, ListTaskExecutions::latest(10))
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = list.iter().map(|item| item.task_id.as_str()).collect();
+        assert_eq!(ids, ["5", "4", "3", "2"]);
+        assert_eq!(list[0].error, ["test error"]);
+
+        // Records can be filtered and limited.
+        let query = ListTaskExecutions::latest(1).with_queue("A");
+        let list: Vec<TaskExecution> = store
+            .query(&context, query)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = list.iter().map(|item| item.task_id.as_str()).collect();
+        assert_eq!(ids, ["5"]);
+
+        let query = ListTaskExecutions::latest(10).with_task_id("3");
+        let list: Vec<TaskExecution> = store
+            .query(&context, query)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = list.iter().map(|item| item.task_id.as_str()).collect();
+        assert_eq!(ids, ["3"]);
+    }
+}
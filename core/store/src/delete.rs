@@ -4,11 +4,15 @@ use replisdk::core::models::namespace::Namespace;
 use replisdk::core::models::platform::Platform;
 
 use replicore_cluster_models::ConvergeState;
+use replicore_events_models::Event;
+use replicore_events_models::EventEntry;
+use replicore_events_models::EventStream;
 
 use self::seal::SealDeleteOp;
 use crate::ids::NamespaceID;
 use crate::ids::NamespacedResourceID;
 use crate::ids::NodeID;
+use crate::query::OutboxEvent;
 
 /// Internal trait to enable delete operations on the persistent store.
 pub trait DeleteOp: Into<DeleteOps> + SealDeleteOp {
@@ -30,8 +34,14 @@ pub enum DeleteOps {
     /// Delete a node by its ID.
     Node(NodeID),
 
+    /// Delete an event from the outbox, after it was published, by its sequence number.
+    OutboxEvent(DeleteOutboxEvent),
+
     /// Delete a platform by Namespace and Name.
     Platform(DeletePlatform),
+
+    /// Delete a record and add events to the outbox in the same transaction.
+    WithEvents(Box<DeleteWithEvents>),
 }
 
 /// List of all responses from delete operations.
@@ -88,6 +98,14 @@ impl From<&str> for DeleteNamespace {
     }
 }
 
+/// Request deletion of an event from the outbox by its sequence number.
+pub struct DeleteOutboxEvent(pub u64);
+impl From<&OutboxEvent> for DeleteOutboxEvent {
+    fn from(value: &OutboxEvent) -> Self {
+        DeleteOutboxEvent(value.seq)
+    }
+}
+
 /// Request deletion of a [`Platform`] record.
 pub struct DeletePlatform(pub NamespacedResourceID);
 impl From<&Platform> for DeletePlatform {
@@ -100,6 +118,46 @@ impl From<&Platform> for DeletePlatform {
     }
 }
 
+/// Delete a record and add events describing the change to the events outbox atomically.
+///
+/// Outbox events are published to the events platform by a relay after the transaction commits.
+/// Backends may not support all operations, for example those on the outbox itself.
+pub struct DeleteWithEvents {
+    /// Events to add to the outbox, in emit order.
+    pub events: Vec<EventEntry>,
+
+    /// The delete operation to perform along with the events.
+    pub op: DeleteOps,
+}
+
+impl DeleteWithEvents {
+    /// Delete a record along with the events to be added with [`DeleteWithEvents::audit`]
+    /// and [`DeleteWithEvents::change`].
+    pub fn new<O>(op: O) -> Self
+    where
+        O: DeleteOp,
+    {
+        DeleteWithEvents {
+            events: Vec::new(),
+            op: op.into(),
+        }
+    }
+
+    /// Add an event for the audit stream to the outbox.
+    pub fn audit(mut self, event: Event) -> Self {
+        let stream = EventStream::Audit;
+        self.events.push(EventEntry { event, stream });
+        self
+    }
+
+    /// Add an event for the change stream to the outbox.
+    pub fn change(mut self, event: Event) -> Self {
+        let stream = EventStream::Change;
+        self.events.push(EventEntry { event, stream });
+        self
+    }
+}
+
 // --- Create internal implementation details follow --- //
 /// Private module to seal implementation details.
 mod seal {
@@ -158,6 +216,26 @@ impl From<&Namespace> for DeleteOps {
     }
 }
 
+impl DeleteOp for DeleteOutboxEvent {
+    type Response = ();
+}
+impl SealDeleteOp for DeleteOutboxEvent {}
+impl From<DeleteOutboxEvent> for DeleteOps {
+    fn from(value: DeleteOutboxEvent) -> Self {
+        DeleteOps::OutboxEvent(value)
+    }
+}
+impl DeleteOp for &OutboxEvent {
+    type Response = ();
+}
+impl SealDeleteOp for &OutboxEvent {}
+impl From<&OutboxEvent> for DeleteOps {
+    fn from(value: &OutboxEvent) -> Self {
+        let value = DeleteOutboxEvent::from(value);
+        DeleteOps::OutboxEvent(value)
+    }
+}
+
 impl DeleteOp for DeletePlatform {
     type Response = ();
 }
@@ -188,6 +266,16 @@ impl From<NodeID> for DeleteOps {
     }
 }
 
+impl DeleteOp for DeleteWithEvents {
+    type Response = ();
+}
+impl SealDeleteOp for DeleteWithEvents {}
+impl From<DeleteWithEvents> for DeleteOps {
+    fn from(value: DeleteWithEvents) -> Self {
+        DeleteOps::WithEvents(Box::new(value))
+    }
+}
+
 // --- Implement DeleteResponses conversions on return types for transparent operations --- //
 impl From<DeleteResponses> for () {
     fn from(value: DeleteResponses) -> Self {
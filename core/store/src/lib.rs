@@ -48,10 +48,12 @@ pub mod ids;
 pub mod persist;
 pub mod query;
 
+mod memory;
+pub use self::memory::MemoryStore;
+
+/// In-memory implementation of a mock [`Store`] for unit tests.
 #[cfg(any(test, feature = "test-fixture"))]
-mod fixture;
-#[cfg(any(test, feature = "test-fixture"))]
-pub use self::fixture::StoreFixture;
+pub type StoreFixture = MemoryStore;
 
 #[cfg(test)]
 mod tests;
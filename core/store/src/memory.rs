@@ -0,0 +1,728 @@
+//! In-memory implementation of the [`StoreBackend`] interface.
+//!
+//! The same implementation backs the in-memory store backend and the unit tests fixture.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use anyhow::Result;
+use futures::StreamExt;
+use uuid::Uuid;
+
+use replisdk::core::models::api::ClusterSpecEntry;
+use replisdk::core::models::api::NActionEntry;
+use replisdk::core::models::api::NamespaceEntry;
+use replisdk::core::models::api::OActionEntry;
+use replisdk::core::models::api::PlatformEntry;
+use replisdk::core::models::cluster::ClusterDiscovery;
+use replisdk::core::models::cluster::ClusterSpec;
+use replisdk::core::models::naction::NAction;
+use replisdk::core::models::naction::NActionPhase;
+use replisdk::core::models::namespace::Namespace;
+use replisdk::core::models::node::Node;
+use replisdk::core::models::node::Shard;
+use replisdk::core::models::node::StoreExtras;
+use replisdk::core::models::oaction::OAction;
+use replisdk::core::models::platform::Platform;
+
+use replicore_cluster_models::ConvergeState;
+use replicore_cluster_models::OrchestrateReport;
+use replicore_context::Context;
+use replicore_events_models::EventEntry;
+use replicore_tasks_models::TaskExecution;
+
+use crate::delete::DeleteOps;
+use crate::delete::DeleteResponses;
+use crate::delete::DeleteWithEvents;
+use crate::persist::PersistOps;
+use crate::persist::PersistResponses;
+use crate::persist::PersistWithEvents;
+use crate::query::OutboxEvent;
+use crate::query::QueryOps;
+use crate::query::QueryResponses;
+use crate::StoreBackend;
+
+/// Implementation of the [`StoreBackend`] interface keeping records in memory.
+///
+/// Clones of the store share the same records.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    /// Shared in-memory records.
+    inner: Arc<Mutex<MemoryStoreState>>,
+}
+
+impl MemoryStore {
+    /// Lock and access the shared records.
+    fn access(&self) -> MutexGuard<MemoryStoreState> {
+        self.inner
+            .lock()
+            .expect("MemoryStore::inner state lock poisoned")
+    }
+}
+
+#[async_trait::async_trait]
+impl StoreBackend for MemoryStore {
+    async fn delete(&self, _: &Context, op: DeleteOps) -> Result<DeleteResponses> {
+        let mut store = self.access();
+        delete_op(&mut store, op);
+        Ok(DeleteResponses::Success)
+    }
+
+    async fn query(&self, _: &Context, op: QueryOps) -> Result<QueryResponses> {
+        let store = self.access();
+        match op {
+            QueryOps::ClusterConvergeState(cluster) => {
+                let key = (cluster.ns_id, cluster.name);
+                let state = store.cluster_converge_states.get(&key).cloned();
+                Ok(QueryResponses::ClusterConvergeState(state))
+            }
+            QueryOps::ClusterDiscovery(cluster) => {
+                let key = (cluster.ns_id, cluster.name);
+                let discovery = store.cluster_discoveries.get(&key).cloned();
+                Ok(QueryResponses::ClusterDiscovery(discovery))
+            }
+            QueryOps::ClusterSpec(cluster) => {
+                let key = (cluster.ns_id, cluster.name);
+                let spec = store.cluster_specs.get(&key).cloned();
+                Ok(QueryResponses::ClusterSpec(spec))
+            }
+            QueryOps::ListClusterSpecs(query) => {
+                let items: Vec<_> = store
+                    .cluster_specs
+                    .values()
+                    .filter(|spec| spec.ns_id == query.id)
+                    .map(|spec| ClusterSpecEntry {
+                        ns_id: spec.ns_id.clone(),
+                        cluster_id: spec.cluster_id.clone(),
+                        active: spec.active,
+                    })
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::ClusterSpecEntries(items))
+            }
+            QueryOps::ListNActions(query) => {
+                let mut actions: Vec<_> = store
+                    .nactions
+                    .values()
+                    .filter(|action| {
+                        action.ns_id == query.ns_id
+                            && action.cluster_id == query.cluster_id
+                            && (query.include_finished || action.finished_time.is_none())
+                    })
+                    .filter(|action| match &query.node_id {
+                        Some(node_id) => node_id == &action.node_id,
+                        None => true,
+                    })
+                    .collect();
+                actions.sort_by_key(|action| action.created_time);
+                let items: Vec<_> = actions
+                    .into_iter()
+                    .map(|action| NActionEntry {
+                        ns_id: action.ns_id.clone(),
+                        cluster_id: action.cluster_id.clone(),
+                        node_id: action.node_id.clone(),
+                        action_id: action.action_id,
+                        created_time: action.created_time,
+                        finished_time: action.finished_time,
+                        kind: action.kind.clone(),
+                        state: action.state.phase,
+                    })
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::NActionEntries(items))
+            }
+            QueryOps::ListNamespaces => {
+                let items: Vec<_> = store
+                    .namespaces
+                    .values()
+                    .map(|ns| NamespaceEntry {
+                        id: ns.id.clone(),
+                        status: ns.status.clone(),
+                    })
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::NamespaceEntries(items))
+            }
+            QueryOps::ListNodes(query) => {
+                let items: Vec<_> = store
+                    .nodes
+                    .values()
+                    .filter(|node| node.ns_id == query.ns_id && node.cluster_id == query.name)
+                    .cloned()
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::NodesList(items))
+            }
+            QueryOps::ListOActions(query) => {
+                let mut actions: Vec<_> = store
+                    .oactions
+                    .values()
+                    .filter(|action| {
+                        action.ns_id == query.ns_id
+                            && action.cluster_id == query.cluster_id
+                            && (query.include_finished || action.finished_ts.is_none())
+                    })
+                    .collect();
+                actions.sort_by_key(|action| action.created_ts);
+                let items: Vec<_> = actions
+                    .into_iter()
+                    .map(|action| OActionEntry {
+                        ns_id: action.ns_id.clone(),
+                        cluster_id: action.cluster_id.clone(),
+                        action_id: action.action_id,
+                        created_ts: action.created_ts,
+                        finished_ts: action.finished_ts,
+                        kind: action.kind.clone(),
+                        state: action.state,
+                    })
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::OActionEntries(items))
+            }
+            QueryOps::ListPlatforms(query) => {
+                let items: Vec<_> = store
+                    .platforms
+                    .values()
+                    .filter(|platform| platform.ns_id == query.id)
+                    .map(|platform| PlatformEntry {
+                        active: platform.active,
+                        name: platform.name.clone(),
+                    })
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::PlatformEntries(items))
+            }
+            QueryOps::ListOutboxEvents(query) => {
+                let items: Vec<_> = store
+                    .outbox
+                    .iter()
+                    .take(query.limit)
+                    .map(|(seq, entry)| OutboxEvent {
+                        entry: entry.clone(),
+                        seq: *seq,
+                    })
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::OutboxEvents(items))
+            }
+            QueryOps::ListShards(query) => {
+                let mut items: Vec<_> = store
+                    .shards
+                    .values()
+                    .filter(|shard| {
+                        shard.ns_id == query.ns_id && shard.cluster_id == query.cluster_id
+                    })
+                    .filter(|shard| match &query.node_id {
+                        Some(node_id) => node_id == &shard.node_id,
+                        None => true,
+                    })
+                    .cloned()
+                    .collect();
+                items.sort_by(|left, right| left.shard_id.cmp(&right.shard_id));
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::ShardsList(items))
+            }
+            QueryOps::ListStoreExtras(query) => {
+                let items: Vec<_> = store
+                    .store_extras
+                    .values()
+                    .filter(|extras| extras.ns_id == query.ns_id && extras.cluster_id == query.name)
+                    .cloned()
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::StoreExtrasList(items))
+            }
+            QueryOps::ListTaskExecutions(query) => {
+                let items: Vec<_> = store
+                    .task_executions
+                    .iter()
+                    .rev()
+                    .filter(|execution| match &query.queue {
+                        Some(queue) => queue == &execution.queue,
+                        None => true,
+                    })
+                    .filter(|execution| match &query.task_id {
+                        Some(task_id) => task_id == &execution.task_id,
+                        None => true,
+                    })
+                    .take(query.limit)
+                    .cloned()
+                    .collect();
+                let items = futures::stream::iter(items).map(Ok).boxed();
+                Ok(QueryResponses::TaskExecutions(items))
+            }
+            QueryOps::NAction(query) => {
+                let key = (
+                    query.0.ns_id,
+                    query.0.cluster_id,
+                    query.0.node_id,
+                    query.0.action_id,
+                );
+                let action = store.nactions.get(&key).cloned();
+                Ok(QueryResponses::NAction(action))
+            }
+            QueryOps::Namespace(ns) => {
+                let ns = store.namespaces.get(&ns.0.id).cloned();
+                Ok(QueryResponses::Namespace(ns))
+            }
+            QueryOps::OAction(query) => {
+                let key = (query.0.ns_id, query.0.cluster_id, query.0.action_id);
+                let oaction = store.oactions.get(&key).cloned();
+                Ok(QueryResponses::OAction(oaction))
+            }
+            QueryOps::OrchestrateReport(query) => {
+                let key = (query.ns_id, query.name);
+                let report = store.orchestrate_reports.get(&key).cloned();
+                Ok(QueryResponses::OrchestrateReport(report))
+            }
+            QueryOps::Platform(query) => {
+                let key = (query.ns_id, query.name);
+                let platform = store.platforms.get(&key).cloned();
+                Ok(QueryResponses::Platform(platform))
+            }
+            QueryOps::UnfinishedNAction(cluster) => {
+                let mut actions: Vec<_> = store
+                    .nactions
+                    .values()
+                    .filter(|action| {
+                        action.ns_id == cluster.ns_id
+                            && action.cluster_id == cluster.name
+                            && action.finished_time.is_none()
+                    })
+                    .cloned()
+                    .collect();
+                actions.sort_by_key(|action| action.created_time);
+                let actions = futures::stream::iter(actions).map(Ok).boxed();
+                Ok(QueryResponses::NActions(actions))
+            }
+            QueryOps::UnfinishedOAction(cluster) => {
+                let mut actions: Vec<_> = store
+                    .oactions
+                    .values()
+                    .filter(|action| {
+                        action.ns_id == cluster.ns_id
+                            && action.cluster_id == cluster.name
+                            && action.finished_ts.is_none()
+                    })
+                    .cloned()
+                    .collect();
+                actions.sort_by_key(|action| action.created_ts);
+                let actions = futures::stream::iter(actions).map(Ok).boxed();
+                Ok(QueryResponses::OActions(actions))
+            }
+        }
+    }
+
+    async fn persist(&self, _: &Context, op: PersistOps) -> Result<PersistResponses> {
+        let mut store = self.access();
+        persist_op(&mut store, op);
+        Ok(PersistResponses::Success)
+    }
+}
+
+/// Add events to the outbox, in order, while the caller holds the lock.
+fn add_outbox_events(store: &mut MemoryStoreState, events: Vec<EventEntry>) {
+    for entry in events {
+        store.outbox_seq += 1;
+        let seq = store.outbox_seq;
+        store.outbox.insert(seq, entry);
+    }
+}
+
+/// Apply a delete operation to the records while the caller holds the lock.
+///
+/// Holding the lock for the entire operation makes records and outbox events visible atomically.
+fn delete_op(store: &mut MemoryStoreState, op: DeleteOps) {
+    match op {
+        DeleteOps::ClusterConvergeState(cluster) => {
+            let key = (cluster.0.ns_id, cluster.0.name);
+            store.cluster_converge_states.remove(&key);
+        }
+        DeleteOps::ClusterSpec(cluster) => {
+            let key = (cluster.0.ns_id, cluster.0.name);
+            store.cluster_specs.remove(&key);
+        }
+        DeleteOps::Namespace(ns) => {
+            store.namespaces.remove(&ns.0.id);
+        }
+        DeleteOps::Node(node) => {
+            let key = (node.ns_id, node.cluster_id, node.node_id);
+            store.nodes.remove(&key);
+            store.store_extras.remove(&key);
+            store
+                .shards
+                .retain(|id, _| id.0 != key.0 || id.1 != key.1 || id.2 != key.2);
+        }
+        DeleteOps::OutboxEvent(event) => {
+            store.outbox.remove(&event.0);
+        }
+        DeleteOps::Platform(pl) => {
+            let key = (pl.0.ns_id, pl.0.name);
+            store.platforms.remove(&key);
+        }
+        DeleteOps::WithEvents(op) => {
+            let DeleteWithEvents { events, op } = *op;
+            delete_op(store, op);
+            add_outbox_events(store, events);
+        }
+    };
+}
+
+/// Apply a persist operation to the records while the caller holds the lock.
+///
+/// Holding the lock for the entire operation makes records and outbox events visible atomically.
+fn persist_op(store: &mut MemoryStoreState, op: PersistOps) {
+    match op {
+        PersistOps::ClusterConvergeState(state) => {
+            let key = (state.ns_id.clone(), state.cluster_id.clone());
+            store.cluster_converge_states.insert(key, state);
+        }
+        PersistOps::ClusterDiscovery(disc) => {
+            let key = (disc.ns_id.clone(), disc.cluster_id.clone());
+            store.cluster_discoveries.insert(key, disc);
+        }
+        PersistOps::ClusterSpec(spec) => {
+            let key = (spec.ns_id.clone(), spec.cluster_id.clone());
+            store.cluster_specs.insert(key, spec);
+        }
+        PersistOps::NAction(action) => {
+            let key = (
+                action.ns_id.clone(),
+                action.cluster_id.clone(),
+                action.node_id.clone(),
+                action.action_id,
+            );
+            store.nactions.insert(key, action);
+        }
+        PersistOps::Namespace(ns) => {
+            store.namespaces.insert(ns.id.clone(), ns);
+        }
+        PersistOps::Node(node) => {
+            let key = (
+                node.ns_id.clone(),
+                node.cluster_id.clone(),
+                node.node_id.clone(),
+            );
+            store.nodes.insert(key, node);
+        }
+        PersistOps::NodeCancelAllActions(node) => {
+            // Only unfinished actions are cancelled, like the SQLite store does.
+            let finished_time = time::OffsetDateTime::now_utc();
+            let actions = store.nactions.values_mut().filter(|action| {
+                action.ns_id == node.ns_id
+                    && action.cluster_id == node.cluster_id
+                    && action.node_id == node.node_id
+                    && action.finished_time.is_none()
+            });
+            for action in actions {
+                action.finished_time = Some(finished_time);
+                action.state.phase = NActionPhase::Cancelled;
+            }
+        }
+        PersistOps::OAction(oaction) => {
+            let key = (
+                oaction.ns_id.clone(),
+                oaction.cluster_id.clone(),
+                oaction.action_id,
+            );
+            store.oactions.insert(key, oaction);
+        }
+        PersistOps::OrchestrateReport(report) => {
+            let key = (report.ns_id.clone(), report.cluster_id.clone());
+            store.orchestrate_reports.insert(key, report);
+        }
+        PersistOps::Platform(platform) => {
+            let key = (platform.ns_id.clone(), platform.name.clone());
+            store.platforms.insert(key, platform);
+        }
+        PersistOps::Shard(shard) => {
+            let key = (
+                shard.ns_id.clone(),
+                shard.cluster_id.clone(),
+                shard.node_id.clone(),
+                shard.shard_id.clone(),
+            );
+            store.shards.insert(key, shard);
+        }
+        PersistOps::StoreExtras(extras) => {
+            let key = (
+                extras.ns_id.clone(),
+                extras.cluster_id.clone(),
+                extras.node_id.clone(),
+            );
+            store.store_extras.insert(key, extras);
+        }
+        PersistOps::TaskExecution(record) => {
+            // Drop the oldest records for the task once over the retention limit.
+            let queue = record.execution.queue.clone();
+            let key = record.execution.history_key().to_string();
+            store.task_executions.push(record.execution);
+            let same_task = |execution: &TaskExecution| {
+                execution.queue == queue && execution.history_key() == key
+            };
+            let count = store
+                .task_executions
+                .iter()
+                .filter(|execution| same_task(execution))
+                .count();
+            let mut excess = count.saturating_sub(record.retain);
+            store.task_executions.retain(|execution| {
+                if excess > 0 && same_task(execution) {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            });
+        }
+        PersistOps::WithEvents(op) => {
+            let PersistWithEvents { events, op } = *op;
+            persist_op(store, op);
+            add_outbox_events(store, events);
+        }
+    };
+}
+
+/// Records kept by the in-memory store.
+///
+/// Records are kept in sorted maps so they can be listed in a stable order.
+#[derive(Default)]
+struct MemoryStoreState {
+    // (ns, cluster)
+    cluster_converge_states: BTreeMap<(String, String), ConvergeState>,
+    cluster_discoveries: BTreeMap<(String, String), ClusterDiscovery>,
+    cluster_specs: BTreeMap<(String, String), ClusterSpec>,
+    namespaces: BTreeMap<String, Namespace>,
+    // (ns, cluster, node, action)
+    nactions: BTreeMap<(String, String, String, Uuid), NAction>,
+    // (ns, cluster, node)
+    nodes: BTreeMap<(String, String, String), Node>,
+    // (ns, cluster, action)
+    oactions: BTreeMap<(String, String, Uuid), OAction>,
+    // (seq)
+    outbox: BTreeMap<u64, EventEntry>,
+    outbox_seq: u64,
+    // (ns, cluster)
+    orchestrate_reports: BTreeMap<(String, String), OrchestrateReport>,
+    // (ns, platform)
+    platforms: BTreeMap<(String, String), Platform>,
+    // (ns, cluster, node, shard)
+    shards: BTreeMap<(String, String, String, String), Shard>,
+    // (ns, cluster, node)
+    store_extras: BTreeMap<(String, String, String), StoreExtras>,
+    // Oldest record first.
+    task_executions: Vec<TaskExecution>,
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use replisdk::core::models::cluster::ClusterSpec;
+    use replisdk::core::models::namespace::Namespace;
+    use replisdk::core::models::namespace::NamespaceStatus;
+
+    use replicore_events_models::Event;
+    use replicore_tasks_models::TaskExecution;
+    use replicore_tasks_models::TaskOutcome;
+
+    use super::MemoryStore;
+    use crate::delete::DeleteWithEvents;
+    use crate::ids::NamespaceID;
+    use crate::persist::PersistWithEvents;
+    use crate::persist::RecordTaskExecution;
+    use crate::query::ListClusterSpecs;
+    use crate::query::ListNamespaces;
+    use crate::query::ListOutboxEvents;
+    use crate::query::ListTaskExecutions;
+    use crate::query::LookupNamespace;
+    use crate::Store;
+
+    fn mock_namespace(id: &str) -> Namespace {
+        Namespace {
+            id: id.into(),
+            tls: Default::default(),
+            settings: Default::default(),
+            status: NamespaceStatus::Active,
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_get_persist() {
+        let context = replicore_context::Context::fixture();
+        let store = Store::from(MemoryStore::default());
+        let ns = mock_namespace("test");
+        let lookup = LookupNamespace(NamespaceID { id: "test".into() });
+
+        // Check lookup and delete without record.
+        let record = store.query(&context, lookup.clone()).await.unwrap();
+        assert!(record.is_none());
+        store.delete(&context, &ns).await.unwrap();
+
+        // Check persisting (and looking up) a record.
+        store.persist(&context, ns.clone()).await.unwrap();
+        let record = store
+            .query(&context, lookup.clone())
+            .await
+            .unwrap()
+            .expect("ns record not in store");
+        assert_eq!(record.id, "test");
+
+        // Check deleting a record.
+        store.delete(&context, &ns).await.unwrap();
+        let record = store.query(&context, lookup).await.unwrap();
+        assert!(record.is_none());
+    }
+
+    #[tokio::test]
+    async fn list_sorted() {
+        let context = replicore_context::Context::fixture();
+        let store = Store::from(MemoryStore::default());
+        for id in ["test-3", "test-1", "test-2"] {
+            store.persist(&context, mock_namespace(id)).await.unwrap();
+            let spec = ClusterSpec::synthetic("test-1", id);
+            store.persist(&context, spec).await.unwrap();
+        }
+
+        let ids: Vec<String> = store
+            .query(&context, ListNamespaces)
+            .await
+            .unwrap()
+            .map_ok(|entry| entry.id)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(ids, ["test-1", "test-2", "test-3"]);
+
+        let op = ListClusterSpecs(NamespaceID {
+            id: "test-1".into(),
+        });
+        let ids: Vec<String> = store
+            .query(&context, op)
+            .await
+            .unwrap()
+            .map_ok(|entry| entry.cluster_id)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(ids, ["test-1", "test-2", "test-3"]);
+    }
+
+    #[tokio::test]
+    async fn clones_share_records() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryStore::default();
+        let one = Store::from(backend.clone());
+        let two = Store::from(backend);
+
+        one.persist(&context, mock_namespace("test")).await.unwrap();
+        let lookup = LookupNamespace(NamespaceID { id: "test".into() });
+        let record = two.query(&context, lookup).await.unwrap();
+        assert!(record.is_some());
+    }
+
+    #[tokio::test]
+    async fn task_executions_retained() {
+        let context = replicore_context::Context::fixture();
+        let store = Store::from(MemoryStore::default());
+        let records = [
+            ("1", None),
+            ("2", Some("A")),
+            ("3", Some("A")),
+            ("4", Some("A")),
+        ];
+        for (task_id, dedup_key) in records {
+            let execution = TaskExecution {
+                task_id: task_id.into(),
+                attempt: 1,
+                dedup_key: dedup_key.map(String::from),
+                error: Vec::new(),
+                finished_time: time::OffsetDateTime::now_utc(),
+                outcome: TaskOutcome::Success,
+                payload: String::from("null"),
+                queue: String::from("TEST"),
+                started_time: time::OffsetDateTime::now_utc(),
+                trace_id: None,
+            };
+            let record = RecordTaskExecution::new(execution, 2);
+            store.persist(&context, record).await.unwrap();
+        }
+
+        let list: Vec<TaskExecution> = store
+            .query(&context, ListTaskExecutions::latest(10))
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = list.iter().map(|item| item.task_id.as_str()).collect();
+        assert_eq!(ids, ["4", "3", "1"]);
+    }
+
+    #[tokio::test]
+    async fn outbox_events_sequenced() {
+        let context = replicore_context::Context::fixture();
+        let store = Store::from(MemoryStore::default());
+        for id in ["test-1", "test-2", "test-3"] {
+            let ns = mock_namespace(id);
+            let event = Event::new_with_payload("TEST", &ns).unwrap();
+            let op = PersistWithEvents::new(ns).change(event);
+            store.persist(&context, op).await.unwrap();
+        }
+
+        let pending = store
+            .query(&context, ListOutboxEvents::oldest(2))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let seqs: Vec<_> = pending.iter().map(|event| event.seq).collect();
+        assert_eq!(seqs, [1, 2]);
+
+        store.delete(&context, &pending[0]).await.unwrap();
+        let pending = store
+            .query(&context, ListOutboxEvents::oldest(10))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let seqs: Vec<_> = pending.iter().map(|event| event.seq).collect();
+        assert_eq!(seqs, [2, 3]);
+        let ids: Vec<String> = store
+            .query(&context, ListNamespaces)
+            .await
+            .unwrap()
+            .map_ok(|entry| entry.id)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(ids, ["test-1", "test-2", "test-3"]);
+    }
+
+    #[tokio::test]
+    async fn outbox_events_on_delete() {
+        let context = replicore_context::Context::fixture();
+        let store = Store::from(MemoryStore::default());
+        let ns = mock_namespace("test");
+        store.persist(&context, ns.clone()).await.unwrap();
+
+        let event = Event::new_with_payload("TEST", &ns).unwrap();
+        let op = DeleteWithEvents::new(&ns).change(event.clone());
+        store.delete(&context, op).await.unwrap();
+
+        let lookup = LookupNamespace(NamespaceID { id: "test".into() });
+        let record = store.query(&context, lookup).await.unwrap();
+        assert!(record.is_none());
+        let pending = store
+            .query(&context, ListOutboxEvents::oldest(10))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].entry.event, event);
+    }
+}
@@ -11,6 +11,10 @@ use replisdk::core::models::platform::Platform;
 
 use replicore_cluster_models::ConvergeState;
 use replicore_cluster_models::OrchestrateReport;
+use replicore_events_models::Event;
+use replicore_events_models::EventEntry;
+use replicore_events_models::EventStream;
+use replicore_tasks_models::TaskExecution;
 
 use self::seal::SealPersistOp;
 use super::ids::NodeID;
@@ -58,6 +62,12 @@ pub enum PersistOps {
 
     /// Persist a cluster node's StoreExtras record.
     StoreExtras(StoreExtras),
+
+    /// Persist a task execution record, pruning older records for the queue.
+    TaskExecution(RecordTaskExecution),
+
+    /// Persist a record and add events to the outbox in the same transaction.
+    WithEvents(Box<PersistWithEvents>),
 }
 
 /// List of all responses from persist operations.
@@ -76,6 +86,64 @@ impl From<NodeID> for NodeCancelAllActions {
     }
 }
 
+/// Record a task execution attempt and keep only the most recent records for its task.
+///
+/// Recurring tasks submitted with the same de-duplication key share their records.
+pub struct RecordTaskExecution {
+    /// The task execution record to persist.
+    pub execution: TaskExecution,
+
+    /// Number of the most recent execution records to keep for the task.
+    pub retain: usize,
+}
+
+impl RecordTaskExecution {
+    /// Record a task execution keeping up to `retain` records for its task.
+    pub fn new(execution: TaskExecution, retain: usize) -> Self {
+        RecordTaskExecution { execution, retain }
+    }
+}
+
+/// Persist a record and add events describing the change to the events outbox atomically.
+///
+/// Outbox events are published to the events platform by a relay after the transaction commits.
+/// Backends may not support all operations, for example those spanning multiple records.
+pub struct PersistWithEvents {
+    /// Events to add to the outbox, in emit order.
+    pub events: Vec<EventEntry>,
+
+    /// The persist operation to perform along with the events.
+    pub op: PersistOps,
+}
+
+impl PersistWithEvents {
+    /// Persist a record along with the events to be added with [`PersistWithEvents::audit`]
+    /// and [`PersistWithEvents::change`].
+    pub fn new<O>(op: O) -> Self
+    where
+        O: PersistOp,
+    {
+        PersistWithEvents {
+            events: Vec::new(),
+            op: op.into(),
+        }
+    }
+
+    /// Add an event for the audit stream to the outbox.
+    pub fn audit(mut self, event: Event) -> Self {
+        let stream = EventStream::Audit;
+        self.events.push(EventEntry { event, stream });
+        self
+    }
+
+    /// Add an event for the change stream to the outbox.
+    pub fn change(mut self, event: Event) -> Self {
+        let stream = EventStream::Change;
+        self.events.push(EventEntry { event, stream });
+        self
+    }
+}
+
 // --- Create internal implementation details follow --- //
 /// Private module to seal implementation details.
 mod seal {
@@ -204,6 +272,31 @@ impl From<StoreExtras> for PersistOps {
     }
 }
 
+impl PersistOp for RecordTaskExecution {
+    type Response = ();
+}
+impl SealPersistOp for RecordTaskExecution {}
+impl From<RecordTaskExecution> for PersistOps {
+    fn from(value: RecordTaskExecution) -> Self {
+        PersistOps::TaskExecution(value)
+    }
+}
+
+impl PersistOp for PersistOps {
+    type Response = PersistResponses;
+}
+impl SealPersistOp for PersistOps {}
+
+impl PersistOp for PersistWithEvents {
+    type Response = ();
+}
+impl SealPersistOp for PersistWithEvents {}
+impl From<PersistWithEvents> for PersistOps {
+    fn from(value: PersistWithEvents) -> Self {
+        PersistOps::WithEvents(Box::new(value))
+    }
+}
+
 // --- Implement PersistResponses conversions on return types for transparent operations --- //
 impl From<PersistResponses> for () {
     fn from(value: PersistResponses) -> Self {
@@ -20,6 +20,8 @@ use replisdk::core::models::platform::Platform;
 
 use replicore_cluster_models::ConvergeState;
 use replicore_cluster_models::OrchestrateReport;
+use replicore_events_models::EventEntry;
+use replicore_tasks_models::TaskExecution;
 
 use self::seal::SealQueryOp;
 use crate::ids::NActionID;
@@ -62,12 +64,18 @@ pub enum QueryOps {
     /// List summary information about known platforms in the namespace, sorted alphabetically.
     ListPlatforms(NamespaceID),
 
+    /// List events in the outbox that are pending publication, oldest first.
+    ListOutboxEvents(ListOutboxEvents),
+
     /// List shards in a cluster.
     ListShards(ListShards),
 
     /// List store extras for all nodes in a cluster.
     ListStoreExtras(NamespacedResourceID),
 
+    /// List recorded task execution attempts, most recent first.
+    ListTaskExecutions(ListTaskExecutions),
+
     /// Query a node action by namespace, cluster, node and action ID.
     NAction(LookupNAction),
 
@@ -131,6 +139,9 @@ pub enum QueryResponses {
     /// Return a [`Stream`] of [`OActionEntry`] objects.
     OActionEntries(OActionEntryStream),
 
+    /// Return a [`Stream`] of [`OutboxEvent`] records.
+    OutboxEvents(OutboxEventStream),
+
     /// Return an [`OrchestrateReport`], if one was found for the cluster.
     OrchestrateReport(Option<OrchestrateReport>),
 
@@ -148,11 +159,15 @@ pub enum QueryResponses {
 
     /// Return a [`Stream`] (async iterator) of strings (useful for IDs).
     StringStream(StringStream),
+
+    /// Return a [`Stream`] of [`TaskExecution`] records.
+    TaskExecutions(TaskExecutionStream),
 }
 
 // --- Operations return types --- //
 /// Alias for a heap-allocated [`Stream`] of cluster spec summaries.
-pub type ClusterSpecEntryStream = std::pin::Pin<Box<dyn Stream<Item = Result<ClusterSpecEntry>>>>;
+pub type ClusterSpecEntryStream =
+    std::pin::Pin<Box<dyn Stream<Item = Result<ClusterSpecEntry>> + Send>>;
 
 /// Alias for a heap-allocated [`Stream`] of node actions.
 pub type NActionStream = std::pin::Pin<Box<dyn Stream<Item = Result<NAction>> + Send>>;
@@ -161,7 +176,8 @@ pub type NActionStream = std::pin::Pin<Box<dyn Stream<Item = Result<NAction>> +
 pub type NActionEntryStream = std::pin::Pin<Box<dyn Stream<Item = Result<NActionEntry>>>>;
 
 /// Alias for a heap-allocated [`Stream`] of namespace summaries.
-pub type NamespaceEntryStream = std::pin::Pin<Box<dyn Stream<Item = Result<NamespaceEntry>>>>;
+pub type NamespaceEntryStream =
+    std::pin::Pin<Box<dyn Stream<Item = Result<NamespaceEntry>> + Send>>;
 
 /// Alias for a heap-allocated [`Stream`] of cluster nodes.
 pub type NodesStream = std::pin::Pin<Box<dyn Stream<Item = Result<Node>> + Send>>;
@@ -172,8 +188,11 @@ pub type OActionStream = std::pin::Pin<Box<dyn Stream<Item = Result<OAction>> +
 /// Alias for a heap-allocated [`Stream`] of orchestrator action summaries.
 pub type OActionEntryStream = std::pin::Pin<Box<dyn Stream<Item = Result<OActionEntry>>>>;
 
+/// Alias for a heap-allocated [`Stream`] of events pending in the outbox.
+pub type OutboxEventStream = std::pin::Pin<Box<dyn Stream<Item = Result<OutboxEvent>> + Send>>;
+
 /// Alias for a heap-allocated [`Stream`] of platform summaries.
-pub type PlatformEntryStream = std::pin::Pin<Box<dyn Stream<Item = Result<PlatformEntry>>>>;
+pub type PlatformEntryStream = std::pin::Pin<Box<dyn Stream<Item = Result<PlatformEntry>> + Send>>;
 
 /// Alias for a heap-allocated [`Stream`] of cluster [`Shard`]s.
 pub type ShardsStream = std::pin::Pin<Box<dyn Stream<Item = Result<Shard>> + Send>>;
@@ -184,6 +203,9 @@ pub type StoreExtrasStream = std::pin::Pin<Box<dyn Stream<Item = Result<StoreExt
 /// Alias for a heap-allocated [`Stream`] of strings (useful for IDs).
 pub type StringStream = std::pin::Pin<Box<dyn Stream<Item = Result<String>>>>;
 
+/// Alias for a heap-allocated [`Stream`] of task execution records.
+pub type TaskExecutionStream = std::pin::Pin<Box<dyn Stream<Item = Result<TaskExecution>> + Send>>;
+
 // --- High level query operations --- //
 /// List the summary information of all cluster specs in a namespace, sorted alphabetically.
 pub struct ListClusterSpecs(pub NamespaceID);
@@ -616,6 +638,41 @@ impl From<ListPlatforms> for QueryOps {
     }
 }
 
+/// List up to `limit` events pending publication in the outbox, oldest first.
+pub struct ListOutboxEvents {
+    /// Maximum number of outbox events to return.
+    pub limit: usize,
+}
+
+impl SealQueryOp for ListOutboxEvents {}
+impl QueryOp for ListOutboxEvents {
+    type Response = OutboxEventStream;
+}
+impl From<ListOutboxEvents> for QueryOps {
+    fn from(value: ListOutboxEvents) -> Self {
+        QueryOps::ListOutboxEvents(value)
+    }
+}
+
+impl ListOutboxEvents {
+    /// List up to `limit` of the oldest events in the outbox.
+    pub fn oldest(limit: usize) -> Self {
+        ListOutboxEvents { limit }
+    }
+}
+
+/// Event added to the outbox along with a persisted record, pending publication.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutboxEvent {
+    /// The event to publish and the stream to publish it onto.
+    pub entry: EventEntry,
+
+    /// Sequence number assigned by the store when the event was added to the outbox.
+    ///
+    /// Sequence numbers increase in the order events are added to the outbox.
+    pub seq: u64,
+}
+
 /// List [`Shard`]s for a cluster, optionally filtering by node.
 pub struct ListShards {
     /// The namespace ID the cluster is in.
@@ -672,6 +729,57 @@ impl From<ListStoreExtras> for QueryOps {
     }
 }
 
+/// List recorded [`TaskExecution`]s, most recent first.
+pub struct ListTaskExecutions {
+    /// Maximum number of execution records to return.
+    pub limit: usize,
+
+    /// Only list executions of tasks received from the given queue.
+    pub queue: Option<String>,
+
+    /// Only list executions of the given task.
+    pub task_id: Option<String>,
+}
+
+impl SealQueryOp for ListTaskExecutions {}
+impl QueryOp for ListTaskExecutions {
+    type Response = TaskExecutionStream;
+}
+impl From<ListTaskExecutions> for QueryOps {
+    fn from(value: ListTaskExecutions) -> Self {
+        QueryOps::ListTaskExecutions(value)
+    }
+}
+
+impl ListTaskExecutions {
+    /// List up to `limit` of the most recent [`TaskExecution`]s.
+    pub fn latest(limit: usize) -> Self {
+        ListTaskExecutions {
+            limit,
+            queue: None,
+            task_id: None,
+        }
+    }
+
+    /// Only list executions of tasks received from the given queue.
+    pub fn with_queue<S>(mut self, queue: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.queue = Some(queue.into());
+        self
+    }
+
+    /// Only list executions of the given task.
+    pub fn with_task_id<S>(mut self, task_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.task_id = Some(task_id.into());
+        self
+    }
+}
+
 impl SealQueryOp for LookupConvergeState {}
 impl QueryOp for LookupConvergeState {
     type Response = Option<ConvergeState>;
@@ -930,6 +1038,22 @@ impl From<QueryResponses> for StoreExtrasStream {
         }
     }
 }
+impl From<QueryResponses> for OutboxEventStream {
+    fn from(value: QueryResponses) -> Self {
+        match value {
+            QueryResponses::OutboxEvents(stream) => stream,
+            _ => panic!("unexpected result type for the given query operation"),
+        }
+    }
+}
+impl From<QueryResponses> for TaskExecutionStream {
+    fn from(value: QueryResponses) -> Self {
+        match value {
+            QueryResponses::TaskExecutions(stream) => stream,
+            _ => panic!("unexpected result type for the given query operation"),
+        }
+    }
+}
 impl From<QueryResponses> for StringStream {
     fn from(value: QueryResponses) -> Self {
         match value {
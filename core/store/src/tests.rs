@@ -2,9 +2,15 @@
 use replisdk::core::models::namespace::Namespace;
 use replisdk::core::models::namespace::NamespaceStatus;
 
+use futures::TryStreamExt;
+
 use replicore_context::Context;
+use replicore_events_models::Event;
+use replicore_events_models::EventStream;
 
 use crate::ids::NamespaceID;
+use crate::persist::PersistWithEvents;
+use crate::query::ListOutboxEvents;
 use crate::query::LookupNamespace;
 use crate::Store;
 
@@ -50,3 +56,44 @@ async fn check_persist_interface() {
         .await
         .expect("namespace persist to be ok");
 }
+
+#[tokio::test]
+async fn check_outbox_interface() {
+    let context = Context::fixture();
+    let namespace = mock_namespace();
+    let store = Store::fixture();
+    let event = Event::new_with_payload("NAMESPACE_TEST", &namespace).unwrap();
+    let op = PersistWithEvents::new(namespace)
+        .audit(event.clone())
+        .change(event);
+    store
+        .persist(&context, op)
+        .await
+        .expect("namespace persist with events to be ok");
+
+    let lookup = LookupNamespace::from("test");
+    let namespace = store.query(&context, lookup).await.unwrap();
+    assert!(namespace.is_some());
+    let events: Vec<_> = store
+        .query(&context, ListOutboxEvents::oldest(10))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].entry.stream, EventStream::Audit);
+    assert_eq!(events[1].entry.stream, EventStream::Change);
+    assert!(events[0].seq < events[1].seq);
+
+    store.delete(&context, &events[0]).await.unwrap();
+    let events: Vec<_> = store
+        .query(&context, ListOutboxEvents::oldest(10))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].entry.stream, EventStream::Change);
+}
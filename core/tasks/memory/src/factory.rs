@@ -0,0 +1,51 @@
+//! Initialise the in-memory Tasks backend.
+use anyhow::Result;
+use serde_json::Value as Json;
+
+use replicore_context::Context;
+use replicore_tasks::execute::TaskAck;
+use replicore_tasks::execute::TaskSource;
+use replicore_tasks::factory::TasksFactory;
+use replicore_tasks::factory::TasksFactoryArgs;
+use replicore_tasks::factory::TasksFactorySyncArgs;
+use replicore_tasks::submit::Tasks;
+
+use crate::MemoryTasks;
+
+/// Initialise the in-memory Tasks backend.
+///
+/// All clients created by the same factory share the same queues
+/// so tasks submitted with one client are received by the others.
+#[derive(Clone, Default)]
+pub struct MemoryFactory {
+    tasks: MemoryTasks,
+}
+
+#[async_trait::async_trait]
+impl TasksFactory for MemoryFactory {
+    fn conf_check(&self, _: &Context, _: &Json) -> Result<()> {
+        Ok(())
+    }
+
+    async fn consume<'a>(&self, _: TasksFactoryArgs<'a>) -> Result<(TaskSource, TaskAck)> {
+        let source = self.tasks.source();
+        let ack = self.tasks.clone();
+        Ok((TaskSource::from(source), TaskAck::from(ack)))
+    }
+
+    fn register_metrics(&self, registry: &prometheus::Registry) -> Result<()> {
+        crate::telemetry::register_metrics(registry)
+    }
+
+    async fn submit<'a>(&self, args: TasksFactoryArgs<'a>) -> Result<Tasks> {
+        slog::warn!(
+            args.context.logger,
+            "Using in-memory tasks means tasks will be lost once the process terminates"
+        );
+        Ok(Tasks::from(self.tasks.clone()))
+    }
+
+    async fn sync<'a>(&self, _: TasksFactorySyncArgs<'a>) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+//! Background Tasks queued in the memory of the RepliCore process.
+//!
+//! This backend is intended for demos, tests and embedding the Control Plane:
+//!
+//! - All tasks are lost as soon as the process terminates.
+//! - Tasks are NOT shared across processes so only single process deployments are supported.
+//! - Retries, redelivery, delayed and de-duplicated tasks and the dead-letter queue
+//!   behave like the SQLite backend.
+mod factory;
+mod source;
+mod state;
+mod tasks;
+mod telemetry;
+
+pub use self::factory::MemoryFactory;
+pub use self::source::MemoryTaskSource;
+pub use self::tasks::MemoryTasks;
@@ -0,0 +1,265 @@
+//! Receive tasks queued in memory for execution.
+use std::collections::HashMap;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tokio::sync::watch;
+
+use replicore_context::Context;
+use replicore_tasks::conf::Queue;
+use replicore_tasks::execute::QueueCapacity;
+use replicore_tasks::execute::ReceivedTask;
+use replicore_tasks::execute::TaskSourceBackend;
+
+use crate::MemoryTasks;
+
+/// Implementation of the [`TaskSourceBackend`] interface using memory.
+///
+/// Sources wait for tasks to be submitted or become due instead of polling for them.
+pub struct MemoryTaskSource {
+    /// Notifications of changes to queued tasks.
+    changes: watch::Receiver<()>,
+
+    /// Queues the source is subscribed to.
+    subscriptions: HashMap<String, &'static Queue>,
+
+    /// Queued tasks to receive from.
+    tasks: MemoryTasks,
+}
+
+impl MemoryTaskSource {
+    /// Initialise a source of tasks from the given in-memory queues.
+    pub(crate) fn new(tasks: MemoryTasks, changes: watch::Receiver<()>) -> MemoryTaskSource {
+        MemoryTaskSource {
+            changes,
+            subscriptions: Default::default(),
+            tasks,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TaskSourceBackend for MemoryTaskSource {
+    async fn next(&mut self, _: &Context, capacity: &QueueCapacity) -> Result<ReceivedTask> {
+        let queues: HashMap<String, &'static Queue> = self
+            .subscriptions
+            .iter()
+            .filter(|(queue, _)| !capacity.is_full(queue))
+            .map(|(queue, config)| (queue.clone(), *config))
+            .collect();
+        loop {
+            // Mark changes as seen before looking for tasks so none are missed.
+            self.changes.borrow_and_update();
+            let now = OffsetDateTime::now_utc();
+            let wake = {
+                let mut state = self.tasks.access();
+                let moved = state.sweep(now);
+                crate::telemetry::DLQ_MOVED.inc_by(moved as f64);
+                if let Some(task) = state.claim(now, &queues) {
+                    return Ok(task);
+                }
+                state.next_wake(now, &queues)
+            };
+
+            // Wait for tasks to change or for the next task to become due.
+            let wait = wake
+                .map(|wake| std::time::Duration::try_from(wake - now).unwrap_or_default())
+                .unwrap_or_default();
+            tokio::select! {
+                _ = self.changes.changed() => (),
+                _ = tokio::time::sleep(wait), if wake.is_some() => (),
+            }
+        }
+    }
+
+    async fn subscribe(&mut self, _: &Context, queue: &'static Queue) -> Result<()> {
+        self.subscriptions.insert(queue.queue.clone(), queue);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use once_cell::sync::Lazy;
+
+    use replicore_tasks::conf::Queue;
+    use replicore_tasks::execute::QueueCapacity;
+    use replicore_tasks::execute::TaskAck;
+    use replicore_tasks::execute::TaskSource;
+    use replicore_tasks::execute::TEST_QUEUE;
+    use replicore_tasks::execute::TEST_QUEUE_ALTERNATE;
+    use replicore_tasks::submit::TaskSubmission;
+    use replicore_tasks::submit::Tasks;
+
+    use crate::MemoryTasks;
+
+    const NEXT_TIMEOUT: Duration = Duration::from_millis(20);
+
+    /// Queue with a retry timeout long enough to heartbeat tasks reliably.
+    static HEARTBEAT_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
+        queue: String::from("UNIT_TEST_HEARTBEAT"),
+        max_execution_time: None,
+        retry_count: 2,
+        retry_timeout: Duration::from_millis(200),
+    });
+
+    #[tokio::test]
+    async fn next_delayed_task() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+
+        let task = TaskSubmission::new(&TEST_QUEUE, &42)
+            .unwrap()
+            .delay(Duration::from_millis(50));
+        tasks.submit(&context, task).await.unwrap();
+
+        // Delayed tasks are not delivered before their time.
+        let next = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context)).await;
+        assert!(next.is_err());
+        let next = tokio::time::timeout(Duration::from_millis(100), source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.id, "1");
+    }
+
+    #[tokio::test]
+    async fn next_full_queues() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+
+        let task = TaskSubmission::new(&TEST_QUEUE, &1).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+        let task = TaskSubmission::new(&TEST_QUEUE_ALTERNATE, &2).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+
+        let capacity = QueueCapacity::from_iter([(TEST_QUEUE.queue.clone(), 0)]);
+        let next = tokio::time::timeout(NEXT_TIMEOUT, source.next_within(&context, &capacity))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.queue.queue, TEST_QUEUE_ALTERNATE.queue);
+
+        let next =
+            tokio::time::timeout(NEXT_TIMEOUT, source.next_within(&context, &capacity)).await;
+        assert!(next.is_err());
+    }
+
+    #[tokio::test]
+    async fn next_waits_for_submission() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+
+        let submit = async {
+            tokio::time::sleep(NEXT_TIMEOUT).await;
+            let task = TaskSubmission::new(&TEST_QUEUE, &42).unwrap();
+            tasks.submit(&context, task).await.unwrap();
+        };
+        let next = tokio::time::timeout(Duration::from_millis(100), source.next(&context));
+        let (next, _) = tokio::join!(next, submit);
+        assert_eq!(next.unwrap().unwrap().id, "1");
+    }
+
+    #[tokio::test]
+    async fn redeliver_after_retry_timeout() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+
+        let task = TaskSubmission::new(&TEST_QUEUE, &42).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+        let first = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Tasks are not acknowledged so they are delivered again after the retry timeout.
+        let next = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context)).await;
+        assert!(next.is_err());
+        let second = tokio::time::timeout(Duration::from_millis(100), source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.attempt, 2);
+
+        let stats = tasks.stats(&context, 1).await.unwrap();
+        assert_eq!(stats[0].in_flight, 1);
+        assert_eq!(stats[0].previews[0].attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn heartbeat_postpones_redelivery() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let ack = TaskAck::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &HEARTBEAT_QUEUE).await.unwrap();
+
+        let task = TaskSubmission::new(&HEARTBEAT_QUEUE, &42).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Heartbeat the task before the retry timeout so it is not redelivered.
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        ack.heartbeat(&context, &task).await.unwrap();
+        let next = tokio::time::timeout(Duration::from_millis(100), source.next(&context)).await;
+        assert!(next.is_err());
+    }
+
+    #[tokio::test]
+    async fn outdated_attempt_ack_ignored() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let ack = TaskAck::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+
+        // Fetch a task and let it be delivered again after the retry timeout.
+        let task = TaskSubmission::new(&TEST_QUEUE, &42).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+        let outdated = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        let current = tokio::time::timeout(Duration::from_millis(100), source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(outdated.id, current.id);
+        assert_eq!(current.attempt, 2);
+        let before = backend.access().inspect(&current.id).unwrap();
+
+        // Acknowledge the outdated attempt and check the task is left alone.
+        let error = anyhow::anyhow!("outdated attempt failed");
+        ack.heartbeat(&context, &outdated).await.unwrap();
+        ack.failed(&context, &outdated, &error).await.unwrap();
+        ack.done(&context, &outdated).await.unwrap();
+        let (attempts, last_error, next_retry) = backend.access().inspect(&current.id).unwrap();
+        assert_eq!(attempts, 2);
+        assert!(last_error.is_none());
+        assert_eq!(next_retry, before.2);
+    }
+}
@@ -0,0 +1,364 @@
+//! Tasks and dead-letter queue records kept in memory.
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use opentelemetry_api::Context as OTelContext;
+use serde_json::Value as Json;
+use time::OffsetDateTime;
+
+use replicore_tasks::conf::Queue;
+use replicore_tasks::conf::RunTaskAs;
+use replicore_tasks::execute::ReceivedTask;
+use replicore_tasks::submit::TaskSubmission;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
+use replicore_tasks_models::TaskPreview;
+
+/// Task waiting on a queue to be executed.
+struct QueuedTask {
+    /// Number of times the task was delivered for execution.
+    attempts: u32,
+
+    /// Key to de-duplicate submissions of the task while it is pending.
+    dedup_key: Option<String>,
+
+    /// Time of the last delivery attempt for the task.
+    last_attempt: Option<OffsetDateTime>,
+
+    /// Error reported by the last failed execution attempt.
+    last_error: Option<String>,
+
+    /// Time after which the task can be delivered (again).
+    next_retry: Option<OffsetDateTime>,
+
+    /// Payload submitted as part of this task.
+    payload: Json,
+
+    /// ID of the queue the task was submitted to.
+    queue: String,
+
+    /// Remaining delivery attempts, the task is exhausted when this is negative.
+    retries: i32,
+
+    /// Time to wait before a delivered task can be delivered again.
+    retry_delay: Duration,
+
+    /// Entity to use for authentication and authorisation when the task actually executes.
+    run_as: Option<RunTaskAs>,
+
+    /// Time the task was submitted.
+    submitted: OffsetDateTime,
+
+    /// OpenTelemetry context for trace data propagation.
+    trace: Option<OTelContext>,
+}
+
+impl QueuedTask {
+    /// Check if the task has no delivery attempts left and is waiting to move to the DLQ.
+    fn exhausted(&self, now: OffsetDateTime) -> bool {
+        self.retries < 0 && self.next_retry.map(|next| next <= now).unwrap_or(true)
+    }
+
+    /// Check if the task can be delivered for execution.
+    fn due(&self, now: OffsetDateTime) -> bool {
+        self.retries >= 0 && self.next_retry.map(|next| next <= now).unwrap_or(true)
+    }
+}
+
+/// Task that exhausted all delivery attempts, with the time it was exhausted.
+struct ExhaustedTask {
+    exhausted: OffsetDateTime,
+    task: QueuedTask,
+}
+
+/// Tasks and dead-letter queue records kept in memory.
+///
+/// Tasks are identified by an increasing number so they are delivered in submission order.
+#[derive(Default)]
+pub struct MemoryTasksState {
+    dlq: BTreeMap<u64, ExhaustedTask>,
+    last_id: u64,
+    tasks: BTreeMap<u64, QueuedTask>,
+}
+
+impl MemoryTasksState {
+    /// Deliver the next due task from the given queues for execution.
+    ///
+    /// Delivered tasks are redelivered after their queue retry timeout unless acknowledged.
+    pub fn claim(
+        &mut self,
+        now: OffsetDateTime,
+        queues: &HashMap<String, &'static Queue>,
+    ) -> Option<ReceivedTask> {
+        let (id, task) = self
+            .tasks
+            .iter_mut()
+            .find(|(_, task)| queues.contains_key(&task.queue) && task.due(now))?;
+        task.retries -= 1;
+        task.next_retry = Some(now + task.retry_delay);
+        task.attempts += 1;
+        task.last_attempt = Some(now);
+        let queue = *queues
+            .get(&task.queue)
+            .expect("claimed task on unsubscribed queue");
+        let received = ReceivedTask {
+            id: id.to_string(),
+            attempt: task.attempts,
+            dedup_key: task.dedup_key.clone(),
+            payload: task.payload.clone(),
+            queue,
+            run_as: task.run_as.clone(),
+            trace: task.trace.clone(),
+        };
+        Some(received)
+    }
+
+    /// Remove a completed task from its queue.
+    ///
+    /// Acknowledgements from outdated attempts are ignored as the task was delivered again.
+    pub fn done(&mut self, id: &str, attempt: u32) {
+        let id = match parse_id(id) {
+            None => return,
+            Some(id) => id,
+        };
+        if self.tasks.get(&id).map(|task| task.attempts) == Some(attempt) {
+            self.tasks.remove(&id);
+        }
+    }
+
+    /// List tasks in the dead-letter queue.
+    pub fn dlq_list(&self) -> Vec<DeadLetterTask> {
+        self.dlq
+            .iter()
+            .map(|(id, task)| dead_letter(*id, task))
+            .collect()
+    }
+
+    /// Lookup a task in the dead-letter queue.
+    pub fn dlq_lookup(&self, id: &str) -> Option<DeadLetterTask> {
+        let id = parse_id(id)?;
+        self.dlq.get(&id).map(|task| dead_letter(id, task))
+    }
+
+    /// Remove a task from the dead-letter queue, or all tasks if no ID is given.
+    pub fn dlq_purge(&mut self, id: Option<&str>) -> u64 {
+        match id {
+            None => {
+                let count = self.dlq.len();
+                self.dlq.clear();
+                count as u64
+            }
+            Some(id) => {
+                let removed = parse_id(id).and_then(|id| self.dlq.remove(&id));
+                u64::from(removed.is_some())
+            }
+        }
+    }
+
+    /// Move a task from the dead-letter queue back onto its queue.
+    ///
+    /// Requeued tasks are given as many delivery attempts as they had before they were exhausted.
+    pub fn dlq_requeue(&mut self, id: &str) -> bool {
+        let exhausted = match parse_id(id).and_then(|id| self.dlq.remove_entry(&id)) {
+            None => return false,
+            Some(exhausted) => exhausted,
+        };
+        let (id, ExhaustedTask { task, .. }) = exhausted;
+        let task = QueuedTask {
+            attempts: 0,
+            dedup_key: None,
+            last_attempt: None,
+            last_error: None,
+            next_retry: None,
+            retries: task.attempts.saturating_sub(1) as i32,
+            ..task
+        };
+        self.tasks.insert(id, task);
+        true
+    }
+
+    /// Record the error that caused an execution attempt of the task to fail.
+    ///
+    /// Exhausted tasks are made due so they move to the dead-letter queue promptly.
+    /// Failures from outdated attempts are ignored as the task was delivered again.
+    pub fn failed(&mut self, id: &str, attempt: u32, error: String, now: OffsetDateTime) {
+        let task = match parse_id(id).and_then(|id| self.tasks.get_mut(&id)) {
+            Some(task) if task.attempts == attempt => task,
+            _ => return,
+        };
+        task.last_error = Some(error);
+        if task.retries < 0 {
+            task.next_retry = Some(now);
+        }
+    }
+
+    /// Postpone redelivery of a running task by its retry delay.
+    ///
+    /// Heartbeats from outdated attempts are ignored as the task was delivered again.
+    pub fn heartbeat(&mut self, id: &str, attempt: u32, now: OffsetDateTime) {
+        let task = parse_id(id).and_then(|id| self.tasks.get_mut(&id));
+        if let Some(task) = task.filter(|task| task.attempts == attempt) {
+            task.next_retry = Some(now + task.retry_delay);
+        }
+    }
+
+    /// Inspect the delivery attempts, last error and next retry of a queued task.
+    #[cfg(test)]
+    pub fn inspect(&self, id: &str) -> Option<(u32, Option<String>, Option<OffsetDateTime>)> {
+        let task = parse_id(id).and_then(|id| self.tasks.get(&id))?;
+        Some((task.attempts, task.last_error.clone(), task.next_retry))
+    }
+
+    /// Earliest time a task on the given queues may become due, or exhausted, if any.
+    pub fn next_wake(
+        &self,
+        now: OffsetDateTime,
+        queues: &HashMap<String, &'static Queue>,
+    ) -> Option<OffsetDateTime> {
+        self.tasks
+            .values()
+            .filter(|task| task.retries < 0 || queues.contains_key(&task.queue))
+            .filter_map(|task| task.next_retry)
+            .filter(|next| *next > now)
+            .min()
+    }
+
+    /// Summarise the state of tasks on each queue with tasks in them.
+    pub fn stats(&self, now: OffsetDateTime, previews: usize) -> Vec<QueueStats> {
+        let mut stats: BTreeMap<String, QueueStats> = BTreeMap::new();
+        for (id, task) in &self.tasks {
+            let queue = stats
+                .entry(task.queue.clone())
+                .or_insert_with(|| empty_stats(&task.queue));
+            if task.attempts == 0 {
+                queue.pending += 1;
+            } else if task.next_retry.map(|next| next > now).unwrap_or(false) {
+                queue.in_flight += 1;
+            } else if task.retries >= 0 {
+                queue.retrying += 1;
+            } else {
+                queue.exhausted += 1;
+            }
+
+            let age = (now - task.submitted).whole_seconds().max(0) as u64;
+            let oldest = queue.oldest_task_age_secs.get_or_insert(age);
+            *oldest = age.max(*oldest);
+
+            if queue.previews.len() < previews {
+                queue.previews.push(TaskPreview {
+                    id: id.to_string(),
+                    attempts: task.attempts,
+                    payload: task.payload.to_string(),
+                    submitted_time: Some(task.submitted),
+                });
+            }
+        }
+
+        // Include tasks in the dead-letter queue in the exhausted count.
+        for exhausted in self.dlq.values() {
+            let queue = &exhausted.task.queue;
+            let queue = stats
+                .entry(queue.clone())
+                .or_insert_with(|| empty_stats(queue));
+            queue.exhausted += 1;
+        }
+        stats.into_values().collect()
+    }
+
+    /// Add a task onto its queue, unless it was merged into a pending duplicate.
+    ///
+    /// Returns `true` if the task was merged into an existing task.
+    pub fn submit(&mut self, task: TaskSubmission, now: OffsetDateTime) -> bool {
+        // Merge a submission into a pending task with the same key,
+        // keeping the earliest delivery time.
+        if let Some(dedup_key) = &task.dedup_key {
+            let pending = self.tasks.values_mut().find(|pending| {
+                pending.attempts == 0
+                    && pending.queue == task.queue.queue
+                    && pending.dedup_key.as_ref() == Some(dedup_key)
+            });
+            if let Some(pending) = pending {
+                pending.next_retry = match (pending.next_retry, task.not_before) {
+                    (Some(current), Some(not_before)) => Some(current.min(not_before)),
+                    _ => None,
+                };
+                return true;
+            }
+        }
+
+        // Delayed tasks are stored as if waiting for a retry so they are not delivered early.
+        self.last_id += 1;
+        let queued = QueuedTask {
+            attempts: 0,
+            dedup_key: task.dedup_key,
+            last_attempt: None,
+            last_error: None,
+            next_retry: task.not_before,
+            payload: task.payload,
+            queue: task.queue.queue.clone(),
+            retries: i32::from(task.queue.retry_count),
+            retry_delay: task.queue.retry_timeout,
+            run_as: task.run_as,
+            submitted: now,
+            trace: task.trace,
+        };
+        self.tasks.insert(self.last_id, queued);
+        false
+    }
+
+    /// Move exhausted tasks to the dead-letter queue.
+    ///
+    /// Returns the number of tasks moved.
+    pub fn sweep(&mut self, now: OffsetDateTime) -> u64 {
+        let exhausted: Vec<u64> = self
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.exhausted(now))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &exhausted {
+            if let Some(task) = self.tasks.remove(id) {
+                let task = ExhaustedTask {
+                    exhausted: now,
+                    task,
+                };
+                self.dlq.insert(*id, task);
+            }
+        }
+        exhausted.len() as u64
+    }
+}
+
+/// Convert an exhausted task into a [`DeadLetterTask`] model.
+fn dead_letter(id: u64, exhausted: &ExhaustedTask) -> DeadLetterTask {
+    let task = &exhausted.task;
+    DeadLetterTask {
+        id: id.to_string(),
+        attempts: task.attempts,
+        exhausted_time: exhausted.exhausted,
+        last_attempt_time: task.last_attempt,
+        last_error: task.last_error.clone(),
+        payload: task.payload.clone(),
+        queue: task.queue.clone(),
+        submitted_time: Some(task.submitted),
+    }
+}
+
+/// Initialise the summary of a queue with no tasks.
+fn empty_stats(queue: &str) -> QueueStats {
+    QueueStats {
+        queue: queue.to_string(),
+        exhausted: 0,
+        in_flight: 0,
+        oldest_task_age_secs: None,
+        pending: 0,
+        previews: Vec::new(),
+        retrying: 0,
+    }
+}
+
+/// Parse task IDs, ignoring IDs that could not have been generated by the backend.
+fn parse_id(id: &str) -> Option<u64> {
+    id.parse().ok()
+}
@@ -0,0 +1,218 @@
+//! Submit and acknowledge tasks queued in memory.
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tokio::sync::watch;
+
+use replicore_context::Context;
+use replicore_tasks::execute::ReceivedTask;
+use replicore_tasks::execute::TaskAckBackend;
+use replicore_tasks::submit::TaskSubmission;
+use replicore_tasks::submit::TasksBackend;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
+
+use crate::state::MemoryTasksState;
+use crate::MemoryTaskSource;
+
+/// Implementation of the [`TasksBackend`] and [`TaskAckBackend`] interfaces using memory.
+///
+/// Clones of the backend share the same queues.
+#[derive(Clone)]
+pub struct MemoryTasks {
+    /// Notify task sources of changes to queued tasks.
+    changes: Arc<watch::Sender<()>>,
+
+    /// Shared in-memory queues.
+    state: Arc<Mutex<MemoryTasksState>>,
+}
+
+impl MemoryTasks {
+    /// Create a [`MemoryTaskSource`] to receive tasks from these queues.
+    pub fn source(&self) -> MemoryTaskSource {
+        MemoryTaskSource::new(self.clone(), self.changes.subscribe())
+    }
+}
+
+impl Default for MemoryTasks {
+    fn default() -> Self {
+        let (changes, _) = watch::channel(());
+        MemoryTasks {
+            changes: Arc::new(changes),
+            state: Default::default(),
+        }
+    }
+}
+
+impl MemoryTasks {
+    /// Lock and access the shared queues.
+    pub(crate) fn access(&self) -> MutexGuard<MemoryTasksState> {
+        self.state.lock().expect("MemoryTasks::state lock poisoned")
+    }
+
+    /// Wake up task sources waiting for tasks to become available.
+    fn notify(&self) {
+        self.changes.send_replace(());
+    }
+}
+
+#[async_trait::async_trait]
+impl TaskAckBackend for MemoryTasks {
+    async fn done(&self, _: &Context, task: &ReceivedTask) -> Result<()> {
+        self.access().done(&task.id, task.attempt);
+        Ok(())
+    }
+
+    async fn failed(&self, _: &Context, task: &ReceivedTask, error: &anyhow::Error) -> Result<()> {
+        let error = format!("{:#}", error);
+        let now = OffsetDateTime::now_utc();
+        self.access().failed(&task.id, task.attempt, error, now);
+        self.notify();
+        Ok(())
+    }
+
+    async fn heartbeat(&self, _: &Context, task: &ReceivedTask) -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        self.access().heartbeat(&task.id, task.attempt, now);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TasksBackend for MemoryTasks {
+    async fn dlq_list(&self, _: &Context) -> Result<Vec<DeadLetterTask>> {
+        Ok(self.access().dlq_list())
+    }
+
+    async fn dlq_lookup(&self, _: &Context, id: &str) -> Result<Option<DeadLetterTask>> {
+        Ok(self.access().dlq_lookup(id))
+    }
+
+    async fn dlq_purge(&self, _: &Context, id: Option<&str>) -> Result<u64> {
+        Ok(self.access().dlq_purge(id))
+    }
+
+    async fn dlq_requeue(&self, _: &Context, id: &str) -> Result<bool> {
+        let requeued = self.access().dlq_requeue(id);
+        if requeued {
+            self.notify();
+        }
+        Ok(requeued)
+    }
+
+    async fn stats(&self, _: &Context, previews: usize) -> Result<Vec<QueueStats>> {
+        let now = OffsetDateTime::now_utc();
+        Ok(self.access().stats(now, previews))
+    }
+
+    async fn submit(&self, _: &Context, task: TaskSubmission) -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let merged = self.access().submit(task, now);
+        if merged {
+            crate::telemetry::SUBMIT_DEDUP.inc();
+        }
+        self.notify();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use replicore_tasks::execute::TaskAck;
+    use replicore_tasks::execute::TaskSource;
+    use replicore_tasks::execute::TEST_QUEUE;
+    use replicore_tasks::execute::TEST_QUEUE_ALTERNATE;
+    use replicore_tasks::submit::TaskSubmission;
+    use replicore_tasks::submit::Tasks;
+
+    use super::MemoryTasks;
+
+    const NEXT_TIMEOUT: Duration = Duration::from_millis(20);
+
+    #[tokio::test]
+    async fn submit_and_done() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let ack = TaskAck::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+
+        let task = TaskSubmission::new(&TEST_QUEUE, &42).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "1");
+        assert_eq!(task.decode::<u64>().unwrap(), 42);
+
+        ack.done(&context, &task).await.unwrap();
+        let stats = tasks.stats(&context, 0).await.unwrap();
+        assert!(stats.is_empty());
+    }
+
+    #[tokio::test]
+    async fn submit_dedup_pending() {
+        let context = replicore_context::Context::fixture();
+        let tasks = Tasks::from(MemoryTasks::default());
+        for _ in 0..3 {
+            let task = TaskSubmission::new(&TEST_QUEUE, &42)
+                .unwrap()
+                .dedup_key("test");
+            tasks.submit(&context, task).await.unwrap();
+        }
+
+        let stats = tasks.stats(&context, 5).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].pending, 1);
+        assert_eq!(stats[0].previews.len(), 1);
+        assert_eq!(stats[0].previews[0].payload, "42");
+    }
+
+    #[tokio::test]
+    async fn exhausted_to_dlq_and_requeue() {
+        let context = replicore_context::Context::fixture();
+        let backend = MemoryTasks::default();
+        let tasks = Tasks::from(backend.clone());
+        let ack = TaskAck::from(backend.clone());
+        let mut source = TaskSource::from(backend.source());
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+
+        // Fail the only delivery attempt for the task.
+        let task = TaskSubmission::new(&TEST_QUEUE_ALTERNATE, &42).unwrap();
+        tasks.submit(&context, task).await.unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        let error = anyhow::anyhow!("test failure");
+        ack.failed(&context, &task, &error).await.unwrap();
+
+        // Exhausted tasks are not redelivered and move to the DLQ.
+        let next = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context)).await;
+        assert!(next.is_err());
+        let dlq = tasks.dlq_list(&context).await.unwrap();
+        assert_eq!(dlq.len(), 1);
+        assert_eq!(dlq[0].id, "1");
+        assert_eq!(dlq[0].attempts, 1);
+        assert_eq!(dlq[0].last_error.as_deref(), Some("test failure"));
+
+        // Requeued tasks are delivered again.
+        assert!(tasks.dlq_requeue(&context, "1").await.unwrap());
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "1");
+        assert!(tasks.dlq_list(&context).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,43 @@
+//! Telemetry related to the in-memory tasks implementation.
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use prometheus::Counter;
+
+/// Total number of tasks moved to the dead-letter queue.
+pub static DLQ_MOVED: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_memory_dlq_moved",
+        "Total number of tasks moved to the dead-letter queue",
+    )
+    .expect("failed to initialise DLQ_MOVED counter")
+});
+
+/// Total number of task submissions merged into an already pending task.
+pub static SUBMIT_DEDUP: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_memory_submit_deduplicated",
+        "Total number of task submissions merged into an already pending task",
+    )
+    .expect("failed to initialise SUBMIT_DEDUP counter")
+});
+
+/// Ensure metrics are registered only once.
+static METRICS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// The first time this method is called it will register the in-memory tasks backend metrics.
+pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
+    // Skip registration if already done before.
+    if METRICS_REGISTERED.swap(true, Ordering::AcqRel) {
+        return Ok(());
+    }
+
+    let collectors: [Box<dyn prometheus::core::Collector>; 2] =
+        [Box::new(DLQ_MOVED.clone()), Box::new(SUBMIT_DEDUP.clone())];
+    for collector in collectors {
+        reg.register(collector)?;
+    }
+    Ok(())
+}
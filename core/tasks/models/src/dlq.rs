@@ -0,0 +1,54 @@
+//! Data models for background tasks moved to the dead-letter queue.
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value as Json;
+use time::OffsetDateTime;
+
+/// Background task that exhausted all delivery attempts without completing.
+///
+/// Exhausted tasks are moved to a dead-letter queue so they are not lost and can be
+/// inspected, requeued for execution or purged by users.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetterTask {
+    /// ID of the task (as determined by the queuing backend).
+    pub id: String,
+
+    /// Number of times the task was delivered for execution.
+    pub attempts: u32,
+
+    /// Time the task exhausted all delivery attempts.
+    #[serde(with = "time::serde::rfc3339")]
+    pub exhausted_time: OffsetDateTime,
+
+    /// Time of the last delivery attempt for the task, if known.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_attempt_time: Option<OffsetDateTime>,
+
+    /// Error reported by the last failed execution attempt, if any was reported.
+    #[serde(default)]
+    pub last_error: Option<String>,
+
+    /// Payload submitted as part of this task.
+    pub payload: Json,
+
+    /// ID of the queue the task was submitted to.
+    pub queue: String,
+
+    /// Time the task was submitted, if known.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub submitted_time: Option<OffsetDateTime>,
+}
+
+/// List of tasks in the dead-letter queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetterList {
+    /// Tasks in the dead-letter queue.
+    pub items: Vec<DeadLetterTask>,
+}
+
+/// Result of purging tasks from the dead-letter queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetterPurged {
+    /// Number of tasks removed from the dead-letter queue.
+    pub count: u64,
+}
@@ -0,0 +1,96 @@
+//! Data models to record the history of background tasks execution.
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+/// Record of an attempt to execute a background task.
+///
+/// Execution records are kept for a bounded number of recent attempts of each task
+/// so failures can be investigated after the fact.
+/// Recurring tasks submitted with the same de-duplication key share their history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskExecution {
+    /// ID of the task (as determined by the queuing backend).
+    pub task_id: String,
+
+    /// Delivery attempt number of the execution, starting at 1.
+    pub attempt: u32,
+
+    /// De-duplication key the task was submitted with, if any.
+    #[serde(default)]
+    pub dedup_key: Option<String>,
+
+    /// Chain of errors reported by the task handler, outermost first.
+    #[serde(default)]
+    pub error: Vec<String>,
+
+    /// Time the execution attempt completed.
+    #[serde(with = "time::serde::rfc3339")]
+    pub finished_time: OffsetDateTime,
+
+    /// Outcome of the execution attempt.
+    pub outcome: TaskOutcome,
+
+    /// Summary of the payload submitted as part of the task.
+    ///
+    /// Large payloads are truncated to limit the size of execution records.
+    pub payload: String,
+
+    /// ID of the queue the task was received from.
+    pub queue: String,
+
+    /// Time the execution attempt started.
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_time: OffsetDateTime,
+
+    /// ID of the OpenTelemetry trace the execution was part of, if traced.
+    #[serde(default)]
+    pub trace_id: Option<String>,
+}
+
+/// List of background task execution records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskExecutionList {
+    /// Execution records, most recent first.
+    pub items: Vec<TaskExecution>,
+}
+
+/// Outcome of a background task execution attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskOutcome {
+    /// The task failed with a permanent error and will not be retried.
+    #[serde(rename = "abandoned")]
+    Abandoned,
+
+    /// The task failed and will be retried if it has delivery attempts left.
+    #[serde(rename = "failed")]
+    Failed,
+
+    /// The task completed successfully.
+    #[serde(rename = "success")]
+    Success,
+
+    /// The task exceeded the maximum execution time for its queue.
+    #[serde(rename = "timeout")]
+    Timeout,
+}
+
+impl TaskExecution {
+    /// Key grouping executions of the same task, or recurring tasks, for retention.
+    ///
+    /// Tasks with a de-duplication key are grouped by it, other tasks by their ID.
+    pub fn history_key(&self) -> &str {
+        self.dedup_key.as_deref().unwrap_or(&self.task_id)
+    }
+}
+
+impl std::fmt::Display for TaskOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Abandoned => write!(f, "ABANDONED"),
+            Self::Failed => write!(f, "FAILED"),
+            Self::Success => write!(f, "SUCCESS"),
+            Self::Timeout => write!(f, "TIMEOUT"),
+        }
+    }
+}
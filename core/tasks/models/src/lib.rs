@@ -0,0 +1,14 @@
+//! Data models for RepliCore Control Plane background tasks related operations.
+mod dlq;
+mod history;
+mod stats;
+
+pub use self::dlq::DeadLetterList;
+pub use self::dlq::DeadLetterPurged;
+pub use self::dlq::DeadLetterTask;
+pub use self::history::TaskExecution;
+pub use self::history::TaskExecutionList;
+pub use self::history::TaskOutcome;
+pub use self::stats::QueueStats;
+pub use self::stats::QueueStatsList;
+pub use self::stats::TaskPreview;
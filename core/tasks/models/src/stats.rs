@@ -0,0 +1,55 @@
+//! Data models to inspect the state of background task queues.
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+/// Summary of the state of tasks on a background task queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStats {
+    /// ID of the queue the summary is for.
+    pub queue: String,
+
+    /// Number of tasks that exhausted all delivery attempts.
+    pub exhausted: u64,
+
+    /// Number of tasks delivered for execution and waiting to complete.
+    pub in_flight: u64,
+
+    /// Age, in seconds, of the oldest task on the queue (if any and known).
+    #[serde(default)]
+    pub oldest_task_age_secs: Option<u64>,
+
+    /// Number of tasks that were never delivered for execution.
+    pub pending: u64,
+
+    /// Preview of the oldest tasks on the queue.
+    #[serde(default)]
+    pub previews: Vec<TaskPreview>,
+
+    /// Number of tasks that failed execution and are waiting to be delivered again.
+    pub retrying: u64,
+}
+
+/// List of background task queue summaries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStatsList {
+    /// Summaries of known background task queues.
+    pub items: Vec<QueueStats>,
+}
+
+/// Short preview of a task on a background task queue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskPreview {
+    /// ID of the task (as determined by the queuing backend).
+    pub id: String,
+
+    /// Number of times the task was delivered for execution.
+    pub attempts: u32,
+
+    /// JSON encoded payload of the task, truncated for previews.
+    pub payload: String,
+
+    /// Time the task was submitted, if known.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub submitted_time: Option<OffsetDateTime>,
+}
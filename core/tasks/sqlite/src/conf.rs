@@ -8,12 +8,20 @@ pub struct Conf {
     /// Path to the SQLite DB file.
     pub path: String,
 
+    /// Maximum number of tasks claimed with each DB query for tasks to execute.
+    #[serde(default = "Conf::default_poll_batch")]
+    pub poll_batch: u32,
+
     /// Delay between DB queries for pending/retry tasks to become available for execution.
     #[serde(default = "Conf::default_poll_delay_s")]
     pub poll_delay_s: u64,
 }
 
 impl Conf {
+    fn default_poll_batch() -> u32 {
+        10
+    }
+
     fn default_poll_delay_s() -> u64 {
         30
     }
@@ -25,6 +33,7 @@ impl Conf {
     {
         Conf {
             path: path.into(),
+            poll_batch: Conf::default_poll_batch(),
             poll_delay_s: Conf::default_poll_delay_s(),
         }
     }
@@ -0,0 +1,29 @@
+-- Track delivery attempts and failures of tasks so exhausted tasks can be reported.
+--  Number of times the task was delivered for execution.
+ALTER TABLE tasks_queue ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+--  Error reported by the last failed execution attempt, if any.
+ALTER TABLE tasks_queue ADD COLUMN last_error TEXT DEFAULT NULL;
+--  EPoc timestamp (in seconds) of the last delivery attempt.
+ALTER TABLE tasks_queue ADD COLUMN last_attempt_ts INTEGER DEFAULT NULL;
+--  EPoc timestamp (in seconds) of the task submission.
+ALTER TABLE tasks_queue ADD COLUMN submitted_ts INTEGER DEFAULT NULL;
+
+-- Tasks that exhausted all delivery attempts without completing.
+-- Tasks keep the ID they had in the queue so they can be tracked across moves.
+CREATE TABLE IF NOT EXISTS tasks_dlq(
+  task_id INTEGER PRIMARY KEY,
+  queue_id TEXT NOT NULL,
+  payload TEXT NOT NULL,
+  run_as TEXT DEFAULT NULL,
+  trace TEXT DEFAULT NULL,
+  retry_delay INTEGER NOT NULL,
+
+  -- Delivery attempts and failure information copied from the queue.
+  attempts INTEGER NOT NULL,
+  last_error TEXT DEFAULT NULL,
+  last_attempt_ts INTEGER DEFAULT NULL,
+  submitted_ts INTEGER DEFAULT NULL,
+
+  --  EPoc timestamp (in seconds) the task was moved to the dead-letter queue.
+  exhausted_ts INTEGER NOT NULL
+);
@@ -0,0 +1,4 @@
+-- Optional de-duplication key to coalesce repeated submissions of the same task.
+--  Pending tasks (never delivered) with the same queue and key absorb new submissions.
+ALTER TABLE tasks_queue ADD COLUMN dedup_key TEXT DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS tasks_queue_dedup_key ON tasks_queue(queue_id, dedup_key);
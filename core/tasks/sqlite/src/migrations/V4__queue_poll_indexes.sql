@@ -0,0 +1,5 @@
+-- Index tasks by queue and delivery time to poll for due tasks without scanning the queue.
+CREATE INDEX IF NOT EXISTS tasks_queue_next_retry ON tasks_queue(queue_id, next_retry);
+
+-- Partial index to find exhausted tasks that need moving to the dead-letter queue.
+CREATE INDEX IF NOT EXISTS tasks_queue_exhausted ON tasks_queue(next_retry) WHERE retries < 0;
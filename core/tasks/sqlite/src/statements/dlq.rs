@@ -0,0 +1,429 @@
+//! Background Tasks operations to manage the dead-letter queue of exhausted tasks.
+use anyhow::Result;
+use opentelemetry_api::trace::FutureExt;
+use time::OffsetDateTime;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_tasks_models::DeadLetterTask;
+
+const LIST_SQL: &str = r#"
+SELECT
+    task_id,
+    queue_id,
+    payload,
+    attempts,
+    last_error,
+    last_attempt_ts,
+    submitted_ts,
+    exhausted_ts
+FROM tasks_dlq
+ORDER BY task_id ASC;
+"#;
+
+const LOOKUP_SQL: &str = r#"
+SELECT
+    task_id,
+    queue_id,
+    payload,
+    attempts,
+    last_error,
+    last_attempt_ts,
+    submitted_ts,
+    exhausted_ts
+FROM tasks_dlq
+WHERE task_id = ?1;
+"#;
+
+const PURGE_ALL_SQL: &str = r#"
+DELETE FROM tasks_dlq;
+"#;
+
+const PURGE_SQL: &str = r#"
+DELETE FROM tasks_dlq
+WHERE task_id = ?1;
+"#;
+
+// Requeued tasks are given as many delivery attempts as they had before they were exhausted.
+const REQUEUE_INSERT_SQL: &str = r#"
+INSERT INTO tasks_queue (
+    task_id,
+    queue_id,
+    payload,
+    run_as,
+    trace,
+    retries,
+    retry_delay,
+    submitted_ts
+)
+SELECT
+    task_id,
+    queue_id,
+    payload,
+    run_as,
+    trace,
+    MAX(attempts - 1, 0),
+    retry_delay,
+    submitted_ts
+FROM tasks_dlq
+WHERE task_id = ?1;
+"#;
+
+const SWEEP_INSERT_SQL: &str = r#"
+INSERT INTO tasks_dlq (
+    task_id,
+    queue_id,
+    payload,
+    run_as,
+    trace,
+    retry_delay,
+    attempts,
+    last_error,
+    last_attempt_ts,
+    submitted_ts,
+    exhausted_ts
+)
+SELECT
+    task_id,
+    queue_id,
+    payload,
+    run_as,
+    trace,
+    retry_delay,
+    attempts,
+    last_error,
+    last_attempt_ts,
+    submitted_ts,
+    unixepoch()
+FROM tasks_queue
+WHERE
+    retries < 0 AND
+    next_retry <= unixepoch();
+"#;
+
+const SWEEP_DELETE_SQL: &str = r#"
+DELETE FROM tasks_queue
+WHERE
+    retries < 0 AND
+    task_id IN (SELECT task_id FROM tasks_dlq);
+"#;
+
+/// SQL extracted dead-letter task object return from SQLite connection calls.
+#[derive(Debug)]
+struct SQLDeadLetterTask {
+    task_id: i64,
+    queue_id: String,
+    payload: String,
+    attempts: u32,
+    last_error: Option<String>,
+    last_attempt_ts: Option<i64>,
+    submitted_ts: Option<i64>,
+    exhausted_ts: i64,
+}
+
+impl SQLDeadLetterTask {
+    /// Extract a dead-letter task from an SQL result row.
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<SQLDeadLetterTask> {
+        let task = SQLDeadLetterTask {
+            task_id: row.get("task_id")?,
+            queue_id: row.get("queue_id")?,
+            payload: row.get("payload")?,
+            attempts: row.get("attempts")?,
+            last_error: row.get("last_error")?,
+            last_attempt_ts: row.get("last_attempt_ts")?,
+            submitted_ts: row.get("submitted_ts")?,
+            exhausted_ts: row.get("exhausted_ts")?,
+        };
+        Ok(task)
+    }
+
+    /// Decode SQL data into a [`DeadLetterTask`] model.
+    fn decode(self) -> Result<DeadLetterTask> {
+        let task = DeadLetterTask {
+            id: self.task_id.to_string(),
+            attempts: self.attempts,
+            exhausted_time: OffsetDateTime::from_unix_timestamp(self.exhausted_ts)?,
+            last_attempt_time: self
+                .last_attempt_ts
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()?,
+            last_error: self.last_error,
+            payload: replisdk::utils::encoding::decode_serde(&self.payload)?,
+            queue: self.queue_id,
+            submitted_time: self
+                .submitted_ts
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()?,
+        };
+        Ok(task)
+    }
+}
+
+/// List tasks in the dead-letter queue.
+pub async fn list(_: &Context, connection: &Connection) -> Result<Vec<DeadLetterTask>> {
+    let (err_count, timer) = crate::telemetry::observe_op("task.dlqList");
+    let trace = crate::telemetry::trace_op("task.dlqList");
+    let tasks = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(LIST_SQL)?;
+            let mut rows = statement.query([])?;
+            let mut tasks = Vec::new();
+            while let Some(row) = rows.next()? {
+                tasks.push(SQLDeadLetterTask::from_row(row)?);
+            }
+            Ok(tasks)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    drop(timer);
+    tasks.into_iter().map(SQLDeadLetterTask::decode).collect()
+}
+
+/// Lookup a task in the dead-letter queue by ID.
+pub async fn lookup(
+    _: &Context,
+    connection: &Connection,
+    id: &str,
+) -> Result<Option<DeadLetterTask>> {
+    let id = id.to_string();
+    let (err_count, timer) = crate::telemetry::observe_op("task.dlqLookup");
+    let trace = crate::telemetry::trace_op("task.dlqLookup");
+    let task = connection
+        .call(move |connection| {
+            let mut statement = connection.prepare_cached(LOOKUP_SQL)?;
+            let mut rows = statement.query([id])?;
+            let task = match rows.next()? {
+                None => None,
+                Some(row) => Some(SQLDeadLetterTask::from_row(row)?),
+            };
+            Ok(task)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    drop(timer);
+    task.map(SQLDeadLetterTask::decode).transpose()
+}
+
+/// Remove a task from the dead-letter queue, or all tasks if no ID is given.
+pub async fn purge(_: &Context, connection: &Connection, id: Option<&str>) -> Result<u64> {
+    let id = id.map(str::to_string);
+    let (err_count, _timer) = crate::telemetry::observe_op("task.dlqPurge");
+    let trace = crate::telemetry::trace_op("task.dlqPurge");
+    let count = connection
+        .call(move |connection| {
+            let count = match id {
+                None => connection.execute(PURGE_ALL_SQL, [])?,
+                Some(id) => connection.execute(PURGE_SQL, [id])?,
+            };
+            Ok(count)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(count as u64)
+}
+
+/// Move a task from the dead-letter queue back onto its queue.
+pub async fn requeue(_: &Context, connection: &Connection, id: &str) -> Result<bool> {
+    let id = id.to_string();
+    let (err_count, _timer) = crate::telemetry::observe_op("task.dlqRequeue");
+    let trace = crate::telemetry::trace_op("task.dlqRequeue");
+    let found = connection
+        .call(move |connection| {
+            let transaction = connection.transaction()?;
+            let count = transaction.execute(REQUEUE_INSERT_SQL, [&id])?;
+            transaction.execute(PURGE_SQL, [&id])?;
+            transaction.commit()?;
+            Ok(count > 0)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(found)
+}
+
+/// Move tasks that exhausted all delivery attempts from the queue to the dead-letter queue.
+pub async fn sweep(_: &Context, connection: &Connection) -> Result<()> {
+    let (err_count, _timer) = crate::telemetry::observe_op("task.dlqSweep");
+    let trace = crate::telemetry::trace_op("task.dlqSweep");
+    let count = connection
+        .call(move |connection| {
+            let transaction = connection.transaction()?;
+            let count = transaction.execute(SWEEP_INSERT_SQL, [])?;
+            transaction.execute(SWEEP_DELETE_SQL, [])?;
+            transaction.commit()?;
+            Ok(count)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    crate::telemetry::DLQ_MOVED.inc_by(count as f64);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_tasks::execute::TaskAck;
+    use replicore_tasks::execute::TaskSource;
+    use replicore_tasks::execute::TEST_QUEUE;
+    use replicore_tasks::submit::Tasks;
+
+    const NEXT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(20);
+
+    /// Insert exhausted and pending tasks for tests.
+    async fn insert_tasks(connection: &tokio_rusqlite::Connection) {
+        connection
+            .call(|connection| {
+                connection
+                    .execute(
+                        r#"
+                        INSERT INTO tasks_queue (
+                            queue_id, payload, retries, retry_delay, next_retry,
+                            attempts, last_error
+                        )
+                        VALUES
+                            ("UNIT_TEST", "1", -1, 1, unixepoch() - 10, 3, "test error"),
+                            ("UNIT_TEST", "2", -1, 1, unixepoch() + 3600, 3, NULL),
+                            ("UNIT_TEST", "3", 2, 1, NULL, 0, NULL)
+                        ;
+                        "#,
+                        rusqlite::params![],
+                    )
+                    .unwrap();
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    /// Count the number of tasks in the queue.
+    async fn queue_count(connection: &tokio_rusqlite::Connection) -> u64 {
+        connection
+            .call(move |connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT COUNT(*) FROM tasks_queue;")?;
+                let mut rows = statement.query([])?;
+                let row = rows.next()?.expect("count of table records");
+                let count: u64 = row.get("COUNT(*)")?;
+                Ok(count)
+            })
+            .await
+            .expect("count SQL execution error")
+    }
+
+    #[tokio::test]
+    async fn exhausted_tasks_move_to_dlq() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        insert_tasks(&connection).await;
+
+        super::sweep(&context, &connection).await.unwrap();
+        let tasks = Tasks::from(backend);
+        let dlq = tasks.dlq_list(&context).await.unwrap();
+        assert_eq!(dlq.len(), 1);
+        assert_eq!(dlq[0].id, "1");
+        assert_eq!(dlq[0].attempts, 3);
+        assert_eq!(dlq[0].last_error.as_deref(), Some("test error"));
+        assert_eq!(dlq[0].payload, serde_json::json!(1));
+        assert_eq!(queue_count(&connection).await, 2);
+    }
+
+    #[tokio::test]
+    async fn failed_exhausted_task_moves_to_dlq() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let ack = TaskAck::from(backend.clone());
+        let tasks = Tasks::from(backend.clone());
+        let mut source = TaskSource::from(backend);
+        connection
+            .call(|connection| {
+                connection
+                    .execute(
+                        r#"
+                        INSERT INTO tasks_queue (queue_id, payload, retries, retry_delay)
+                        VALUES ("UNIT_TEST", "null", 0, 3600);
+                        "#,
+                        rusqlite::params![],
+                    )
+                    .unwrap();
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // Receive the task and fail its last attempt.
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        let error = anyhow::anyhow!("root cause").context("task failed");
+        ack.failed(&context, &task, &error).await.unwrap();
+
+        // The next poll moves the task to the DLQ.
+        let next = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context)).await;
+        assert!(next.is_err());
+        let dlq = tasks.dlq_lookup(&context, &task.id).await.unwrap().unwrap();
+        assert_eq!(dlq.attempts, 1);
+        assert_eq!(dlq.last_error.as_deref(), Some("task failed: root cause"));
+        assert!(dlq.last_attempt_time.is_some());
+        assert_eq!(queue_count(&connection).await, 0);
+    }
+
+    #[tokio::test]
+    async fn purge_tasks() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        insert_tasks(&connection).await;
+        super::sweep(&context, &connection).await.unwrap();
+
+        let tasks = Tasks::from(backend);
+        let count = tasks.dlq_purge(&context, Some("2")).await.unwrap();
+        assert_eq!(count, 0);
+        let count = tasks.dlq_purge(&context, Some("1")).await.unwrap();
+        assert_eq!(count, 1);
+        let dlq = tasks.dlq_list(&context).await.unwrap();
+        assert!(dlq.is_empty());
+    }
+
+    #[tokio::test]
+    async fn requeue_task() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        insert_tasks(&connection).await;
+        super::sweep(&context, &connection).await.unwrap();
+
+        let tasks = Tasks::from(backend);
+        assert!(!tasks.dlq_requeue(&context, "42").await.unwrap());
+        assert!(tasks.dlq_requeue(&context, "1").await.unwrap());
+        assert!(tasks.dlq_lookup(&context, "1").await.unwrap().is_none());
+        assert_eq!(queue_count(&connection).await, 3);
+
+        let retries = connection
+            .call(|connection| {
+                let mut statement = connection
+                    .prepare_cached("SELECT retries FROM tasks_queue WHERE task_id = 1;")?;
+                let mut rows = statement.query([])?;
+                let row = rows.next()?.expect("requeued task");
+                let retries: i64 = row.get("retries")?;
+                Ok(retries)
+            })
+            .await
+            .unwrap();
+        assert_eq!(retries, 2);
+    }
+}
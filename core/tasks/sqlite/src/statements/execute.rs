@@ -1,5 +1,7 @@
 //! Background Tasks operations to poll and acknowledge pending tasks.
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::Result;
 use opentelemetry_api::trace::FutureExt;
@@ -13,37 +15,93 @@ use replicore_context::Context;
 use replicore_tasks::conf::Queue;
 use replicore_tasks::execute::ReceivedTask;
 
+use super::Buffer;
+
+// Tasks claimed again since (by attempt number) are left for the new attempt to acknowledge.
 const DELETE_SQL: &str = r#"
 DELETE FROM tasks_queue
-WHERE task_id = ?1;
+WHERE task_id = ?1 AND attempts = ?2;
+"#;
+
+// Exhausted tasks that failed their last attempt are made due so they move to the DLQ promptly.
+//  Tasks claimed again since (by attempt number) are left for the new attempt to acknowledge.
+const FAILED_SQL: &str = r#"
+UPDATE tasks_queue
+SET
+    last_error = ?3,
+    next_retry = CASE WHEN retries < 0 THEN unixepoch() ELSE next_retry END
+WHERE task_id = ?1 AND attempts = ?2;
 "#;
 
+// Running tasks push back their redelivery by the retry delay of the task.
+//  Tasks claimed again since (by attempt number) are not extended by outdated attempts.
+const HEARTBEAT_SQL: &str = r#"
+UPDATE tasks_queue
+SET next_retry = unixepoch() + retry_delay
+WHERE task_id = ?1 AND attempts = ?2;
+"#;
+
+// Claim a batch of due tasks using an update & return statement to avoid race conditions.
+//  Subscribed queues are bound as a JSON object mapping queue IDs to the number of tasks
+//  that can be claimed from them to keep the statement cacheable.
 const GET_NEXT_SQL: &str = r#"
 UPDATE tasks_queue
 SET
     retries = retries - 1,
-    next_retry = unixepoch() + retry_delay
+    next_retry = unixepoch() + retry_delay,
+    attempts = attempts + 1,
+    last_attempt_ts = unixepoch()
 WHERE task_id IN (
-    SELECT task_id FROM tasks_queue
-    WHERE
-        queue_id IN ({%IDS%}) AND
-        retries >= 0 AND
-        (next_retry IS NULL OR next_retry <= unixepoch())
+    SELECT task_id FROM (
+        SELECT
+            task_id,
+            ROW_NUMBER() OVER (PARTITION BY queue_id ORDER BY task_id ASC) AS position,
+            capacity.value AS capacity
+        FROM tasks_queue
+        JOIN json_each(?1) AS capacity ON capacity.key = tasks_queue.queue_id
+        WHERE
+            retries >= 0 AND
+            (next_retry IS NULL OR next_retry <= unixepoch())
+    )
+    WHERE position <= capacity
     ORDER BY task_id ASC
-    LIMIT 1
+    LIMIT ?2
 )
 RETURNING
     task_id,
+    attempts,
+    dedup_key,
     queue_id,
     payload,
     run_as,
     trace
 ;"#;
 
+// Claimed tasks handed out for execution push back their redelivery as heartbeats do.
+//  Tasks claimed again since (by attempt number) or removed are not handed out.
+const HANDOUT_SQL: &str = r#"
+UPDATE tasks_queue
+SET next_retry = unixepoch() + retry_delay
+WHERE task_id = ?1 AND attempts = ?2;
+"#;
+
+// Claimed tasks that were never handed out are given back with their attempt restored.
+//  Tasks claimed again since (by attempt number) or removed are left alone.
+const RELEASE_SQL: &str = r#"
+UPDATE tasks_queue
+SET
+    retries = retries + 1,
+    next_retry = NULL,
+    attempts = attempts - 1
+WHERE task_id = ?1 AND attempts = ?2;
+"#;
+
 /// SQL extracted task object return from SQLite connection calls.
 #[derive(Debug)]
 pub struct SQLReceivedTask {
-    task_id: String,
+    task_id: i64,
+    attempts: u32,
+    dedup_key: Option<String>,
     queue_id: String,
     payload: String,
     run_as: Option<String>,
@@ -52,11 +110,52 @@ pub struct SQLReceivedTask {
 
 pub async fn done(_: &Context, connection: &Connection, task: &ReceivedTask) -> Result<()> {
     let task_id = task.id.clone();
+    let attempt = task.attempt;
     let (err_count, _timer) = crate::telemetry::observe_op("task.next");
     let trace = crate::telemetry::trace_op("task.next");
     connection
         .call(move |connection| {
-            connection.execute(DELETE_SQL, rusqlite::params![task_id])?;
+            connection.execute(DELETE_SQL, rusqlite::params![task_id, attempt])?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+pub async fn failed(
+    _: &Context,
+    connection: &Connection,
+    task: &ReceivedTask,
+    error: &anyhow::Error,
+) -> Result<()> {
+    let task_id = task.id.clone();
+    let attempt = task.attempt;
+    let error = format!("{:#}", error);
+    let (err_count, _timer) = crate::telemetry::observe_op("task.failed");
+    let trace = crate::telemetry::trace_op("task.failed");
+    connection
+        .call(move |connection| {
+            connection.execute(FAILED_SQL, rusqlite::params![task_id, attempt, error])?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+pub async fn heartbeat(_: &Context, connection: &Connection, task: &ReceivedTask) -> Result<()> {
+    let task_id = task.id.clone();
+    let attempt = task.attempt;
+    let (err_count, _timer) = crate::telemetry::observe_op("task.heartbeat");
+    let trace = crate::telemetry::trace_op("task.heartbeat");
+    connection
+        .call(move |connection| {
+            connection.execute(HEARTBEAT_SQL, rusqlite::params![task_id, attempt])?;
             Ok(())
         })
         .count_on_err(err_count)
@@ -66,63 +165,127 @@ pub async fn done(_: &Context, connection: &Connection, task: &ReceivedTask) ->
     Ok(())
 }
 
+/// Mark a claimed task as handed out for execution, if the claim is still valid.
+///
+/// Returns `false` if the task was claimed again or removed since it was claimed.
+pub async fn handout(_: &Context, connection: &Connection, task: &ReceivedTask) -> Result<bool> {
+    let task_id = task.id.clone();
+    let attempt = task.attempt;
+    let (err_count, _timer) = crate::telemetry::observe_op("task.handout");
+    let trace = crate::telemetry::trace_op("task.handout");
+    let updated = connection
+        .call(move |connection| {
+            let updated = connection.execute(HANDOUT_SQL, rusqlite::params![task_id, attempt])?;
+            Ok(updated)
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(updated > 0)
+}
+
+/// Claim due tasks from subscribed queues, up to the number of tasks each queue can take.
+///
+/// Claimed tasks are added to the buffer in the order they were submitted.
+/// The buffer is updated along with the DB so tasks are not lost if the caller stops waiting.
 pub async fn next(
     _: &Context,
     connection: &Connection,
     queues: &HashMap<&'static String, &'static Queue>,
-) -> Result<Option<ReceivedTask>> {
-    // Can't easily pass a list of queues as a SQL parameter so we manually build the SQL.
-    let subscribed = queues
-        .keys()
-        .map(|queue| format!("\"{}\"", queue))
-        .collect::<Vec<_>>()
-        .join(", ");
-    let sql = GET_NEXT_SQL.replace("{%IDS%}", &subscribed);
-
-    // Query the next pending task using an update & return statement to avoid race conditions.
-    let (err_count, timer) = crate::telemetry::observe_op("task.next");
+    capacity: HashMap<&'static String, usize>,
+    limit: u32,
+    buffer: &Buffer,
+) -> Result<usize> {
+    let capacity: HashMap<_, _> = capacity.into_iter().filter(|(_, free)| *free > 0).collect();
+    if capacity.is_empty() || limit == 0 {
+        return Ok(0);
+    }
+    let capacity = serde_json::to_string(&capacity)?;
+    let queues = queues.clone();
+    let buffer = Arc::clone(buffer);
+
+    let (err_count, _timer) = crate::telemetry::observe_op("task.next");
     let trace = crate::telemetry::trace_op("task.next");
-    let task = connection
+    let claimed = connection
         .call(move |connection| {
-            let mut statement = connection.prepare_cached(&sql)?;
-            let mut rows = statement.query([])?;
-            let row = match rows.next()? {
-                None => return Ok(None),
-                Some(row) => row,
-            };
-            let task = SQLReceivedTask {
-                task_id: row.get::<&str, i64>("task_id")?.to_string(),
-                queue_id: row.get("queue_id")?,
-                payload: row.get("payload")?,
-                run_as: row.get("run_as")?,
-                trace: row.get("trace")?,
-            };
-            Ok(Some(task))
+            let mut statement = connection.prepare_cached(GET_NEXT_SQL)?;
+            let mut rows = statement.query(rusqlite::params![capacity, limit])?;
+            let mut tasks = Vec::new();
+            while let Some(row) = rows.next()? {
+                let task = SQLReceivedTask {
+                    task_id: row.get("task_id")?,
+                    attempts: row.get("attempts")?,
+                    dedup_key: row.get("dedup_key")?,
+                    queue_id: row.get("queue_id")?,
+                    payload: row.get("payload")?,
+                    run_as: row.get("run_as")?,
+                    trace: row.get("trace")?,
+                };
+                tasks.push(task);
+            }
+
+            // SQLite does not guarantee the order of RETURNING rows so sort tasks explicitly.
+            tasks.sort_by_key(|task| task.task_id);
+            let claimed = Instant::now();
+            let mut received = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                let task = decode_task(&queues, task)
+                    .map_err(|error| tokio_rusqlite::Error::Other(error.into()))?;
+                received.push((claimed, task));
+            }
+            let count = received.len();
+            buffer
+                .lock()
+                .expect("SQLiteTasks::buffer lock poisoned")
+                .extend(received);
+            Ok(count)
         })
         .count_on_err(err_count)
         .trace_on_err_with_status()
         .with_context(trace)
         .await?;
-    drop(timer);
-    let task = match task {
-        None => return Ok(None),
-        Some(task) => task,
-    };
+    Ok(claimed)
+}
 
+/// Give back a claimed task that was never handed out, if the claim is still valid.
+pub async fn release(_: &Context, connection: &Connection, task: &ReceivedTask) -> Result<()> {
+    let task_id = task.id.clone();
+    let attempt = task.attempt;
+    let (err_count, _timer) = crate::telemetry::observe_op("task.release");
+    let trace = crate::telemetry::trace_op("task.release");
+    connection
+        .call(move |connection| {
+            connection.execute(RELEASE_SQL, rusqlite::params![task_id, attempt])?;
+            Ok(())
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    Ok(())
+}
+
+/// Decode a claimed task returned by SQLite.
+fn decode_task(
+    queues: &HashMap<&'static String, &'static Queue>,
+    task: SQLReceivedTask,
+) -> Result<ReceivedTask> {
     let queue = queues
         .get(&task.queue_id)
         .expect("received task on unsubscribed queue");
     let payload = replisdk::utils::encoding::decode_serde(&task.payload)?;
     let run_as = replisdk::utils::encoding::decode_serde_option(&task.run_as)?;
     let trace = task.trace.map(decode_trace).transpose()?;
-    let received = ReceivedTask {
-        id: task.task_id,
+    Ok(ReceivedTask {
+        id: task.task_id.to_string(),
+        attempt: task.attempts,
+        dedup_key: task.dedup_key,
         payload,
         queue,
         run_as,
         trace,
-    };
-    Ok(Some(received))
+    })
 }
 
 /// Extract an OpenTelemetry context from the encoded task data.
@@ -140,6 +303,7 @@ mod tests {
     use once_cell::sync::Lazy;
 
     use replicore_tasks::conf::Queue;
+    use replicore_tasks::execute::QueueCapacity;
     use replicore_tasks::execute::TaskAck;
     use replicore_tasks::execute::TaskSource;
     use replicore_tasks::execute::TEST_QUEUE_ALTERNATE;
@@ -149,6 +313,7 @@ mod tests {
     /// Fixed queue to submit unit test tasks to or receive them from.
     static EMPTY_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
         queue: String::from("UNIT_TEST_EMPTY"),
+        max_execution_time: None,
         retry_count: 2,
         retry_timeout: Duration::from_millis(50),
     });
@@ -177,6 +342,67 @@ mod tests {
             .unwrap();
     }
 
+    /// List the IDs of tasks with delivery attempts.
+    async fn claimed_tasks(connection: &tokio_rusqlite::Connection) -> Vec<i64> {
+        connection
+            .call(|connection| {
+                let mut statement = connection.prepare_cached(
+                    "SELECT task_id FROM tasks_queue WHERE attempts > 0 ORDER BY task_id;",
+                )?;
+                let claimed = statement
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<_, _>>()?;
+                Ok(claimed)
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn heartbeat_postpones_retry() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let ack = TaskAck::from(backend.clone());
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Fetch the next task and pretend it is due for redelivery.
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        connection
+            .call(|connection| {
+                connection.execute(
+                    "UPDATE tasks_queue SET next_retry = 0 WHERE task_id = ?1;",
+                    ["3"],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // Heartbeat the task and check redelivery is pushed back.
+        ack.heartbeat(&context, &task).await.unwrap();
+        connection
+            .call(|connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT * FROM tasks_queue WHERE task_id = ?1;")?;
+                let mut rows = statement.query(["3"])?;
+                let row = rows.next()?.unwrap();
+                let next_retry: Option<i64> = row.get("next_retry").unwrap();
+                assert!(next_retry.unwrap() > 0);
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn next_task() {
         let backend = crate::statements::tests::sqlite_tasks().await;
@@ -195,6 +421,7 @@ mod tests {
             .unwrap()
             .unwrap();
         assert_eq!(task.id, "3");
+        assert_eq!(task.attempt, 1);
         assert_eq!(task.queue.queue, "UNIT_TEST_ALTERNATE");
 
         // Check the DB is updated as needed.
@@ -216,6 +443,164 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn next_task_batch() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Fetch the next task and check all due tasks were claimed with it.
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "3");
+        let claimed = claimed_tasks(&connection).await;
+        assert_eq!(claimed, [3, 4]);
+
+        // The next task is returned from the buffer of claimed tasks.
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "4");
+    }
+
+    #[tokio::test]
+    async fn next_task_full_queues() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Subscribe to both queues but fill the one with tasks.
+        source.subscribe(&context, &EMPTY_QUEUE).await.unwrap();
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let capacity = QueueCapacity::from_iter([(TEST_QUEUE_ALTERNATE.queue.clone(), 0)]);
+        let task =
+            tokio::time::timeout(NEXT_TIMEOUT, source.next_within(&context, &capacity)).await;
+        assert!(task.is_err());
+
+        // Tasks are fetched again once the queue has free capacity.
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.queue.queue, "UNIT_TEST_ALTERNATE");
+    }
+
+    #[tokio::test]
+    async fn next_task_limited_claims() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Fetch a task from a queue that can only take one more task.
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let capacity = QueueCapacity::from_iter([(TEST_QUEUE_ALTERNATE.queue.clone(), 1)]);
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next_within(&context, &capacity))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "3");
+
+        // Check no more tasks than the queue can take were claimed.
+        let claimed = claimed_tasks(&connection).await;
+        assert_eq!(claimed, [3]);
+    }
+
+    #[tokio::test]
+    async fn release_unused_tasks() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Claim a batch of tasks and release the ones not returned.
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "3");
+        source.release(&context).await.unwrap();
+
+        // Check the released task has its retries and attempts restored.
+        let claimed = claimed_tasks(&connection).await;
+        assert_eq!(claimed, [3]);
+        connection
+            .call(|connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT * FROM tasks_queue WHERE task_id = ?1;")?;
+                let mut rows = statement.query(["4"])?;
+                let row = rows.next()?.unwrap();
+                let retries: i64 = row.get("retries").unwrap();
+                let next_retry: Option<i64> = row.get("next_retry").unwrap();
+                assert_eq!(retries, 0);
+                assert!(next_retry.is_none());
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn skip_reclaimed_buffered_tasks() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Claim a batch of tasks and pretend the buffered one was claimed again elsewhere.
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "3");
+        connection
+            .call(|connection| {
+                connection.execute(
+                    "UPDATE tasks_queue SET attempts = attempts + 1 WHERE task_id = ?1;",
+                    ["4"],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // The stale buffered copy is skipped and the due task is claimed again.
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "4");
+        assert_eq!(task.attempt, 3);
+    }
+
     #[tokio::test]
     async fn next_task_retry() {
         let backend = crate::statements::tests::sqlite_tasks().await;
@@ -260,6 +645,59 @@ mod tests {
         assert!(task.is_err());
     }
 
+    #[tokio::test]
+    async fn outdated_attempt_ack_ignored() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let ack = TaskAck::from(backend.clone());
+        let mut source = TaskSource::from(backend);
+        insert_tasks(&connection).await;
+
+        // Fetch the next task and pretend it was claimed again by another executor.
+        source
+            .subscribe(&context, &TEST_QUEUE_ALTERNATE)
+            .await
+            .unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.id, "3");
+        connection
+            .call(|connection| {
+                connection.execute(
+                    "UPDATE tasks_queue SET attempts = 2, next_retry = 0 WHERE task_id = ?1;",
+                    ["3"],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // Acknowledge the outdated attempt and check the task is left alone.
+        let error = anyhow::anyhow!("outdated attempt failed");
+        ack.heartbeat(&context, &task).await.unwrap();
+        ack.failed(&context, &task, &error).await.unwrap();
+        ack.done(&context, &task).await.unwrap();
+        connection
+            .call(|connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT * FROM tasks_queue WHERE task_id = ?1;")?;
+                let mut rows = statement.query(["3"])?;
+                let row = rows.next()?.unwrap();
+                let attempts: u32 = row.get("attempts").unwrap();
+                let last_error: Option<String> = row.get("last_error").unwrap();
+                let next_retry: Option<i64> = row.get("next_retry").unwrap();
+                assert_eq!(attempts, 2);
+                assert!(last_error.is_none());
+                assert_eq!(next_retry, Some(0));
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn remove_task_on_done() {
         let backend = crate::statements::tests::sqlite_tasks().await;
@@ -1,27 +1,48 @@
 //! SQL statements to implement the [`TasksBackend`] with SQLite.
 use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Result;
 use tokio_rusqlite::Connection;
 
 use replicore_context::Context;
 use replicore_tasks::conf::Queue;
+use replicore_tasks::execute::QueueCapacity;
 use replicore_tasks::execute::ReceivedTask;
 use replicore_tasks::execute::TaskAckBackend;
 use replicore_tasks::execute::TaskSourceBackend;
 use replicore_tasks::submit::TaskSubmission;
 use replicore_tasks::submit::TasksBackend;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
 
+mod dlq;
 mod execute;
+mod stats;
 mod submit;
 
+/// Tasks claimed from the DB but not yet returned, with the time they were claimed at.
+type Buffer = Arc<Mutex<VecDeque<(Instant, ReceivedTask)>>>;
+
 /// Implementation of the [`TasksBackend`] interface using SQLite.
+///
+/// Clones share the buffer of claimed tasks so each task is returned at most once.
 #[derive(Clone)]
 pub struct SQLiteTasks {
+    /// Tasks claimed from the DB but not yet returned (for TaskSourceBackend).
+    buffer: Buffer,
+
     /// Connection to the SQLite DB persisting data.
     connection: Connection,
 
+    /// Maximum number of tasks to claim with each DB query.
+    pub poll_batch: u32,
+
     /// Delay between DB queries for pending/retry tasks to become available for execution.
     pub poll_delay: Duration,
 
@@ -33,32 +54,153 @@ impl SQLiteTasks {
     /// Initialise a new SQLite backed [`TasksBackend`].
     pub fn new(connection: Connection, conf: &crate::Conf) -> Self {
         SQLiteTasks {
+            buffer: Default::default(),
             connection,
+            poll_batch: conf.poll_batch,
             poll_delay: Duration::from_secs(conf.poll_delay_s),
             subscriptions: Default::default(),
         }
     }
 }
 
+impl SQLiteTasks {
+    /// Access the buffer of claimed tasks.
+    fn buffer(&self) -> MutexGuard<VecDeque<(Instant, ReceivedTask)>> {
+        self.buffer
+            .lock()
+            .expect("SQLiteTasks::buffer lock poisoned")
+    }
+
+    /// Compute how many tasks can be claimed from each subscribed queue.
+    ///
+    /// Tasks already in the buffer count against the capacity of their queue.
+    fn claim_capacity(&self, capacity: &QueueCapacity) -> HashMap<&'static String, usize> {
+        let batch = self.poll_batch as usize;
+        let mut claim: HashMap<&'static String, usize> = self
+            .subscriptions
+            .keys()
+            .map(|queue| {
+                let free = capacity.free(queue).unwrap_or(batch).min(batch);
+                (*queue, free)
+            })
+            .collect();
+        for (_, task) in self.buffer().iter() {
+            if let Some(free) = claim.get_mut(&task.queue.queue) {
+                *free = free.saturating_sub(1);
+            }
+        }
+        claim
+    }
+
+    /// Take the oldest buffered task from a subscribed queue with free capacity.
+    ///
+    /// Buffered tasks past their retry timeout are released as the DB will deliver them again.
+    async fn next_buffered(
+        &self,
+        context: &Context,
+        capacity: &QueueCapacity,
+    ) -> Result<Option<ReceivedTask>> {
+        let expired: Vec<ReceivedTask> = {
+            let mut buffer = self.buffer();
+            let mut expired = Vec::new();
+            buffer.retain(|(claimed, task)| {
+                let valid = claimed.elapsed() < task.queue.retry_timeout;
+                if !valid {
+                    expired.push(task.clone());
+                }
+                valid
+            });
+            expired
+        };
+        crate::telemetry::BUFFER_EXPIRED.inc_by(expired.len() as f64);
+        for task in expired {
+            self::execute::release(context, &self.connection, &task).await?;
+        }
+
+        loop {
+            let task = {
+                let mut buffer = self.buffer();
+                let index = buffer.iter().position(|(_, task)| {
+                    let queue = &task.queue.queue;
+                    self.subscriptions.contains_key(queue) && !capacity.is_full(queue)
+                });
+                match index {
+                    None => return Ok(None),
+                    Some(index) => buffer.remove(index),
+                }
+            };
+            let (claimed, task) = task.expect("buffer index must be valid");
+
+            // Push back redelivery of the task as it will start executing now.
+            match self::execute::handout(context, &self.connection, &task).await {
+                Ok(true) => return Ok(Some(task)),
+                Ok(false) => continue,
+                Err(error) => {
+                    self.buffer().push_front((claimed, task));
+                    return Err(error);
+                }
+            }
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl TaskAckBackend for SQLiteTasks {
     async fn done(&self, context: &Context, task: &ReceivedTask) -> Result<()> {
         self::execute::done(context, &self.connection, task).await
     }
+
+    async fn failed(
+        &self,
+        context: &Context,
+        task: &ReceivedTask,
+        error: &anyhow::Error,
+    ) -> Result<()> {
+        self::execute::failed(context, &self.connection, task, error).await
+    }
+
+    async fn heartbeat(&self, context: &Context, task: &ReceivedTask) -> Result<()> {
+        self::execute::heartbeat(context, &self.connection, task).await
+    }
 }
 
 #[async_trait::async_trait]
 impl TaskSourceBackend for SQLiteTasks {
-    async fn next(&mut self, context: &Context) -> Result<ReceivedTask> {
+    async fn next(&mut self, context: &Context, capacity: &QueueCapacity) -> Result<ReceivedTask> {
         loop {
-            let next = self::execute::next(context, &self.connection, &self.subscriptions).await?;
-            match next {
-                Some(task) => return Ok(task),
-                None => tokio::time::sleep(self.poll_delay).await,
+            if let Some(task) = self.next_buffered(context, capacity).await? {
+                return Ok(task);
+            }
+            self::dlq::sweep(context, &self.connection).await?;
+            let claimed = self::execute::next(
+                context,
+                &self.connection,
+                &self.subscriptions,
+                self.claim_capacity(capacity),
+                self.poll_batch,
+                &self.buffer,
+            )
+            .await?;
+            if claimed == 0 {
+                tokio::time::sleep(self.poll_delay).await;
             }
         }
     }
 
+    async fn release(&mut self, context: &Context) -> Result<()> {
+        let tasks: Vec<ReceivedTask> = self
+            .buffer()
+            .iter()
+            .filter(|(_, task)| self.subscriptions.contains_key(&task.queue.queue))
+            .map(|(_, task)| task.clone())
+            .collect();
+        for task in tasks {
+            self::execute::release(context, &self.connection, &task).await?;
+            self.buffer().retain(|(_, buffered)| buffered.id != task.id);
+        }
+        Ok(())
+    }
+
     async fn subscribe(&mut self, _: &Context, queue: &'static Queue) -> Result<()> {
         self.subscriptions.insert(&queue.queue, queue);
         Ok(())
@@ -67,6 +209,26 @@ impl TaskSourceBackend for SQLiteTasks {
 
 #[async_trait::async_trait]
 impl TasksBackend for SQLiteTasks {
+    async fn dlq_list(&self, context: &Context) -> Result<Vec<DeadLetterTask>> {
+        self::dlq::list(context, &self.connection).await
+    }
+
+    async fn dlq_lookup(&self, context: &Context, id: &str) -> Result<Option<DeadLetterTask>> {
+        self::dlq::lookup(context, &self.connection, id).await
+    }
+
+    async fn dlq_purge(&self, context: &Context, id: Option<&str>) -> Result<u64> {
+        self::dlq::purge(context, &self.connection, id).await
+    }
+
+    async fn dlq_requeue(&self, context: &Context, id: &str) -> Result<bool> {
+        self::dlq::requeue(context, &self.connection, id).await
+    }
+
+    async fn stats(&self, context: &Context, previews: usize) -> Result<Vec<QueueStats>> {
+        self::stats::stats(context, &self.connection, previews).await
+    }
+
     async fn submit(&self, context: &Context, task: TaskSubmission) -> Result<()> {
         self::submit::submit(context, &self.connection, task).await
     }
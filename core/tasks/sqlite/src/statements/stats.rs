@@ -0,0 +1,257 @@
+//! Background Tasks operations to summarise the state of task queues.
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use opentelemetry_api::trace::FutureExt;
+use time::OffsetDateTime;
+use tokio_rusqlite::Connection;
+
+use replisdk::utils::metrics::CountFutureErrExt;
+use replisdk::utils::trace::TraceFutureStdErrExt;
+
+use replicore_context::Context;
+use replicore_tasks_models::QueueStats;
+use replicore_tasks_models::TaskPreview;
+
+// Tasks are classified based on their delivery state:
+//  - Pending tasks were never delivered.
+//  - In-flight tasks were delivered and their redelivery time is in the future.
+//  - Retrying tasks were delivered, have attempts left and are due for redelivery.
+//  - Exhausted tasks have no attempts left and are waiting to move to the dead-letter queue.
+const QUEUE_STATS_SQL: &str = r#"
+SELECT
+    queue_id,
+    SUM(CASE WHEN attempts = 0 THEN 1 ELSE 0 END) AS pending,
+    SUM(CASE WHEN attempts > 0 AND next_retry > unixepoch() THEN 1 ELSE 0 END) AS in_flight,
+    SUM(
+        CASE WHEN attempts > 0 AND next_retry <= unixepoch() AND retries >= 0 THEN 1 ELSE 0 END
+    ) AS retrying,
+    SUM(
+        CASE WHEN attempts > 0 AND next_retry <= unixepoch() AND retries < 0 THEN 1 ELSE 0 END
+    ) AS exhausted,
+    unixepoch() - MIN(submitted_ts) AS oldest_age
+FROM tasks_queue
+GROUP BY queue_id;
+"#;
+
+const DLQ_STATS_SQL: &str = r#"
+SELECT
+    queue_id,
+    COUNT(*) AS exhausted
+FROM tasks_dlq
+GROUP BY queue_id;
+"#;
+
+const PREVIEW_SQL: &str = r#"
+SELECT
+    task_id,
+    attempts,
+    payload,
+    submitted_ts
+FROM tasks_queue
+WHERE queue_id = ?1
+ORDER BY task_id ASC
+LIMIT ?2;
+"#;
+
+/// SQL extracted task preview returned from SQLite connection calls.
+#[derive(Debug)]
+struct SQLTaskPreview {
+    task_id: i64,
+    attempts: u32,
+    payload: String,
+    submitted_ts: Option<i64>,
+}
+
+impl SQLTaskPreview {
+    /// Decode SQL data into a [`TaskPreview`] model.
+    fn decode(self) -> Result<TaskPreview> {
+        let preview = TaskPreview {
+            id: self.task_id.to_string(),
+            attempts: self.attempts,
+            payload: self.payload,
+            submitted_time: self
+                .submitted_ts
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()?,
+        };
+        Ok(preview)
+    }
+}
+
+/// Summarise the state of tasks on all queues with tasks in them.
+pub async fn stats(
+    _: &Context,
+    connection: &Connection,
+    previews: usize,
+) -> Result<Vec<QueueStats>> {
+    let (err_count, timer) = crate::telemetry::observe_op("task.stats");
+    let trace = crate::telemetry::trace_op("task.stats");
+    let stats = connection
+        .call(move |connection| {
+            let mut stats = BTreeMap::new();
+            let mut statement = connection.prepare_cached(QUEUE_STATS_SQL)?;
+            let mut rows = statement.query([])?;
+            while let Some(row) = rows.next()? {
+                let queue: String = row.get("queue_id")?;
+                let oldest: Option<i64> = row.get("oldest_age")?;
+                let queue_stats = QueueStats {
+                    queue: queue.clone(),
+                    exhausted: row.get("exhausted")?,
+                    in_flight: row.get("in_flight")?,
+                    oldest_task_age_secs: oldest.map(|age| age.max(0) as u64),
+                    pending: row.get("pending")?,
+                    previews: Vec::new(),
+                    retrying: row.get("retrying")?,
+                };
+                stats.insert(queue, queue_stats);
+            }
+
+            // Include tasks in the dead-letter queue in the exhausted count.
+            let mut statement = connection.prepare_cached(DLQ_STATS_SQL)?;
+            let mut rows = statement.query([])?;
+            while let Some(row) = rows.next()? {
+                let queue: String = row.get("queue_id")?;
+                let exhausted: u64 = row.get("exhausted")?;
+                let queue_stats = stats.entry(queue.clone()).or_insert_with(|| QueueStats {
+                    queue,
+                    exhausted: 0,
+                    in_flight: 0,
+                    oldest_task_age_secs: None,
+                    pending: 0,
+                    previews: Vec::new(),
+                    retrying: 0,
+                });
+                queue_stats.exhausted += exhausted;
+            }
+
+            // Collect previews of the oldest tasks on each queue.
+            let mut previews_sql = Vec::new();
+            if previews > 0 {
+                let mut statement = connection.prepare_cached(PREVIEW_SQL)?;
+                for queue in stats.keys() {
+                    let mut rows = statement.query(rusqlite::params![queue, previews])?;
+                    while let Some(row) = rows.next()? {
+                        let preview = SQLTaskPreview {
+                            task_id: row.get("task_id")?,
+                            attempts: row.get("attempts")?,
+                            payload: row.get("payload")?,
+                            submitted_ts: row.get("submitted_ts")?,
+                        };
+                        previews_sql.push((queue.clone(), preview));
+                    }
+                }
+            }
+            Ok((stats, previews_sql))
+        })
+        .count_on_err(err_count)
+        .trace_on_err_with_status()
+        .with_context(trace)
+        .await?;
+    drop(timer);
+
+    let (mut stats, previews) = stats;
+    for (queue, preview) in previews {
+        let preview = preview.decode()?;
+        if let Some(queue_stats) = stats.get_mut(&queue) {
+            queue_stats.previews.push(preview);
+        }
+    }
+    Ok(stats.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use replicore_tasks::submit::Tasks;
+
+    /// Insert tasks in different delivery states for tests.
+    async fn insert_tasks(connection: &tokio_rusqlite::Connection) {
+        connection
+            .call(|connection| {
+                connection
+                    .execute(
+                        r#"
+                        INSERT INTO tasks_queue (
+                            queue_id, payload, retries, retry_delay, next_retry,
+                            attempts, submitted_ts
+                        )
+                        VALUES
+                            ("UNIT_TEST", "1", 2, 1, NULL, 0, unixepoch() - 60),
+                            ("UNIT_TEST", "2", 1, 1, unixepoch() + 3600, 1, unixepoch() - 30),
+                            ("UNIT_TEST", "3", 1, 1, unixepoch() - 10, 1, unixepoch() - 20),
+                            ("UNIT_TEST", "4", -1, 1, unixepoch() - 10, 3, unixepoch() - 10),
+                            ("UNIT_TEST_ALTERNATE", "5", 2, 1, NULL, 0, NULL)
+                        ;
+                        "#,
+                        rusqlite::params![],
+                    )
+                    .unwrap();
+                connection
+                    .execute(
+                        r#"
+                        INSERT INTO tasks_dlq (
+                            task_id, queue_id, payload, retry_delay, attempts, exhausted_ts
+                        )
+                        VALUES
+                            (100, "UNIT_TEST", "6", 1, 3, unixepoch()),
+                            (101, "UNIT_TEST_DLQ", "7", 1, 3, unixepoch())
+                        ;
+                        "#,
+                        rusqlite::params![],
+                    )
+                    .unwrap();
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn stats_by_queue() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let tasks = Tasks::from(backend);
+        insert_tasks(&connection).await;
+
+        let stats = tasks.stats(&context, 0).await.unwrap();
+        assert_eq!(stats.len(), 3);
+
+        let queue = &stats[0];
+        assert_eq!(queue.queue, "UNIT_TEST");
+        assert_eq!(queue.pending, 1);
+        assert_eq!(queue.in_flight, 1);
+        assert_eq!(queue.retrying, 1);
+        assert_eq!(queue.exhausted, 2);
+        assert!(queue.oldest_task_age_secs.unwrap() >= 60);
+        assert!(queue.previews.is_empty());
+
+        let queue = &stats[1];
+        assert_eq!(queue.queue, "UNIT_TEST_ALTERNATE");
+        assert_eq!(queue.pending, 1);
+        assert_eq!(queue.oldest_task_age_secs, None);
+
+        let queue = &stats[2];
+        assert_eq!(queue.queue, "UNIT_TEST_DLQ");
+        assert_eq!(queue.pending, 0);
+        assert_eq!(queue.exhausted, 1);
+    }
+
+    #[tokio::test]
+    async fn stats_previews() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let tasks = Tasks::from(backend);
+        insert_tasks(&connection).await;
+
+        let stats = tasks.stats(&context, 2).await.unwrap();
+        let previews = &stats[0].previews;
+        assert_eq!(previews.len(), 2);
+        assert_eq!(previews[0].id, "1");
+        assert_eq!(previews[0].payload, "1");
+        assert_eq!(previews[1].id, "2");
+        assert_eq!(previews[1].attempts, 1);
+        assert!(stats[2].previews.is_empty());
+    }
+}
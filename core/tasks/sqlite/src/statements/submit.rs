@@ -1,4 +1,6 @@
 //! Background Tasks operations to submit tasks to the queue.
+use std::time::Duration;
+
 use anyhow::Result;
 use opentelemetry_api::trace::FutureExt;
 use tokio_rusqlite::Connection;
@@ -10,8 +12,32 @@ use replicore_context::Context;
 use replicore_tasks::submit::TaskSubmission;
 
 const SUBMIT_SQL: &str = r#"
-INSERT INTO tasks_queue (queue_id, payload, run_as, trace, retries, retry_delay)
-VALUES (?1, ?2, ?3, ?4, ?5, ?6);
+INSERT INTO tasks_queue (
+    queue_id,
+    payload,
+    run_as,
+    trace,
+    retries,
+    retry_delay,
+    next_retry,
+    dedup_key,
+    submitted_ts
+)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, unixepoch());
+"#;
+
+// Merge a submission into a pending task with the same key, keeping the earliest delivery time.
+// Tasks are pending until they are first delivered for execution.
+const SUBMIT_DEDUP_SQL: &str = r#"
+UPDATE tasks_queue
+SET next_retry = CASE
+    WHEN ?3 IS NULL OR next_retry IS NULL THEN NULL
+    ELSE MIN(next_retry, ?3)
+END
+WHERE
+    queue_id = ?1 AND
+    dedup_key = ?2 AND
+    attempts = 0;
 "#;
 
 pub async fn submit(_: &Context, connection: &Connection, task: TaskSubmission) -> Result<()> {
@@ -29,12 +55,28 @@ pub async fn submit(_: &Context, connection: &Connection, task: TaskSubmission)
         .transpose()?;
     let queue_id = &task.queue.queue;
     let retries = task.queue.retry_count;
-    let retry_delay = task.queue.retry_timeout.as_secs();
+    let retry_delay = retry_delay_secs(task.queue.retry_timeout);
+    // Delayed tasks are stored as if waiting for a retry so they are not delivered early.
+    let next_retry = task
+        .not_before
+        .map(|not_before| not_before.unix_timestamp());
+    let dedup_key = task.dedup_key;
     let (err_count, _timer) = crate::telemetry::observe_op("task.submit");
     let trace = crate::telemetry::trace_op("task.submit");
-    connection
+    let merged = connection
         .call(move |connection| {
-            connection.execute(
+            let transaction = connection.transaction()?;
+            if let Some(dedup_key) = &dedup_key {
+                let merged = transaction.execute(
+                    SUBMIT_DEDUP_SQL,
+                    rusqlite::params![queue_id, dedup_key, next_retry],
+                )?;
+                if merged > 0 {
+                    transaction.commit()?;
+                    return Ok(true);
+                }
+            }
+            transaction.execute(
                 SUBMIT_SQL,
                 rusqlite::params![
                     queue_id,
@@ -42,24 +84,75 @@ pub async fn submit(_: &Context, connection: &Connection, task: TaskSubmission)
                     run_as,
                     trace_context,
                     retries,
-                    retry_delay
+                    retry_delay,
+                    next_retry,
+                    dedup_key
                 ],
             )?;
-            Ok(())
+            transaction.commit()?;
+            Ok(false)
         })
         .count_on_err(err_count)
         .trace_on_err_with_status()
         .with_context(trace)
         .await?;
+    if merged {
+        crate::telemetry::SUBMIT_DEDUP.inc();
+    }
     Ok(())
 }
 
+/// Convert a queue retry timeout to the whole seconds stored with tasks, rounding up.
+///
+/// Sub-second timeouts are stored as one second so delivered tasks are never due for
+/// redelivery immediately, which would make heartbeats ineffective.
+fn retry_delay_secs(timeout: Duration) -> u64 {
+    let secs = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+    secs.max(1)
+}
+
 #[cfg(test)]
 mod tests {
+    use replicore_tasks::execute::TaskSource;
     use replicore_tasks::execute::TEST_QUEUE;
     use replicore_tasks::submit::TaskSubmission;
     use replicore_tasks::submit::Tasks;
 
+    const NEXT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(20);
+
+    #[test]
+    fn retry_delay_rounds_up() {
+        let delay = |millis| super::retry_delay_secs(std::time::Duration::from_millis(millis));
+        assert_eq!(delay(0), 1);
+        assert_eq!(delay(50), 1);
+        assert_eq!(delay(1000), 1);
+        assert_eq!(delay(1500), 2);
+        assert_eq!(delay(30000), 30);
+    }
+
+    #[tokio::test]
+    async fn submit_sub_second_retry_delay() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let task = TaskSubmission::new(&TEST_QUEUE, &false).unwrap();
+        let tasks = Tasks::from(backend);
+        tasks.submit(&context, task).await.unwrap();
+
+        let retry_delay = connection
+            .call(move |connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT retry_delay FROM tasks_queue;")?;
+                let mut rows = statement.query([])?;
+                let row = rows.next()?.expect("submitted task record");
+                let retry_delay: i64 = row.get("retry_delay")?;
+                Ok(retry_delay)
+            })
+            .await
+            .expect("retry_delay SQL execution error");
+        assert_eq!(retry_delay, 1);
+    }
+
     #[tokio::test]
     async fn submit() {
         let backend = crate::statements::tests::sqlite_tasks().await;
@@ -82,4 +175,124 @@ mod tests {
             .expect("count SQL execution error");
         assert_eq!(count, 1);
     }
+
+    #[tokio::test]
+    async fn submit_delayed() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let not_before = time::OffsetDateTime::now_utc() + std::time::Duration::from_secs(600);
+        let task = TaskSubmission::new(&TEST_QUEUE, &false)
+            .unwrap()
+            .not_before(not_before);
+        let mut source = TaskSource::from(backend.clone());
+        let tasks = Tasks::from(backend);
+        tasks.submit(&context, task).await.unwrap();
+
+        let next_retry = connection
+            .call(move |connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT next_retry FROM tasks_queue;")?;
+                let mut rows = statement.query([])?;
+                let row = rows.next()?.expect("submitted task record");
+                let next_retry: Option<i64> = row.get("next_retry")?;
+                Ok(next_retry)
+            })
+            .await
+            .expect("next_retry SQL execution error");
+        assert_eq!(next_retry, Some(not_before.unix_timestamp()));
+
+        // Delayed tasks are not delivered before their time.
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+        let task = tokio::time::timeout(NEXT_TIMEOUT, source.next(&context)).await;
+        assert!(task.is_err());
+    }
+
+    #[tokio::test]
+    async fn submit_dedup_pending() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let later = time::OffsetDateTime::now_utc() + std::time::Duration::from_secs(600);
+        let tasks = Tasks::from(backend);
+
+        // Submit a delayed task and merge an immediate one with the same key into it.
+        let task = TaskSubmission::new(&TEST_QUEUE, &false)
+            .unwrap()
+            .dedup_key("test/key")
+            .not_before(later);
+        tasks.submit(&context, task).await.unwrap();
+        let task = TaskSubmission::new(&TEST_QUEUE, &false)
+            .unwrap()
+            .dedup_key("test/key");
+        tasks.submit(&context, task).await.unwrap();
+
+        // Submissions with a different key are not merged.
+        let task = TaskSubmission::new(&TEST_QUEUE, &false)
+            .unwrap()
+            .dedup_key("test/other");
+        tasks.submit(&context, task).await.unwrap();
+
+        let records = connection
+            .call(move |connection| {
+                let mut statement = connection.prepare_cached(
+                    "SELECT dedup_key, next_retry FROM tasks_queue ORDER BY task_id;",
+                )?;
+                let mut rows = statement.query([])?;
+                let mut records = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let key: String = row.get("dedup_key")?;
+                    let next_retry: Option<i64> = row.get("next_retry")?;
+                    records.push((key, next_retry));
+                }
+                Ok(records)
+            })
+            .await
+            .expect("tasks SQL execution error");
+        assert_eq!(
+            records,
+            vec![
+                (String::from("test/key"), None),
+                (String::from("test/other"), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn submit_dedup_delivered() {
+        let backend = crate::statements::tests::sqlite_tasks().await;
+        let connection = backend.connection.clone();
+        let context = replicore_context::Context::fixture();
+        let mut source = TaskSource::from(backend.clone());
+        let tasks = Tasks::from(backend);
+
+        // Tasks already delivered for execution no longer absorb new submissions.
+        let task = TaskSubmission::new(&TEST_QUEUE, &false)
+            .unwrap()
+            .dedup_key("test/key");
+        tasks.submit(&context, task).await.unwrap();
+        source.subscribe(&context, &TEST_QUEUE).await.unwrap();
+        tokio::time::timeout(NEXT_TIMEOUT, source.next(&context))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let task = TaskSubmission::new(&TEST_QUEUE, &false)
+            .unwrap()
+            .dedup_key("test/key");
+        tasks.submit(&context, task).await.unwrap();
+
+        let count = connection
+            .call(move |connection| {
+                let mut statement =
+                    connection.prepare_cached("SELECT COUNT(*) FROM tasks_queue;")?;
+                let mut rows = statement.query([])?;
+                let row = rows.next()?.expect("count of table records");
+                let count: u64 = row.get("COUNT(*)")?;
+                Ok(count)
+            })
+            .await
+            .expect("count SQL execution error");
+        assert_eq!(count, 2);
+    }
 }
@@ -17,6 +17,24 @@ use prometheus::HistogramTimer;
 use prometheus::HistogramVec;
 use prometheus::Opts;
 
+/// Total number of claimed tasks released from the local buffer after their retry timeout.
+pub static BUFFER_EXPIRED: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_sqlite_buffer_expired",
+        "Total number of claimed tasks released from the local buffer after their retry timeout",
+    )
+    .expect("failed to initialise BUFFER_EXPIRED counter")
+});
+
+/// Total number of tasks moved to the dead-letter queue.
+pub static DLQ_MOVED: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_sqlite_dlq_moved",
+        "Total number of tasks moved to the dead-letter queue",
+    )
+    .expect("failed to initialise DLQ_MOVED counter")
+});
+
 /// Duration (in seconds) of SQLite operations.
 pub static OPS_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
     HistogramVec::new(
@@ -42,6 +60,15 @@ pub static OPS_ERR: Lazy<CounterVec> = Lazy::new(|| {
     .expect("failed to initialise OPS_ERR counter")
 });
 
+/// Total number of task submissions merged into an already pending task.
+pub static SUBMIT_DEDUP: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_sqlite_submit_deduplicated",
+        "Total number of task submissions merged into an already pending task",
+    )
+    .expect("failed to initialise SUBMIT_DEDUP counter")
+});
+
 /// Open Telemetry tracer for the SQLite tasks backend.
 pub static TRACER: Lazy<BoxedTracer> = Lazy::new(|| {
     opentelemetry_api::global::tracer_provider().versioned_tracer(
@@ -62,8 +89,13 @@ pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
         return Ok(());
     }
 
-    let collectors: [Box<dyn prometheus::core::Collector>; 2] =
-        [Box::new(OPS_DURATION.clone()), Box::new(OPS_ERR.clone())];
+    let collectors: [Box<dyn prometheus::core::Collector>; 5] = [
+        Box::new(BUFFER_EXPIRED.clone()),
+        Box::new(DLQ_MOVED.clone()),
+        Box::new(OPS_DURATION.clone()),
+        Box::new(OPS_ERR.clone()),
+        Box::new(SUBMIT_DEDUP.clone()),
+    ];
     for collector in collectors {
         reg.register(collector)?;
     }
@@ -1,4 +1,5 @@
 //! How to define queues and their configuration.
+use std::collections::HashMap;
 use std::time::Duration;
 
 use serde::Deserialize;
@@ -17,10 +18,19 @@ pub struct Queue {
     /// Identifier of the queue.
     pub queue: String,
 
+    /// Maximum time task handlers can run for before they are aborted and the task failed.
+    ///
+    /// If not set task handlers can run for as long as they need.
+    /// Executors refuse to subscribe to queues with a maximum execution time shorter
+    /// than the interval between heartbeats (half the retry timeout, at least 100ms).
+    pub max_execution_time: Option<Duration>,
+
     /// Number of times submitted tasks are retired in case of non-permanent failures.
     pub retry_count: u16,
 
     /// Amount of time a delivered task wait before redelivery attempts.
+    ///
+    /// Tasks still being executed are kept from redelivery with periodic heartbeats.
     pub retry_timeout: Duration,
 }
 
@@ -106,6 +116,18 @@ pub struct TasksExecutorConf {
     /// Filter queues from which tasks should be processed.
     #[serde(default)]
     pub filters: TasksExecutorFilters,
+
+    /// Number of task execution records to keep for each task.
+    ///
+    /// Recurring tasks submitted with the same de-duplication key share their records.
+    ///
+    /// Set to 0 to disable recording the history of task executions.
+    #[serde(default = "TasksExecutorConf::default_history_limit")]
+    pub history_limit: usize,
+
+    /// Per-queue limits to the number of tasks executed concurrently.
+    #[serde(default)]
+    pub queues: HashMap<String, TasksExecutorQueueConf>,
 }
 
 impl Default for TasksExecutorConf {
@@ -114,6 +136,8 @@ impl Default for TasksExecutorConf {
             backoff: Default::default(),
             concurrent_tasks: TasksExecutorConf::default_concurrent_tasks(),
             filters: Default::default(),
+            history_limit: TasksExecutorConf::default_history_limit(),
+            queues: Default::default(),
         }
     }
 }
@@ -125,6 +149,10 @@ impl TasksExecutorConf {
             .unwrap_or(8);
         parallel * 2
     }
+
+    fn default_history_limit() -> usize {
+        100
+    }
 }
 
 /// Filter queues from which tasks should be processed.
@@ -143,3 +171,21 @@ pub struct TasksExecutorFilters {
     #[serde(default)]
     pub process: Vec<String>,
 }
+
+/// Limits to the number of tasks from a queue executed concurrently.
+///
+/// These limits apply in addition to the global [`TasksExecutorConf::concurrent_tasks`] limit
+/// and prevent busy queues from starving others of execution capacity.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TasksExecutorQueueConf {
+    /// Maximum number of tasks from the queue to execute concurrently.
+    #[serde(default)]
+    pub concurrent_tasks: Option<usize>,
+
+    /// Limit the queue to a share of the global concurrent tasks proportional to this weight.
+    ///
+    /// Shares are computed against the weights of all subscribed queues,
+    /// with queues that don't set a weight counting as a weight of 1.
+    #[serde(default)]
+    pub weight: Option<u32>,
+}
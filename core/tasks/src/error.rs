@@ -1,4 +1,5 @@
 //! Errors reported by the async task framework.
+use std::time::Duration;
 
 /// background task encountered a permanent error and will not be retired.
 #[derive(Debug, thiserror::Error)]
@@ -17,6 +18,54 @@ impl AlreadySubscribed {
     }
 }
 
+/// Task handler exceeded the maximum execution time for the queue.
+#[derive(Debug, thiserror::Error)]
+#[error("task handler for queue '{queue}' exceeded the maximum execution time of {limit:?}")]
+pub struct ExecutionTimeout {
+    limit: Duration,
+    queue: String,
+}
+
+impl ExecutionTimeout {
+    /// Report a task handler on the given queue was aborted after exceeding its execution time.
+    pub fn new<S>(queue: S, limit: Duration) -> ExecutionTimeout
+    where
+        S: Into<String>,
+    {
+        ExecutionTimeout {
+            limit,
+            queue: queue.into(),
+        }
+    }
+}
+
+/// Queue maximum execution time is shorter than the interval between heartbeats for its tasks.
+///
+/// Task handlers would be aborted before they could ever send a heartbeat.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "maximum execution time of {limit:?} for queue '{queue}' is shorter than its heartbeat interval of {interval:?}"
+)]
+pub struct ExecutionTimeTooShort {
+    interval: Duration,
+    limit: Duration,
+    queue: String,
+}
+
+impl ExecutionTimeTooShort {
+    /// Report the maximum execution time of a queue is shorter than its heartbeat interval.
+    pub fn new<S>(queue: S, limit: Duration, interval: Duration) -> ExecutionTimeTooShort
+    where
+        S: Into<String>,
+    {
+        ExecutionTimeTooShort {
+            interval,
+            limit,
+            queue: queue.into(),
+        }
+    }
+}
+
 /// Exceeded maximum number of retries.
 #[derive(Debug, thiserror::Error)]
 #[error("exceeded maximum of {0} retries")]
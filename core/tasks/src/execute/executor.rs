@@ -2,7 +2,10 @@
 use std::any::Any;
 use std::collections::HashMap;
 use std::future::Future;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use futures::stream::FuturesUnordered;
@@ -11,6 +14,8 @@ use opentelemetry_api::trace::FutureExt;
 use opentelemetry_api::trace::TraceContextExt;
 use opentelemetry_api::trace::Tracer;
 use opentelemetry_api::Context as OTelContext;
+use serde_json::Value as Json;
+use time::OffsetDateTime;
 
 use replisdk::core::models::auth::Action;
 use replisdk::core::models::auth::AuthContext;
@@ -18,15 +23,27 @@ use replisdk::core::models::auth::Resource;
 use replisdk::utils::trace::TraceFutureErrExt;
 
 use replicore_context::Context;
+use replicore_tasks_models::TaskExecution;
+use replicore_tasks_models::TaskOutcome;
 
 use super::backoff::Backoff;
+use super::QueueCapacity;
 use super::ReceivedTask;
 use super::TaskAck;
 use super::TaskCallback;
+use super::TaskHistory;
 use super::TaskSource;
 use crate::conf::Queue;
 use crate::conf::TasksExecutorConf;
 use crate::error::AbandonTask;
+use crate::error::ExecutionTimeTooShort;
+use crate::error::ExecutionTimeout;
+
+/// Minimum interval between heartbeats for running tasks.
+const HEARTBEAT_MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Maximum length of task payload summaries in execution records.
+const PAYLOAD_SUMMARY_MAX_LEN: usize = 1024;
 
 /// Asynchronously execute subscribed tasks when they become available.
 ///
@@ -42,13 +59,24 @@ use crate::error::AbandonTask;
 /// configurable limit to the number of tasks executed concurrently.
 /// When this limit is reached the [`TasksExecutor`] will stop asking for new tasks.
 ///
+/// Queues can also be limited to a maximum number of concurrent tasks, or to a weighted share
+/// of the global limit, so that a flood of tasks on one queue does not starve the others.
+/// Queues that reached their limit are excluded when asking for new tasks while
+/// the [`TasksExecutor`] continues to fetch tasks from other queues.
+/// Backends that receive tasks in batches are told how many more tasks each queue can run.
+///
 /// ## Executor Shutdown
 ///
 /// A process shutdown notification can be received by resolving a unit [`Future`].
 /// If the exit signal future resolves the executor will:
 ///
-/// 1. Stop fetching new tasks to execute.
-/// 2. Abandon execution of all in-progress tasks (the standard retry logic will apply here).
+/// 1. Stop fetching new tasks to execute and release tasks received but not yet started.
+/// 2. Drain in-progress tasks, waiting for them to complete up to the configured drain timeout.
+/// 3. Abandon execution of tasks still running after the timeout
+///    (the standard retry logic will apply here).
+///
+/// The drain timeout defaults to zero, which abandons all in-progress tasks immediately.
+/// Tasks are never drained if the executor stops because of an error.
 ///
 /// ## Error Handling
 ///
@@ -79,13 +107,33 @@ use crate::error::AbandonTask;
 /// it can mark the error with the [`AbandonTask`] context.
 /// This causes the [`TasksExecutor`] to ack the task as completed and avoids needless retries.
 ///
+/// ## Long Running Tasks
+///
+/// Queues can set a maximum execution time for their tasks.
+/// Task handlers that exceed this time are aborted and the task is failed
+/// so the standard retry logic applies.
+///
+/// While task handlers run the [`TasksExecutor`] periodically sends heartbeats
+/// to the queue with [`TaskAck::heartbeat`].
+/// This prevents tasks that take longer than the queue retry timeout from being
+/// redelivered while they are still being executed.
+///
+/// ## Execution History
+///
+/// When a [`TaskHistory`] is set the outcome of every execution attempt is recorded,
+/// including errors and trace IDs, so failures can be investigated after the fact.
+/// Errors recording the history are logged and do not affect task execution.
+///
 /// [`ack`]: TaskSourceBackend::ack
 /// [`nack`]: TaskSourceBackend::nack
 pub struct TasksExecutor {
     ack: TaskAck,
     callbacks: HashMap<String, Arc<dyn TaskCallback>>,
     conf: TasksExecutorConf,
+    drain_timeout: Duration,
+    history: Option<TaskHistory>,
     pool: FuturesUnordered<tokio::task::JoinHandle<Result<()>>>,
+    running: HashMap<String, Arc<AtomicUsize>>,
     source: TaskSource,
 }
 
@@ -96,11 +144,26 @@ impl TasksExecutor {
             ack,
             callbacks: Default::default(),
             conf,
+            drain_timeout: Duration::ZERO,
+            history: None,
             pool: FuturesUnordered::new(),
+            running: Default::default(),
             source,
         }
     }
 
+    /// Set the maximum time to wait for in-progress tasks to complete on shutdown.
+    pub fn drain_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Record the outcome of task execution attempts with the given [`TaskHistory`].
+    pub fn history(&mut self, history: TaskHistory) -> &mut Self {
+        self.history = Some(history);
+        self
+    }
+
     /// Execute tasks once they are received.
     pub async fn execute(
         &mut self,
@@ -112,8 +175,28 @@ impl TasksExecutor {
             .execute_inner(context, exit, &mut propagate_panic)
             .await;
 
-        // If the loop stopped we either failed or the process is exiting.
-        // In either case proceed to cancel executing tokio tasks before we return.
+        // Give back tasks the source received ahead of time so they don't wait for retries.
+        if let Err(error) = self.source.release(context).await {
+            slog::warn!(
+                context.logger, "Failed to release received tasks before shutdown";
+                replisdk::utils::error::slog::ErrorAttributes::from(&error),
+            );
+        }
+
+        // If the process is exiting give in-progress tasks a chance to complete.
+        if dispatch.is_ok() && propagate_panic.is_none() {
+            self.drain(context, &mut propagate_panic).await;
+        }
+
+        // Cancel tokio tasks still executing before we return.
+        let abandoned = self.pool.len();
+        if abandoned > 0 {
+            slog::warn!(
+                context.logger, "Abandoning execution of in-progress tasks";
+                "tasks" => abandoned,
+            );
+        }
+        crate::telemetry::SHUTDOWN_ABANDONED.inc_by(abandoned as f64);
         for task in self.pool.iter() {
             task.abort();
         }
@@ -144,6 +227,55 @@ impl TasksExecutor {
         self.subscribe_arc(context, queue, callback).await
     }
 
+    /// Wait for in-progress tasks to complete, up to the drain timeout.
+    async fn drain(
+        &mut self,
+        context: &Context,
+        propagate_panic: &mut Option<Box<dyn Any + Send + 'static>>,
+    ) {
+        if self.pool.is_empty() {
+            return;
+        }
+        slog::info!(
+            context.logger, "Draining in-progress tasks before shutdown";
+            "tasks" => self.pool.len(),
+            "timeout" => ?self.drain_timeout,
+        );
+
+        let deadline = tokio::time::sleep(self.drain_timeout);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                result = self.pool.next() => {
+                    let result = match result {
+                        None => break,
+                        Some(result) => result,
+                    };
+                    crate::telemetry::SHUTDOWN_DRAINED.inc();
+                    match result {
+                        Err(error) if error.is_panic() => {
+                            *propagate_panic = Some(error.into_panic());
+                            break;
+                        }
+                        Err(error) => {
+                            let error = anyhow::Error::from(error);
+                            slog::warn!(
+                                context.logger, "Unknown error from drained async task";
+                                replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                            );
+                        }
+                        Ok(Err(error)) => slog::warn!(
+                            context.logger, "Failed to acknowledge drained async task";
+                            replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                        ),
+                        Ok(Ok(())) => (),
+                    };
+                },
+            };
+        }
+    }
+
     /// Implement the fetch, dispatch, join loop for queue processing.
     async fn execute_inner(
         &mut self,
@@ -157,16 +289,19 @@ impl TasksExecutor {
         // Track errors while processing async task.
         let mut ack_backoff = Backoff::new(&self.conf.backoff);
         let mut source_backoff = Backoff::new(&self.conf.backoff);
+        let limits = self.queue_limits();
 
         // Process tasks as they come in, until exit or error.
         loop {
-            let poll_task = self.pool.len() < self.conf.concurrent_tasks;
+            let capacity = self.queue_capacity(&limits);
+            let poll_task = self.pool.len() < self.conf.concurrent_tasks
+                && self.callbacks.keys().any(|queue| !capacity.is_full(queue));
             tokio::select! {
                 // Exit early if process needs to shut down.
                 _ = &mut exit => break,
 
                 // Wait for async tasks to execute.
-                task = self.source.next(context), if poll_task => {
+                task = self.source.next_within(context, &capacity), if poll_task => {
                     let task = match task {
                         Err(error) => {
                             source_backoff.retry(context, error).await?;
@@ -220,6 +355,11 @@ impl TasksExecutor {
             .get(&task.queue.queue)
             .expect("received message for a queue we are not subscribed to")
             .clone();
+        let running = RunningTask::start(
+            self.running
+                .get(&task.queue.queue)
+                .expect("received message for a queue we are not subscribed to"),
+        );
 
         // Derive an updated context to propagate task specific information.
         let mut context = context
@@ -246,12 +386,23 @@ impl TasksExecutor {
         span.span_kind = Some(opentelemetry_api::trace::SpanKind::Consumer);
         let span = crate::telemetry::TRACER.build_with_context(span, &otel_parent);
         let otel_context = otel_parent.with_span(span);
+        let span_context = otel_context.span().span_context().clone();
+        let trace_id = span_context
+            .is_valid()
+            .then(|| span_context.trace_id().to_string());
 
         // Execute the task in parallel with other activities.
         let ack_backend = self.ack.clone();
+        let history = self.history.clone();
         let work = async move {
             let ack_backend = ack_backend;
-            let result = handler.execute(&context, &task).await;
+            let _running = running;
+            let started_time = OffsetDateTime::now_utc();
+            let result = execute_handler(&context, &ack_backend, handler, &task).await;
+            if let Some(history) = history {
+                let execution = execution_record(&task, started_time, trace_id, &result);
+                record_execution(&context, &history, execution).await;
+            }
             match result {
                 Ok(()) => ack_backend.done(&context, &task).await,
                 Err(error) => {
@@ -259,10 +410,10 @@ impl TasksExecutor {
                         context.logger, "Background Task encountered an error during processing";
                         replisdk::utils::error::slog::ErrorAttributes::from(&error),
                     );
-                    let abandon = error.is::<AbandonTask>()
-                        || error.chain().any(|cause| cause.is::<AbandonTask>());
-                    if abandon {
+                    if is_abandoned(&error) {
                         ack_backend.done(&context, &task).await?;
+                    } else {
+                        ack_backend.failed(&context, &task, &error).await?;
                     }
                     Ok(())
                 }
@@ -273,6 +424,51 @@ impl TasksExecutor {
         self.pool.push(join);
     }
 
+    /// Compute the maximum number of concurrent tasks for each subscribed queue.
+    fn queue_limits(&self) -> HashMap<String, usize> {
+        let global = self.conf.concurrent_tasks;
+        let total_weight: u64 = self
+            .callbacks
+            .keys()
+            .map(|queue| {
+                let weight = self.conf.queues.get(queue).and_then(|conf| conf.weight);
+                u64::from(weight.unwrap_or(1))
+            })
+            .sum();
+
+        let mut limits = HashMap::new();
+        for queue in self.callbacks.keys() {
+            let conf = self.conf.queues.get(queue);
+            let mut limit = global;
+            if let Some(max) = conf.and_then(|conf| conf.concurrent_tasks) {
+                limit = limit.min(max);
+            }
+            if let Some(weight) = conf.and_then(|conf| conf.weight) {
+                // Round shares up so every weighted queue can execute at least one task.
+                let share = (global as u64 * u64::from(weight)).div_ceil(total_weight.max(1));
+                limit = limit.min(share.max(1) as usize);
+            }
+            limits.insert(queue.clone(), limit);
+        }
+        limits
+    }
+
+    /// Compute how many more tasks each subscribed queue is allowed to execute.
+    fn queue_capacity(&self, limits: &HashMap<String, usize>) -> QueueCapacity {
+        let global = self.conf.concurrent_tasks.saturating_sub(self.pool.len());
+        self.running
+            .iter()
+            .map(|(queue, running)| {
+                let limit = limits
+                    .get(queue)
+                    .copied()
+                    .unwrap_or(self.conf.concurrent_tasks);
+                let free = limit.saturating_sub(running.load(Ordering::SeqCst));
+                (queue.clone(), free.min(global))
+            })
+            .collect()
+    }
+
     /// Register a callback to execute tasks received on the corresponding queue.
     async fn subscribe_arc(
         &mut self,
@@ -294,6 +490,14 @@ impl TasksExecutor {
             anyhow::bail!(crate::error::AlreadySubscribed::new(&queue.queue));
         }
 
+        // Fail if handlers would be aborted before they send a heartbeat.
+        let interval = heartbeat_interval(queue);
+        if let Some(limit) = queue.max_execution_time {
+            if limit < interval {
+                anyhow::bail!(ExecutionTimeTooShort::new(&queue.queue, limit, interval));
+            }
+        }
+
         // Register the queue handler and subscribe to tasks.
         slog::info!(
             context.logger,
@@ -301,10 +505,147 @@ impl TasksExecutor {
             "queue" => &queue.queue
         );
         self.callbacks.insert(queue.queue.clone(), callback);
+        self.running.insert(queue.queue.clone(), Default::default());
         self.source.subscribe(context, queue).await
     }
 }
 
+/// Track a task being executed against its queue concurrency limit until dropped.
+struct RunningTask(Arc<AtomicUsize>);
+
+impl RunningTask {
+    /// Count a new task as running on the queue tracked by the given counter.
+    fn start(counter: &Arc<AtomicUsize>) -> RunningTask {
+        counter.fetch_add(1, Ordering::SeqCst);
+        RunningTask(Arc::clone(counter))
+    }
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Interval between heartbeats for running tasks received on a queue.
+fn heartbeat_interval(queue: &Queue) -> Duration {
+    std::cmp::max(queue.retry_timeout / 2, HEARTBEAT_MIN_INTERVAL)
+}
+
+/// Run a task handler within the queue execution time limit, sending heartbeats while it runs.
+async fn execute_handler(
+    context: &Context,
+    ack: &TaskAck,
+    handler: Arc<dyn TaskCallback>,
+    task: &ReceivedTask,
+) -> Result<()> {
+    let execution = handler.execute(context, task);
+    tokio::pin!(execution);
+    let deadline = async {
+        match task.queue.max_execution_time {
+            None => futures::future::pending().await,
+            Some(limit) => tokio::time::sleep(limit).await,
+        }
+    };
+    tokio::pin!(deadline);
+
+    let interval = heartbeat_interval(task.queue);
+    let mut heartbeat = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            result = &mut execution => return result,
+            _ = &mut deadline => {
+                let limit = task.queue.max_execution_time.unwrap_or_default();
+                crate::telemetry::EXECUTE_TIMEOUT
+                    .with_label_values(&[&task.queue.queue])
+                    .inc();
+                anyhow::bail!(ExecutionTimeout::new(&task.queue.queue, limit));
+            }
+            _ = heartbeat.tick() => {
+                if let Err(error) = ack.heartbeat(context, task).await {
+                    crate::telemetry::HEARTBEAT_ERR
+                        .with_label_values(&[&task.queue.queue])
+                        .inc();
+                    slog::warn!(
+                        context.logger, "Failed to send heartbeat for running task";
+                        replisdk::utils::error::slog::ErrorAttributes::from(&error),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Build the record of a task execution attempt from its result.
+fn execution_record(
+    task: &ReceivedTask,
+    started_time: OffsetDateTime,
+    trace_id: Option<String>,
+    result: &Result<()>,
+) -> TaskExecution {
+    let (outcome, error) = match result {
+        Ok(()) => (TaskOutcome::Success, Vec::new()),
+        Err(error) => {
+            let outcome = if is_abandoned(error) {
+                TaskOutcome::Abandoned
+            } else if error.is::<ExecutionTimeout>() {
+                TaskOutcome::Timeout
+            } else {
+                TaskOutcome::Failed
+            };
+            let chain = error.chain().map(ToString::to_string).collect();
+            (outcome, chain)
+        }
+    };
+    TaskExecution {
+        task_id: task.id.clone(),
+        attempt: task.attempt,
+        dedup_key: task.dedup_key.clone(),
+        error,
+        finished_time: OffsetDateTime::now_utc(),
+        outcome,
+        payload: payload_summary(&task.payload),
+        queue: task.queue.queue.clone(),
+        started_time,
+        trace_id,
+    }
+}
+
+/// Check if a task handler error requests the task is not retried.
+fn is_abandoned(error: &anyhow::Error) -> bool {
+    error.is::<AbandonTask>() || error.chain().any(|cause| cause.is::<AbandonTask>())
+}
+
+/// Encode a task payload for execution records, truncating large payloads.
+fn payload_summary(payload: &Json) -> String {
+    let mut summary = payload.to_string();
+    if summary.len() <= PAYLOAD_SUMMARY_MAX_LEN {
+        return summary;
+    }
+    let mut end = PAYLOAD_SUMMARY_MAX_LEN;
+    while !summary.is_char_boundary(end) {
+        end -= 1;
+    }
+    summary.truncate(end);
+    summary.push_str("...");
+    summary
+}
+
+/// Record a task execution attempt, logging any error in the process.
+async fn record_execution(context: &Context, history: &TaskHistory, execution: TaskExecution) {
+    let queue = execution.queue.clone();
+    if let Err(error) = history.record(context, execution).await {
+        crate::telemetry::HISTORY_ERR
+            .with_label_values(&[&queue])
+            .inc();
+        slog::warn!(
+            context.logger, "Failed to record task execution history";
+            replisdk::utils::error::slog::ErrorAttributes::from(&error),
+        );
+    }
+}
+
 /// Type of one-off functions for late initialisation of [`TaskCallback`]s.
 type LateTaskCallback = Box<dyn FnOnce() -> Arc<dyn TaskCallback>>;
 
@@ -312,6 +653,8 @@ type LateTaskCallback = Box<dyn FnOnce() -> Arc<dyn TaskCallback>>;
 pub struct TasksExecutorBuilder {
     callbacks: Vec<(&'static Queue, LateTaskCallback)>,
     conf: TasksExecutorConf,
+    drain_timeout: Duration,
+    history: Option<TaskHistory>,
 }
 
 impl TasksExecutorBuilder {
@@ -320,6 +663,8 @@ impl TasksExecutorBuilder {
         TasksExecutorBuilder {
             callbacks: Default::default(),
             conf,
+            drain_timeout: Duration::ZERO,
+            history: None,
         }
     }
 
@@ -331,12 +676,28 @@ impl TasksExecutorBuilder {
         ack: TaskAck,
     ) -> Result<TasksExecutor> {
         let mut tasks = TasksExecutor::new(source, ack, self.conf);
+        tasks.drain_timeout(self.drain_timeout);
+        if let Some(history) = self.history {
+            tasks.history(history);
+        }
         for (queue, callback) in self.callbacks.into_iter() {
             tasks.subscribe_arc(context, queue, callback()).await?;
         }
         Ok(tasks)
     }
 
+    /// Set the maximum time to wait for in-progress tasks to complete on shutdown.
+    pub fn drain_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Record the outcome of task execution attempts with the given [`TaskHistory`].
+    pub fn history(&mut self, history: TaskHistory) -> &mut Self {
+        self.history = Some(history);
+        self
+    }
+
     /// Handle tasks received on a queue with the given callback.
     pub fn subscribe<C>(&mut self, queue: &'static Queue, callback: C)
     where
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::sync::atomic::AtomicU16;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -11,10 +13,13 @@ use tokio::sync::broadcast::Receiver;
 use tokio::sync::broadcast::Sender;
 
 use replicore_context::Context;
+use replicore_tasks_models::TaskExecution;
 
+use super::QueueCapacity;
 use super::ReceivedTask;
 use super::TaskAck;
 use super::TaskAckBackend;
+use super::TaskHistoryBackend;
 use super::TaskSource;
 use super::TaskSourceBackend;
 use crate::conf::Queue;
@@ -22,6 +27,7 @@ use crate::conf::Queue;
 /// Fixed queue to submit unit test tasks to or receive them from.
 pub static TEST_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
     queue: String::from("UNIT_TEST"),
+    max_execution_time: None,
     retry_count: 2,
     retry_timeout: Duration::from_millis(50),
 });
@@ -29,6 +35,15 @@ pub static TEST_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
 /// Fixed queue to submit unit test tasks to or receive them from.
 pub static TEST_QUEUE_ALTERNATE: Lazy<Queue> = Lazy::new(|| Queue {
     queue: String::from("UNIT_TEST_ALTERNATE"),
+    max_execution_time: None,
+    retry_count: 0,
+    retry_timeout: Duration::from_millis(10),
+});
+
+/// Fixed queue with a short maximum execution time for tasks.
+pub static TEST_QUEUE_TIMEOUT: Lazy<Queue> = Lazy::new(|| Queue {
+    queue: String::from("UNIT_TEST_TIMEOUT"),
+    max_execution_time: Some(Duration::from_millis(100)),
     retry_count: 0,
     retry_timeout: Duration::from_millis(10),
 });
@@ -36,6 +51,7 @@ pub static TEST_QUEUE_ALTERNATE: Lazy<Queue> = Lazy::new(|| Queue {
 /// Fixed queue to return an error when tasks are received on it.
 pub static TEST_FETCH_FAILURE: Lazy<Queue> = Lazy::new(|| Queue {
     queue: String::from("TEST_FETCH_FAILURE"),
+    max_execution_time: None,
     retry_count: 0,
     retry_timeout: Duration::from_millis(10),
 });
@@ -44,6 +60,8 @@ pub static TEST_FETCH_FAILURE: Lazy<Queue> = Lazy::new(|| Queue {
 #[derive(Clone)]
 pub struct ReceivedTaskFixture {
     done_count: Arc<AtomicU16>,
+    failed_count: Arc<AtomicU16>,
+    heartbeat_count: Arc<AtomicU16>,
     send_task: Sender<ReceivedTask>,
 }
 
@@ -52,6 +70,8 @@ impl ReceivedTaskFixture {
     pub fn ack(&self) -> TaskAck {
         let ack = FixtureAckBackend {
             done_count: self.done_count.clone(),
+            failed_count: self.failed_count.clone(),
+            heartbeat_count: self.heartbeat_count.clone(),
         };
         TaskAck::from(ack)
     }
@@ -61,11 +81,23 @@ impl ReceivedTaskFixture {
         self.done_count.load(Ordering::Relaxed)
     }
 
+    /// Check the number of failed execution attempts reported to the backend.
+    pub fn failed_count(&self) -> u16 {
+        self.failed_count.load(Ordering::Relaxed)
+    }
+
+    /// Check the number of heartbeats for running tasks received by the backend.
+    pub fn heartbeat_count(&self) -> u16 {
+        self.heartbeat_count.load(Ordering::Relaxed)
+    }
+
     /// Initialise a task queue backend fixture for unit tests.
     pub fn new() -> ReceivedTaskFixture {
         let (send_task, _) = broadcast::channel(50);
         ReceivedTaskFixture {
             done_count: Default::default(),
+            failed_count: Default::default(),
+            heartbeat_count: Default::default(),
             send_task,
         }
     }
@@ -73,6 +105,7 @@ impl ReceivedTaskFixture {
     /// Create a backend that will receive tasks from this fixture.
     pub fn source(&self) -> TaskSource {
         let source = FixtureSourceBackend {
+            deferred: Default::default(),
             tasks: self.send_task.subscribe(),
             subscriptions: Default::default(),
         };
@@ -89,6 +122,8 @@ impl ReceivedTaskFixture {
 /// Tasks ack backend for unit tests.
 pub struct FixtureAckBackend {
     done_count: Arc<AtomicU16>,
+    failed_count: Arc<AtomicU16>,
+    heartbeat_count: Arc<AtomicU16>,
 }
 
 #[async_trait::async_trait]
@@ -97,17 +132,37 @@ impl TaskAckBackend for FixtureAckBackend {
         self.done_count.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
+
+    async fn failed(&self, _: &Context, _: &ReceivedTask, _: &anyhow::Error) -> Result<()> {
+        self.failed_count.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn heartbeat(&self, _: &Context, _: &ReceivedTask) -> Result<()> {
+        self.heartbeat_count.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
 }
 
 /// Tasks source backend for unit tests.
 pub struct FixtureSourceBackend {
+    deferred: VecDeque<ReceivedTask>,
     tasks: Receiver<ReceivedTask>,
     subscriptions: HashSet<String>,
 }
 
 #[async_trait::async_trait]
 impl TaskSourceBackend for FixtureSourceBackend {
-    async fn next(&mut self, _: &Context) -> Result<ReceivedTask> {
+    async fn next(&mut self, _: &Context, capacity: &QueueCapacity) -> Result<ReceivedTask> {
+        // Return tasks deferred while their queue was full first.
+        let deferred = self
+            .deferred
+            .iter()
+            .position(|task| !capacity.is_full(&task.queue.queue));
+        if let Some(task) = deferred.and_then(|index| self.deferred.remove(index)) {
+            return Ok(task);
+        }
+
         loop {
             let next = self.tasks.recv().await?;
             if next.queue.queue == TEST_FETCH_FAILURE.queue {
@@ -117,6 +172,10 @@ impl TaskSourceBackend for FixtureSourceBackend {
             if !subscribed {
                 continue;
             }
+            if capacity.is_full(&next.queue.queue) {
+                self.deferred.push_back(next);
+                continue;
+            }
             return Ok(next);
         }
     }
@@ -128,3 +187,30 @@ impl TaskSourceBackend for FixtureSourceBackend {
         Ok(())
     }
 }
+
+/// Task execution history backend for unit tests.
+#[derive(Clone, Default)]
+pub struct TaskHistoryFixture {
+    records: Arc<Mutex<Vec<TaskExecution>>>,
+}
+
+impl TaskHistoryFixture {
+    /// List execution records received by the backend, in the order they were recorded.
+    pub fn records(&self) -> Vec<TaskExecution> {
+        self.records
+            .lock()
+            .expect("TaskHistoryFixture::records lock poisoned")
+            .clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl TaskHistoryBackend for TaskHistoryFixture {
+    async fn record(&self, _: &Context, execution: TaskExecution) -> Result<()> {
+        self.records
+            .lock()
+            .expect("TaskHistoryFixture::records lock poisoned")
+            .push(execution);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 //! Logic and interface to receive and execute submitted tasks.
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -9,6 +10,7 @@ use serde_json::Value;
 use replisdk::utils::metrics::CountFutureErrExt;
 
 use replicore_context::Context;
+use replicore_tasks_models::TaskExecution;
 
 mod backoff;
 mod executor;
@@ -20,7 +22,8 @@ pub use self::executor::TasksExecutorBuilder;
 mod fixture;
 #[cfg(any(test, feature = "test-fixture"))]
 pub use self::fixture::{
-    FixtureSourceBackend, ReceivedTaskFixture, TEST_FETCH_FAILURE, TEST_QUEUE, TEST_QUEUE_ALTERNATE,
+    FixtureSourceBackend, ReceivedTaskFixture, TaskHistoryFixture, TEST_FETCH_FAILURE, TEST_QUEUE,
+    TEST_QUEUE_ALTERNATE, TEST_QUEUE_TIMEOUT,
 };
 
 #[cfg(test)]
@@ -35,6 +38,12 @@ pub struct ReceivedTask {
     /// ID of the received task (as determined by the queuing backend).
     pub id: String,
 
+    /// Delivery attempt number for the task, starting at 1.
+    pub attempt: u32,
+
+    /// De-duplication key the task was submitted with, if any.
+    pub dedup_key: Option<String>,
+
     /// Payload received for this task.
     pub payload: Value,
 
@@ -59,6 +68,41 @@ impl ReceivedTask {
     }
 }
 
+/// Number of tasks subscribed queues can receive before reaching their concurrency limit.
+///
+/// Queues without a known capacity are not limited.
+#[derive(Clone, Debug, Default)]
+pub struct QueueCapacity(HashMap<String, usize>);
+
+impl QueueCapacity {
+    /// Number of tasks the queue can receive, if the queue is limited.
+    pub fn free(&self, queue: &str) -> Option<usize> {
+        self.0.get(queue).copied()
+    }
+
+    /// Check if the queue reached its concurrency limit and can't receive tasks.
+    pub fn is_full(&self, queue: &str) -> bool {
+        self.free(queue) == Some(0)
+    }
+
+    /// Limit the number of tasks the queue can receive.
+    pub fn limit<S>(&mut self, queue: S, free: usize)
+    where
+        S: Into<String>,
+    {
+        self.0.insert(queue.into(), free);
+    }
+}
+
+impl FromIterator<(String, usize)> for QueueCapacity {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (String, usize)>,
+    {
+        QueueCapacity(iter.into_iter().collect())
+    }
+}
+
 /// Notify the backing queue platform of updates to tasks.
 #[derive(Clone)]
 pub struct TaskAck(Arc<dyn TaskAckBackend>);
@@ -68,6 +112,28 @@ impl TaskAck {
     pub async fn done(&self, context: &Context, task: &ReceivedTask) -> Result<()> {
         self.0.done(context, task).await
     }
+
+    /// Record the error that caused an execution attempt of the task to fail.
+    ///
+    /// The task is NOT removed and the standard retry logic applies.
+    /// Once all retries are exhausted the last recorded error is reported
+    /// as part of the dead-letter queue record for the task.
+    pub async fn failed(
+        &self,
+        context: &Context,
+        task: &ReceivedTask,
+        error: &anyhow::Error,
+    ) -> Result<()> {
+        self.0.failed(context, task, error).await
+    }
+
+    /// Notify the queue that the task is still being executed.
+    ///
+    /// Heartbeats postpone redelivery of the task by the queue retry timeout
+    /// so tasks are not executed twice in parallel while a handler is still working on them.
+    pub async fn heartbeat(&self, context: &Context, task: &ReceivedTask) -> Result<()> {
+        self.0.heartbeat(context, task).await
+    }
 }
 
 impl<T> From<T> for TaskAck
@@ -84,6 +150,44 @@ where
 pub trait TaskAckBackend: Send + Sync {
     /// Mark the task as processed (either successfully or not) so it can be removed.
     async fn done(&self, context: &Context, task: &ReceivedTask) -> Result<()>;
+
+    /// Record the error that caused an execution attempt of the task to fail.
+    async fn failed(
+        &self,
+        context: &Context,
+        task: &ReceivedTask,
+        error: &anyhow::Error,
+    ) -> Result<()>;
+
+    /// Postpone redelivery of a task that is still being executed.
+    async fn heartbeat(&self, context: &Context, task: &ReceivedTask) -> Result<()>;
+}
+
+/// Record the outcome of task execution attempts for later inspection.
+#[derive(Clone)]
+pub struct TaskHistory(Arc<dyn TaskHistoryBackend>);
+
+impl TaskHistory {
+    /// Record the outcome of an attempt to execute a task.
+    pub async fn record(&self, context: &Context, execution: TaskExecution) -> Result<()> {
+        self.0.record(context, execution).await
+    }
+}
+
+impl<T> From<T> for TaskHistory
+where
+    T: TaskHistoryBackend + 'static,
+{
+    fn from(value: T) -> Self {
+        TaskHistory(Arc::new(value))
+    }
+}
+
+/// Operations to persist records of task execution attempts.
+#[async_trait::async_trait]
+pub trait TaskHistoryBackend: Send + Sync {
+    /// Record the outcome of an attempt to execute a task.
+    async fn record(&self, context: &Context, execution: TaskExecution) -> Result<()>;
 }
 
 /// Async callback invoked to execute received tasks.
@@ -99,9 +203,25 @@ pub struct TaskSource(Box<dyn TaskSourceBackend>);
 impl TaskSource {
     /// Fetch the next task available for processing.
     pub async fn next(&mut self, context: &Context) -> Result<ReceivedTask> {
+        self.next_within(context, &QueueCapacity::default()).await
+    }
+
+    /// Fetch the next task available for processing from queues with free capacity.
+    pub async fn next_within(
+        &mut self,
+        context: &Context,
+        capacity: &QueueCapacity,
+    ) -> Result<ReceivedTask> {
         let err_count = crate::telemetry::RECEIVE_ERR.clone();
         crate::telemetry::RECEIVE_COUNT.inc();
-        self.0.next(context).count_on_err(err_count).await
+        self.0.next(context, capacity).count_on_err(err_count).await
+    }
+
+    /// Give back tasks received by the backend but not yet returned for processing.
+    ///
+    /// Released tasks become available for delivery again as if they were never received.
+    pub async fn release(&mut self, context: &Context) -> Result<()> {
+        self.0.release(context).await
     }
 
     /// Configure the backend to subscribe to tasks submitted to a [`Queue`].
@@ -123,7 +243,17 @@ where
 #[async_trait::async_trait]
 pub trait TaskSourceBackend: Send + Sync {
     /// Fetch the next task available for processing.
-    async fn next(&mut self, context: &Context) -> Result<ReceivedTask>;
+    ///
+    /// Tasks must not be fetched from queues that reached their concurrency limit
+    /// and backends that receive tasks in batches must not exceed the free capacity of queues.
+    async fn next(&mut self, context: &Context, capacity: &QueueCapacity) -> Result<ReceivedTask>;
+
+    /// Give back tasks received by the backend but not yet returned for processing.
+    ///
+    /// Backends that do not receive tasks ahead of time have nothing to release.
+    async fn release(&mut self, _: &Context) -> Result<()> {
+        Ok(())
+    }
 
     /// Configure the backend to subscribe to tasks submitted to a [`Queue`].
     async fn subscribe(&mut self, context: &Context, queue: &'static Queue) -> Result<()>;
@@ -3,17 +3,25 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use anyhow::Result;
+use once_cell::sync::Lazy;
 
 use replicore_context::Context;
+use replicore_tasks_models::TaskOutcome;
 
 use super::ReceivedTask;
 use super::ReceivedTaskFixture;
 use super::TaskCallback;
+use super::TaskHistory;
+use super::TaskHistoryFixture;
 use super::TasksExecutor;
 use super::TEST_FETCH_FAILURE;
 use super::TEST_QUEUE;
 use super::TEST_QUEUE_ALTERNATE;
+use super::TEST_QUEUE_TIMEOUT;
+use crate::conf::Queue;
 use crate::conf::TasksExecutorConf;
+use crate::conf::TasksExecutorQueueConf;
+use crate::error::ExecutionTimeTooShort;
 
 /// Task handler that can return errors.
 pub enum AckTask {
@@ -124,6 +132,8 @@ async fn acknowledge_fail_permanent() {
 
     let task = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE,
         run_as: None,
@@ -135,6 +145,7 @@ async fn acknowledge_fail_permanent() {
         .await
         .unwrap();
     assert_eq!(1, fixtures.tasks.done_count());
+    assert_eq!(0, fixtures.tasks.failed_count());
 }
 
 #[tokio::test]
@@ -148,6 +159,8 @@ async fn acknowledge_fail_retry() {
 
     let task = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE,
         run_as: None,
@@ -159,6 +172,7 @@ async fn acknowledge_fail_retry() {
         .await
         .unwrap();
     assert_eq!(0, fixtures.tasks.done_count());
+    assert_eq!(1, fixtures.tasks.failed_count());
 }
 
 #[tokio::test]
@@ -172,6 +186,8 @@ async fn acknowledge_success() {
 
     let task = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE,
         run_as: None,
@@ -196,6 +212,8 @@ async fn cancel_task_on_exit() {
 
     let task = ReceivedTask {
         id: "long".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::json!(200u64),
         queue: &TEST_QUEUE,
         run_as: None,
@@ -204,6 +222,8 @@ async fn cancel_task_on_exit() {
     fixtures.tasks.submit(task).await.unwrap();
     let task = ReceivedTask {
         id: "short".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::json!(50u64),
         queue: &TEST_QUEUE,
         run_as: None,
@@ -230,6 +250,8 @@ async fn cancel_task_on_error() {
 
     let task = ReceivedTask {
         id: "error".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_FETCH_FAILURE,
         run_as: None,
@@ -238,6 +260,8 @@ async fn cancel_task_on_error() {
     fixtures.tasks.submit(task).await.unwrap();
     let task = ReceivedTask {
         id: "short".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::json!(50u64),
         queue: &TEST_QUEUE,
         run_as: None,
@@ -267,6 +291,8 @@ async fn dispatch_task() {
     // Submit tasks for test.
     let task = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE,
         run_as: None,
@@ -274,6 +300,8 @@ async fn dispatch_task() {
     };
     let task_alternate = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE_ALTERNATE,
         run_as: None,
@@ -292,7 +320,145 @@ async fn dispatch_task() {
     assert_eq!(count, 2);
 }
 
+#[tokio::test]
+async fn drain_tasks_on_exit() {
+    let fixtures = Fixtures::new();
+    let mut executor = fixtures.executor;
+    executor.drain_timeout(std::time::Duration::from_millis(100));
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE, Sleep)
+        .await
+        .unwrap();
+
+    // Running tasks that complete within the drain timeout are not abandoned.
+    let task = ReceivedTask {
+        id: "drained".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::json!(150u64),
+        queue: &TEST_QUEUE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    let task = ReceivedTask {
+        id: "abandoned".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::json!(500u64),
+        queue: &TEST_QUEUE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    executor
+        .execute(&fixtures.context, fixtures.exit)
+        .await
+        .unwrap();
+    assert_eq!(1, fixtures.tasks.done_count());
+
+    // Abandoned tasks are not acknowledged after the executor returns.
+    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    assert_eq!(1, fixtures.tasks.done_count());
+}
+
+#[tokio::test]
+async fn heartbeat_long_running_task() {
+    let fixtures = Fixtures::new();
+    let mut executor = fixtures.executor;
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE, Sleep)
+        .await
+        .unwrap();
+
+    let task = ReceivedTask {
+        id: "long".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::json!(250u64),
+        queue: &TEST_QUEUE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    let exit = tokio::time::sleep(std::time::Duration::from_millis(300));
+    executor.execute(&fixtures.context, exit).await.unwrap();
+    assert_eq!(1, fixtures.tasks.done_count());
+    assert!(fixtures.tasks.heartbeat_count() > 0);
+}
+
 #[should_panic(expected = "test panic propagation")]
+#[tokio::test]
+async fn history_records_failure() {
+    let fixtures = Fixtures::new();
+    let history = TaskHistoryFixture::default();
+    let mut executor = fixtures.executor;
+    executor.history(TaskHistory::from(history.clone()));
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE, AckTask::FailPermanent)
+        .await
+        .unwrap();
+
+    let task = ReceivedTask {
+        id: "test".into(),
+        attempt: 2,
+        dedup_key: None,
+        payload: serde_json::json!({"platform": "test"}),
+        queue: &TEST_QUEUE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    executor
+        .execute(&fixtures.context, fixtures.exit)
+        .await
+        .unwrap();
+
+    let records = history.records();
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record.task_id, "test");
+    assert_eq!(record.attempt, 2);
+    assert_eq!(record.queue, TEST_QUEUE.queue);
+    assert_eq!(record.payload, r#"{"platform":"test"}"#);
+    assert_eq!(record.outcome, TaskOutcome::Abandoned);
+    assert_eq!(record.error.len(), 2);
+    assert_eq!(record.error[1], "test error");
+    assert!(record.started_time <= record.finished_time);
+}
+
+#[tokio::test]
+async fn history_records_success() {
+    let fixtures = Fixtures::new();
+    let history = TaskHistoryFixture::default();
+    let mut executor = fixtures.executor;
+    executor.history(TaskHistory::from(history.clone()));
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE, AckTask::Succeed)
+        .await
+        .unwrap();
+
+    let task = ReceivedTask {
+        id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::Value::Null,
+        queue: &TEST_QUEUE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    executor
+        .execute(&fixtures.context, fixtures.exit)
+        .await
+        .unwrap();
+
+    let records = history.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].outcome, TaskOutcome::Success);
+    assert!(records[0].error.is_empty());
+}
+
 #[tokio::test]
 async fn panic_propagates() {
     let fixtures = Fixtures::new();
@@ -304,6 +470,8 @@ async fn panic_propagates() {
 
     let task = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE,
         run_as: None,
@@ -316,6 +484,116 @@ async fn panic_propagates() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn queue_concurrency_limit() {
+    let mut conf = TasksExecutorConf::default();
+    conf.backoff.max_retries = 0;
+    conf.concurrent_tasks = 10;
+    conf.queues.insert(
+        TEST_QUEUE.queue.clone(),
+        TasksExecutorQueueConf {
+            concurrent_tasks: Some(1),
+            weight: None,
+        },
+    );
+    let fixtures = Fixtures::with_conf(conf);
+    let mut executor = fixtures.executor;
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE, Sleep)
+        .await
+        .unwrap();
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE_ALTERNATE, Sleep)
+        .await
+        .unwrap();
+
+    // Only one task on the limited queue can complete before exit.
+    for id in ["one", "two"] {
+        let task = ReceivedTask {
+            id: id.into(),
+            attempt: 1,
+            dedup_key: None,
+            payload: serde_json::json!(60u64),
+            queue: &TEST_QUEUE,
+            run_as: None,
+            trace: None,
+        };
+        fixtures.tasks.submit(task).await.unwrap();
+    }
+
+    // Tasks on other queues are executed while the limited queue is busy.
+    let task = ReceivedTask {
+        id: "alternate".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::json!(10u64),
+        queue: &TEST_QUEUE_ALTERNATE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    executor
+        .execute(&fixtures.context, fixtures.exit)
+        .await
+        .unwrap();
+    assert_eq!(2, fixtures.tasks.done_count());
+}
+
+#[tokio::test]
+async fn queue_weight_limit() {
+    let mut conf = TasksExecutorConf::default();
+    conf.backoff.max_retries = 0;
+    conf.concurrent_tasks = 2;
+    conf.queues.insert(
+        TEST_QUEUE.queue.clone(),
+        TasksExecutorQueueConf {
+            concurrent_tasks: None,
+            weight: Some(1),
+        },
+    );
+    let fixtures = Fixtures::with_conf(conf);
+    let mut executor = fixtures.executor;
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE, Sleep)
+        .await
+        .unwrap();
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE_ALTERNATE, Sleep)
+        .await
+        .unwrap();
+
+    // Equal weights split the global limit so only one task on the queue completes.
+    for id in ["one", "two"] {
+        let task = ReceivedTask {
+            id: id.into(),
+            attempt: 1,
+            dedup_key: None,
+            payload: serde_json::json!(60u64),
+            queue: &TEST_QUEUE,
+            run_as: None,
+            trace: None,
+        };
+        fixtures.tasks.submit(task).await.unwrap();
+    }
+
+    // Tasks on other queues are executed while the limited queue is busy.
+    let task = ReceivedTask {
+        id: "alternate".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::json!(10u64),
+        queue: &TEST_QUEUE_ALTERNATE,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    executor
+        .execute(&fixtures.context, fixtures.exit)
+        .await
+        .unwrap();
+    assert_eq!(2, fixtures.tasks.done_count());
+}
+
 #[tokio::test]
 async fn subscriptions_are_filtered() {
     let counter = Counter::default();
@@ -339,6 +617,8 @@ async fn subscriptions_are_filtered() {
     // Submit tasks for test.
     let task = ReceivedTask {
         id: "test".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE,
         run_as: None,
@@ -346,6 +626,8 @@ async fn subscriptions_are_filtered() {
     };
     let task_alternate = ReceivedTask {
         id: "filtered".into(),
+        attempt: 1,
+        dedup_key: None,
         payload: serde_json::Value::Null,
         queue: &TEST_QUEUE_ALTERNATE,
         run_as: None,
@@ -362,3 +644,45 @@ async fn subscriptions_are_filtered() {
     let count = counter_atomic.load(Ordering::Relaxed);
     assert_eq!(count, 1);
 }
+
+#[tokio::test]
+async fn timeout_fails_task() {
+    let fixtures = Fixtures::new();
+    let mut executor = fixtures.executor;
+    executor
+        .subscribe(&fixtures.context, &TEST_QUEUE_TIMEOUT, Sleep)
+        .await
+        .unwrap();
+
+    let task = ReceivedTask {
+        id: "slow".into(),
+        attempt: 1,
+        dedup_key: None,
+        payload: serde_json::json!(250u64),
+        queue: &TEST_QUEUE_TIMEOUT,
+        run_as: None,
+        trace: None,
+    };
+    fixtures.tasks.submit(task).await.unwrap();
+    let exit = tokio::time::sleep(std::time::Duration::from_millis(200));
+    executor.execute(&fixtures.context, exit).await.unwrap();
+    assert_eq!(0, fixtures.tasks.done_count());
+    assert_eq!(1, fixtures.tasks.failed_count());
+}
+
+#[tokio::test]
+async fn timeout_shorter_than_heartbeat_rejected() {
+    static SHORT_QUEUE: Lazy<Queue> = Lazy::new(|| Queue {
+        queue: String::from("UNIT_TEST_SHORT"),
+        max_execution_time: Some(std::time::Duration::from_millis(20)),
+        retry_count: 0,
+        retry_timeout: std::time::Duration::from_secs(1),
+    });
+    let fixtures = Fixtures::new();
+    let mut executor = fixtures.executor;
+    let error = executor
+        .subscribe(&fixtures.context, &SHORT_QUEUE, Sleep)
+        .await
+        .unwrap_err();
+    assert!(error.is::<ExecutionTimeTooShort>());
+}
@@ -1,21 +1,39 @@
 //! Interface to submit tasks to a message queue platform.
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use opentelemetry_api::Context as OTelContext;
 use serde::Serialize;
 use serde_json::Value;
+use time::OffsetDateTime;
 
 use replisdk::utils::metrics::CountFutureErrExt;
 
 use replicore_context::Context;
+use replicore_tasks_models::DeadLetterTask;
+use replicore_tasks_models::QueueStats;
 
 use crate::conf::Queue;
 use crate::conf::RunTaskAs;
 
+/// Maximum length, in characters, of task payloads included in previews.
+pub const PAYLOAD_PREVIEW_MAX_LEN: usize = 120;
+
 /// Information about a task to submit for async execution.
 #[derive(Clone, Debug)]
 pub struct TaskSubmission {
+    /// Optional key to de-duplicate repeated submissions of the same task.
+    ///
+    /// While a task with the same key is still pending on the same queue,
+    /// backends drop or merge new submissions into the pending task.
+    pub dedup_key: Option<String>,
+
+    /// Do not deliver the task for execution before this time.
+    ///
+    /// When not set the task is available for immediate execution.
+    pub not_before: Option<OffsetDateTime>,
+
     /// Payload submitted as part of this task.
     pub payload: Value,
 
@@ -36,6 +54,8 @@ impl TaskSubmission {
         P: Serialize,
     {
         let task = TaskSubmission {
+            dedup_key: None,
+            not_before: None,
             payload: serde_json::to_value(payload)?,
             queue,
             run_as: None,
@@ -43,6 +63,26 @@ impl TaskSubmission {
         };
         Ok(task)
     }
+
+    /// De-duplicate submissions of this task with others sharing the same key.
+    pub fn dedup_key<S>(mut self, key: S) -> TaskSubmission
+    where
+        S: Into<String>,
+    {
+        self.dedup_key = Some(key.into());
+        self
+    }
+
+    /// Delay delivery of the task for execution by the given amount of time from now.
+    pub fn delay(self, delay: Duration) -> TaskSubmission {
+        self.not_before(OffsetDateTime::now_utc() + delay)
+    }
+
+    /// Do not deliver the task for execution before the given time.
+    pub fn not_before(mut self, not_before: OffsetDateTime) -> TaskSubmission {
+        self.not_before = Some(not_before);
+        self
+    }
 }
 
 /// Submit tasks to the backing task queue platform.
@@ -50,6 +90,50 @@ impl TaskSubmission {
 pub struct Tasks(Arc<dyn TasksBackend>);
 
 impl Tasks {
+    /// List tasks that exhausted all delivery attempts and were moved to the dead-letter queue.
+    pub async fn dlq_list(&self, context: &Context) -> Result<Vec<DeadLetterTask>> {
+        self.0.dlq_list(context).await
+    }
+
+    /// Lookup a task in the dead-letter queue by ID.
+    pub async fn dlq_lookup(&self, context: &Context, id: &str) -> Result<Option<DeadLetterTask>> {
+        self.0.dlq_lookup(context, id).await
+    }
+
+    /// Remove a task from the dead-letter queue, or all tasks if no ID is given.
+    ///
+    /// Returns the number of tasks removed from the dead-letter queue.
+    pub async fn dlq_purge(&self, context: &Context, id: Option<&str>) -> Result<u64> {
+        self.0.dlq_purge(context, id).await
+    }
+
+    /// Move a task from the dead-letter queue back onto its queue for execution.
+    ///
+    /// Returns `false` if no task with the given ID is in the dead-letter queue.
+    pub async fn dlq_requeue(&self, context: &Context, id: &str) -> Result<bool> {
+        self.0.dlq_requeue(context, id).await
+    }
+
+    /// Summarise the state of tasks on each queue known to the backend.
+    ///
+    /// Up to `previews` of the oldest tasks on each queue are included in the summaries,
+    /// with payloads truncated to [`PAYLOAD_PREVIEW_MAX_LEN`] characters.
+    pub async fn stats(&self, context: &Context, previews: usize) -> Result<Vec<QueueStats>> {
+        let mut stats = self.0.stats(context, previews).await?;
+        for queue in &mut stats {
+            queue.previews.truncate(previews);
+            for preview in &mut queue.previews {
+                if let Some((index, _)) =
+                    preview.payload.char_indices().nth(PAYLOAD_PREVIEW_MAX_LEN)
+                {
+                    preview.payload.truncate(index);
+                    preview.payload.push_str("...");
+                }
+            }
+        }
+        Ok(stats)
+    }
+
     /// Submit a task onto its queue.
     pub async fn submit<T>(&self, context: &Context, task: T) -> Result<()>
     where
@@ -93,7 +177,30 @@ where
 /// Operations implemented by Message Queue Platforms supported by Replicante Core.
 #[async_trait::async_trait]
 pub trait TasksBackend: Send + Sync {
+    /// List tasks that exhausted all delivery attempts and were moved to the dead-letter queue.
+    async fn dlq_list(&self, context: &Context) -> Result<Vec<DeadLetterTask>>;
+
+    /// Lookup a task in the dead-letter queue by ID.
+    async fn dlq_lookup(&self, context: &Context, id: &str) -> Result<Option<DeadLetterTask>>;
+
+    /// Remove a task from the dead-letter queue, or all tasks if no ID is given.
+    async fn dlq_purge(&self, context: &Context, id: Option<&str>) -> Result<u64>;
+
+    /// Move a task from the dead-letter queue back onto its queue for execution.
+    async fn dlq_requeue(&self, context: &Context, id: &str) -> Result<bool>;
+
+    /// Summarise the state of tasks on each queue known to the backend.
+    ///
+    /// Summaries should include up to `previews` of the oldest tasks on each queue.
+    async fn stats(&self, context: &Context, previews: usize) -> Result<Vec<QueueStats>>;
+
     /// Submit a task onto its queue.
+    ///
+    /// Tasks with a [`TaskSubmission::not_before`] time must not be delivered
+    /// for execution until that time has passed.
+    ///
+    /// Tasks with a [`TaskSubmission::dedup_key`] must be dropped, or merged into the existing
+    /// task, while a task with the same key is pending on the same queue.
     async fn submit(&self, context: &Context, task: TaskSubmission) -> Result<()>;
 }
 
@@ -102,6 +209,9 @@ pub use self::fixture::{TasksFixture, TasksFixtureBackend};
 
 #[cfg(any(test, feature = "test-fixture"))]
 mod fixture {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::sync::Mutex;
     use std::time::Duration;
 
     use anyhow::Result;
@@ -110,12 +220,18 @@ mod fixture {
     use tokio::sync::broadcast::Sender;
 
     use replicore_context::Context;
+    use replicore_tasks_models::DeadLetterTask;
+    use replicore_tasks_models::QueueStats;
 
     use super::TaskSubmission;
     use super::TasksBackend;
 
+    /// Keys of de-duplicated tasks submitted to the fixture and not yet popped.
+    type PendingKeys = Arc<Mutex<HashSet<(String, String)>>>;
+
     /// Introspection tools for tasks submitted during unit tests.
     pub struct TasksFixture {
+        pending: PendingKeys,
         tasks: Receiver<TaskSubmission>,
         send_task: Sender<TaskSubmission>,
     }
@@ -124,6 +240,7 @@ mod fixture {
         fn clone(&self) -> Self {
             let tasks = self.send_task.subscribe();
             Self {
+                pending: self.pending.clone(),
                 tasks,
                 send_task: self.send_task.clone(),
             }
@@ -134,6 +251,7 @@ mod fixture {
         /// Create a backend that will send tasks to this fixture.
         pub fn backend(&self) -> TasksFixtureBackend {
             TasksFixtureBackend {
+                pending: self.pending.clone(),
                 send_task: self.send_task.clone(),
             }
         }
@@ -141,12 +259,25 @@ mod fixture {
         /// Initialise a task queue backend fixture for unit tests.
         pub fn new() -> TasksFixture {
             let (send_task, tasks) = broadcast::channel(50);
-            TasksFixture { tasks, send_task }
+            TasksFixture {
+                pending: Default::default(),
+                tasks,
+                send_task,
+            }
         }
 
         /// Fetch the next [`Task`] submitted to the fixture.
+        ///
+        /// Popped tasks are no longer pending and stop de-duplicating new submissions.
         pub async fn pop_task(&mut self) -> Result<TaskSubmission> {
             let task = self.tasks.recv().await?;
+            if let Some(key) = &task.dedup_key {
+                let key = (task.queue.queue.clone(), key.clone());
+                self.pending
+                    .lock()
+                    .expect("TasksFixture pending lock poisoned")
+                    .remove(&key);
+            }
             Ok(task)
         }
 
@@ -158,14 +289,52 @@ mod fixture {
     }
 
     /// Tasks backend for unit tests.
+    ///
+    /// Submissions with a de-duplication key are dropped while a task with the same key
+    /// is pending (submitted but not yet popped from the [`TasksFixture`]).
     pub struct TasksFixtureBackend {
+        pending: PendingKeys,
         send_task: Sender<TaskSubmission>,
     }
 
     #[async_trait::async_trait]
     impl TasksBackend for TasksFixtureBackend {
+        async fn dlq_list(&self, _: &Context) -> Result<Vec<DeadLetterTask>> {
+            Ok(Vec::new())
+        }
+
+        async fn dlq_lookup(&self, _: &Context, _: &str) -> Result<Option<DeadLetterTask>> {
+            Ok(None)
+        }
+
+        async fn dlq_purge(&self, _: &Context, _: Option<&str>) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn dlq_requeue(&self, _: &Context, _: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn stats(&self, _: &Context, _: usize) -> Result<Vec<QueueStats>> {
+            Ok(Vec::new())
+        }
+
         async fn submit(&self, _: &Context, task: TaskSubmission) -> Result<()> {
+            let key = task
+                .dedup_key
+                .as_ref()
+                .map(|key| (task.queue.queue.clone(), key.clone()));
+            let mut pending = self
+                .pending
+                .lock()
+                .expect("TasksFixture pending lock poisoned");
+            if matches!(&key, Some(key) if pending.contains(key)) {
+                return Ok(());
+            }
             self.send_task.send(task)?;
+            if let Some(key) = key {
+                pending.insert(key);
+            }
             Ok(())
         }
     }
@@ -10,6 +10,42 @@ use prometheus::Counter;
 use prometheus::CounterVec;
 use prometheus::Opts;
 
+/// Number of task executions aborted for exceeding the maximum execution time.
+pub static EXECUTE_TIMEOUT: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_tasks_execute_timeout",
+            "Number of task executions aborted for exceeding the maximum execution time",
+        ),
+        &["queue"],
+    )
+    .expect("failed to initialise EXECUTE_TIMEOUT counter")
+});
+
+/// Number of heartbeats for running tasks that resulted in error.
+pub static HEARTBEAT_ERR: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_tasks_heartbeat_error",
+            "Number of heartbeats for running tasks that resulted in error",
+        ),
+        &["queue"],
+    )
+    .expect("failed to initialise HEARTBEAT_ERR counter")
+});
+
+/// Number of task execution records that failed to be recorded.
+pub static HISTORY_ERR: Lazy<CounterVec> = Lazy::new(|| {
+    CounterVec::new(
+        Opts::new(
+            "replicore_tasks_history_error",
+            "Number of task execution records that failed to be recorded",
+        ),
+        &["queue"],
+    )
+    .expect("failed to initialise HISTORY_ERR counter")
+});
+
 /// Total number of task received for execution.
 pub static RECEIVE_COUNT: Lazy<Counter> = Lazy::new(|| {
     Counter::new(
@@ -28,6 +64,24 @@ pub static RECEIVE_ERR: Lazy<Counter> = Lazy::new(|| {
     .expect("failed to initialise RECEIVE_ERR counter")
 });
 
+/// Number of in-progress tasks abandoned when the executor stopped.
+pub static SHUTDOWN_ABANDONED: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_shutdown_abandoned",
+        "Number of in-progress tasks abandoned when the executor stopped",
+    )
+    .expect("failed to initialise SHUTDOWN_ABANDONED counter")
+});
+
+/// Number of in-progress tasks that completed while draining the executor on shutdown.
+pub static SHUTDOWN_DRAINED: Lazy<Counter> = Lazy::new(|| {
+    Counter::new(
+        "replicore_tasks_shutdown_drained",
+        "Number of in-progress tasks that completed while draining the executor on shutdown",
+    )
+    .expect("failed to initialise SHUTDOWN_DRAINED counter")
+});
+
 /// Total number of task submissions.
 pub static SUBMIT_COUNT: Lazy<CounterVec> = Lazy::new(|| {
     CounterVec::new(
@@ -72,9 +126,14 @@ pub fn register_metrics(reg: &prometheus::Registry) -> Result<()> {
         return Ok(());
     }
 
-    let collectors: [Box<dyn prometheus::core::Collector>; 4] = [
+    let collectors: [Box<dyn prometheus::core::Collector>; 9] = [
+        Box::new(EXECUTE_TIMEOUT.clone()),
+        Box::new(HEARTBEAT_ERR.clone()),
+        Box::new(HISTORY_ERR.clone()),
         Box::new(RECEIVE_COUNT.clone()),
         Box::new(RECEIVE_ERR.clone()),
+        Box::new(SHUTDOWN_ABANDONED.clone()),
+        Box::new(SHUTDOWN_DRAINED.clone()),
         Box::new(SUBMIT_COUNT.clone()),
         Box::new(SUBMIT_ERR.clone()),
     ];